@@ -52,7 +52,7 @@ target_cfg! {
             // Write the bindings to the $OUT_DIR/x11.rs file.
             let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap()).join("x11.rs");
 
-            // Tell cargo to link to X11 library
+            // Tell cargo to link to X11. XInput2, MIT-SHM and Present libraries are loaded at runtime, their headers only give types.
             println!("cargo:rustc-link-search=/usr/include/X11");
             println!("cargo:rustc-link-lib=X11");
 
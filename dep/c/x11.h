@@ -1 +1,6 @@
-#include <Xlib.h>
\ No newline at end of file
+#include <Xlib.h>
+#include <Xutil.h>
+#include <XKBlib.h>
+#include <extensions/XInput2.h>
+#include <extensions/XShm.h>
+#include <extensions/Xpresent.h>
\ No newline at end of file
@@ -71,56 +71,56 @@ const WB_DEFAULT_TASKBAR : bool = true;
 pub struct WindowBuilder {
 
     /// Buffer of the icon of [Window].
-    icon : Option<Vec<u8>>,
+    pub(crate) icon : Option<Vec<u8>>,
 
     /// Title of the [Window]
-    title : String,
+    pub(crate) title : String,
 
     /// Minimum size of the [Window].
-    min_size : WindowSize,
+    pub(crate) min_size : WindowSize,
 
     /// Maximum size of the [Window].
-    max_size : WindowSize,
+    pub(crate) max_size : WindowSize,
 
     /// Size of the [Window]
-    size : WindowSize,
+    pub(crate) size : WindowSize,
 
     /// Relative position of the [Window].
-    position : WindowRelativePosition,
+    pub(crate) position : WindowRelativePosition,
 
     /// Parent of the [Window].
     #[cfg (any (doc , not (feature = "single_opt")))]
     #[cfg_attr (docsrs , doc (cfg (not (feature = "single_opt"))))]
-    parent : Option<WindowHandle>,
+    pub(crate) parent : Option<WindowHandle>,
 
     /// Frame properties for [Window].
-    frame : WindowFrame,
+    pub(crate) frame : WindowFrame,
 
     /// Subwindow properties for [Window].
     #[cfg (any (doc , not (feature = "single_opt")))]
     #[cfg_attr (docsrs , doc (cfg (not (feature = "single_opt"))))]
-    sub : crate::sub::SubWindow,
+    pub(crate) sub : crate::sub::SubWindow,
 
     /// Keyboard properties for [Window].
-    keyboard : WindowKeyboard,
+    pub(crate) keyboard : WindowKeyboard,
 
     /// Pointer properties for [Window]
-    pointer : WindowPointer,
+    pub(crate) pointer : WindowPointer,
 
     /// Fullscreen mode
-    fsmode : Option<WindowFullScreenMode>,
+    pub(crate) fsmode : Option<WindowFullScreenMode>,
 
     /// Window minimized
-    minimized : bool,
+    pub(crate) minimized : bool,
 
     /// Window maximized
-    maximized : bool,
+    pub(crate) maximized : bool,
 
     /// Window will be showed on desktop when created.
-    visible : bool,
+    pub(crate) visible : bool,
 
     /// Window will be showed in the taskbar
-    taskbar : bool,
+    pub(crate) taskbar : bool,
 }
 
 impl WindowBuilder {
@@ -0,0 +1,52 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! [Cairo](https://www.cairographics.org/) 2D drawing surfaces of [Window](crate::Window).
+//! 
+//! Cairo isn't linked. `libcairo.so.2` is loaded when the first surface is requested. Surfaces are given as `cairo_surface_t` 
+//! pointers, to use with any binding like [cairo-rs](https://crates.io/crates/cairo-rs).
+//! 
+//! # Example
+//! ```no_run
+//! # use nswindow::{WindowManager, WindowBuilder, event::WindowEvent};
+//! let mut wm = WindowManager::new().unwrap();
+//! WindowBuilder::new().build(&mut wm).unwrap();
+//! 
+//! while let Some(event) = wm.event().cloned() {
+//!     if let WindowEvent::Exposed(..) = event.event {
+//!         let window = wm.window_mut(event.window).unwrap();
+//!         let surface = window.cairo_surface().unwrap();
+//! 
+//!         // Draw on surface with cairo_create(surface), then
+//!         window.cairo_present(&[]).unwrap();
+//!     }
+//! }
+//! ```
+//! 
+//! # Known issue(s)
+//! * `(Linux only)` Wayland surfaces are image surfaces of the [software framebuffer](crate::Window::framebuffer) drawn by the CPU.
+
+/// Pointer to a Cairo `cairo_surface_t` owned by its [Window](crate::Window).
+pub type CairoSurface = *mut std::ffi::c_void;
@@ -0,0 +1,153 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! Input devices information such as keyboards, mice and tablets.
+
+/// Input devices unit tests
+#[cfg(test)]
+pub(crate) mod tests {
+    include!("tests/device.rs");
+}
+
+/// Identifier of an input device given by the system.
+pub type InputDeviceId = u32;
+
+/// Possible kind of [InputDevice].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum InputDeviceKind {
+    /// Keyboard device.
+    Keyboard,
+
+    /// Mouse or any relative pointing device.
+    Mouse,
+
+    /// Touchpad, usually found on laptops.
+    Touchpad,
+
+    /// Touchscreen where touches are directly on the display.
+    Touchscreen,
+
+    /// Graphic tablet with pen or stylus.
+    Tablet,
+
+    /// Device that doesn't fit in any other kind.
+    Other,
+}
+
+/// Capabilities of an [InputDevice].
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct InputDeviceCapabilities {
+    /// Count of keys of the device. 0 if device has no key.
+    pub keys : u32,
+
+    /// Count of buttons of the device. 0 if device has no button.
+    pub buttons : u32,
+
+    /// Count of axes (valuators) of the device like x, y, pressure and tilt.
+    pub axes : u32,
+
+    /// Maximum simultaneous touches supported. 0 if device isn't a touch device.
+    pub touches : u32,
+
+    /// Device can scroll.
+    pub scroll : bool,
+
+    /// Device give absolute positions instead of relative motion.
+    pub absolute : bool,
+}
+
+/// Details of an input device.
+#[derive(Debug, PartialEq, Clone)]
+pub struct InputDevice {
+    /// Identifier of the device.
+    pub id : InputDeviceId,
+
+    /// Name of the device given by the system.
+    pub name : String,
+
+    /// Kind of device.
+    pub kind : InputDeviceKind,
+
+    /// Capabilities of the device.
+    pub capabilities : InputDeviceCapabilities,
+
+    /// Device is currently enabled.
+    pub enabled : bool,
+}
+
+/// Contains list of all input devices connected.
+#[derive(Debug)]
+pub struct InputDevices {
+    /// Inner list of input devices
+    pub list : Vec<InputDevice>,
+}
+
+impl InputDevices {
+    /// Create an input devices list from vector of [InputDevice].
+    pub(crate) fn create(list : Vec<InputDevice>) -> InputDevices {
+        InputDevices { list }
+    }
+
+    /// Get an [InputDevice] reference from [InputDeviceId].
+    /// 
+    /// Returns Some([InputDevice]) or None if no device has that id.
+    pub fn get(&self, id : InputDeviceId) -> Option<&InputDevice> {
+        self.list.iter().find(|device| device.id == id)
+    }
+
+    /// Returns an iterator of all [InputDevice] of a [InputDeviceKind].
+    pub fn of_kind(&self, kind : InputDeviceKind) -> impl Iterator<Item = &InputDevice> {
+        self.list.iter().filter(move |device| device.kind == kind)
+    }
+
+    /// Add or replace an [InputDevice].
+    /// 
+    /// Returns true if device was added, false if replaced.
+    pub(crate) fn add(&mut self, device : InputDevice) -> bool {
+        match self.list.iter().position(|d| d.id == device.id) {
+            Some(index) => {
+                self.list[index] = device;
+                false
+            },
+            None => {
+                self.list.push(device);
+                true
+            },
+        }
+    }
+
+    /// Remove an [InputDevice] from [InputDeviceId].
+    /// 
+    /// Returns true if device was removed, false if not found.
+    pub(crate) fn remove(&mut self, id : InputDeviceId) -> bool {
+        match self.list.iter().position(|d| d.id == id) {
+            Some(index) => {
+                self.list.remove(index);
+                true
+            },
+            None => false,
+        }
+    }
+}
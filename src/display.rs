@@ -25,6 +25,8 @@ SOFTWARE.
 
 //! Hardware display information.
 
+use std::time::Duration;
+
 /// Display unit tests
 #[cfg(test)]
 pub(crate) mod tests {
@@ -126,6 +128,9 @@ pub struct Display {
     /// Identifier of that display
     pub identifier : String,
 
+    /// Human readable description of that display, like the monitor make and model. Can be empty.
+    pub(crate) description : String,
+
     /// Physical size of display in millimeters
     pub size : DisplaySizeMM,
 
@@ -141,11 +146,38 @@ pub struct Display {
     /// Return true if primary display
     pub primary : bool,    
 
+    /// Scale factor applied to that display by the system. 1.0 if not scaled.
+    pub(crate) scale : f64,
+
     /// Display supported resolutions with refresh rates
     pub supported : Vec<DisplaySupportedResolution>,
 
 }
 
+impl Display {
+    /// Get the human readable description of that display, like the monitor make and model. Can be empty.
+    #[inline(always)]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Get the scale factor applied to that display by the system. 1.0 if not scaled.
+    #[inline(always)]
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    /// Get the time between two refreshes of the display from [Display::refresh_rate], like 16.67ms at 60hz.
+    /// 
+    /// Returns [Duration::ZERO] if the refresh rate is unknown.
+    pub fn refresh_interval(&self) -> Duration {
+        match self.refresh_rate {
+            0 => Duration::ZERO,
+            rate => Duration::from_nanos(100_000_000_000 / rate as u64),
+        }
+    }
+}
+
 /// Hardware display supported resolution with available refresh rate for that [DisplayResolution].
 /// 
 /// # Note(s)
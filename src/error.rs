@@ -23,6 +23,9 @@ SOFTWARE.
 */
 
 /// Errors that can happens within `nswindow`.
+/// 
+/// Variants have no explicit discriminant since some carry data, like the reasons of [WindowError::NoWindowManager].
+/// Match on variants instead of casting them to integers.
 #[derive(Debug, PartialEq, Clone)]
 pub enum WindowError {
 
@@ -31,14 +34,37 @@ pub enum WindowError {
     // **********
 
     /// Happens when using an invalid [WindowHandle](crate::WindowHandle).
-    InvalidWindowHandle = 1,
+    InvalidWindowHandle,
 
     /// Happens when no system window manager is available.
-    NoWindowManager,
+    /// 
+    /// Contains each [WindowBackend](crate::WindowBackend) tried with the error that rejected it.
+    NoWindowManager(Vec<(crate::WindowBackend, WindowError)>),
+
+    /// Happens when the `NSWINDOW_BACKEND` environment variable doesn't name a [WindowBackend](crate::WindowBackend).
+    WindowBackendInvalid(String),
 
     /// Happens when a system window manager is not supported
     WindowManagerNotSupported,
 
+    /// Happens when the environment variable locating the display server isn't set, like `WAYLAND_DISPLAY` or `DISPLAY`.
+    /// 
+    /// Contains the name of the variable.
+    WindowManagerDisplayUnset(String),
+
+    /// Happens when the connection with the display server couldn't be established.
+    /// 
+    /// Contains the display name or socket path tried.
+    WindowManagerConnectionFailed(String),
+
+    /// Happens when the display server lacks a protocol required to create windows, like `xdg_wm_base` on Wayland.
+    /// 
+    /// Contains the name of the protocol.
+    WindowManagerMissingProtocol(String),
+
+    /// Happens when the connection with the system window manager is lost or a protocol error occurred.
+    WindowManagerConnectionLost,
+
     /// Happens when a given [WindowRelativePosition](crate::WindowRelativePosition) is out of bound.
     WindowRelativePositionOOB,
 
@@ -62,6 +88,37 @@ pub enum WindowError {
     /// Happens when trying to set the [WindowPointer](crate::pointer::WindowPointer) when it is out of window bounds.
     WindowpointerOOB,
 
+    /// Happens when keyboard and pointer couldn't be grabbed by the [Window](crate::Window).
+    WindowInputGrabFailed,
+
+    /// Happens when the software framebuffer of the [Window](crate::Window) couldn't be allocated or shared with the system.
+    WindowFramebufferFailed,
+
+    /// Happens when Vulkan surfaces aren't supported by the [WindowManager](crate::WindowManager) backend.
+    WindowVulkanUnsupported,
+
+    /// Happens when the Vulkan surface of the [Window](crate::Window) couldn't be created. Contains the `VkResult`.
+    WindowVulkanSurfaceFailed(i32),
+
+    /// Happens when OpenGL contexts aren't supported by the [WindowManager](crate::WindowManager) backend or the OpenGL library is missing.
+    WindowOpenGLUnsupported,
+
+    /// Happens when the OpenGL context of the [Window](crate::Window) couldn't be created, made current or configured.
+    WindowOpenGLContextFailed,
+
+    /// Happens when the Cairo library is missing.
+    WindowCairoUnsupported,
+
+    /// Happens when the Cairo surface of the [Window](crate::Window) couldn't be created.
+    WindowCairoSurfaceFailed,
+
+    // ************
+    // * KEYBOARD *
+    // ************
+
+    /// Happens when a hotkey [KeyCombination](crate::keyboard::KeyCombination) is already registered by this or another application.
+    HotkeyUnavailable,
+
     // ***********
     // * DISPLAY *
     // ***********
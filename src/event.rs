@@ -25,12 +25,17 @@ SOFTWARE.
 
 //! Events polled by a [WindowManager](crate::WindowManager).
 
-use crate::{WindowHandle, WindowPosition, WindowSize};
+use std::time::{Duration, Instant};
+
+use crate::{device::InputDeviceId, keyboard::HotkeyId, WindowHandle, WindowPosition, WindowSize};
 
 /// [WindowEvent] with [WindowHandle] returned with [WindowManager::event()](crate::WindowManager::event()).
+#[derive(Debug, Clone, PartialEq)]
 pub struct WindowManagerEvent {
     
     /// [WindowHandle] of [Window](crate::Window) which [Event] refer to.
+    /// 
+    /// Is [WindowHandle::NULL] for events not related to a [Window](crate::Window) like [WindowEvent::DeviceAdded].
     pub window : WindowHandle,
 
     /// [WindowEvent] that occurred.
@@ -42,6 +47,18 @@ pub struct WindowManagerEvent {
 
 }
 
+impl WindowManagerEvent {
+    /// Create a new [WindowManagerEvent], timestamped if `event_ts` feature is enabled.
+    pub(crate) fn new(window : WindowHandle, event : WindowEvent) -> WindowManagerEvent {
+        WindowManagerEvent { 
+            window, 
+            event,
+            #[cfg(feature = "event_ts")]
+            timestamp : std::time::SystemTime::now(),
+        }
+    }
+}
+
 /// [Window](crate::Window) coordinate where the [WindowEvent] occurred.
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub struct WindowCoordinate {
@@ -49,8 +66,19 @@ pub struct WindowCoordinate {
     pub y : i32,
 }
 
+/// Timing of the frame to draw after [WindowEvent::RedrawRequested].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct WindowFrameTiming {
+    /// Time the frame is expected to be shown on the display.
+    pub target : Instant,
+
+    /// Time between two refreshes of the display the [Window](crate::Window) is on, from 
+    /// [Display::refresh_rate](crate::display::Display::refresh_rate). [Duration::ZERO] if unknown.
+    pub interval : Duration,
+}
 
 /// Possible [WindowEvent] that can occur.
+#[derive(Debug, Clone, PartialEq)]
 pub enum WindowEvent {
 
     /// Events that refer to keyboard presses
@@ -72,6 +100,10 @@ pub enum WindowEvent {
     /// Provides position (x, y) and size (width, height) of region exposed. 
     Exposed(WindowCoordinate, WindowSize),
 
+    /// Happens when the Window should draw its next frame, after [Window::request_redraw()](crate::Window::request_redraw()) 
+    /// or when exposed. Provides the [WindowFrameTiming] of the frame.
+    RedrawRequested(WindowFrameTiming),
+
     /// Happens when Window is moved. Provides (x,y) of new position.
     Moved(WindowPosition),
 
@@ -81,6 +113,10 @@ pub enum WindowEvent {
     /// Happens when Window is Resized. Provides (height, width) of new size.
     Resized(WindowSize),
 
+    /// Happens when the scale factor of Window changes, like when moved to a display with another scale. 
+    /// Provides the new scale factor, pixels drawn being the size multiplied by it.
+    ScaleChanged(f64),
+
     /// Happens when Window is minimized.
     /// 
     /// # Known issue(s)
@@ -108,6 +144,10 @@ pub enum WindowEvent {
     /// Happens when Window lose focus.
     Blur,
 
+    /// Happens when the input grab of [Window::grab_input()](crate::Window::grab_input()) was released 
+    /// by the system or the emergency key combination.
+    InputGrabReleased,
+
     /// Happens when a user press on a overriden close button.
     CloseButtonPressed,
 
@@ -129,13 +169,36 @@ pub enum WindowEvent {
     /// Happens when a Modal subwindow closed
     ModalClosed,
 
+    /// Happens when an input device is connected. Provides [InputDeviceId] of device.
+    /// 
+    /// Device details are available with [WindowManager::input_devices()](crate::WindowManager::input_devices()).
+    DeviceAdded(InputDeviceId),
+
+    /// Happens when an input device is disconnected. Provides [InputDeviceId] of device.
+    DeviceRemoved(InputDeviceId),
+
+    /// Happens when the active keyboard layout changed, like switching from `English (US)` to `French`.
+    /// 
+    /// New layout is available with [WindowManager::keyboard_layout()](crate::WindowManager::keyboard_layout()).
+    KeyboardLayoutChanged,
+
+    /// Happens when a system-wide hotkey is pressed, even if no [Window](crate::Window) has focus. Provides [HotkeyId] of hotkey.
+    HotkeyPressed(HotkeyId),
+
+    /// Happens when a system-wide hotkey is released. Provides [HotkeyId] of hotkey.
+    HotkeyReleased(HotkeyId),
+
+    /// Given by [WindowManager::event_wait()](crate::WindowManager::event_wait()) when no event will ever come, like when the 
+    /// connection with the system window manager is lost or with a headless manager without queued event.
+    NoEvent,
+
 }
 
 /// Keyboard keycode
 pub type KeyCode = u32;
 
 /// Possible [WindowKeyboardEvent] that can occur.
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum WindowKeyboardEvent {
     // Keyboard key down event of direct input mode. Provides keycode as u32.
     KeyDown(KeyCode),
@@ -143,6 +206,17 @@ pub enum WindowKeyboardEvent {
     // Keyboard key down event of direct input mode. Provides keycode as u32.
     KeyUp(KeyCode),
 
+    /// Text committed by the input method in [WindowKeyboardMode::Text](crate::keyboard::WindowKeyboardMode::Text).
+    Text(String),
+
+    /// Pre-edit text composed by the input method, replacing the previous one. Provides the cursor range in bytes, [None] if cursor is hidden.
+    /// 
+    /// An empty text removes the pre-edit.
+    Preedit(String, Option<(usize, usize)>),
+
+    /// Delete text around the cursor before inserting [WindowKeyboardEvent::Text]. Provides bytes to delete before and after the cursor.
+    DeleteSurrounding(usize, usize),
+
     // KeyPress happens provides [Key] struct
     //KeyPress(Key),
 }
@@ -157,7 +231,15 @@ pub struct PointerAcceleration {
     pub y : i32,
 }
 
+/// Pointer scroll as pair of x,y axis, in wheel steps. Positive toward right and bottom.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PointerScroll {
+    pub x : f64,
+    pub y : f64,
+}
+
 /// Possible [WindowPointerEvent] that can occur.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum WindowPointerEvent {
     /// Pointer move event. Provides new (x, y) position. Only when in pointer mode.
     Move(WindowCoordinate),
@@ -170,4 +252,7 @@ pub enum WindowPointerEvent {
 
     /// Pointer button up event. Provides button number and cursor position (x,y).
     ButtonUp(PointerButton, WindowCoordinate),
+
+    /// Pointer wheel or touchpad scroll event. Provides scroll delta (x, y).
+    Scroll(PointerScroll),
 }
\ No newline at end of file
@@ -77,7 +77,7 @@ pub struct WindowFrame {
 
     /// Linux [WindowFrame] abstraction for calls. Is set as [Option] since [WindowBuilder] can use it.
     #[cfg(target_os = "linux")]
-    frame : Option<crate::linux::frame::LinuxWindowFrame>,
+    pub(crate) frame : Option<crate::linux::frame::LinuxWindowFrame>,
 
     /// Is window frame visible
     pub(crate)  visible : bool,
@@ -109,6 +109,18 @@ impl WindowFrame {
             close_button: WF_DEFAULT_BUTTON 
         }
     }
+
+    /// Copy of the frame properties without the system frame. Used to create a [Window](crate::Window) from a [WindowBuilder](crate::WindowBuilder).
+    pub(crate) fn properties(&self) -> WindowFrame {
+        WindowFrame { 
+            frame: None, 
+            visible: self.visible, 
+            resizable: self.resizable, 
+            min_button: self.min_button, 
+            max_button: self.max_button, 
+            close_button: self.close_button 
+        }
+    }
     
     /// Returns true if the [WindowFrame] is visible around the [Window](crate::Window).
     pub fn visible(&self) -> bool {
@@ -33,6 +33,8 @@ pub(crate) mod tests {
 
 use nscfg::match_cfg;
 
+use crate::{event::{KeyCode, WindowCoordinate}, WindowSize};
+
 /// Default [WindowKeyboardMode].
 const WKB_DEFAULT_MODE : WindowKeyboardMode = WindowKeyboardMode::Direct;
 
@@ -45,7 +47,7 @@ pub struct WindowKeyboard {
 
     /// Linux [WindowKeyboard] abstraction for calls. Is set as [Option] since [WindowBuilder] can use it.
     #[cfg(target_os = "linux")]
-    keyboard : Option<crate::linux::keyboard::LinuxKeyboard>,
+    pub(crate) keyboard : Option<crate::linux::keyboard::LinuxKeyboard>,
 
     /// [KeyboardMode] of the keyboard. Use [KeyboardMode::DirectInput] by default.
     pub(crate) mode : WindowKeyboardMode,
@@ -53,12 +55,28 @@ pub struct WindowKeyboard {
     /// If enabled, keys are repeated when pressed down. Disabled by default.
     pub(crate) auto_repeat : bool,
 
+    /// Keys currently pressed down.
+    pub(crate) pressed : Vec<KeyCode>,
+
+    /// Modifiers currently pressed.
+    pub(crate) modifiers : KeyModifiers,
+
+    /// Lock keys states.
+    pub(crate) locks : KeyLocks,
+
 }
 
 impl WindowKeyboard {
     /// Create new instance of keyboard property with auto repeat to false.
     pub fn new() -> WindowKeyboard {
-        WindowKeyboard { keyboard : None,  mode : WKB_DEFAULT_MODE, auto_repeat : WKB_DEFAULT_REPEAT}
+        WindowKeyboard { keyboard : None,  mode : WKB_DEFAULT_MODE, auto_repeat : WKB_DEFAULT_REPEAT, 
+            pressed : Vec::new(), modifiers : KeyModifiers::default(), locks : KeyLocks::default() }
+    }
+
+    /// Copy of the keyboard properties without the system keyboard. Used to create a [Window](crate::Window) from a [WindowBuilder](crate::WindowBuilder).
+    pub(crate) fn properties(&self) -> WindowKeyboard {
+        WindowKeyboard { keyboard : None, mode : self.mode, auto_repeat : self.auto_repeat, 
+            pressed : Vec::new(), modifiers : KeyModifiers::default(), locks : KeyLocks::default() }
     }
 
     /// Returns the [WindowKeyboardMode] of the [Window](crate::Window).
@@ -127,6 +145,93 @@ impl WindowKeyboard {
 
 
 
+
+    /// Set the text around the cursor of the text being edited in [WindowKeyboardMode::Text], with `cursor` and `anchor` 
+    /// as byte offsets in `text`. Lets the input method refine its suggestions.
+    pub fn set_surrounding_text(&mut self, text : &str, cursor : usize, anchor : usize) {
+        match_cfg! {
+            linux => {
+                match self.keyboard {
+                    Some(ref mut wkb) => wkb.set_surrounding_text(text, cursor, anchor),
+                    None => {},
+                }
+            },
+            _ => {};
+        }
+    }
+
+    /// Set the area of the text cursor in [WindowKeyboardMode::Text], relative to the [Window](crate::Window). 
+    /// Lets the input method show its candidates next to the cursor.
+    pub fn set_text_cursor(&mut self, position : WindowCoordinate, size : WindowSize) {
+        match_cfg! {
+            linux => {
+                match self.keyboard {
+                    Some(ref mut wkb) => wkb.set_text_cursor(position, size),
+                    None => {},
+                }
+            },
+            _ => {};
+        }
+    }
+
+    /// Returns true if key is currently pressed down.
+    pub fn is_pressed(&self, key : KeyCode) -> bool {
+        self.pressed.contains(&key)
+    }
+
+    /// Returns the [KeyModifiers] currently pressed.
+    pub fn modifiers(&self) -> KeyModifiers {
+        self.modifiers
+    }
+
+    /// Returns the [KeyLocks] states like Caps Lock.
+    pub fn locks(&self) -> KeyLocks {
+        self.locks
+    }
+
+    /// Track a key pressed down.
+    pub(crate) fn key_down(&mut self, key : KeyCode) {
+        if !self.pressed.contains(&key) {
+            self.pressed.push(key);
+        }
+    }
+
+    /// Track a key released.
+    pub(crate) fn key_up(&mut self, key : KeyCode) {
+        self.pressed.retain(|k| *k != key);
+    }
+
+    /// Set the [KeyModifiers] and [KeyLocks] states given by the system with key events.
+    pub(crate) fn set_modifiers(&mut self, modifiers : KeyModifiers, locks : KeyLocks) {
+        self.modifiers = modifiers;
+        self.locks = locks;
+    }
+
+    /// Release all keys, modifiers included. Used when [Window](crate::Window) lose focus since 
+    /// keys released elsewhere aren't received.
+    pub(crate) fn clear(&mut self) {
+        self.pressed.clear();
+        self.modifiers = KeyModifiers::default();
+    }
+
+    /// Resynchronize keys, modifiers and locks with the system keyboard state. Used when [Window](crate::Window) gain focus
+    /// so that keys held while focus changed do not stay stuck.
+    pub(crate) fn resync(&mut self) {
+        match_cfg! {
+            linux => {
+                match self.keyboard {
+                    Some(ref wkb) => {
+                        let (pressed, modifiers, locks) = wkb.query_state();
+                        self.pressed = pressed;
+                        self.set_modifiers(modifiers, locks);
+                    },
+                    None => {},
+                }
+            },
+            _ => {};
+        }
+    }
+
 }
 
 
@@ -137,6 +242,90 @@ pub enum WindowKeyboardMode {
     /// and [EventKeyboard::KeyDown](super::event::keyboard::EventKeyboard).
     Direct,
 
-    /// Text mode is slower since it provides more information for text entry. Provides [EventKeyboard::KeyPress](super::event::keyboard::EventKeyboard).
+    /// Text mode is slower since it provides more information for text entry. Enables the input method which provides 
+    /// [WindowKeyboardEvent::Text](crate::event::WindowKeyboardEvent::Text) and [WindowKeyboardEvent::Preedit](crate::event::WindowKeyboardEvent::Preedit).
     Text,
-}
\ No newline at end of file
+}
+
+/// State of the keyboard modifiers keys.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyModifiers {
+    /// Shift key is pressed.
+    pub shift : bool,
+
+    /// Control key is pressed.
+    pub ctrl : bool,
+
+    /// Alt key is pressed.
+    pub alt : bool,
+
+    /// Meta key is pressed. Also known as Super or Windows key.
+    pub meta : bool,
+}
+
+impl KeyModifiers {
+    /// Create a new [KeyModifiers] from each modifier state.
+    pub fn new(shift : bool, ctrl : bool, alt : bool, meta : bool) -> KeyModifiers {
+        KeyModifiers { shift, ctrl, alt, meta }
+    }
+
+    /// Returns true if no modifier is pressed.
+    pub fn is_empty(&self) -> bool {
+        !(self.shift || self.ctrl || self.alt || self.meta)
+    }
+}
+
+/// State of the keyboard lock keys.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyLocks {
+    /// Caps Lock is active.
+    pub caps : bool,
+
+    /// Num Lock is active.
+    pub num : bool,
+
+    /// Scroll Lock is active.
+    pub scroll : bool,
+}
+
+/// Combination of a [KeyCode] with [KeyModifiers].
+/// 
+/// # Note(s)
+/// [KeyCode] is the physical key code given by the system. On linux, it is the xkb keycode (evdev code + 8).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyCombination {
+    /// Physical key of the combination.
+    pub key : KeyCode,
+
+    /// Modifiers that must be pressed with key.
+    pub modifiers : KeyModifiers,
+}
+
+impl KeyCombination {
+    /// Create a new [KeyCombination] from a [KeyCode] and [KeyModifiers].
+    pub fn new(key : KeyCode, modifiers : KeyModifiers) -> KeyCombination {
+        KeyCombination { key, modifiers }
+    }
+
+    /// Returns true if key and modifiers are exactly those of the combination.
+    pub fn matches(&self, key : KeyCode, modifiers : KeyModifiers) -> bool {
+        self.key == key && self.modifiers == modifiers
+    }
+}
+
+/// Identifier of a system-wide hotkey registered with [WindowManager::register_hotkey()](crate::WindowManager::register_hotkey()).
+/// 
+/// Identifiers start at 1 on every backend, 0 is never given.
+pub type HotkeyId = u32;
+
+/// Active keyboard layout of the system.
+/// 
+/// Linux xkb can have up to 4 layouts (groups) active at the same time, switchable by the user.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct KeyboardLayout {
+    /// Index of the active layout (xkb group).
+    pub group : u32,
+
+    /// Name of the active layout like `English (US)`. Empty if unknown.
+    pub name : String,
+}
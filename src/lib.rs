@@ -56,6 +56,7 @@ pub mod frame;
 pub mod keyboard;
 pub mod pointer; 
 pub mod display;
+pub mod device;
 pub mod event;
 
 /// Window builder
@@ -69,9 +70,33 @@ target_cfg! {
     }
 }
 
+target_cfg! {
+    cairo:ft => {
+        pub mod cairo;
+    }
+}
+
+target_cfg! {
+    vulkan:ft => {
+        pub mod vulkan;
+    }
+}
+
+target_cfg! {
+    opengl:ft => {
+        pub mod opengl;
+    }
+}
+
+/// raw-window-handle traits implementation. Not in target_cfg! since doc would build it without its dependency.
+#[cfg(feature = "raw_window_handle")]
+mod rwh;
+
 // Re-import
 pub use builder::WindowBuilder as WindowBuilder;
 pub use manager::WindowManager as WindowManager;
+pub use manager::WindowBackend as WindowBackend;
+pub use manager::WindowManagerBuilder as WindowManagerBuilder;
 pub use window::Window as Window;
 pub use window::WindowHandle as WindowHandle;
 pub use window::WindowFullScreenMode as WindowFullScreenMode;
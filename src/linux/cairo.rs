@@ -0,0 +1,238 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! Cairo surfaces of windows, loaded at runtime from libcairo.
+//! 
+//! Reference(s)
+//! <https://www.cairographics.org/manual/cairo-XLib-Surfaces.html>
+//! <https://www.cairographics.org/manual/cairo-Image-Surfaces.html>
+
+use std::ffi::{c_int, c_uchar, c_ulong, c_void};
+
+use crate::{cairo::CairoSurface, WindowError, WindowSize};
+
+/// `CAIRO_FORMAT_RGB24`, the XRGB8888 native endian pixels of software framebuffers.
+const CAIRO_FORMAT_RGB24 : c_int = 1;
+
+/// `CAIRO_STATUS_SUCCESS`
+const CAIRO_STATUS_SUCCESS : c_int = 0;
+
+/// Cairo functions of libcairo.
+#[derive(Debug)]
+struct Cairo {
+    xlib_surface_create : unsafe extern "C" fn(*mut c_void, c_ulong, *mut c_void, c_int, c_int) -> CairoSurface,
+    xlib_surface_set_size : unsafe extern "C" fn(CairoSurface, c_int, c_int),
+    image_surface_create_for_data : unsafe extern "C" fn(*mut c_uchar, c_int, c_int, c_int, c_int) -> CairoSurface,
+    format_stride_for_width : unsafe extern "C" fn(c_int, c_int) -> c_int,
+    surface_status : unsafe extern "C" fn(CairoSurface) -> c_int,
+    surface_flush : unsafe extern "C" fn(CairoSurface),
+    surface_destroy : unsafe extern "C" fn(CairoSurface),
+
+    /// Functions are valid while library is open.
+    _library : super::library::Library,
+}
+
+impl Cairo {
+    /// Load Cairo from libcairo. None if libcairo or one of its functions is missing.
+    fn load() -> Option<Cairo> {
+        let library = super::library::Library::open(&[c"libcairo.so.2", c"libcairo.so"])?;
+        unsafe {
+            Some(Cairo {
+                xlib_surface_create : library.symbol(c"cairo_xlib_surface_create")?,
+                xlib_surface_set_size : library.symbol(c"cairo_xlib_surface_set_size")?,
+                image_surface_create_for_data : library.symbol(c"cairo_image_surface_create_for_data")?,
+                format_stride_for_width : library.symbol(c"cairo_format_stride_for_width")?,
+                surface_status : library.symbol(c"cairo_surface_status")?,
+                surface_flush : library.symbol(c"cairo_surface_flush")?,
+                surface_destroy : library.symbol(c"cairo_surface_destroy")?,
+                _library : library,
+            })
+        }
+    }
+}
+
+/// Cairo surface of a window, created when first drawn and following the window size.
+#[derive(Debug)]
+pub(crate) struct LinuxCairoSurface {
+    /// libcairo, None until the first surface is created.
+    cairo : Option<Cairo>,
+
+    /// cairo_surface_t, null if not created.
+    surface : CairoSurface,
+
+    /// Size in pixels of surface
+    size : WindowSize,
+
+    /// Pixels drawn by image surfaces, null for xlib surfaces.
+    pixels : *mut u32,
+}
+
+impl PartialEq for LinuxCairoSurface {
+    fn eq(&self, other: &Self) -> bool {
+        self.surface == other.surface
+    }
+}
+
+impl Drop for LinuxCairoSurface {
+    fn drop(&mut self) {
+        self.destroy();
+    }
+}
+
+impl LinuxCairoSurface {
+
+    /// Create an empty surface. libcairo is loaded when first drawn.
+    pub(crate) fn new() -> LinuxCairoSurface {
+        LinuxCairoSurface { cairo : None, surface : std::ptr::null_mut(), size : WindowSize::new(0, 0), pixels : std::ptr::null_mut() }
+    }
+
+    /// Returns the cairo-xlib surface of X11 window with the visual given by `visual` when created, resized to `size` if it changed.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowCairoUnsupported`]) if libcairo is missing and Err([`WindowError::WindowCairoSurfaceFailed`]) 
+    /// if the surface couldn't be created.
+    pub(crate) fn xlib(&mut self, display : *mut c_void, window : c_ulong, visual : impl FnOnce() -> Option<*mut c_void>, 
+        size : WindowSize) -> Result<CairoSurface, WindowError> {
+        let cairo = Self::load(&mut self.cairo)?;
+
+        unsafe {
+            if self.surface.is_null() {
+                let visual = visual().ok_or(WindowError::WindowCairoSurfaceFailed)?;
+                self.surface = (cairo.xlib_surface_create)(display, window, visual, size.width as c_int, size.height as c_int);
+                self.size = size;
+                self.check()?;
+            } else if size != self.size {
+                (cairo.xlib_surface_set_size)(self.surface, size.width as c_int, size.height as c_int);
+                self.size = size;
+            }
+        }
+        Ok(self.surface)
+    }
+
+    /// Returns an image surface drawing in `pixels` of `size`, recreated if pixels or size changed.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowCairoUnsupported`]) if libcairo is missing and Err([`WindowError::WindowCairoSurfaceFailed`]) 
+    /// if the surface couldn't be created.
+    pub(crate) fn image(&mut self, pixels : &mut [u32], size : WindowSize) -> Result<CairoSurface, WindowError> {
+        if !self.surface.is_null() && self.pixels == pixels.as_mut_ptr() && self.size == size {
+            return Ok(self.surface);
+        }
+        self.destroy();
+        let cairo = Self::load(&mut self.cairo)?;
+
+        unsafe {
+            // Framebuffer rows are packed, cairo must agree on the stride.
+            let stride = size.width as c_int * 4;
+            if (cairo.format_stride_for_width)(CAIRO_FORMAT_RGB24, size.width as c_int) != stride || pixels.len() < size.width as usize * size.height as usize {
+                return Err(WindowError::WindowCairoSurfaceFailed);
+            }
+
+            self.surface = (cairo.image_surface_create_for_data)(pixels.as_mut_ptr() as *mut c_uchar, CAIRO_FORMAT_RGB24, 
+                size.width as c_int, size.height as c_int, stride);
+        }
+        self.pixels = pixels.as_mut_ptr();
+        self.size = size;
+        self.check()?;
+        Ok(self.surface)
+    }
+
+    /// Finish pending drawing of the surface.
+    pub(crate) fn flush(&self) {
+        if let (Some(cairo), false) = (&self.cairo, self.surface.is_null()) {
+            unsafe { (cairo.surface_flush)(self.surface) };
+        }
+    }
+
+    /// Destroy the surface. Drawing with it afterward is undefined.
+    pub(crate) fn destroy(&mut self) {
+        if let (Some(cairo), false) = (&self.cairo, self.surface.is_null()) {
+            unsafe { (cairo.surface_destroy)(self.surface) };
+        }
+        self.surface = std::ptr::null_mut();
+        self.pixels = std::ptr::null_mut();
+    }
+
+    /// Load libcairo if not loaded.
+    fn load(cairo : &mut Option<Cairo>) -> Result<&Cairo, WindowError> {
+        if cairo.is_none() {
+            *cairo = Some(Cairo::load().ok_or(WindowError::WindowCairoUnsupported)?);
+        }
+        Ok(cairo.as_ref().unwrap())
+    }
+
+    /// Destroy surface if it is in error. Cairo returns a nil surface in error instead of null.
+    fn check(&mut self) -> Result<(), WindowError> {
+        let status = match &self.cairo {
+            Some(cairo) if !self.surface.is_null() => unsafe { (cairo.surface_status)(self.surface) },
+            _ => return Err(WindowError::WindowCairoSurfaceFailed),
+        };
+
+        if status == CAIRO_STATUS_SUCCESS {
+            Ok(())
+        } else {
+            self.destroy();
+            Err(WindowError::WindowCairoSurfaceFailed)
+        }
+    }
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Cairo surface tests
+#[cfg(test)]
+mod tests {
+    use crate::{WindowError, WindowSize};
+
+    use super::LinuxCairoSurface;
+
+    #[test]
+    fn cairo_image_surface() {
+        let mut surface = LinuxCairoSurface::new();
+        let mut pixels = vec![0u32; 16 * 8];
+
+        let image = match surface.image(&mut pixels, WindowSize::new(16, 8)) {
+            // libcairo isn't installed.
+            Err(WindowError::WindowCairoUnsupported) => return,
+            result => result.unwrap(),
+        };
+        assert!(!image.is_null());
+
+        // Same pixels and size keep the surface.
+        assert_eq!(surface.image(&mut pixels, WindowSize::new(16, 8)), Ok(image));
+
+        // Resized pixels recreate it.
+        let mut pixels = vec![0u32; 32 * 8];
+        assert!(surface.image(&mut pixels, WindowSize::new(32, 8)).is_ok());
+        assert_eq!(surface.size, WindowSize::new(32, 8));
+        assert_eq!(surface.pixels, pixels.as_mut_ptr());
+
+        // Pixels missing for size
+        assert_eq!(surface.image(&mut pixels, WindowSize::new(32, 16)), Err(WindowError::WindowCairoSurfaceFailed));
+        assert!(surface.surface.is_null());
+    }
+}
@@ -0,0 +1,330 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! EGL contexts drawing on native windows of X11 or Wayland, loaded at runtime from libEGL.
+//! 
+//! Reference(s)
+//! <https://registry.khronos.org/EGL/sdk/docs/man/html/eglIntro.xhtml>
+//! <https://registry.khronos.org/EGL/extensions/EXT/EGL_EXT_platform_base.txt>
+
+use std::{ffi::{c_char, c_uint, c_void, CStr}, rc::Rc};
+
+use crate::{linux::library::Library, opengl::{GLConfig, GLProfile}, WindowError};
+
+pub(crate) type EGLDisplay = *mut c_void;
+pub(crate) type EGLConfig = *mut c_void;
+pub(crate) type EGLContext = *mut c_void;
+pub(crate) type EGLSurface = *mut c_void;
+
+/// `EGLNativeWindowType`, a X11 window id or a wl_egl_window pointer.
+pub(crate) type EGLNativeWindow = usize;
+
+// EGL attributes
+const EGL_NONE : i32 = 0x3038;
+const EGL_TRUE : i32 = 1;
+const EGL_ALPHA_SIZE : i32 = 0x3021;
+const EGL_BLUE_SIZE : i32 = 0x3022;
+const EGL_GREEN_SIZE : i32 = 0x3023;
+const EGL_RED_SIZE : i32 = 0x3024;
+const EGL_DEPTH_SIZE : i32 = 0x3025;
+const EGL_STENCIL_SIZE : i32 = 0x3026;
+const EGL_NATIVE_VISUAL_ID : i32 = 0x302E;
+const EGL_SAMPLES : i32 = 0x3031;
+const EGL_SAMPLE_BUFFERS : i32 = 0x3032;
+const EGL_SURFACE_TYPE : i32 = 0x3033;
+const EGL_RENDERABLE_TYPE : i32 = 0x3040;
+const EGL_EXTENSIONS : i32 = 0x3055;
+const EGL_WINDOW_BIT : i32 = 0x4;
+const EGL_OPENGL_ES_BIT : i32 = 0x1;
+const EGL_OPENGL_ES2_BIT : i32 = 0x4;
+const EGL_OPENGL_BIT : i32 = 0x8;
+const EGL_OPENGL_ES3_BIT : i32 = 0x40;
+const EGL_OPENGL_ES_API : c_uint = 0x30A0;
+const EGL_OPENGL_API : c_uint = 0x30A2;
+const EGL_CONTEXT_MAJOR_VERSION : i32 = 0x3098;
+const EGL_CONTEXT_MINOR_VERSION : i32 = 0x30FB;
+const EGL_CONTEXT_OPENGL_PROFILE_MASK : i32 = 0x30FD;
+const EGL_CONTEXT_OPENGL_CORE_PROFILE_BIT : i32 = 0x1;
+const EGL_CONTEXT_OPENGL_COMPATIBILITY_PROFILE_BIT : i32 = 0x2;
+const EGL_CONTEXT_OPENGL_DEBUG : i32 = 0x31B0;
+
+// EGL platforms
+pub(crate) const EGL_PLATFORM_X11_KHR : c_uint = 0x31D5;
+pub(crate) const EGL_PLATFORM_WAYLAND_KHR : c_uint = 0x31D8;
+
+/// `eglGetPlatformDisplayEXT`
+type EglGetPlatformDisplay = unsafe extern "C" fn(c_uint, *mut c_void, *const i32) -> EGLDisplay;
+
+/// EGL functions of libEGL.
+#[derive(Debug)]
+pub(crate) struct Egl {
+    get_display : unsafe extern "C" fn(*mut c_void) -> EGLDisplay,
+    initialize : unsafe extern "C" fn(EGLDisplay, *mut i32, *mut i32) -> c_uint,
+    query_string : unsafe extern "C" fn(EGLDisplay, i32) -> *const c_char,
+    bind_api : unsafe extern "C" fn(c_uint) -> c_uint,
+    choose_config : unsafe extern "C" fn(EGLDisplay, *const i32, *mut EGLConfig, i32, *mut i32) -> c_uint,
+    get_config_attrib : unsafe extern "C" fn(EGLDisplay, EGLConfig, i32, *mut i32) -> c_uint,
+    create_context : unsafe extern "C" fn(EGLDisplay, EGLConfig, EGLContext, *const i32) -> EGLContext,
+    create_window_surface : unsafe extern "C" fn(EGLDisplay, EGLConfig, EGLNativeWindow, *const i32) -> EGLSurface,
+    make_current : unsafe extern "C" fn(EGLDisplay, EGLSurface, EGLSurface, EGLContext) -> c_uint,
+    get_current_context : unsafe extern "C" fn() -> EGLContext,
+    swap_buffers : unsafe extern "C" fn(EGLDisplay, EGLSurface) -> c_uint,
+    swap_interval : unsafe extern "C" fn(EGLDisplay, i32) -> c_uint,
+    destroy_surface : unsafe extern "C" fn(EGLDisplay, EGLSurface) -> c_uint,
+    destroy_context : unsafe extern "C" fn(EGLDisplay, EGLContext) -> c_uint,
+    get_proc_address : unsafe extern "C" fn(*const c_char) -> *const c_void,
+
+    /// Functions are valid while library is open.
+    _library : Library,
+}
+
+impl Egl {
+    /// Load EGL from libEGL. None if libEGL or one of its functions is missing.
+    pub(crate) fn load() -> Option<Egl> {
+        let library = Library::open(&[c"libEGL.so.1", c"libEGL.so"])?;
+        unsafe {
+            Some(Egl {
+                get_display : library.symbol(c"eglGetDisplay")?,
+                initialize : library.symbol(c"eglInitialize")?,
+                query_string : library.symbol(c"eglQueryString")?,
+                bind_api : library.symbol(c"eglBindAPI")?,
+                choose_config : library.symbol(c"eglChooseConfig")?,
+                get_config_attrib : library.symbol(c"eglGetConfigAttrib")?,
+                create_context : library.symbol(c"eglCreateContext")?,
+                create_window_surface : library.symbol(c"eglCreateWindowSurface")?,
+                make_current : library.symbol(c"eglMakeCurrent")?,
+                get_current_context : library.symbol(c"eglGetCurrentContext")?,
+                swap_buffers : library.symbol(c"eglSwapBuffers")?,
+                swap_interval : library.symbol(c"eglSwapInterval")?,
+                destroy_surface : library.symbol(c"eglDestroySurface")?,
+                destroy_context : library.symbol(c"eglDestroyContext")?,
+                get_proc_address : library.symbol(c"eglGetProcAddress")?,
+                _library : library,
+            })
+        }
+    }
+
+    /// Get the EGL display of the native `display` of `platform` when the client `extension` like EGL_EXT_platform_x11 
+    /// is supported, or eglGetDisplay if the platform extension is missing.
+    pub(crate) unsafe fn platform_display(&self, platform : c_uint, extension : &str, display : *mut c_void) -> EGLDisplay {
+        // EGL_EXTENSIONS of EGL_NO_DISPLAY are client extensions.
+        let extensions = (self.query_string)(std::ptr::null_mut(), EGL_EXTENSIONS);
+        let function = (self.get_proc_address)(c"eglGetPlatformDisplayEXT".as_ptr());
+
+        if !extensions.is_null() && !function.is_null() && has_extension(CStr::from_ptr(extensions), extension) {
+            let get_platform_display = std::mem::transmute::<*const c_void, EglGetPlatformDisplay>(function);
+            get_platform_display(platform, display, std::ptr::null())
+        } else {
+            (self.get_display)(display)
+        }
+    }
+
+    /// Bind the rendering api of the [GLProfile] to the calling thread.
+    unsafe fn bind(&self, profile : GLProfile) -> bool {
+        (self.bind_api)(match profile {
+            GLProfile::Es => EGL_OPENGL_ES_API,
+            _ => EGL_OPENGL_API,
+        }) != 0
+    }
+}
+
+/// EGL context drawing on a window surface, destroyed when dropped.
+#[derive(Debug)]
+pub(crate) struct EglContext {
+    /// EGL library, shared with contexts sharing objects.
+    pub(crate) egl : Rc<Egl>,
+
+    /// Initialized EGL display
+    display : EGLDisplay,
+
+    /// EGL context
+    context : EGLContext,
+
+    /// Window surface drawn on
+    surface : EGLSurface,
+
+    /// Config with the sizes of the chosen framebuffer
+    config : GLConfig,
+}
+
+impl Drop for EglContext {
+    fn drop(&mut self) {
+        unsafe {
+            if (self.egl.get_current_context)() == self.context {
+                (self.egl.make_current)(self.display, std::ptr::null_mut(), std::ptr::null_mut(), std::ptr::null_mut());
+            }
+            (self.egl.destroy_surface)(self.display, self.surface);
+            (self.egl.destroy_context)(self.display, self.context);
+        }
+    }
+}
+
+impl EglContext {
+    /// Create a context of `config` and a surface on the native `window` of `display`, given by [Egl::platform_display]. 
+    /// Configs of the native `visual` are preferred when given. Objects are shared with `shared` if given.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowOpenGLUnsupported`]) if display can't be initialized or the api bound and 
+    /// Err([`WindowError::WindowOpenGLContextFailed`]) if no framebuffer matches config or creation failed.
+    pub(crate) unsafe fn new(egl : Rc<Egl>, display : EGLDisplay, window : EGLNativeWindow, visual : Option<i32>, config : &GLConfig, 
+        shared : Option<&EglContext>) -> Result<EglContext, WindowError> {
+        let (mut major, mut minor) = (0, 0);
+        if display.is_null() || (egl.initialize)(display, &mut major, &mut minor) == 0 || !egl.bind(config.profile) {
+            return Err(WindowError::WindowOpenGLUnsupported);
+        }
+
+        let mut configs : [EGLConfig; 64] = [std::ptr::null_mut(); 64];
+        let mut count = 0;
+        if (egl.choose_config)(display, egl_config_attributes(config).as_ptr(), configs.as_mut_ptr(), configs.len() as i32, &mut count) == 0 || count == 0 {
+            return Err(WindowError::WindowOpenGLContextFailed);
+        }
+        let configs = &configs[..count as usize];
+        let egl_config = configs.iter().copied().find(|egl_config| {
+            let mut id = 0;
+            (egl.get_config_attrib)(display, *egl_config, EGL_NATIVE_VISUAL_ID, &mut id);
+            Some(id) == visual
+        }).unwrap_or(configs[0]);
+
+        let share = shared.map_or(std::ptr::null_mut(), |shared| shared.context);
+        let context = (egl.create_context)(display, egl_config, share, egl_context_attributes(config).as_ptr());
+        if context.is_null() {
+            return Err(WindowError::WindowOpenGLContextFailed);
+        }
+        let surface = (egl.create_window_surface)(display, egl_config, window, std::ptr::null());
+        if surface.is_null() {
+            (egl.destroy_context)(display, context);
+            return Err(WindowError::WindowOpenGLContextFailed);
+        }
+
+        let attrib = |attribute| {
+            let mut value = 0;
+            (egl.get_config_attrib)(display, egl_config, attribute, &mut value);
+            value as u8
+        };
+        let config = GLConfig { red : attrib(EGL_RED_SIZE), green : attrib(EGL_GREEN_SIZE), blue : attrib(EGL_BLUE_SIZE), 
+            alpha : attrib(EGL_ALPHA_SIZE), depth : attrib(EGL_DEPTH_SIZE), stencil : attrib(EGL_STENCIL_SIZE), 
+            samples : attrib(EGL_SAMPLES), ..*config };
+
+        Ok(EglContext { egl, display, context, surface, config })
+    }
+
+    /// Make the context current on the calling thread, binding its api first.
+    pub(crate) fn make_current(&self) -> bool {
+        unsafe { self.egl.bind(self.config.profile) && (self.egl.make_current)(self.display, self.surface, self.surface, self.context) != 0 }
+    }
+
+    /// Swap buffers of the window surface with eglSwapBuffers.
+    pub(crate) fn swap_buffers(&self) -> bool {
+        unsafe { (self.egl.swap_buffers)(self.display, self.surface) != 0 }
+    }
+
+    /// Set swap interval of the current context with eglSwapInterval.
+    pub(crate) fn set_swap_interval(&self, interval : i32) -> bool {
+        unsafe { (self.egl.swap_interval)(self.display, interval) != 0 }
+    }
+
+    /// Get an OpenGL function with eglGetProcAddress.
+    pub(crate) fn get_proc_address(&self, name : &CStr) -> *const c_void {
+        unsafe { (self.egl.get_proc_address)(name.as_ptr()) }
+    }
+
+    /// Config with the sizes of the chosen framebuffer.
+    #[inline(always)]
+    pub(crate) fn config(&self) -> &GLConfig {
+        &self.config
+    }
+}
+
+/// Returns true if the space separated `extensions` contains `name`.
+pub(crate) fn has_extension(extensions : &CStr, name : &str) -> bool {
+    extensions.to_string_lossy().split_whitespace().any(|extension| extension == name)
+}
+
+/// eglChooseConfig attributes of a window renderable by the config api with config sizes.
+fn egl_config_attributes(config : &GLConfig) -> Vec<i32> {
+    let renderable = match (config.profile, config.major) {
+        (GLProfile::Es, 0..=1) => EGL_OPENGL_ES_BIT,
+        (GLProfile::Es, 2) => EGL_OPENGL_ES2_BIT,
+        (GLProfile::Es, _) => EGL_OPENGL_ES3_BIT,
+        _ => EGL_OPENGL_BIT,
+    };
+
+    let mut attributes = vec![EGL_SURFACE_TYPE, EGL_WINDOW_BIT, EGL_RENDERABLE_TYPE, renderable, 
+        EGL_RED_SIZE, config.red as i32, EGL_GREEN_SIZE, config.green as i32, EGL_BLUE_SIZE, config.blue as i32, 
+        EGL_ALPHA_SIZE, config.alpha as i32, EGL_DEPTH_SIZE, config.depth as i32, EGL_STENCIL_SIZE, config.stencil as i32];
+
+    if config.samples > 0 {
+        attributes.extend([EGL_SAMPLE_BUFFERS, 1, EGL_SAMPLES, config.samples as i32]);
+    }
+    attributes.push(EGL_NONE);
+    attributes
+}
+
+/// eglCreateContext attributes of config version, profile and debug flag. Profiles only exist for desktop OpenGL.
+fn egl_context_attributes(config : &GLConfig) -> Vec<i32> {
+    let mut attributes = vec![EGL_CONTEXT_MAJOR_VERSION, config.major as i32, EGL_CONTEXT_MINOR_VERSION, config.minor as i32];
+
+    match config.profile {
+        GLProfile::Core => attributes.extend([EGL_CONTEXT_OPENGL_PROFILE_MASK, EGL_CONTEXT_OPENGL_CORE_PROFILE_BIT]),
+        GLProfile::Compatibility => attributes.extend([EGL_CONTEXT_OPENGL_PROFILE_MASK, EGL_CONTEXT_OPENGL_COMPATIBILITY_PROFILE_BIT]),
+        GLProfile::Es => {},
+    }
+    if config.debug {
+        attributes.extend([EGL_CONTEXT_OPENGL_DEBUG, EGL_TRUE]);
+    }
+    attributes.push(EGL_NONE);
+    attributes
+}
+
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// EGL context tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn egl_attributes() {
+        let config = GLConfig::default();
+        assert_eq!(egl_config_attributes(&config), vec![EGL_SURFACE_TYPE, EGL_WINDOW_BIT, EGL_RENDERABLE_TYPE, EGL_OPENGL_BIT, 
+            EGL_RED_SIZE, 8, EGL_GREEN_SIZE, 8, EGL_BLUE_SIZE, 8, EGL_ALPHA_SIZE, 0, EGL_DEPTH_SIZE, 24, EGL_STENCIL_SIZE, 8, EGL_NONE]);
+        assert_eq!(egl_context_attributes(&config), vec![EGL_CONTEXT_MAJOR_VERSION, 3, EGL_CONTEXT_MINOR_VERSION, 3, 
+            EGL_CONTEXT_OPENGL_PROFILE_MASK, EGL_CONTEXT_OPENGL_CORE_PROFILE_BIT, EGL_NONE]);
+
+        // MSAA and debug
+        let config = GLConfig { samples : 8, debug : true, ..GLConfig::new(4, 5, GLProfile::Compatibility) };
+        assert!(egl_config_attributes(&config).ends_with(&[EGL_SAMPLE_BUFFERS, 1, EGL_SAMPLES, 8, EGL_NONE]));
+        assert_eq!(egl_context_attributes(&config), vec![EGL_CONTEXT_MAJOR_VERSION, 4, EGL_CONTEXT_MINOR_VERSION, 5, 
+            EGL_CONTEXT_OPENGL_PROFILE_MASK, EGL_CONTEXT_OPENGL_COMPATIBILITY_PROFILE_BIT, EGL_CONTEXT_OPENGL_DEBUG, EGL_TRUE, EGL_NONE]);
+
+        // OpenGL ES has no profile, renderable type follows version.
+        assert_eq!(egl_config_attributes(&GLConfig::new(2, 0, GLProfile::Es))[3], EGL_OPENGL_ES2_BIT);
+        assert_eq!(egl_config_attributes(&GLConfig::new(3, 2, GLProfile::Es))[3], EGL_OPENGL_ES3_BIT);
+        assert_eq!(egl_context_attributes(&GLConfig::new(3, 2, GLProfile::Es)), vec![EGL_CONTEXT_MAJOR_VERSION, 3, EGL_CONTEXT_MINOR_VERSION, 2, EGL_NONE]);
+    }
+}
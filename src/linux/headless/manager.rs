@@ -0,0 +1,463 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{device::InputDevices, display::Displays, event::{KeyCode, WindowEvent, WindowManagerEvent}, keyboard::{HotkeyId, KeyCombination, KeyModifiers, KeyboardLayout}, 
+    linux::window::LinuxWindow, Window, WindowBuilder, WindowError, WindowHandle};
+
+use super::{headless_complete, window::HeadlessWindow, HeadlessServer};
+
+/// Window manager without display server. Windows live in memory on synthetic [Displays] and have no input device.
+pub struct HeadlessWindowManager {
+
+    /// In-memory server shared with windows
+    server : Rc<RefCell<HeadlessServer>>,
+
+    /// Last [WindowManagerEvent] fetched
+    event : Option<WindowManagerEvent>,
+
+    /// Synthetic displays
+    displays : Rc<Displays>,
+
+    /// [Window] collection of this manager.
+    windows : Vec<Window>,
+
+    /// Input devices, always empty.
+    devices : InputDevices,
+
+    /// Keyboard layout, always the default one.
+    layout : KeyboardLayout,
+
+    /// Hotkeys registered, never pressed.
+    hotkeys : Vec<(HotkeyId, KeyCombination)>,
+
+    /// Id of the next hotkey registered
+    hotkey_next : HotkeyId,
+}
+
+impl HeadlessWindowManager {
+
+    /// Create a manager showing windows on synthetic `displays`.
+    pub fn new(displays : Displays) -> HeadlessWindowManager {
+        HeadlessWindowManager { 
+            server : Rc::new(RefCell::new(HeadlessServer::default())), 
+            event : None, 
+            displays : Rc::new(headless_complete(displays)), 
+            windows : Vec::new(), 
+            devices : InputDevices::create(Vec::new()), 
+            layout : KeyboardLayout::default(), 
+            hotkeys : Vec::new(), 
+            hotkey_next : 1,
+        }
+    }
+
+    #[inline(always)]
+    pub fn event(&mut self) -> Option<&WindowManagerEvent> {
+        self.next_event();
+        self.event.as_ref()
+    }
+
+    /// Events only come from window requests, queued right away. Returns [WindowEvent::NoEvent] if no event is queued 
+    /// since none will ever come.
+    #[inline(always)]
+    pub fn event_wait(&mut self) -> &WindowManagerEvent {
+        self.next_event();
+        self.event.get_or_insert(WindowManagerEvent::new(WindowHandle::NULL, WindowEvent::NoEvent))
+    }
+
+    /// Pop the next event from queue and update the [Window] it refers to.
+    fn next_event(&mut self) {
+        let event = self.server.borrow_mut().queue.pop_front();
+
+        if let Some(event) = &event {
+            if let Some(index) = self.windows.iter().position(|w| w.handle == event.window) {
+                if self.windows[index].handle_event(&event.event) {
+                    self.server.borrow_mut().queue.push_back(WindowManagerEvent::new(event.window, WindowEvent::InputGrabReleased));
+                }
+
+                // Closed windows are removed from collection with their handle.
+                if event.event == WindowEvent::Closed {
+                    self.windows.remove(index);
+                    self.server.borrow_mut().handles.remove(event.window);
+                }
+            }
+        }
+
+        self.event = event;
+    }
+
+    /// Create an in-memory [Window] from builder properties, rejecting the ones a window manager would refuse.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowMinSizeBiggerThanMax`]) if min size is bigger than max size.
+    /// Returns Err([`WindowError::WindowSizeOOB`]) if size isn't between min and max size.
+    /// Returns Err([`WindowError::InvalidWindowHandle`]) if parent doesn't exist.
+    /// Returns Err([`WindowError::WindowRelativePositionOOB`]) or Err([`WindowError::DisplayInvalidHandle`]) if position is invalid.
+    pub(crate) fn build(&mut self, builder : &WindowBuilder) -> Result<WindowHandle, WindowError> {
+
+        if builder.min_size > builder.max_size {
+            return Err(WindowError::WindowMinSizeBiggerThanMax);
+        }
+        if builder.size > builder.max_size || builder.size < builder.min_size {
+            return Err(WindowError::WindowSizeOOB);
+        }
+
+        #[cfg(not(feature = "single_opt"))]
+        if let Some(parent) = builder.parent {
+            if !self.windows.iter().any(|w| w.handle == parent) {
+                return Err(WindowError::InvalidWindowHandle);
+            }
+        }
+
+        let lw = HeadlessWindow::new(self.server.clone(), self.displays.clone(), builder.size, builder.min_size, builder.max_size);
+        let handle = lw.handle;
+        let mut window = Window::new(builder, LinuxWindow::Headless(lw), self.displays.clone(), handle);
+
+        // Window refused is forgotten by the server before any event is sent.
+        if let Err(err) = window.set_position(builder.position.clone()) {
+            let mut server = self.server.borrow_mut();
+            server.windows.retain(|g| g.handle != handle);
+            server.handles.remove(handle);
+            return Err(err);
+        }
+
+        if builder.maximized {
+            window.window.maximize();
+        }
+        if let Some(fsmode) = &builder.fsmode {
+            window.window.set_fullscreen(fsmode.clone());
+        }
+        if builder.visible {
+            window.window.show();
+        }
+        if builder.minimized {
+            window.window.minimize();
+        }
+
+        self.windows.push(window);
+        Ok(handle)
+    }
+
+    #[inline(always)]
+    pub fn window(&self, window : WindowHandle) -> Result<&Window, WindowError> {
+        match self.windows.iter().find(|w| w.handle == window) {
+            Some(window) => Ok(window),
+            None => Err(WindowError::InvalidWindowHandle),
+        }
+    } 
+
+    #[inline(always)]
+    pub fn window_mut(&mut self, window : WindowHandle) -> Result<&mut Window, WindowError> {
+        match self.windows.iter_mut().find(|w| w.handle == window) {
+            Some(window) => Ok(window),
+            None => Err(WindowError::InvalidWindowHandle),
+        }
+    }
+
+    #[inline(always)]
+    pub fn displays(&self) -> &Displays {
+        &self.displays
+    }
+
+    /// Headless manager has no display connection.
+    #[cfg(feature = "raw_window_handle")]
+    #[inline(always)]
+    pub(crate) fn raw_display_handle(&self) -> Result<raw_window_handle::RawDisplayHandle, raw_window_handle::HandleError> {
+        Err(raw_window_handle::HandleError::NotSupported)
+    }
+
+    /// Headless windows have no surface to present Vulkan images on.
+    #[cfg(feature = "vulkan")]
+    #[inline(always)]
+    pub(crate) fn vulkan_required_extensions(&self) -> Result<&'static [&'static std::ffi::CStr], WindowError> {
+        Err(WindowError::WindowVulkanUnsupported)
+    }
+
+    #[inline(always)]
+    pub fn input_devices(&self) -> &InputDevices {
+        &self.devices
+    }
+
+    #[inline(always)]
+    pub fn keyboard_layout(&self) -> &KeyboardLayout {
+        &self.layout
+    }
+
+    /// No keymap is loaded without display server.
+    #[inline(always)]
+    pub fn key_label(&self, _key : KeyCode) -> Option<String> {
+        None
+    }
+
+    #[inline(always)]
+    pub fn register_hotkey(&mut self, key : KeyCode, modifiers : KeyModifiers) -> Result<HotkeyId, WindowError> {
+        let keys = KeyCombination::new(key, modifiers);
+
+        if self.hotkeys.iter().any(|(_, hk)| *hk == keys) {
+            Err(WindowError::HotkeyUnavailable)
+        } else {
+            let id = self.hotkey_next;
+            self.hotkey_next += 1;
+            self.hotkeys.push((id, keys));
+            Ok(id)
+        }
+    }
+
+    #[inline(always)]
+    pub fn unregister_hotkey(&mut self, hotkey : HotkeyId) -> bool {
+        match self.hotkeys.iter().position(|(id, _)| *id == hotkey) {
+            Some(index) => {
+                self.hotkeys.remove(index);
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Headless window manager tests
+#[cfg(test)]
+mod tests {
+    use crate::{display::{DisplayDesktopPosition, Displays}, event::WindowEvent, keyboard::KeyModifiers, WindowBuilder, WindowError, WindowFullScreenMode, 
+        WindowPosition, WindowRelativePosition, WindowSize};
+
+    use super::{super::headless_displays, HeadlessWindowManager};
+
+    /// Returns all events fetched.
+    fn events(wm : &mut HeadlessWindowManager) -> Vec<WindowEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = wm.event() {
+            events.push(event.event.clone());
+        }
+        events
+    }
+
+    /// Two 1920x1080 displays side by side, the second one scaled twice.
+    fn dual_displays() -> Displays {
+        let mut list = headless_displays().list;
+        let mut second = list[0].clone();
+        second.position = DisplayDesktopPosition { x : 1920, y : 0 };
+        second.primary = false;
+        second.scale = 2.0;
+        list.push(second);
+        Displays { list, desktop : None }
+    }
+
+    #[test]
+    fn headless_manager_build() {
+        let mut wm = HeadlessWindowManager::new(headless_displays());
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(800, 600))).unwrap();
+
+        // Window is centered on primary display, shown, focused then asked for its first frame.
+        assert!(matches!(events(&mut wm)[..], [WindowEvent::Moved(WindowPosition { x : 560, y : 240 }), WindowEvent::Shown, WindowEvent::Focus, 
+            WindowEvent::RedrawRequested(_)]));
+
+        let window = wm.window_mut(handle).unwrap();
+        assert!(window.visible());
+        assert_eq!(window.position(), WindowPosition::new(560, 240));
+    }
+
+    #[test]
+    fn headless_manager_build_error() {
+        let mut wm = HeadlessWindowManager::new(headless_displays());
+
+        assert_eq!(wm.build(WindowBuilder::new().size_min(WindowSize::new(u32::MAX, u32::MAX))).err(), Some(WindowError::WindowMinSizeBiggerThanMax));
+        assert_eq!(wm.build(WindowBuilder::new().size(WindowSize::new(u32::MAX, u32::MAX))).err(), Some(WindowError::WindowSizeOOB));
+        assert_eq!(wm.build(WindowBuilder::new().position(WindowRelativePosition::Desktop(WindowPosition::new(1800, 0)))).err(), 
+            Some(WindowError::WindowRelativePositionOOB));
+        assert_eq!(wm.build(WindowBuilder::new().position(WindowRelativePosition::DisplayCenter(3))).err(), Some(WindowError::DisplayInvalidHandle));
+        assert!(events(&mut wm).is_empty());
+        assert!(wm.server.borrow().windows.is_empty());
+    }
+
+    #[test]
+    fn headless_manager_build_hidden() {
+        let mut wm = HeadlessWindowManager::new(headless_displays());
+        let handle = wm.build(WindowBuilder::new().hide().position(WindowRelativePosition::Desktop(WindowPosition::new(0, 0)))).unwrap();
+        assert!(events(&mut wm).is_empty());
+
+        // Redraw requested while hidden is sent when shown.
+        wm.window_mut(handle).unwrap().request_redraw();
+        assert!(events(&mut wm).is_empty());
+        wm.window_mut(handle).unwrap().show();
+        assert!(matches!(events(&mut wm)[..], [WindowEvent::Shown, WindowEvent::Focus, WindowEvent::RedrawRequested(_)]));
+
+        wm.window_mut(handle).unwrap().hide();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Blur, WindowEvent::Hidden]);
+        assert!(!wm.window(handle).unwrap().visible());
+    }
+
+    #[test]
+    fn headless_manager_redraw() {
+        let mut wm = HeadlessWindowManager::new(headless_displays());
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+
+        // Requests are merged while the frame is pending.
+        let window = wm.window_mut(handle).unwrap();
+        window.request_redraw();
+        window.request_redraw();
+        assert!(events(&mut wm).is_empty());
+
+        let window = wm.window_mut(handle).unwrap();
+        window.framebuffer().unwrap()[0] = 0x00FF0000;
+        window.present(&[]).unwrap();
+        let events = events(&mut wm);
+        match &events[..] {
+            [WindowEvent::RedrawRequested(timing)] => assert_eq!(timing.interval, wm.displays().list[0].refresh_interval()),
+            _ => panic!("{:?}", events),
+        }
+
+        // Presented pixels stay readable.
+        assert_eq!(wm.window_mut(handle).unwrap().framebuffer().unwrap()[0], 0x00FF0000);
+    }
+
+    #[test]
+    fn headless_manager_geometry() {
+        let mut wm = HeadlessWindowManager::new(dual_displays());
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+
+        wm.window_mut(handle).unwrap().set_size(WindowSize::new(800, 600)).unwrap();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Resized(WindowSize::new(800, 600))]);
+
+        // Moving to the scaled display changes the scale.
+        wm.window_mut(handle).unwrap().set_position(WindowRelativePosition::Display(1, WindowPosition::new(10, 20))).unwrap();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Moved(WindowPosition::new(1930, 20)), WindowEvent::ScaleChanged(2.0)]);
+        assert_eq!(wm.window(handle).unwrap().scale(), 2.0);
+
+        wm.window_mut(handle).unwrap().maximize();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Maximized, 
+            WindowEvent::MovedResized(WindowPosition::new(1920, 0), WindowSize::new(1920, 1080))]);
+        assert!(wm.window_mut(handle).unwrap().maximized());
+
+        // Size asked while maximized is given when restored.
+        wm.window_mut(handle).unwrap().set_size(WindowSize::new(1024, 768)).unwrap();
+        assert!(events(&mut wm).is_empty());
+        wm.window_mut(handle).unwrap().restore();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Restored, 
+            WindowEvent::MovedResized(WindowPosition::new(1930, 20), WindowSize::new(1024, 768))]);
+        assert!(!wm.window_mut(handle).unwrap().maximized());
+
+        wm.window_mut(handle).unwrap().set_fullscreen(WindowFullScreenMode::Desktop);
+        assert_eq!(events(&mut wm), vec![WindowEvent::Fullscreen, 
+            WindowEvent::MovedResized(WindowPosition::new(0, 0), WindowSize::new(3840, 1080))]);
+        assert!(wm.window(handle).unwrap().fullscreen());
+    }
+
+    #[test]
+    fn headless_manager_rebuild() {
+        let mut wm = HeadlessWindowManager::new(headless_displays());
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(800, 600))).unwrap();
+        events(&mut wm);
+
+        // Rebuilt window keeps its handle and gets the builder properties.
+        let mut builder = WindowBuilder::new();
+        builder.title("Rebuilt").size(WindowSize::new(400, 300)).maximize();
+        assert_eq!(builder.rebuild(wm.window_mut(handle).unwrap()), Ok(handle));
+        let window = wm.window(handle).unwrap();
+        assert_eq!((window.title(), window.size()), ("Rebuilt", WindowSize::new(400, 300)));
+        assert!(events(&mut wm).contains(&WindowEvent::Maximized));
+
+        // Invalid builders leave the window untouched.
+        assert_eq!(WindowBuilder::new().size_min(WindowSize::new(u32::MAX, u32::MAX)).rebuild(wm.window_mut(handle).unwrap()), 
+            Err(WindowError::WindowMinSizeBiggerThanMax));
+        let position = WindowRelativePosition::Desktop(WindowPosition::new(100000, 0));
+        assert_eq!(WindowBuilder::new().title("Moved").position(position).rebuild(wm.window_mut(handle).unwrap()), 
+            Err(WindowError::WindowRelativePositionOOB));
+        #[cfg(not(feature = "single_opt"))]
+        assert_eq!(WindowBuilder::new().title("Child").parent(Some(handle)).rebuild(wm.window_mut(handle).unwrap()), 
+            Err(WindowError::WindowParentSelf));
+        assert_eq!(wm.window(handle).unwrap().title(), "Rebuilt");
+        assert_eq!(wm.window(handle).unwrap().size(), WindowSize::new(400, 300));
+    }
+
+    #[test]
+    fn headless_manager_focus() {
+        let mut wm = HeadlessWindowManager::new(headless_displays());
+        let first = wm.build(&WindowBuilder::new()).unwrap();
+        let second = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+
+        wm.window_mut(first).unwrap().activate();
+        let fetched : Vec<_> = std::iter::from_fn(|| wm.event().cloned()).map(|e| (e.window, e.event)).collect();
+        assert_eq!(fetched, vec![(second, WindowEvent::Blur), (first, WindowEvent::Focus)]);
+
+        // Minimized windows lose focus and release their grab.
+        wm.window_mut(first).unwrap().grab_input().unwrap();
+        wm.window_mut(first).unwrap().minimize();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Blur, WindowEvent::Minimized]);
+        assert!(wm.window(first).unwrap().minimized());
+        assert!(!wm.window(first).unwrap().input_grabbed());
+
+        wm.window_mut(first).unwrap().restore();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Restored]);
+    }
+
+    #[test]
+    fn headless_manager_close() {
+        let mut wm = HeadlessWindowManager::new(headless_displays());
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+
+        wm.window_mut(handle).unwrap().grab_input().unwrap();
+        wm.window_mut(handle).unwrap().close();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Closed]);
+        assert_eq!(wm.window(handle).err(), Some(WindowError::InvalidWindowHandle));
+        assert!(wm.server.borrow().focus.is_none());
+
+        // New window reusing the slot doesn't make the closed handle valid again.
+        let other = wm.build(&WindowBuilder::new()).unwrap();
+        assert_ne!(other, handle);
+        assert_eq!(wm.window_mut(handle).err(), Some(WindowError::InvalidWindowHandle));
+        assert_eq!(wm.window(other).unwrap().native_handle(), 2);
+    }
+
+    #[test]
+    fn headless_manager_hotkey() {
+        let mut wm = HeadlessWindowManager::new(headless_displays());
+        let id = wm.register_hotkey(65, KeyModifiers::new(false, true, false, false)).unwrap();
+        assert_eq!(id, 1);
+        assert!(!wm.unregister_hotkey(0));
+        assert_eq!(wm.register_hotkey(65, KeyModifiers::new(false, true, false, false)).err(), Some(WindowError::HotkeyUnavailable));
+        assert!(wm.unregister_hotkey(id));
+        assert!(!wm.unregister_hotkey(id));
+        assert!(wm.key_label(65).is_none());
+    }
+
+    #[test]
+    fn headless_manager_event_wait() {
+        let mut wm = HeadlessWindowManager::new(headless_displays());
+        assert_eq!(wm.event_wait().event, WindowEvent::NoEvent);
+
+        // Queued events are returned right away.
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        assert_eq!(wm.event_wait().window, handle);
+    }
+}
@@ -0,0 +1,166 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Headless backend with in-memory windows on synthetic [Displays], used to run window logic without display server like in `cargo test`.
+//! 
+//! Every request is accepted as a window manager would, windows updating their geometry and queuing the events a real backend sends.
+
+use std::collections::VecDeque;
+
+use crate::{display::{Desktop, Display, DisplayDesktopPosition, DisplayResolution, DisplaySizeMM, DisplaySupportedResolution, Displays}, 
+    event::WindowManagerEvent, window::WindowHandles, WindowHandle, WindowPosition, WindowSize};
+
+pub(crate) mod manager;
+pub(crate) mod window;
+
+/// Geometry of a headless window known by the [HeadlessServer].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct HeadlessGeometry {
+    /// Handle of the window
+    pub handle : WindowHandle,
+
+    /// Position on the desktop
+    pub position : WindowPosition,
+
+    /// Size of the window
+    pub size : WindowSize,
+}
+
+/// In-memory window manager shared by the [HeadlessWindowManager](manager::HeadlessWindowManager) and its windows.
+#[derive(Debug, Default)]
+pub(crate) struct HeadlessServer {
+    /// [WindowManagerEvent] queued by windows until fetched.
+    pub queue : VecDeque<WindowManagerEvent>,
+
+    /// Geometry of windows not closed
+    pub windows : Vec<HeadlessGeometry>,
+
+    /// Window having the focus, if any.
+    pub focus : Option<WindowHandle>,
+
+    /// Handles given to windows
+    pub handles : WindowHandles,
+
+    /// Last native window id given, ids start at 1.
+    pub last_id : usize,
+}
+
+impl HeadlessServer {
+    /// Get the geometry of window `handle` if it isn't closed.
+    pub fn geometry(&self, handle : WindowHandle) -> Option<HeadlessGeometry> {
+        self.windows.iter().find(|g| g.handle == handle).copied()
+    }
+
+    /// Update the geometry of a window, adding it if unknown.
+    pub fn set_geometry(&mut self, geometry : HeadlessGeometry) {
+        match self.windows.iter_mut().find(|g| g.handle == geometry.handle) {
+            Some(g) => *g = geometry,
+            None => self.windows.push(geometry),
+        }
+    }
+}
+
+/// Synthetic 1920x1080 display at 60hz, used when no [Displays] are given to the headless backend.
+pub(crate) fn headless_displays() -> Displays {
+    let resolution = DisplayResolution { width : 1920, height : 1080 };
+    let mut supported = DisplaySupportedResolution::new(resolution.clone(), false);
+    supported.add_refresh_rate(6000);
+
+    let display = Display { 
+        handle : 0, 
+        identifier : String::from("HEADLESS-1"), 
+        description : String::from("Headless display"), 
+        size : DisplaySizeMM { width : 527, height : 296 }, 
+        position : DisplayDesktopPosition { x : 0, y : 0 }, 
+        resolution : resolution.clone(), 
+        refresh_rate : 6000, 
+        primary : true, 
+        scale : 1.0, 
+        supported : vec![supported], 
+    };
+
+    Displays::create(vec![display], Some(Desktop { min : resolution.clone(), max : resolution.clone(), current : resolution }))
+}
+
+/// Complete synthetic [Displays] given by the user. Handles are set to the index in list and 
+/// a missing desktop is the bounding box of the displays, like Wayland gives it.
+pub(crate) fn headless_complete(mut displays : Displays) -> Displays {
+    for (handle, display) in displays.list.iter_mut().enumerate() {
+        display.handle = handle;
+    }
+
+    if displays.desktop.is_none() {
+        displays.desktop = displays.list.iter().map(|d| (d.position.x, d.position.y, d.position.x + d.resolution.width as i32, d.position.y + d.resolution.height as i32))
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))).map(|(left, top, right, bottom)| {
+                let current = DisplayResolution { width : (right - left).max(0) as usize, height : (bottom - top).max(0) as usize };
+                Desktop { min : current.clone(), max : current.clone(), current }
+            });
+    }
+
+    displays
+}
+
+/// Get the display a window at `position` of `size` is on, the one containing its center. 
+/// Primary display, then the first one, are used if center is outside of all displays.
+pub(crate) fn headless_display(displays : &Displays, position : WindowPosition, size : WindowSize) -> Option<&Display> {
+    let (x, y) = (position.x + (size.width / 2) as i32, position.y + (size.height / 2) as i32);
+
+    displays.list.iter().find(|d| x >= d.position.x && x < d.position.x + d.resolution.width as i32 && 
+        y >= d.position.y && y < d.position.y + d.resolution.height as i32)
+        .or_else(|| displays.primary()).or_else(|| displays.list.first())
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Headless displays tests
+#[cfg(test)]
+mod tests {
+    use crate::{display::{DisplayDesktopPosition, DisplayResolution, Displays}, WindowPosition, WindowSize};
+
+    use super::{headless_complete, headless_display, headless_displays};
+
+    #[test]
+    fn headless_displays_complete() {
+        let mut first = headless_displays().list.remove(0);
+        let mut second = first.clone();
+        second.position = DisplayDesktopPosition { x : 1920, y : -200 };
+        second.resolution = DisplayResolution { width : 1280, height : 1024 };
+        second.primary = false;
+        second.handle = 7;
+        first.primary = false;
+
+        let displays = headless_complete(Displays { list : vec![first, second], desktop : None });
+        assert_eq!(displays.list[1].handle, 1);
+        let desktop = displays.desktop.as_ref().unwrap();
+        assert_eq!(desktop.current, DisplayResolution { width : 3200, height : 1280 });
+
+        // Center decides the display, the first one being used without primary.
+        assert_eq!(headless_display(&displays, WindowPosition::new(1800, 0), WindowSize::new(400, 300)).unwrap().handle, 1);
+        assert_eq!(headless_display(&displays, WindowPosition::new(100, 100), WindowSize::new(400, 300)).unwrap().handle, 0);
+        assert_eq!(headless_display(&displays, WindowPosition::new(-5000, 0), WindowSize::new(400, 300)).unwrap().handle, 0);
+        assert!(headless_display(&Displays { list : Vec::new(), desktop : None }, WindowPosition::new(0, 0), WindowSize::new(1, 1)).is_none());
+    }
+}
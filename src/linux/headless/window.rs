@@ -0,0 +1,477 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::{cell::RefCell, rc::Rc, time::{Duration, Instant}};
+
+use crate::{display::Displays, event::{WindowEvent, WindowFrameTiming, WindowManagerEvent}, WindowError, WindowFullScreenMode, WindowHandle, WindowPosition, WindowSize};
+
+use super::{headless_display, HeadlessGeometry, HeadlessServer};
+
+/// Window living in memory only. Requests are applied right away and answered with the events 
+/// a window manager would send, queued in the [HeadlessServer].
+#[derive(Debug)]
+pub struct HeadlessWindow {
+    /// Server shared with the manager and other windows
+    server : Rc<RefCell<HeadlessServer>>,
+
+    /// Synthetic displays of the manager
+    displays : Rc<Displays>,
+
+    /// Handle given by the server
+    pub(crate) handle : WindowHandle,
+
+    /// Native window id given by the server
+    id : usize,
+
+    /// Position on the desktop
+    position : WindowPosition,
+
+    /// Current size
+    size : WindowSize,
+
+    /// Minimum size
+    min_size : WindowSize,
+
+    /// Maximum size
+    max_size : WindowSize,
+
+    /// Geometry before maximize or fullscreen, given back by restore.
+    saved : Option<(WindowPosition, WindowSize)>,
+
+    /// Window is mapped
+    shown : bool,
+
+    /// Window is minimized
+    minimized : bool,
+
+    /// Window is maximized
+    maximized : bool,
+
+    /// Window is fullscreen
+    fullscreen : bool,
+
+    /// Scale of the display the window is on
+    scale : f64,
+
+    /// Redraw sent and not presented yet
+    frame_pending : bool,
+
+    /// Redraw requested while a frame is pending or the window is hidden
+    pub(crate) redraw : bool,
+
+    /// Software framebuffer pixels
+    pub(crate) pixels : Vec<u32>,
+
+    /// Cairo image surface of the framebuffer
+    #[cfg(feature = "cairo")]
+    cairo : crate::linux::cairo::LinuxCairoSurface,
+}
+
+impl PartialEq for HeadlessWindow {
+    fn eq(&self, other: &Self) -> bool {
+        self.handle == other.handle
+    }
+}
+
+impl HeadlessWindow {
+
+    /// Create a hidden window of `size` at the desktop origin, registered in the server.
+    pub(crate) fn new(server : Rc<RefCell<HeadlessServer>>, displays : Rc<Displays>, size : WindowSize, min_size : WindowSize, max_size : WindowSize) -> HeadlessWindow {
+        let (handle, id) = {
+            let mut server = server.borrow_mut();
+            server.last_id += 1;
+            let handle = server.handles.insert();
+            server.set_geometry(HeadlessGeometry { handle, position : WindowPosition::new(0, 0), size });
+            (handle, server.last_id)
+        };
+
+        HeadlessWindow { 
+            server, 
+            displays, 
+            handle, 
+            id, 
+            position : WindowPosition::new(0, 0), 
+            size, 
+            min_size, 
+            max_size, 
+            saved : None, 
+            shown : false, 
+            minimized : false, 
+            maximized : false, 
+            fullscreen : false, 
+            scale : 1.0, 
+            frame_pending : false, 
+            redraw : false, 
+            pixels : Vec::new(), 
+            #[cfg(feature = "cairo")]
+            cairo : crate::linux::cairo::LinuxCairoSurface::new(),
+        }
+    }
+
+    /// Native window id given by the server
+    #[inline(always)]
+    pub fn native_handle(&self) -> usize {
+        self.id
+    }
+
+    /// Get position and size of a window of the server. Closed windows are empty at the origin.
+    #[inline(always)]
+    pub(crate) fn get_window_pos_size(&self, handle : WindowHandle) -> (WindowPosition, WindowSize) {
+        match self.server.borrow().geometry(handle) {
+            Some(geometry) => (geometry.position, geometry.size),
+            None => (WindowPosition::new(0, 0), WindowSize::new(0, 0)),
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_parent(&mut self, _parent : Option<WindowHandle>) -> Result<bool, WindowError> {
+        Ok(true)
+    }
+
+    #[inline(always)]
+    pub fn set_title(&mut self, _title : &str) -> Result<bool, WindowError> {
+        Ok(true)
+    }
+
+    /// Size is clamped between min and max size. Maximized and fullscreen windows get it when restored.
+    #[inline(always)]
+    pub fn set_size(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        let size = self.clamp(size);
+        if let Some((_, saved)) = &mut self.saved {
+            *saved = size;
+        } else {
+            self.move_resize(self.position, size);
+        }
+        Ok(true)
+    }
+
+    #[inline(always)]
+    pub fn set_size_min(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        self.min_size = size;
+        self.set_size(self.size_restored())
+    }
+
+    #[inline(always)]
+    pub fn set_size_max(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        self.max_size = size;
+        self.set_size(self.size_restored())
+    }
+
+    #[inline(always)]
+    pub fn set_icon(&mut self, _icon : Option<&mut dyn std::io::Read>) {}
+
+    #[inline(always)]
+    pub fn set_taskbar(&mut self, _show : bool) {}
+
+    /// Restored window gets back its geometry from before maximize or fullscreen.
+    #[inline(always)]
+    pub fn restore(&mut self) {
+        if self.minimized || self.maximized || self.fullscreen {
+            self.minimized = false;
+            self.maximized = false;
+            self.fullscreen = false;
+            self.push(WindowEvent::Restored);
+
+            if let Some((position, size)) = self.saved.take() {
+                self.move_resize(position, size);
+            }
+        }
+    }
+
+    /// Mapped window is focused and requests its first frame.
+    #[inline(always)]
+    pub fn show(&mut self) {
+        if !self.shown {
+            self.shown = true;
+            self.push(WindowEvent::Shown);
+            self.enter_display();
+            self.activate();
+            self.request_redraw();
+        }
+    }
+
+    #[inline(always)]
+    pub fn hide(&mut self) {
+        if self.shown {
+            self.blur();
+            self.shown = false;
+            self.frame_pending = false;
+            self.push(WindowEvent::Hidden);
+        }
+    }
+
+    /// Window is removed from the server. Does nothing if already closed.
+    #[inline(always)]
+    pub fn close(&mut self) {
+        let closed = {
+            let mut server = self.server.borrow_mut();
+            let count = server.windows.len();
+            server.windows.retain(|g| g.handle != self.handle);
+            if server.focus == Some(self.handle) {
+                server.focus = None;
+            }
+            count == server.windows.len()
+        };
+
+        if !closed {
+            #[cfg(feature = "cairo")]
+            self.cairo.destroy();
+            self.shown = false;
+            self.push(WindowEvent::Closed);
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_position(&mut self, position : WindowPosition) {
+        self.move_resize(position, self.size);
+    }
+
+    /// Fullscreen covers the display asked, the desktop being the bounding box of all displays.
+    #[inline(always)]
+    pub fn set_fullscreen(&mut self, fsmode : WindowFullScreenMode) {
+        if !self.fullscreen {
+            let area = match fsmode {
+                WindowFullScreenMode::Desktop => match &self.displays.desktop {
+                    Some(desktop) => Some((WindowPosition::new(self.displays.list.iter().map(|d| d.position.x).min().unwrap_or(0), 
+                        self.displays.list.iter().map(|d| d.position.y).min().unwrap_or(0)), 
+                        WindowSize::new(desktop.current.width as u32, desktop.current.height as u32))),
+                    None => None,
+                },
+                fsmode => self.fullscreen_display(fsmode),
+            };
+
+            self.fullscreen = true;
+            self.minimized = false;
+            self.save();
+            self.push(WindowEvent::Fullscreen);
+            if let Some((position, size)) = area {
+                self.move_resize(position, size);
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn minimize(&mut self) {
+        if !self.minimized {
+            self.blur();
+            self.minimized = true;
+            self.push(WindowEvent::Minimized);
+        }
+    }
+
+    /// Maximized window covers the display it is on. Fullscreen windows stay fullscreen.
+    #[inline(always)]
+    pub fn maximize(&mut self) {
+        if !self.maximized {
+            let area = self.fullscreen_display(WindowFullScreenMode::Current);
+
+            self.maximized = true;
+            self.minimized = false;
+            self.save();
+            self.push(WindowEvent::Maximized);
+            if let (Some((position, size)), false) = (area, self.fullscreen) {
+                self.move_resize(position, size);
+            }
+        }
+    }
+
+    #[inline(always)]
+    pub fn grab_input(&mut self) -> Result<bool, WindowError> {
+        Ok(true)
+    }
+
+    #[inline(always)]
+    pub fn ungrab_input(&mut self) {}
+
+    #[inline(always)]
+    pub fn set_idle_inhibit(&mut self, _inhibit : bool) {}
+
+    /// Focus moves from the focused window to this one. Hidden and minimized windows can't be focused.
+    #[inline(always)]
+    pub fn activate(&mut self) {
+        if !self.shown || self.minimized {
+            return;
+        }
+
+        let previous = self.server.borrow_mut().focus.replace(self.handle);
+        match previous {
+            Some(previous) if previous == self.handle => {},
+            Some(previous) => {
+                self.server.borrow_mut().queue.push_back(WindowManagerEvent::new(previous, WindowEvent::Blur));
+                self.push(WindowEvent::Focus);
+            },
+            None => self.push(WindowEvent::Focus),
+        }
+    }
+
+    /// Redraw is sent right away, or when the pending frame is presented. Hidden windows get it when shown.
+    #[inline(always)]
+    pub fn request_redraw(&mut self) {
+        if !self.shown || self.frame_pending {
+            self.redraw = true;
+            return;
+        }
+
+        let interval = match headless_display(&self.displays, self.position, self.size) {
+            Some(display) => display.refresh_interval(),
+            None => Duration::ZERO,
+        };
+
+        self.redraw = false;
+        self.frame_pending = true;
+        self.push(WindowEvent::RedrawRequested(WindowFrameTiming { target : Instant::now() + interval, interval }));
+    }
+
+    /// Headless windows have no system window.
+    #[cfg(feature = "raw_window_handle")]
+    #[inline(always)]
+    pub fn raw_window_handle(&self) -> Result<raw_window_handle::RawWindowHandle, raw_window_handle::HandleError> {
+        Err(raw_window_handle::HandleError::NotSupported)
+    }
+
+    /// Headless windows have no surface to present Vulkan images on.
+    #[cfg(feature = "vulkan")]
+    #[inline(always)]
+    pub unsafe fn create_vulkan_surface(&self, _instance : crate::vulkan::VkInstance, 
+        _get_instance_proc_addr : crate::vulkan::VkGetInstanceProcAddr) -> Result<crate::vulkan::VkSurfaceKHR, WindowError> {
+        Err(WindowError::WindowVulkanUnsupported)
+    }
+
+    /// Headless windows have no drawable for OpenGL.
+    #[cfg(feature = "opengl")]
+    #[inline(always)]
+    pub fn create_gl_context(&self, _config : &crate::opengl::GLConfig, _shared : Option<&crate::opengl::GLContext>) -> Result<crate::opengl::GLContext, WindowError> {
+        Err(WindowError::WindowOpenGLUnsupported)
+    }
+
+    /// Cairo image surface of the framebuffer of `size`.
+    #[cfg(feature = "cairo")]
+    #[inline(always)]
+    pub fn cairo_surface(&mut self, size : WindowSize) -> Result<crate::cairo::CairoSurface, WindowError> {
+        self.framebuffer(size)?;
+        self.cairo.image(&mut self.pixels, size)
+    }
+
+    #[cfg(feature = "cairo")]
+    #[inline(always)]
+    pub fn cairo_present(&mut self, size : WindowSize, damage : &[(WindowPosition, WindowSize)]) -> Result<(), WindowError> {
+        self.cairo.flush();
+        self.present(size, damage)
+    }
+
+    /// Pixels of the framebuffer of `size`, cleared if size changed. Presented pixels stay readable.
+    #[inline(always)]
+    pub fn framebuffer(&mut self, size : WindowSize) -> Result<&mut [u32], WindowError> {
+        let length = size.width as usize * size.height as usize;
+        if self.pixels.len() != length {
+            self.pixels = vec![0; length];
+        }
+        Ok(&mut self.pixels)
+    }
+
+    /// Presenting completes the pending frame, sending the redraw requested meanwhile.
+    #[inline(always)]
+    pub fn present(&mut self, size : WindowSize, _damage : &[(WindowPosition, WindowSize)]) -> Result<(), WindowError> {
+        self.framebuffer(size)?;
+        self.frame_pending = false;
+        if self.redraw {
+            self.request_redraw();
+        }
+        Ok(())
+    }
+
+    /// Queue an event of this window.
+    fn push(&self, event : WindowEvent) {
+        self.server.borrow_mut().queue.push_back(WindowManagerEvent::new(self.handle, event));
+    }
+
+    /// Remove the focus if window has it.
+    fn blur(&mut self) {
+        let focused = self.server.borrow().focus == Some(self.handle);
+        if focused {
+            self.server.borrow_mut().focus = None;
+            self.push(WindowEvent::Blur);
+        }
+    }
+
+    /// Save the geometry given back by restore, if not saved already.
+    fn save(&mut self) {
+        if self.saved.is_none() {
+            self.saved = Some((self.position, self.size));
+        }
+    }
+
+    /// Size given back when restored.
+    fn size_restored(&self) -> WindowSize {
+        match self.saved {
+            Some((_, size)) => size,
+            None => self.size,
+        }
+    }
+
+    /// Clamp `size` between min and max size.
+    fn clamp(&self, size : WindowSize) -> WindowSize {
+        WindowSize::new(size.width.min(self.max_size.width).max(self.min_size.width), size.height.min(self.max_size.height).max(self.min_size.height))
+    }
+
+    /// Position and size of the display of `fsmode`. Current display is used for unknown displays.
+    fn fullscreen_display(&self, fsmode : WindowFullScreenMode) -> Option<(WindowPosition, WindowSize)> {
+        let display = match fsmode {
+            WindowFullScreenMode::Primary => self.displays.primary().or_else(|| self.displays.list.first()),
+            WindowFullScreenMode::Display(handle) => self.displays.list.get(handle),
+            _ => None,
+        }.or_else(|| headless_display(&self.displays, self.position, self.size));
+
+        display.map(|d| (WindowPosition::new(d.position.x, d.position.y), WindowSize::new(d.resolution.width as u32, d.resolution.height as u32)))
+    }
+
+    /// Move and resize the window, sending [WindowEvent::Moved], [WindowEvent::Resized] or [WindowEvent::MovedResized] for what changed.
+    fn move_resize(&mut self, position : WindowPosition, size : WindowSize) {
+        let event = match (position != self.position, size != self.size) {
+            (true, true) => WindowEvent::MovedResized(position, size),
+            (true, false) => WindowEvent::Moved(position),
+            (false, true) => WindowEvent::Resized(size),
+            (false, false) => return,
+        };
+
+        self.position = position;
+        self.size = size;
+        self.server.borrow_mut().set_geometry(HeadlessGeometry { handle : self.handle, position, size });
+        self.push(event);
+        self.enter_display();
+    }
+
+    /// Send [WindowEvent::ScaleChanged] if the display the window is on has another scale. 
+    fn enter_display(&mut self) {
+        let scale = match headless_display(&self.displays, self.position, self.size) {
+            Some(display) => display.scale,
+            None => 1.0,
+        };
+
+        if self.shown && scale != self.scale {
+            self.scale = scale;
+            self.push(WindowEvent::ScaleChanged(scale));
+        }
+    }
+}
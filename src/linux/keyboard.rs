@@ -22,7 +22,7 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use crate::keyboard::WindowKeyboardMode;
+use crate::{event::{KeyCode, WindowCoordinate}, keyboard::{KeyLocks, KeyModifiers, WindowKeyboardMode}, WindowSize};
 
 use super::x11::keyboard::X11Keyboard;
 
@@ -52,6 +52,22 @@ impl LinuxKeyboard {
         }
     }
 
+    #[inline(always)]
+    pub fn set_surrounding_text(&mut self, text : &str, cursor : usize, anchor : usize) {
+        match self {
+            LinuxKeyboard::X11(wkb) => wkb.set_surrounding_text(text, cursor, anchor),
+            LinuxKeyboard::Wayland(wkb) => wkb.set_surrounding_text(text, cursor, anchor),
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_text_cursor(&mut self, position : WindowCoordinate, size : WindowSize) {
+        match self {
+            LinuxKeyboard::X11(wkb) => wkb.set_text_cursor(position, size),
+            LinuxKeyboard::Wayland(wkb) => wkb.set_text_cursor(position, size),
+        }
+    }
+
     #[inline(always)]
     pub fn enable_repeat(&mut self) {
         match self {
@@ -68,4 +84,12 @@ impl LinuxKeyboard {
         }
     }
 
+    #[inline(always)]
+    pub fn query_state(&self) -> (Vec<KeyCode>, KeyModifiers, KeyLocks) {
+        match self {
+            LinuxKeyboard::X11(wkb) => wkb.query_state(),
+            LinuxKeyboard::Wayland(wkb) => wkb.query_state(),
+        }
+    }
+
 }
\ No newline at end of file
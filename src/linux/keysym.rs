@@ -0,0 +1,240 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/// Keysym value used by xkb.
+pub(crate) type Keysym = u32;
+
+/// Offset of keysyms that directly encode an unicode code point.
+const KEYSYM_UNICODE_OFFSET : Keysym = 0x0100_0000;
+
+/// Get the character printed by a keysym. 
+/// 
+/// Latin-1 keysyms have the same value as their code point and unicode keysyms are offsetted by 0x01000000.
+/// 
+/// Returns None if keysym doesn't print a character.
+/// 
+/// Reference(s)
+/// <https://www.x.org/releases/current/doc/xproto/x11protocol.html#keysym_encoding>
+pub(crate) fn keysym_to_char(keysym : Keysym) -> Option<char> {
+    match keysym {
+        0x0020..=0x007e | 0x00a0..=0x00ff => char::from_u32(keysym),
+        0x0100_0100..=0x0110_ffff => char::from_u32(keysym - KEYSYM_UNICODE_OFFSET),
+        _ => None,
+    }
+}
+
+/// Get the label of a printable keysym as shown on keycaps, which is uppercase. 
+/// 
+/// Characters without single uppercase character like `ß` are kept as is.
+/// 
+/// Returns None if keysym doesn't print a character or is a space.
+pub(crate) fn keysym_label(keysym : Keysym) -> Option<String> {
+    match keysym_to_char(keysym) {
+        Some(c) if !c.is_whitespace() => {
+            let mut upper = c.to_uppercase();
+            match (upper.next(), upper.next()) {
+                (Some(u), None) => Some(u.to_string()),
+                _ => Some(c.to_string()),
+            }
+        },
+        _ => None,
+    }
+}
+
+/// Names of the printable keysyms from `space` (0x20) to `asciitilde` (0x7e), except digits and letters.
+const KEYSYM_ASCII_NAMES : [(&str, Keysym); 33] = [
+    ("space", 0x20), ("exclam", 0x21), ("quotedbl", 0x22), ("numbersign", 0x23), ("dollar", 0x24), ("percent", 0x25),
+    ("ampersand", 0x26), ("apostrophe", 0x27), ("parenleft", 0x28), ("parenright", 0x29), ("asterisk", 0x2a),
+    ("plus", 0x2b), ("comma", 0x2c), ("minus", 0x2d), ("period", 0x2e), ("slash", 0x2f), ("colon", 0x3a),
+    ("semicolon", 0x3b), ("less", 0x3c), ("equal", 0x3d), ("greater", 0x3e), ("question", 0x3f), ("at", 0x40),
+    ("bracketleft", 0x5b), ("backslash", 0x5c), ("bracketright", 0x5d), ("asciicircum", 0x5e), ("underscore", 0x5f),
+    ("grave", 0x60), ("braceleft", 0x7b), ("bar", 0x7c), ("braceright", 0x7d), ("asciitilde", 0x7e),
+];
+
+/// Names of the latin-1 keysyms from `nobreakspace` (0xa0) to `ydiaeresis` (0xff).
+const KEYSYM_LATIN1_NAMES : [&str; 96] = [
+    "nobreakspace", "exclamdown", "cent", "sterling", "currency", "yen", "brokenbar", "section", "diaeresis",
+    "copyright", "ordfeminine", "guillemotleft", "notsign", "hyphen", "registered", "macron", "degree",
+    "plusminus", "twosuperior", "threesuperior", "acute", "mu", "paragraph", "periodcentered", "cedilla",
+    "onesuperior", "masculine", "guillemotright", "onequarter", "onehalf", "threequarters", "questiondown",
+    "Agrave", "Aacute", "Acircumflex", "Atilde", "Adiaeresis", "Aring", "AE", "Ccedilla", "Egrave", "Eacute",
+    "Ecircumflex", "Ediaeresis", "Igrave", "Iacute", "Icircumflex", "Idiaeresis", "ETH", "Ntilde", "Ograve",
+    "Oacute", "Ocircumflex", "Otilde", "Odiaeresis", "multiply", "Oslash", "Ugrave", "Uacute", "Ucircumflex",
+    "Udiaeresis", "Yacute", "THORN", "ssharp", "agrave", "aacute", "acircumflex", "atilde", "adiaeresis",
+    "aring", "ae", "ccedilla", "egrave", "eacute", "ecircumflex", "ediaeresis", "igrave", "iacute",
+    "icircumflex", "idiaeresis", "eth", "ntilde", "ograve", "oacute", "ocircumflex", "otilde", "odiaeresis",
+    "division", "oslash", "ugrave", "uacute", "ucircumflex", "udiaeresis", "yacute", "thorn", "ydiaeresis",
+];
+
+/// Names of the function, keypad, modifier and dead keysyms.
+/// 
+/// Aliases like `Page_Up` come after their canonical name so that reverse lookup returns the canonical one.
+const KEYSYM_NAMES : &[(&str, Keysym)] = &[
+    ("NoSymbol", 0x0000), ("BackSpace", 0xff08), ("Tab", 0xff09), ("Linefeed", 0xff0a), ("Clear", 0xff0b), 
+    ("Return", 0xff0d), ("Pause", 0xff13), ("Scroll_Lock", 0xff14), ("Sys_Req", 0xff15), ("Escape", 0xff1b), 
+    ("Multi_key", 0xff20), ("Home", 0xff50), ("Left", 0xff51), ("Up", 0xff52), ("Right", 0xff53), ("Down", 0xff54), 
+    ("Prior", 0xff55), ("Page_Up", 0xff55), ("Next", 0xff56), ("Page_Down", 0xff56), ("End", 0xff57), ("Begin", 0xff58), 
+    ("Select", 0xff60), ("Print", 0xff61), ("Execute", 0xff62), ("Insert", 0xff63), ("Undo", 0xff65), ("Redo", 0xff66), 
+    ("Menu", 0xff67), ("Find", 0xff68), ("Cancel", 0xff69), ("Help", 0xff6a), ("Break", 0xff6b), ("Mode_switch", 0xff7e), 
+    ("Num_Lock", 0xff7f), ("KP_Space", 0xff80), ("KP_Tab", 0xff89), ("KP_Enter", 0xff8d), ("KP_F1", 0xff91), 
+    ("KP_F2", 0xff92), ("KP_F3", 0xff93), ("KP_F4", 0xff94), ("KP_Home", 0xff95), ("KP_Left", 0xff96), ("KP_Up", 0xff97), 
+    ("KP_Right", 0xff98), ("KP_Down", 0xff99), ("KP_Prior", 0xff9a), ("KP_Page_Up", 0xff9a), ("KP_Next", 0xff9b), 
+    ("KP_Page_Down", 0xff9b), ("KP_End", 0xff9c), ("KP_Begin", 0xff9d), ("KP_Insert", 0xff9e), ("KP_Delete", 0xff9f), 
+    ("KP_Multiply", 0xffaa), ("KP_Add", 0xffab), ("KP_Separator", 0xffac), ("KP_Subtract", 0xffad), 
+    ("KP_Decimal", 0xffae), ("KP_Divide", 0xffaf), ("KP_Equal", 0xffbd), ("Shift_L", 0xffe1), ("Shift_R", 0xffe2), 
+    ("Control_L", 0xffe3), ("Control_R", 0xffe4), ("Caps_Lock", 0xffe5), ("Shift_Lock", 0xffe6), ("Meta_L", 0xffe7), 
+    ("Meta_R", 0xffe8), ("Alt_L", 0xffe9), ("Alt_R", 0xffea), ("Super_L", 0xffeb), ("Super_R", 0xffec), 
+    ("Hyper_L", 0xffed), ("Hyper_R", 0xffee), ("Delete", 0xffff), ("ISO_Level3_Shift", 0xfe03), 
+    ("ISO_Next_Group", 0xfe08), ("ISO_Level5_Shift", 0xfe11), ("ISO_Left_Tab", 0xfe20), ("dead_grave", 0xfe50), 
+    ("dead_acute", 0xfe51), ("dead_circumflex", 0xfe52), ("dead_tilde", 0xfe53), ("dead_macron", 0xfe54), 
+    ("dead_breve", 0xfe55), ("dead_abovedot", 0xfe56), ("dead_diaeresis", 0xfe57), ("dead_abovering", 0xfe58), 
+    ("dead_doubleacute", 0xfe59), ("dead_caron", 0xfe5a), ("dead_cedilla", 0xfe5b), ("dead_ogonek", 0xfe5c), 
+    ("EuroSign", 0x20ac), ("XF86AudioLowerVolume", 0x1008_ff11), ("XF86AudioMute", 0x1008_ff12), 
+    ("XF86AudioRaiseVolume", 0x1008_ff13), ("XF86AudioPlay", 0x1008_ff14), ("XF86AudioStop", 0x1008_ff15), 
+    ("XF86AudioPrev", 0x1008_ff16), ("XF86AudioNext", 0x1008_ff17), ("VoidSymbol", 0x00ff_ffff),
+];
+
+/// Keysym of `F1`, followed by `F2` up to `F35`.
+const KEYSYM_F1 : Keysym = 0xffbe;
+
+/// Keysym of `KP_0`, followed by `KP_1` up to `KP_9`.
+const KEYSYM_KP_0 : Keysym = 0xffb0;
+
+/// Get a keysym from its name as written in xkb keymaps.
+/// 
+/// Accepts names like `a` or `Escape`, unicode keysyms like `U20AC` and raw values like `0x1008ff11`.
+/// 
+/// Returns None if the name is unknown.
+/// 
+/// Reference(s)
+/// <https://gitlab.freedesktop.org/xorg/proto/xorgproto/-/blob/master/include/X11/keysymdef.h>
+pub(crate) fn keysym_from_name(name : &str) -> Option<Keysym> {
+    let mut chars = name.chars();
+
+    match (chars.next(), chars.next()) {
+        // Digits and letters are named after themselves.
+        (Some(c), None) if c.is_ascii_alphanumeric() => return Some(c as Keysym),
+        _ => {},
+    }
+
+    if let Some((_, keysym)) = KEYSYM_ASCII_NAMES.iter().find(|(n, _)| *n == name) {
+        return Some(*keysym);
+    }
+    if let Some(index) = KEYSYM_LATIN1_NAMES.iter().position(|n| *n == name) {
+        return Some(0xa0 + index as Keysym);
+    }
+    if let Some((_, keysym)) = KEYSYM_NAMES.iter().find(|(n, _)| *n == name) {
+        return Some(*keysym);
+    }
+
+    if let Some(number) = name.strip_prefix('F').and_then(|n| n.parse::<Keysym>().ok()) {
+        return (1..=35).contains(&number).then(|| KEYSYM_F1 + number - 1);
+    }
+    if let Some(number) = name.strip_prefix("KP_").and_then(|n| n.parse::<Keysym>().ok()) {
+        return (number <= 9).then(|| KEYSYM_KP_0 + number);
+    }
+    if let Some(code) = name.strip_prefix('U').and_then(|n| Keysym::from_str_radix(n, 16).ok()) {
+        // Latin-1 code points keep their legacy keysym.
+        return match code {
+            0x20..=0x7e | 0xa0..=0xff => Some(code),
+            0x100..=0x10ffff => Some(code + KEYSYM_UNICODE_OFFSET),
+            _ => None,
+        };
+    }
+    name.strip_prefix("0x").and_then(|n| Keysym::from_str_radix(n, 16).ok())
+}
+
+/// Get the name of a keysym as written in xkb keymaps.
+/// 
+/// Keysyms without a known name are named after their code point like `U20AC` or their value like `0x1008ff42`.
+pub(crate) fn keysym_name(keysym : Keysym) -> String {
+    match keysym {
+        0x30..=0x39 | 0x41..=0x5a | 0x61..=0x7a => return char::from_u32(keysym).unwrap_or_default().to_string(),
+        0xa0..=0xff => return KEYSYM_LATIN1_NAMES[(keysym - 0xa0) as usize].to_string(),
+        _ => {},
+    }
+
+    if let Some((name, _)) = KEYSYM_ASCII_NAMES.iter().chain(KEYSYM_NAMES.iter()).find(|(_, k)| *k == keysym) {
+        return name.to_string();
+    }
+
+    match keysym {
+        KEYSYM_F1..=0xffe0 => format!("F{}", keysym - KEYSYM_F1 + 1),
+        KEYSYM_KP_0..=0xffb9 => format!("KP_{}", keysym - KEYSYM_KP_0),
+        0x0100_0100..=0x0110_ffff => format!("U{:04X}", keysym - KEYSYM_UNICODE_OFFSET),
+        _ => format!("0x{:08x}", keysym),
+    }
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Keysym conversion tests
+#[cfg(test)]
+mod tests {
+
+    #[test]
+    fn keysym_to_char() {
+        assert_eq!(super::keysym_to_char(0x0061), Some('a'));       // XK_a
+        assert_eq!(super::keysym_to_char(0x00e9), Some('é'));       // XK_eacute
+        assert_eq!(super::keysym_to_char(0x010020ac), Some('€'));   // U+20AC
+        assert_eq!(super::keysym_to_char(0xff1b), None);            // XK_Escape
+    }
+
+    #[test]
+    fn keysym_label() {
+        assert_eq!(super::keysym_label(0x0061), Some("A".to_string()));
+        assert_eq!(super::keysym_label(0x00df), Some("ß".to_string()));    // XK_ssharp
+        assert_eq!(super::keysym_label(0x003b), Some(";".to_string()));
+        assert_eq!(super::keysym_label(0x0020), None);                     // XK_space
+    }
+
+    #[test]
+    fn keysym_from_name() {
+        assert_eq!(super::keysym_from_name("a"), Some(0x0061));
+        assert_eq!(super::keysym_from_name("7"), Some(0x0037));
+        assert_eq!(super::keysym_from_name("exclam"), Some(0x0021));
+        assert_eq!(super::keysym_from_name("eacute"), Some(0x00e9));
+        assert_eq!(super::keysym_from_name("Page_Up"), Some(0xff55));
+        assert_eq!(super::keysym_from_name("F12"), Some(0xffc9));
+        assert_eq!(super::keysym_from_name("KP_5"), Some(0xffb5));
+        assert_eq!(super::keysym_from_name("U20AC"), Some(0x010020ac));
+        assert_eq!(super::keysym_from_name("0x1008ff42"), Some(0x1008ff42));
+        assert_eq!(super::keysym_from_name("F36"), None);
+        assert_eq!(super::keysym_from_name("Unknown_Key"), None);
+    }
+
+    #[test]
+    fn keysym_name() {
+        assert_eq!(super::keysym_name(0x0061), "a");
+        assert_eq!(super::keysym_name(0x003b), "semicolon");
+        assert_eq!(super::keysym_name(0x00c9), "Eacute");
+        assert_eq!(super::keysym_name(0xff55), "Prior");
+        assert_eq!(super::keysym_name(0xffc9), "F12");
+        assert_eq!(super::keysym_name(0x010020ac), "U20AC");
+        assert_eq!(super::keysym_name(0x1008ff42), "0x1008ff42");
+    }
+
+}
@@ -0,0 +1,85 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! Shared libraries opened at runtime with dlopen, so nswindow doesn't link them.
+//! 
+//! Reference(s)
+//! <https://man7.org/linux/man-pages/man3/dlopen.3.html>
+
+use std::ffi::{c_void, CStr};
+
+/// Shared library opened with dlopen and closed when dropped.
+#[derive(Debug)]
+pub(crate) struct Library {
+    /// Handle given by dlopen
+    handle : *mut c_void,
+}
+
+impl Library {
+    /// Open the first library of `names` found, like `libGL.so.1`.
+    pub fn open(names : &[&CStr]) -> Option<Library> {
+        names.iter().find_map(|name| {
+            let handle = unsafe { libc::dlopen(name.as_ptr(), libc::RTLD_NOW | libc::RTLD_LOCAL) };
+            (!handle.is_null()).then(|| Library { handle })
+        })
+    }
+
+    /// Get the function `name` of the library as `T`.
+    /// 
+    /// # Safety
+    /// `T` must be the `extern "C"` function pointer type of the symbol.
+    pub unsafe fn symbol<T : Copy>(&self, name : &CStr) -> Option<T> {
+        let symbol = libc::dlsym(self.handle, name.as_ptr());
+        (!symbol.is_null()).then(|| std::mem::transmute_copy::<*mut c_void, T>(&symbol))
+    }
+}
+
+impl Drop for Library {
+    fn drop(&mut self) {
+        unsafe { libc::dlclose(self.handle) };
+    }
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Shared library tests
+#[cfg(test)]
+mod tests {
+    use super::Library;
+
+    #[test]
+    fn library_symbol() {
+        assert!(Library::open(&[c"libnswindow-missing.so"]).is_none());
+
+        // First library missing, second opened.
+        let library = Library::open(&[c"libnswindow-missing.so", c"libc.so.6"]).unwrap();
+        let getpid = unsafe { library.symbol::<unsafe extern "C" fn() -> i32>(c"getpid") }.unwrap();
+        assert_eq!(unsafe { getpid() }, std::process::id() as i32);
+
+        assert!(unsafe { library.symbol::<unsafe extern "C" fn()>(c"nswindow_missing") }.is_none());
+    }
+}
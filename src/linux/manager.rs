@@ -22,9 +22,9 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use crate::{display::Displays, event::WindowManagerEvent, Window, WindowBuilder, WindowError, WindowHandle};
+use crate::{device::InputDevices, display::Displays, event::{KeyCode, WindowManagerEvent}, keyboard::{HotkeyId, KeyModifiers, KeyboardLayout}, manager::WindowBackend, Window, WindowBuilder, WindowError, WindowHandle};
 
-use super::{wayland::manager::WaylandWindowManager, x11::manager::X11WindowManager};
+use super::{headless::manager::HeadlessWindowManager, wayland::manager::WaylandWindowManager, x11::manager::X11WindowManager};
 
 
 /// Match abstraction of possible linux Window managers.
@@ -36,38 +36,67 @@ pub(crate) enum LinuxWindowManager {
     X11(X11WindowManager),
 
     /// Wayland linux window server
-    Wayland(WaylandWindowManager)
+    Wayland(WaylandWindowManager),
+
+    /// Headless window manager in memory
+    Headless(HeadlessWindowManager)
 
 }
 
 impl LinuxWindowManager {
 
+    #[inline(always)]
+    pub(crate) fn backend(&self) -> WindowBackend {
+        match self {
+            LinuxWindowManager::X11(_) => WindowBackend::X11,
+            LinuxWindowManager::Wayland(_) => WindowBackend::Wayland,
+            LinuxWindowManager::Headless(_) => WindowBackend::Headless,
+        }
+    }
+
     #[inline(always)]
     pub(crate) fn event(&mut self) -> Option<&WindowManagerEvent> {
         match self {
             LinuxWindowManager::X11(wm) => wm.event(),
             LinuxWindowManager::Wayland(wm) => wm.event(),
+            LinuxWindowManager::Headless(wm) => wm.event(),
         }
     }
 
     #[inline(always)]
     pub(crate) fn event_wait(&mut self) -> &WindowManagerEvent {
-        todo!()
+        match self {
+            LinuxWindowManager::X11(wm) => wm.event_wait(),
+            LinuxWindowManager::Wayland(wm) => wm.event_wait(),
+            LinuxWindowManager::Headless(wm) => wm.event_wait(),
+        }
     }
 
     #[inline(always)]
     pub(crate) fn build(&mut self, builder : &WindowBuilder) -> Result<WindowHandle, WindowError> {
-        todo!()
+        match self {
+            LinuxWindowManager::X11(wm) => wm.build(builder),
+            LinuxWindowManager::Wayland(wm) => wm.build(builder),
+            LinuxWindowManager::Headless(wm) => wm.build(builder),
+        }
     }
 
     #[inline(always)]
     pub fn window(&self, window : WindowHandle) -> Result<&Window, WindowError> {
-        todo!()
+        match self {
+            LinuxWindowManager::X11(wm) => wm.window(window),
+            LinuxWindowManager::Wayland(wm) => wm.window(window),
+            LinuxWindowManager::Headless(wm) => wm.window(window),
+        }
     } 
 
     #[inline(always)]
     pub fn window_mut(&mut self, window : WindowHandle) -> Result<&mut Window, WindowError> {
-        todo!()
+        match self {
+            LinuxWindowManager::X11(wm) => wm.window_mut(window),
+            LinuxWindowManager::Wayland(wm) => wm.window_mut(window),
+            LinuxWindowManager::Headless(wm) => wm.window_mut(window),
+        }
     }
 
     #[inline(always)]
@@ -75,6 +104,72 @@ impl LinuxWindowManager {
         match self {
             LinuxWindowManager::X11(wm) => wm.displays(),
             LinuxWindowManager::Wayland(wm) => wm.displays(),
+            LinuxWindowManager::Headless(wm) => wm.displays(),
+        }
+    }
+
+    #[cfg(feature = "raw_window_handle")]
+    #[inline(always)]
+    pub(crate) fn raw_display_handle(&self) -> Result<raw_window_handle::RawDisplayHandle, raw_window_handle::HandleError> {
+        match self {
+            LinuxWindowManager::X11(wm) => wm.raw_display_handle(),
+            LinuxWindowManager::Wayland(wm) => wm.raw_display_handle(),
+            LinuxWindowManager::Headless(wm) => wm.raw_display_handle(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline(always)]
+    pub(crate) fn vulkan_required_extensions(&self) -> Result<&'static [&'static std::ffi::CStr], WindowError> {
+        match self {
+            LinuxWindowManager::X11(wm) => wm.vulkan_required_extensions(),
+            LinuxWindowManager::Wayland(wm) => wm.vulkan_required_extensions(),
+            LinuxWindowManager::Headless(wm) => wm.vulkan_required_extensions(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn input_devices(&self) -> &InputDevices {
+        match self {
+            LinuxWindowManager::X11(wm) => wm.input_devices(),
+            LinuxWindowManager::Wayland(wm) => wm.input_devices(),
+            LinuxWindowManager::Headless(wm) => wm.input_devices(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn keyboard_layout(&self) -> &KeyboardLayout {
+        match self {
+            LinuxWindowManager::X11(wm) => wm.keyboard_layout(),
+            LinuxWindowManager::Wayland(wm) => wm.keyboard_layout(),
+            LinuxWindowManager::Headless(wm) => wm.keyboard_layout(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn key_label(&self, key : KeyCode) -> Option<String> {
+        match self {
+            LinuxWindowManager::X11(wm) => wm.key_label(key),
+            LinuxWindowManager::Wayland(wm) => wm.key_label(key),
+            LinuxWindowManager::Headless(wm) => wm.key_label(key),
+        }
+    }
+
+    #[inline(always)]
+    pub fn register_hotkey(&mut self, key : KeyCode, modifiers : KeyModifiers) -> Result<HotkeyId, WindowError> {
+        match self {
+            LinuxWindowManager::X11(wm) => wm.register_hotkey(key, modifiers),
+            LinuxWindowManager::Wayland(wm) => wm.register_hotkey(key, modifiers),
+            LinuxWindowManager::Headless(wm) => wm.register_hotkey(key, modifiers),
+        }
+    }
+
+    #[inline(always)]
+    pub fn unregister_hotkey(&mut self, hotkey : HotkeyId) -> bool {
+        match self {
+            LinuxWindowManager::X11(wm) => wm.unregister_hotkey(hotkey),
+            LinuxWindowManager::Wayland(wm) => wm.unregister_hotkey(hotkey),
+            LinuxWindowManager::Headless(wm) => wm.unregister_hotkey(hotkey),
         }
     }
 
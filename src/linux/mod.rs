@@ -22,6 +22,8 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
+use std::time::{Duration, Instant};
+
 use wayland::window::WaylandWindow;
 use x11::window::X11Window;
 
@@ -30,6 +32,22 @@ pub(crate) mod manager;
 pub(crate) mod window;
 pub(crate) mod pointer;
 pub(crate) mod keyboard;
+pub(crate) mod keysym;
+
+/// Shared libraries loaded at runtime
+pub(crate) mod library;
+
+/// Cairo surfaces of windows
+#[cfg(feature = "cairo")]
+pub(crate) mod cairo;
+
+/// EGL contexts of X11 and Wayland windows
+#[cfg(feature = "opengl")]
+pub(crate) mod egl;
+
+/// OpenGL contexts of windows
+#[cfg(feature = "opengl")]
+pub(crate) mod opengl;
 
 /// Wayland functions and bindings
 pub(crate) mod wayland;
@@ -37,6 +55,22 @@ pub(crate) mod wayland;
 /// X11 functions and bindings
 pub(crate) mod x11;
 
+/// Headless windows in memory
+pub(crate) mod headless;
 
+/// Get the time of CLOCK_MONOTONIC, the clock of presentation times given by compositors and X servers.
+pub(crate) fn monotonic_time() -> Duration {
+    let mut time = libc::timespec { tv_sec : 0, tv_nsec : 0 };
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut time) };
+    Duration::new(time.tv_sec as u64, time.tv_nsec as u32)
+}
 
-
+/// Get the [Instant] of a presentation that happened `elapsed` ago. Presentations more than a second old are assumed 
+/// timestamped by another clock and replaced by now.
+pub(crate) fn presentation_instant(elapsed : Duration) -> Instant {
+    let now = Instant::now();
+    match elapsed < Duration::from_secs(1) {
+        true => now.checked_sub(elapsed).unwrap_or(now),
+        false => now,
+    }
+}
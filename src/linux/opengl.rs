@@ -0,0 +1,84 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! OpenGL contexts of X11 or Wayland windows.
+
+use std::ffi::{c_void, CStr};
+
+use crate::{opengl::GLConfig, WindowError};
+
+use super::{wayland::opengl::WaylandGLContext, x11::opengl::X11GLContext};
+
+/// OpenGL context of the window backend.
+#[derive(Debug)]
+pub(crate) enum LinuxGLContext {
+    /// GLX or EGL context of a X11 window.
+    X11(X11GLContext),
+
+    /// EGL context of a Wayland window.
+    Wayland(WaylandGLContext),
+}
+
+impl LinuxGLContext {
+    #[inline(always)]
+    pub fn make_current(&self) -> Result<(), WindowError> {
+        match self {
+            LinuxGLContext::X11(context) => context.make_current(),
+            LinuxGLContext::Wayland(context) => context.make_current(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn swap_buffers(&self) -> Result<(), WindowError> {
+        match self {
+            LinuxGLContext::X11(context) => context.swap_buffers(),
+            LinuxGLContext::Wayland(context) => context.swap_buffers(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_swap_interval(&self, interval : i32) -> Result<(), WindowError> {
+        match self {
+            LinuxGLContext::X11(context) => context.set_swap_interval(interval),
+            LinuxGLContext::Wayland(context) => context.set_swap_interval(interval),
+        }
+    }
+
+    #[inline(always)]
+    pub fn get_proc_address(&self, name : &CStr) -> *const c_void {
+        match self {
+            LinuxGLContext::X11(context) => context.get_proc_address(name),
+            LinuxGLContext::Wayland(context) => context.get_proc_address(name),
+        }
+    }
+
+    #[inline(always)]
+    pub fn config(&self) -> &GLConfig {
+        match self {
+            LinuxGLContext::X11(context) => context.config(),
+            LinuxGLContext::Wayland(context) => context.config(),
+        }
+    }
+}
@@ -82,6 +82,14 @@ impl LinuxPointer {
         }
     }
 
+    #[inline(always)]
+    pub fn locked(&self) -> bool {
+        match self {
+            LinuxPointer::X11(lp) => lp.locked(),
+            LinuxPointer::Wayland(lp) => lp.locked(),
+        }
+    }
+
     #[inline(always)]
     pub fn set_cursor(&mut self, cursor : WindowCursor) {
         match self {
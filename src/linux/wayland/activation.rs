@@ -0,0 +1,131 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! Activation and idle inhibition of windows with xdg-activation and idle-inhibit.
+//! 
+//! Activation tokens are requested with the serial of the last input so that compositors 
+//! let the focus move from the focused window to another one.
+//! 
+//! Reference(s)
+//! <https://wayland.app/protocols/xdg-activation-v1>
+//! <https://wayland.app/protocols/idle-inhibit-unstable-v1>
+
+use std::{cell::RefCell, rc::Rc};
+
+use super::{protocol::{XDG_ACTIVATION_TOKEN_V1, XDG_ACTIVATION_TOKEN_V1_COMMIT, XDG_ACTIVATION_TOKEN_V1_DESTROY, XDG_ACTIVATION_TOKEN_V1_SET_SERIAL, 
+    XDG_ACTIVATION_TOKEN_V1_SET_SURFACE, XDG_ACTIVATION_V1_ACTIVATE, XDG_ACTIVATION_V1_GET_ACTIVATION_TOKEN}, wlib::{WaylandArg, WaylandConnection, WaylandId}};
+
+/// Environment variable with the activation token given by the launcher of the application.
+pub(crate) const WL_ACTIVATION_TOKEN_ENV : &str = "XDG_ACTIVATION_TOKEN";
+
+/// Activation state of the seat, updated by the window manager with inputs and shared with windows.
+#[derive(Debug)]
+pub(crate) struct WaylandActivation {
+    /// Connection with the compositor
+    connection : Rc<RefCell<WaylandConnection>>,
+
+    /// xdg_activation_v1 global, 0 if not advertised.
+    pub activation : WaylandId,
+
+    /// zwp_idle_inhibit_manager_v1 global, 0 if not advertised.
+    pub idle_manager : WaylandId,
+
+    /// wl_seat global
+    pub seat : WaylandId,
+
+    /// Serial of the last keyboard or pointer input, 0 if none.
+    pub serial : u32,
+
+    /// wl_surface with the keyboard focus, 0 if none.
+    pub focus : WaylandId,
+
+    /// Tokens requested as (xdg_activation_token_v1, wl_surface to activate). Surface is 0 if closed before done.
+    tokens : Vec<(WaylandId, WaylandId)>,
+}
+
+impl WaylandActivation {
+
+    /// Create the activation state without globals.
+    pub fn new(connection : Rc<RefCell<WaylandConnection>>) -> WaylandActivation {
+        WaylandActivation { connection, activation : 0, idle_manager : 0, seat : 0, serial : 0, focus : 0, tokens : Vec::new() }
+    }
+
+    /// Request a token to activate `surface`, sent with the last input serial and the focused surface.
+    /// 
+    /// Returns false if compositor lacks xdg-activation.
+    pub fn request(&mut self, surface : WaylandId) -> bool {
+        if self.activation == 0 {
+            return false;
+        }
+
+        let mut conn = self.connection.borrow_mut();
+        let token = conn.new_object(&XDG_ACTIVATION_TOKEN_V1, 1);
+        conn.send(self.activation, XDG_ACTIVATION_V1_GET_ACTIVATION_TOKEN, vec![WaylandArg::NewId(token)]);
+        if self.serial != 0 && self.seat != 0 {
+            conn.send(token, XDG_ACTIVATION_TOKEN_V1_SET_SERIAL, vec![WaylandArg::Uint(self.serial), WaylandArg::Object(self.seat)]);
+        }
+        if self.focus != 0 {
+            conn.send(token, XDG_ACTIVATION_TOKEN_V1_SET_SURFACE, vec![WaylandArg::Object(self.focus)]);
+        }
+        conn.send(token, XDG_ACTIVATION_TOKEN_V1_COMMIT, vec![]);
+        let _ = conn.flush();
+
+        self.tokens.push((token, surface));
+        true
+    }
+
+    /// Token requested is done. Its surface is activated and the token object destroyed.
+    pub fn done(&mut self, token : WaylandId, name : &str) {
+        if let Some(index) = self.tokens.iter().position(|(t, _)| *t == token) {
+            let (_, surface) = self.tokens.remove(index);
+            if surface != 0 {
+                self.activate(name, surface);
+            }
+
+            let mut conn = self.connection.borrow_mut();
+            conn.send(token, XDG_ACTIVATION_TOKEN_V1_DESTROY, vec![]);
+            conn.destroyed(token);
+        }
+    }
+
+    /// Activate `surface` with a token name, like the one given by the launcher in `XDG_ACTIVATION_TOKEN`.
+    pub fn activate(&mut self, name : &str, surface : WaylandId) {
+        if self.activation != 0 {
+            let mut conn = self.connection.borrow_mut();
+            conn.send(self.activation, XDG_ACTIVATION_V1_ACTIVATE, vec![WaylandArg::Str(Some(name.to_string())), WaylandArg::Object(surface)]);
+            let _ = conn.flush();
+        }
+    }
+
+    /// Forget the surface of a closed window. Its pending tokens are destroyed when done.
+    pub fn remove(&mut self, surface : WaylandId) {
+        for (_, s) in self.tokens.iter_mut().filter(|(_, s)| *s == surface) {
+            *s = 0;
+        }
+        if self.focus == surface {
+            self.focus = 0;
+        }
+    }
+}
@@ -0,0 +1,309 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Cursors of the Wayland pointer.
+//! 
+//! Compositors with cursor-shape-v1 draw cursors from a shape name. Others need a cursor surface showing an 
+//! image of the user Xcursor theme, which files are parsed here.
+//! 
+//! Reference(s)
+//! <https://wayland.app/protocols/cursor-shape-v1>
+//! <https://www.x.org/releases/current/doc/man/man3/Xcursor.3.xhtml>
+
+use std::path::{Path, PathBuf};
+
+use crate::pointer::WindowCursor;
+
+use super::protocol::{WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_CROSSHAIR, WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_DEFAULT, WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_EW_RESIZE, 
+    WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_MOVE, WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NESW_RESIZE, WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NOT_ALLOWED, 
+    WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NS_RESIZE, WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NWSE_RESIZE, WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_POINTER, 
+    WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_PROGRESS, WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_TEXT, WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_WAIT};
+
+/// Magic bytes starting Xcursor files
+const XCURSOR_MAGIC : &[u8; 4] = b"Xcur";
+
+/// Type of the image chunks of Xcursor files
+const XCURSOR_IMAGE_TYPE : u32 = 0xfffd_0002;
+
+/// Length of the header of an image chunk
+const XCURSOR_IMAGE_HEADER : usize = 36;
+
+/// Largest width and height of a cursor image
+const XCURSOR_IMAGE_MAX_SIZE : u32 = 0x7fff;
+
+/// Cursor size used if `XCURSOR_SIZE` isn't set.
+const XCURSOR_DEFAULT_SIZE : u32 = 24;
+
+/// Directories searched for themes if `XCURSOR_PATH` isn't set. `~` is the home directory.
+const XCURSOR_DEFAULT_PATH : &str = "~/.local/share/icons:~/.icons:/usr/share/icons:/usr/share/pixmaps";
+
+/// Maximum depth of themes inheritance followed.
+const XCURSOR_MAX_INHERITS : usize = 8;
+
+/// Image of a cursor.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WaylandCursorImage {
+    /// Width in pixels
+    pub width : u32,
+
+    /// Height in pixels
+    pub height : u32,
+
+    /// Position of the pointer hotspot in the image
+    pub hotspot : (u32, u32),
+
+    /// Premultiplied ARGB8888 pixels
+    pub pixels : Vec<u32>,
+}
+
+/// Xcursor theme of the user with images loaded on demand.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WaylandCursorTheme {
+    /// Name of the theme like `Adwaita`
+    pub name : String,
+
+    /// Nominal size of cursors
+    pub size : u32,
+
+    /// Directories searched for themes
+    pub paths : Vec<PathBuf>,
+
+    /// Images already loaded. None if cursor isn't available in theme.
+    cache : Vec<(WindowCursor, Option<WaylandCursorImage>)>,
+}
+
+impl WaylandCursorTheme {
+
+    /// Create a theme from its name, cursor size and search directories.
+    pub fn new(name : &str, size : u32, paths : Vec<PathBuf>) -> WaylandCursorTheme {
+        WaylandCursorTheme { name : name.to_string(), size, paths, cache : Vec::new() }
+    }
+
+    /// Create the theme of the user from `XCURSOR_THEME`, `XCURSOR_SIZE` and `XCURSOR_PATH` environment variables.
+    pub fn from_env() -> WaylandCursorTheme {
+        let name = std::env::var("XCURSOR_THEME").unwrap_or_else(|_| "default".to_string());
+        let size = std::env::var("XCURSOR_SIZE").ok().and_then(|s| s.parse::<u32>().ok()).unwrap_or(XCURSOR_DEFAULT_SIZE);
+        let path = std::env::var("XCURSOR_PATH").unwrap_or_else(|_| XCURSOR_DEFAULT_PATH.to_string());
+        let home = std::env::var("HOME").unwrap_or_default();
+
+        let paths = path.split(':').filter(|p| !p.is_empty()).map(|p| match p.strip_prefix('~') {
+            Some(rest) => PathBuf::from(format!("{}{}", home, rest)),
+            None => PathBuf::from(p),
+        }).collect();
+
+        WaylandCursorTheme::new(&name, size, paths)
+    }
+
+    /// Get the image of a [WindowCursor], loading it from the theme files if needed.
+    /// 
+    /// Returns None if the theme and the themes it inherits don't have the cursor.
+    pub fn image(&mut self, cursor : WindowCursor) -> Option<&WaylandCursorImage> {
+        let index = match self.cache.iter().position(|(c, _)| *c == cursor) {
+            Some(index) => index,
+            None => {
+                let image = xcursor_names(cursor).iter().find_map(|name| {
+                    let file = self.find(&self.name, name, 0).or_else(|| self.find("default", name, 0))?;
+                    xcursor_parse(&std::fs::read(file).ok()?, self.size)
+                });
+                self.cache.push((cursor, image));
+                self.cache.len() - 1
+            },
+        };
+
+        self.cache[index].1.as_ref()
+    }
+
+    /// Find the file of a cursor in theme or the themes it inherits.
+    fn find(&self, theme : &str, name : &str, depth : usize) -> Option<PathBuf> {
+        if depth > XCURSOR_MAX_INHERITS {
+            return None;
+        }
+
+        if let Some(file) = self.paths.iter().map(|p| p.join(theme).join("cursors").join(name)).find(|f| f.is_file()) {
+            return Some(file);
+        }
+
+        self.paths.iter().flat_map(|p| xcursor_inherits(&p.join(theme).join("index.theme")))
+            .filter(|parent| parent != theme)
+            .find_map(|parent| self.find(&parent, name, depth + 1))
+    }
+}
+
+/// Get the themes inherited from the `Inherits` key of a theme `index.theme` file.
+fn xcursor_inherits(index : &Path) -> Vec<String> {
+    let text = std::fs::read_to_string(index).unwrap_or_default();
+
+    text.lines().filter_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        (key.trim() == "Inherits").then_some(value)
+    }).flat_map(|value| value.split([',', ';']).map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect::<Vec<String>>())
+    .collect()
+}
+
+/// Parse an Xcursor file and get the image with the nominal size closest to `size`. Animated cursors keep their first frame.
+/// 
+/// Returns None if file isn't a valid Xcursor file.
+pub(crate) fn xcursor_parse(data : &[u8], size : u32) -> Option<WaylandCursorImage> {
+    let word = |offset : usize| -> Option<u32> {
+        data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    if data.get(0..4)? != XCURSOR_MAGIC {
+        return None;
+    }
+    let header = word(4)? as usize;
+    let count = word(12)? as usize;
+
+    // Table of content entries are (type, nominal size, position).
+    let mut images : Vec<(u32, usize)> = Vec::new();
+    for entry in 0..count {
+        let offset = header + entry * 12;
+        if word(offset)? == XCURSOR_IMAGE_TYPE {
+            images.push((word(offset + 4)?, word(offset + 8)? as usize));
+        }
+    }
+
+    let nominal = images.iter().map(|(s, _)| *s).min_by_key(|s| s.abs_diff(size))?;
+    let (_, position) = images.into_iter().find(|(s, _)| *s == nominal)?;
+
+    let (width, height) = (word(position + 16)?, word(position + 20)?);
+    if width > XCURSOR_IMAGE_MAX_SIZE || height > XCURSOR_IMAGE_MAX_SIZE || word(position + 4)? != XCURSOR_IMAGE_TYPE {
+        return None;
+    }
+
+    let start = position + XCURSOR_IMAGE_HEADER;
+    let pixels = (0..(width * height) as usize).map(|i| word(start + i * 4)).collect::<Option<Vec<u32>>>()?;
+
+    Some(WaylandCursorImage { width, height, hotspot : (word(position + 24)?.min(width), word(position + 28)?.min(height)), pixels })
+}
+
+/// Names of a [WindowCursor] in Xcursor themes, from the freedesktop name to the legacy X11 names.
+pub(crate) fn xcursor_names(cursor : WindowCursor) -> &'static [&'static str] {
+    match cursor {
+        WindowCursor::Normal => &["default", "left_ptr"],
+        WindowCursor::Select => &["text", "xterm"],
+        WindowCursor::Working => &["wait", "watch"],
+        WindowCursor::WorkingInBackground => &["progress", "left_ptr_watch"],
+        WindowCursor::Move => &["move", "fleur"],
+        WindowCursor::Cross => &["crosshair", "cross"],
+        WindowCursor::Hand => &["pointer", "hand2"],
+        WindowCursor::Unavailable => &["not-allowed", "crossed_circle"],
+        WindowCursor::Pencil => &["pencil"],
+        WindowCursor::UpArrow => &["up-arrow", "sb_up_arrow"],
+        WindowCursor::DiagonalResizeTLBR => &["nwse-resize", "size_fdiag", "bottom_right_corner"],
+        WindowCursor::DiagonalResizeBLTR => &["nesw-resize", "size_bdiag", "bottom_left_corner"],
+        WindowCursor::HorizontalResize => &["ew-resize", "sb_h_double_arrow"],
+        WindowCursor::VerticalResize => &["ns-resize", "sb_v_double_arrow"],
+    }
+}
+
+/// Get the cursor-shape-v1 shape of a [WindowCursor]. Cursors without shape use the closest one.
+pub(crate) fn cursor_shape(cursor : WindowCursor) -> u32 {
+    match cursor {
+        WindowCursor::Normal | WindowCursor::UpArrow => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_DEFAULT,
+        WindowCursor::Select => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_TEXT,
+        WindowCursor::Working => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_WAIT,
+        WindowCursor::WorkingInBackground => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_PROGRESS,
+        WindowCursor::Move => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_MOVE,
+        WindowCursor::Cross | WindowCursor::Pencil => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_CROSSHAIR,
+        WindowCursor::Hand => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_POINTER,
+        WindowCursor::Unavailable => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NOT_ALLOWED,
+        WindowCursor::DiagonalResizeTLBR => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NWSE_RESIZE,
+        WindowCursor::DiagonalResizeBLTR => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NESW_RESIZE,
+        WindowCursor::HorizontalResize => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_EW_RESIZE,
+        WindowCursor::VerticalResize => WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NS_RESIZE,
+    }
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Xcursor tests
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::pointer::WindowCursor;
+
+    use super::{xcursor_parse, WaylandCursorTheme, XCURSOR_IMAGE_TYPE};
+
+    /// Build an Xcursor file with a square image of each size, filled with its size.
+    fn xcursor(sizes : &[u32]) -> Vec<u8> {
+        let mut words : Vec<u32> = vec![u32::from_le_bytes(*b"Xcur"), 16, 0x10000, sizes.len() as u32];
+        let mut position = 16 + 12 * sizes.len() as u32;
+        for size in sizes {
+            words.extend_from_slice(&[XCURSOR_IMAGE_TYPE, *size, position]);
+            position += 36 + size * size * 4;
+        }
+        for size in sizes {
+            words.extend_from_slice(&[36, XCURSOR_IMAGE_TYPE, *size, 1, *size, *size, size / 2, 1, 50]);
+            words.extend(std::iter::repeat_n(*size, (size * size) as usize));
+        }
+        words.iter().flat_map(|w| w.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn wayland_xcursor_parse() {
+        let data = xcursor(&[16, 24, 32]);
+
+        let image = xcursor_parse(&data, 24).unwrap();
+        assert_eq!((image.width, image.height, image.hotspot), (24, 24, (12, 1)));
+        assert_eq!(image.pixels.len(), 24 * 24);
+        assert!(image.pixels.iter().all(|p| *p == 24));
+
+        // Closest size is used.
+        assert_eq!(xcursor_parse(&data, 30).unwrap().width, 32);
+        assert_eq!(xcursor_parse(&data, 8).unwrap().width, 16);
+
+        // Truncated or invalid files.
+        assert!(xcursor_parse(&data[..data.len() - 4], 32).is_none());
+        assert!(xcursor_parse(b"Xcux", 24).is_none());
+        assert!(xcursor_parse(&xcursor(&[]), 24).is_none());
+    }
+
+    #[test]
+    fn wayland_xcursor_theme() {
+        let root = std::env::temp_dir().join(format!("nswindow-xcursor-{}", std::process::id()));
+        let dir = |theme : &str| -> PathBuf { 
+            let dir = root.join(theme).join("cursors");
+            std::fs::create_dir_all(&dir).unwrap();
+            dir
+        };
+
+        // Theme inherits missing cursors from its parent.
+        std::fs::write(dir("child").join("default"), xcursor(&[24])).unwrap();
+        std::fs::write(dir("parent").join("sb_h_double_arrow"), xcursor(&[32])).unwrap();
+        std::fs::write(root.join("child").join("index.theme"), "[Icon Theme]\nName=Child\nInherits=missing,parent\n").unwrap();
+
+        let mut theme = WaylandCursorTheme::new("child", 24, vec![root.clone()]);
+        assert_eq!(theme.image(WindowCursor::Normal).map(|i| i.width), Some(24));
+        assert_eq!(theme.image(WindowCursor::HorizontalResize).map(|i| i.width), Some(32));
+        assert!(theme.image(WindowCursor::Pencil).is_none());
+
+        // Images are cached.
+        std::fs::remove_dir_all(&root).unwrap();
+        assert!(theme.image(WindowCursor::Normal).is_some());
+    }
+}
@@ -0,0 +1,290 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Wayland outputs converted to [Displays].
+//! 
+//! Informations come from wl_output and xdg-output, which gives the logical position and size on the desktop.
+//! 
+//! Reference(s)
+//! <https://wayland.app/protocols/wayland#wl_output>
+//! <https://wayland.app/protocols/xdg-output-unstable-v1>
+
+use crate::display::{Desktop, Display, DisplayDesktopPosition, DisplayHandle, DisplayResolution, DisplaySizeMM, DisplaySupportedResolution, Displays};
+
+use super::{protocol::WL_OUTPUT_MODE_CURRENT, wlib::WaylandId};
+
+/// Mode advertised by wl_output.mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct WaylandOutputMode {
+    /// Width in pixels
+    pub width : i32,
+
+    /// Height in pixels
+    pub height : i32,
+
+    /// Refresh rate in mHz
+    pub refresh : i32,
+
+    /// Mode is the current one
+    pub current : bool,
+}
+
+/// Output bound from the registry with informations received.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct WaylandOutput {
+    /// Registry name of the wl_output global
+    pub global : u32,
+
+    /// wl_output object
+    pub output : WaylandId,
+
+    /// zxdg_output_v1 object, 0 if xdg-output isn't available.
+    pub xdg_output : WaylandId,
+
+    /// Name like `DP-1`
+    pub name : String,
+
+    /// Human readable description
+    pub description : String,
+
+    /// Manufacturer given by geometry
+    pub make : String,
+
+    /// Model given by geometry
+    pub model : String,
+
+    /// Position given by geometry, used without xdg-output.
+    pub position : (i32, i32),
+
+    /// Physical size in millimeters
+    pub size_mm : (i32, i32),
+
+    /// Transform of the output. Odd values are rotated by 90 or 270 degrees.
+    pub transform : i32,
+
+    /// Integer scale factor
+    pub scale : i32,
+
+    /// Modes advertised
+    pub modes : Vec<WaylandOutputMode>,
+
+    /// Logical position on desktop given by xdg-output
+    pub logical_position : Option<(i32, i32)>,
+
+    /// Logical size on desktop given by xdg-output
+    pub logical_size : Option<(i32, i32)>,
+
+    /// All informations were received at least once.
+    pub done : bool,
+}
+
+impl WaylandOutput {
+    /// Create a new output without informations.
+    pub fn new(global : u32, output : WaylandId, xdg_output : WaylandId) -> WaylandOutput {
+        WaylandOutput { global, output, xdg_output, scale : 1, ..Default::default() }
+    }
+
+    /// Add or update a mode from wl_output.mode.
+    pub fn set_mode(&mut self, flags : u32, width : i32, height : i32, refresh : i32) {
+        let current = flags & WL_OUTPUT_MODE_CURRENT != 0;
+
+        // Only one mode can be current.
+        if current {
+            self.modes.iter_mut().for_each(|m| m.current = false);
+        }
+
+        match self.modes.iter_mut().find(|m| m.width == width && m.height == height && m.refresh == refresh) {
+            Some(mode) => mode.current = current,
+            None => self.modes.push(WaylandOutputMode { width, height, refresh, current }),
+        }
+    }
+
+    /// Current mode, if any.
+    pub fn current_mode(&self) -> Option<&WaylandOutputMode> {
+        self.modes.iter().find(|m| m.current)
+    }
+
+    /// Size in pixels of the current mode once transformed.
+    pub fn pixel_size(&self) -> (i32, i32) {
+        match self.current_mode() {
+            Some(mode) if self.transform % 2 == 1 => (mode.height, mode.width),
+            Some(mode) => (mode.width, mode.height),
+            None => (0, 0),
+        }
+    }
+
+    /// Position on the desktop, from xdg-output if available.
+    pub fn logical_position(&self) -> (i32, i32) {
+        self.logical_position.unwrap_or(self.position)
+    }
+
+    /// Size on the desktop, from xdg-output if available or the pixel size divided by scale.
+    pub fn logical_size(&self) -> (i32, i32) {
+        match self.logical_size {
+            Some(size) => size,
+            None => {
+                let (width, height) = self.pixel_size();
+                (width / self.scale.max(1), height / self.scale.max(1))
+            },
+        }
+    }
+
+    /// Scale between pixels and logical size. Can be fractional with xdg-output.
+    pub fn effective_scale(&self) -> f64 {
+        let (width, _) = self.pixel_size();
+        match self.logical_size {
+            Some((logical, _)) if logical > 0 && width > 0 => width as f64 / logical as f64,
+            _ => self.scale.max(1) as f64,
+        }
+    }
+
+    /// Convert to [Display] with handle.
+    pub fn display(&self, handle : DisplayHandle, primary : bool) -> Display {
+        let (width, height) = self.pixel_size();
+        let (x, y) = self.logical_position();
+
+        let identifier = if self.name.is_empty() {
+            format!("{} {}", self.make, self.model).trim().to_string()
+        } else {
+            self.name.clone()
+        };
+
+        // Modes of the same resolution are grouped with their refresh rates.
+        let mut supported : Vec<DisplaySupportedResolution> = Vec::new();
+        for mode in &self.modes {
+            let resolution = DisplayResolution { width : mode.width as usize, height : mode.height as usize };
+            let index = match supported.iter().position(|s| s.resolution == resolution) {
+                Some(index) => index,
+                None => {
+                    supported.push(DisplaySupportedResolution::new(resolution, false));
+                    supported.len() - 1
+                },
+            };
+            supported[index].add_refresh_rate(mhz_to_refresh_rate(mode.refresh));
+        }
+
+        Display { 
+            handle, 
+            identifier, 
+            description : self.description.clone(), 
+            size : DisplaySizeMM { width : self.size_mm.0.max(0) as usize, height : self.size_mm.1.max(0) as usize }, 
+            position : DisplayDesktopPosition { x, y }, 
+            resolution : DisplayResolution { width : width as usize, height : height as usize }, 
+            refresh_rate : self.current_mode().map_or(0, |m| mhz_to_refresh_rate(m.refresh)), 
+            primary, 
+            scale : self.effective_scale(),
+            supported 
+        }
+    }
+}
+
+/// Create [Displays] from outputs that received all their informations.
+/// 
+/// Wayland doesn't have a primary output, the first one is used as primary.
+/// Desktop is the bounding box of the outputs logical areas.
+pub(crate) fn wayland_displays(outputs : &[WaylandOutput]) -> Displays {
+    let list : Vec<Display> = outputs.iter().filter(|o| o.done).enumerate().map(|(handle, o)| o.display(handle, handle == 0)).collect();
+
+    let desktop = outputs.iter().filter(|o| o.done).map(|o| {
+        let (x, y) = o.logical_position();
+        let (width, height) = o.logical_size();
+        (x, y, x + width, y + height)
+    }).reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))).map(|(left, top, right, bottom)| {
+        // Desktop size can't be changed by clients.
+        let current = DisplayResolution { width : (right - left).max(0) as usize, height : (bottom - top).max(0) as usize };
+        Desktop { min : current.clone(), max : current.clone(), current }
+    });
+
+    Displays::create(list, desktop)
+}
+
+/// Convert a refresh rate in mHz to [DisplayRefreshRate](crate::display::DisplayRefreshRate) where 60hz is 6000.
+#[inline(always)]
+fn mhz_to_refresh_rate(refresh : i32) -> u32 {
+    (refresh.max(0) / 10) as u32
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Wayland outputs conversion tests
+#[cfg(test)]
+mod tests {
+    use super::{wayland_displays, WaylandOutput, WL_OUTPUT_MODE_CURRENT};
+
+    #[test]
+    fn wayland_displays_outputs() {
+        let mut first = WaylandOutput::new(1, 10, 11);
+        first.name = "eDP-1".to_string();
+        first.size_mm = (344, 194);
+        first.set_mode(0, 1920, 1080, 48000);
+        first.set_mode(WL_OUTPUT_MODE_CURRENT, 2880, 1800, 60001);
+        first.set_mode(0, 1920, 1080, 60000);
+        first.logical_position = Some((0, 0));
+        first.logical_size = Some((1920, 1200));
+        first.done = true;
+
+        let mut second = WaylandOutput::new(2, 20, 0);
+        second.make = "Dell".to_string();
+        second.model = "U2415".to_string();
+        second.position = (1920, 0);
+        second.scale = 2;
+        second.transform = 1;
+        second.set_mode(WL_OUTPUT_MODE_CURRENT, 1920, 1200, 59950);
+        second.done = true;
+
+        // Outputs without informations are ignored
+        let third = WaylandOutput::new(3, 30, 0);
+
+        let displays = wayland_displays(&[first, second, third]);
+        assert_eq!(displays.list.len(), 2);
+
+        let display = &displays.list[0];
+        assert_eq!((display.handle, display.identifier.as_str(), display.primary), (0, "eDP-1", true));
+        assert_eq!((display.resolution.width, display.resolution.height, display.refresh_rate), (2880, 1800, 6000));
+        assert_eq!((display.size.width, display.size.height), (344, 194));
+        assert_eq!(display.scale(), 1.5);
+        assert_eq!(display.supported.len(), 2);
+        assert_eq!(display.supported[0].refresh_rates, vec![4800, 6000]);
+
+        // Rotated output without xdg-output
+        let display = &displays.list[1];
+        assert_eq!((display.handle, display.identifier.as_str(), display.primary), (1, "Dell U2415", false));
+        assert_eq!((display.resolution.width, display.resolution.height), (1200, 1920));
+        assert_eq!((display.position.x, display.position.y), (1920, 0));
+        assert_eq!(display.scale(), 2.0);
+
+        // Desktop is 1920x1200 beside 600x960
+        let desktop = displays.desktop.unwrap();
+        assert_eq!((desktop.current.width, desktop.current.height), (2520, 1200));
+    }
+
+    #[test]
+    fn wayland_displays_empty() {
+        let displays = wayland_displays(&[]);
+        assert!(displays.list.is_empty());
+        assert!(displays.desktop.is_none());
+    }
+}
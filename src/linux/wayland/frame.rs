@@ -22,51 +22,744 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use crate::frame::WindowFrameButtonMode;
 
-#[derive(Debug, PartialEq)]
-pub(crate) enum WaylandWindowFrame {
+//! Decorations of Wayland toplevels.
+//! 
+//! Server-side decorations are negotiated with zxdg_decoration_manager_v1. Compositors without it, like GNOME, get a 
+//! client-side frame drawn in a wl_shm buffer of a sub-surface placed below the window surface.
+//! 
+//! Reference(s)
+//! <https://wayland.app/protocols/xdg-decoration-unstable-v1>
 
+use std::{cell::RefCell, collections::VecDeque, fs::File, io::Write, os::fd::{FromRawFd, OwnedFd}, rc::Rc};
+
+use crate::{event::{WindowEvent, WindowManagerEvent}, frame::{WindowFrame, WindowFrameButtonMode}, pointer::WindowCursor, WindowHandle, WindowSize};
+
+use super::{protocol::{BTN_LEFT, WL_BUFFER, WL_BUFFER_DESTROY, WL_COMPOSITOR_CREATE_SURFACE, WL_SHM_CREATE_POOL, WL_SHM_FORMAT_ARGB8888, 
+    WL_SHM_POOL, WL_SHM_POOL_CREATE_BUFFER, WL_SHM_POOL_DESTROY, WL_SUBCOMPOSITOR_GET_SUBSURFACE, WL_SUBSURFACE, WL_SUBSURFACE_DESTROY, 
+    WL_SUBSURFACE_PLACE_BELOW, WL_SUBSURFACE_SET_DESYNC, WL_SUBSURFACE_SET_POSITION, WL_SURFACE, WL_SURFACE_ATTACH, WL_SURFACE_COMMIT, 
+    WL_SURFACE_DAMAGE, WL_SURFACE_DESTROY, XDG_SURFACE_SET_WINDOW_GEOMETRY, XDG_TOPLEVEL_MOVE, XDG_TOPLEVEL_RESIZE, XDG_TOPLEVEL_RESIZE_EDGE_BOTTOM, 
+    XDG_TOPLEVEL_RESIZE_EDGE_LEFT, XDG_TOPLEVEL_RESIZE_EDGE_RIGHT, XDG_TOPLEVEL_RESIZE_EDGE_TOP, XDG_TOPLEVEL_SET_MAXIMIZED, XDG_TOPLEVEL_SET_MINIMIZED, 
+    XDG_TOPLEVEL_UNSET_MAXIMIZED, ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION, ZXDG_TOPLEVEL_DECORATION_V1, ZXDG_TOPLEVEL_DECORATION_V1_DESTROY, 
+    ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE, ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE, ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE}, 
+    wlib::{WaylandArg, WaylandConnection, WaylandId}};
+
+/// Height of the client-side title bar.
+pub(crate) const WL_CSD_TITLE_HEIGHT : i32 = 30;
+
+/// Width of the invisible resize border around the client-side frame.
+pub(crate) const WL_CSD_BORDER : i32 = 8;
+
+/// Width of a client-side title bar button.
+pub(crate) const WL_CSD_BUTTON_WIDTH : i32 = 40;
+
+/// Size of the glyph drawn in a button.
+const WL_CSD_GLYPH_SIZE : i32 = 10;
+
+/// Color of the title bar as ARGB8888.
+const WL_CSD_COLOR_TITLE : u32 = 0xFF30_3030;
+
+/// Color of a button under pointer.
+const WL_CSD_COLOR_HOVER : u32 = 0xFF50_5050;
+
+/// Color of the close button under pointer.
+const WL_CSD_COLOR_CLOSE_HOVER : u32 = 0xFFC0_3030;
+
+/// Color of buttons glyph.
+const WL_CSD_COLOR_GLYPH : u32 = 0xFFE0_E0E0;
+
+/// Color of disabled buttons glyph.
+const WL_CSD_COLOR_DISABLED : u32 = 0xFF70_7070;
+
+/// Who draws the decorations of a toplevel.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum WaylandDecorationMode {
+    /// Compositor didn't answer the mode requested yet.
+    None,
+
+    /// Compositor draws the decorations.
+    Server,
+
+    /// Decorations are drawn by nswindow.
+    Client,
+}
+
+/// Part of the client-side frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum WaylandFrameArea {
+    /// Outside the frame or under the window surface.
+    None,
+
+    /// Title bar, used to move the window.
+    Title,
+
+    /// Resize border with xdg_toplevel resize edges.
+    Edge(u32),
+
+    /// Minimize button
+    Min,
+
+    /// Maximize button
+    Max,
+
+    /// Close button
+    Close,
+}
+
+/// Globals used to decorate toplevels. 0 if not advertised by the compositor.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct WaylandDecorationGlobals {
+    /// wl_compositor global
+    pub compositor : WaylandId,
+
+    /// wl_subcompositor global
+    pub subcompositor : WaylandId,
+
+    /// wl_shm global
+    pub shm : WaylandId,
+
+    /// zxdg_decoration_manager_v1 global
+    pub manager : WaylandId,
+}
+
+/// Decorations of a toplevel, shared between [WaylandWindow](super::window::WaylandWindow) and [WaylandWindowFrame].
+#[derive(Debug)]
+pub(crate) struct WaylandDecoration {
+    /// Connection shared with the manager
+    connection : Rc<RefCell<WaylandConnection>>,
+
+    /// Event queue shared with the manager
+    events : Rc<RefCell<VecDeque<WindowManagerEvent>>>,
+
+    /// Globals used to decorate
+    globals : WaylandDecorationGlobals,
+
+    /// Handle of the window decorated
+    handle : WindowHandle,
+
+    /// wl_surface of the window
+    surface : WaylandId,
+
+    /// xdg_surface of the window
+    xdg_surface : WaylandId,
+
+    /// xdg_toplevel of the window
+    toplevel : WaylandId,
+
+    /// zxdg_toplevel_decoration_v1 object, 0 without decoration manager.
+    pub(crate) decoration : WaylandId,
+
+    /// Mode negotiated with the compositor
+    pub(crate) mode : WaylandDecorationMode,
+
+    /// wl_surface of the client-side frame, 0 if not drawn.
+    pub(crate) frame_surface : WaylandId,
+
+    /// wl_subsurface of the client-side frame
+    subsurface : WaylandId,
+
+    /// wl_buffer attached to the client-side frame
+    buffer : WaylandId,
+
+    /// Window surface is mapped, parent commits are needed to apply frame position.
+    pub(crate) shown : bool,
+
+    /// Frame is visible
+    visible : bool,
+
+    /// Frame can resize the window
+    resizable : bool,
+
+    /// Mode of the min button
+    min_button : WindowFrameButtonMode,
+
+    /// Mode of the max button
+    max_button : WindowFrameButtonMode,
+
+    /// Mode of the close button
+    close_button : WindowFrameButtonMode,
+
+    /// Size of the window content
+    size : WindowSize,
+
+    /// Window is maximized
+    maximized : bool,
+
+    /// Window is fullscreen
+    fullscreen : bool,
+
+    /// Area under pointer
+    hover : WaylandFrameArea,
+
+    /// Button pressed, action happens if released over it.
+    pressed : WaylandFrameArea,
+}
+
+impl WaylandDecoration {
+    /// Create decorations of a toplevel with [WindowFrame] properties.
+    /// 
+    /// With a decoration manager, the mode is requested and applied when the compositor answers. Otherwise the frame is client-side.
+    pub(crate) fn new(connection : Rc<RefCell<WaylandConnection>>, events : Rc<RefCell<VecDeque<WindowManagerEvent>>>, handle : WindowHandle, 
+        globals : WaylandDecorationGlobals, surface : WaylandId, xdg_surface : WaylandId, toplevel : WaylandId, frame : &WindowFrame) -> WaylandDecoration {
+
+        let mut decoration = WaylandDecoration { connection, events, globals, handle, surface, xdg_surface, toplevel, decoration : 0, 
+            mode : if globals.manager == 0 { WaylandDecorationMode::Client } else { WaylandDecorationMode::None }, 
+            frame_surface : 0, subsurface : 0, buffer : 0, shown : false, visible : frame.visible, resizable : frame.resizable, 
+            min_button : frame.min_button, max_button : frame.max_button, close_button : frame.close_button, size : WindowSize::new(0, 0), 
+            maximized : false, fullscreen : false, hover : WaylandFrameArea::None, pressed : WaylandFrameArea::None };
+
+        if globals.manager != 0 {
+            let mut conn = decoration.connection.borrow_mut();
+            let version = conn.version(globals.manager);
+            let id = conn.new_object(&ZXDG_TOPLEVEL_DECORATION_V1, version);
+            conn.send(globals.manager, ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION, vec![WaylandArg::NewId(id), WaylandArg::Object(toplevel)]);
+            drop(conn);
+
+            decoration.decoration = id;
+            decoration.request_mode();
+        }
+
+        decoration
+    }
+
+    /// Mode chosen by the compositor with zxdg_toplevel_decoration_v1.configure.
+    pub(crate) fn configure(&mut self, mode : u32) {
+        self.mode = match mode {
+            ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE => WaylandDecorationMode::Server,
+            _ => WaylandDecorationMode::Client,
+        };
+        self.update();
+    }
+
+    /// Returns true if the frame is drawn by nswindow.
+    pub(crate) fn client_side(&self) -> bool {
+        self.mode == WaylandDecorationMode::Client && self.visible && !self.fullscreen && self.globals.subcompositor != 0 && self.globals.shm != 0
+    }
+
+    /// Height of the client-side title bar, 0 if not drawn.
+    #[inline(always)]
+    pub(crate) fn title_height(&self) -> i32 {
+        if self.client_side() { WL_CSD_TITLE_HEIGHT } else { 0 }
+    }
+
+    /// Set the maximized and fullscreen states. Applied with [WaylandDecoration::set_size].
+    pub(crate) fn set_states(&mut self, maximized : bool, fullscreen : bool) {
+        self.maximized = maximized;
+        self.fullscreen = fullscreen;
+    }
+
+    /// Set the size of the window content and redraw the frame.
+    pub(crate) fn set_size(&mut self, size : WindowSize) {
+        self.size = size;
+        self.update();
+    }
+
+    /// Size of the client-side frame surface including resize borders.
+    pub(crate) fn frame_size(&self) -> (i32, i32) {
+        (self.size.width as i32 + 2 * WL_CSD_BORDER, self.size.height as i32 + WL_CSD_TITLE_HEIGHT + 2 * WL_CSD_BORDER)
+    }
+
+    /// Pointer moved over the client-side frame, at surface coordinates.
+    pub(crate) fn pointer_motion(&mut self, x : f64, y : f64) {
+        let area = self.area(x as i32, y as i32);
+        if area != self.hover {
+            let redraw = is_button(area) || is_button(self.hover);
+            self.hover = area;
+            if redraw {
+                self.update();
+            }
+        }
+    }
+
+    /// [WindowCursor] of the frame area under the pointer. Borders show a resize cursor.
+    pub(crate) fn cursor(&self) -> WindowCursor {
+        const TOP_LEFT : u32 = XDG_TOPLEVEL_RESIZE_EDGE_TOP | XDG_TOPLEVEL_RESIZE_EDGE_LEFT;
+        const BOTTOM_RIGHT : u32 = XDG_TOPLEVEL_RESIZE_EDGE_BOTTOM | XDG_TOPLEVEL_RESIZE_EDGE_RIGHT;
+        const TOP_RIGHT : u32 = XDG_TOPLEVEL_RESIZE_EDGE_TOP | XDG_TOPLEVEL_RESIZE_EDGE_RIGHT;
+        const BOTTOM_LEFT : u32 = XDG_TOPLEVEL_RESIZE_EDGE_BOTTOM | XDG_TOPLEVEL_RESIZE_EDGE_LEFT;
+
+        match self.hover {
+            WaylandFrameArea::Edge(TOP_LEFT | BOTTOM_RIGHT) => WindowCursor::DiagonalResizeTLBR,
+            WaylandFrameArea::Edge(TOP_RIGHT | BOTTOM_LEFT) => WindowCursor::DiagonalResizeBLTR,
+            WaylandFrameArea::Edge(XDG_TOPLEVEL_RESIZE_EDGE_LEFT | XDG_TOPLEVEL_RESIZE_EDGE_RIGHT) => WindowCursor::HorizontalResize,
+            WaylandFrameArea::Edge(_) => WindowCursor::VerticalResize,
+            _ => WindowCursor::Normal,
+        }
+    }
+
+    /// Pointer left the client-side frame.
+    pub(crate) fn pointer_leave(&mut self) {
+        self.pressed = WaylandFrameArea::None;
+        self.pointer_motion(-1.0, -1.0);
+    }
+
+    /// Pointer button pressed or released over the client-side frame.
+    /// 
+    /// Title bar and borders start an interactive move or resize with the `seat` and `serial` of the press.
+    pub(crate) fn pointer_button(&mut self, seat : WaylandId, serial : u32, button : u32, pressed : bool) {
+        if button != BTN_LEFT {
+            return;
+        }
+
+        if pressed {
+            match self.hover {
+                WaylandFrameArea::Title => self.request(self.toplevel, XDG_TOPLEVEL_MOVE, vec![WaylandArg::Object(seat), WaylandArg::Uint(serial)]),
+                WaylandFrameArea::Edge(edges) => self.request(self.toplevel, XDG_TOPLEVEL_RESIZE, vec![WaylandArg::Object(seat), 
+                    WaylandArg::Uint(serial), WaylandArg::Uint(edges)]),
+                area => self.pressed = area,
+            }
+        } else {
+            let area = std::mem::replace(&mut self.pressed, WaylandFrameArea::None);
+            if area == self.hover {
+                self.button_action(area);
+            }
+        }
+    }
+
+    /// Show the frame. Server-side frame is requested again.
+    pub(crate) fn show(&mut self) {
+        self.visible = true;
+        self.request_mode();
+        self.update();
+    }
+
+    /// Hide the frame. Compositor is asked to let the client draw, which draws nothing.
+    pub(crate) fn hide(&mut self) {
+        self.visible = false;
+        self.request_mode();
+        self.update();
+    }
+
+    /// Allow or prevent resizing from the client-side borders.
+    pub(crate) fn set_resizable(&mut self, resizable : bool) {
+        self.resizable = resizable;
+    }
+
+    /// Set the mode of the min button.
+    pub(crate) fn set_button_min(&mut self, mode : WindowFrameButtonMode) {
+        self.min_button = mode;
+        self.update();
+    }
+
+    /// Set the mode of the max button.
+    pub(crate) fn set_button_max(&mut self, mode : WindowFrameButtonMode) {
+        self.max_button = mode;
+        self.update();
+    }
+
+    /// Set the mode of the close button.
+    pub(crate) fn set_button_close(&mut self, mode : WindowFrameButtonMode) {
+        self.close_button = mode;
+        self.update();
+    }
+
+    /// Reset the frame to [WindowFrame] defaults.
+    pub(crate) fn reset(&mut self) {
+        let frame = WindowFrame::new();
+        self.resizable = frame.resizable;
+        self.min_button = frame.min_button;
+        self.max_button = frame.max_button;
+        self.close_button = frame.close_button;
+
+        if frame.visible { self.show() } else { self.hide() }
+    }
+
+    /// Destroy decorations objects. Must be called before the toplevel is destroyed.
+    pub(crate) fn destroy(&mut self) {
+        self.destroy_frame();
+
+        if self.decoration != 0 {
+            self.request(self.decoration, ZXDG_TOPLEVEL_DECORATION_V1_DESTROY, vec![]);
+            self.connection.borrow_mut().destroyed(self.decoration);
+            self.decoration = 0;
+        }
+    }
+
+    /// Area of the client-side frame at surface coordinates.
+    pub(crate) fn area(&self, x : i32, y : i32) -> WaylandFrameArea {
+        let (width, height) = self.frame_size();
+        if x < 0 || y < 0 || x >= width || y >= height {
+            return WaylandFrameArea::None;
+        }
+
+        let vertical = if y < WL_CSD_BORDER { XDG_TOPLEVEL_RESIZE_EDGE_TOP } else if y >= height - WL_CSD_BORDER { XDG_TOPLEVEL_RESIZE_EDGE_BOTTOM } else { 0 };
+        let horizontal = if x < WL_CSD_BORDER { XDG_TOPLEVEL_RESIZE_EDGE_LEFT } else if x >= width - WL_CSD_BORDER { XDG_TOPLEVEL_RESIZE_EDGE_RIGHT } else { 0 };
+
+        if vertical | horizontal != 0 {
+            // Maximized windows can't be resized.
+            return if self.resizable && !self.maximized { WaylandFrameArea::Edge(vertical | horizontal) } else { WaylandFrameArea::None };
+        }
+
+        if y < WL_CSD_BORDER + WL_CSD_TITLE_HEIGHT {
+            match self.buttons().into_iter().find(|(_, bx)| x >= *bx && x < bx + WL_CSD_BUTTON_WIDTH) {
+                Some((area, _)) => area,
+                None => WaylandFrameArea::Title,
+            }
+        } else {
+            WaylandFrameArea::None
+        }
+    }
+
+    /// Pixels of the client-side frame as ARGB8888. Resize borders are transparent.
+    pub(crate) fn pixels(&self) -> Vec<u32> {
+        let (width, height) = self.frame_size();
+        let mut pixels = vec![0u32; (width * height).max(0) as usize];
+
+        fill_rect(&mut pixels, width, WL_CSD_BORDER, WL_CSD_BORDER, self.size.width as i32, WL_CSD_TITLE_HEIGHT, WL_CSD_COLOR_TITLE);
+
+        for (area, x) in self.buttons() {
+            let mode = self.button_mode(area);
+
+            if area == self.hover && mode != WindowFrameButtonMode::Disable {
+                let color = if area == WaylandFrameArea::Close { WL_CSD_COLOR_CLOSE_HOVER } else { WL_CSD_COLOR_HOVER };
+                fill_rect(&mut pixels, width, x, WL_CSD_BORDER, WL_CSD_BUTTON_WIDTH, WL_CSD_TITLE_HEIGHT, color);
+            }
+
+            let color = if mode == WindowFrameButtonMode::Disable { WL_CSD_COLOR_DISABLED } else { WL_CSD_COLOR_GLYPH };
+            draw_glyph(&mut pixels, width, area, x + (WL_CSD_BUTTON_WIDTH - WL_CSD_GLYPH_SIZE) / 2, 
+                WL_CSD_BORDER + (WL_CSD_TITLE_HEIGHT - WL_CSD_GLYPH_SIZE) / 2, color);
+        }
+
+        pixels
+    }
+
+    /// Visible buttons with their x position, from right to left.
+    fn buttons(&self) -> Vec<(WaylandFrameArea, i32)> {
+        let mut x = WL_CSD_BORDER + self.size.width as i32;
+        let mut buttons : Vec<(WaylandFrameArea, i32)> = Vec::new();
+
+        for area in [WaylandFrameArea::Close, WaylandFrameArea::Max, WaylandFrameArea::Min] {
+            if self.button_mode(area) != WindowFrameButtonMode::Hidden {
+                x -= WL_CSD_BUTTON_WIDTH;
+                buttons.push((area, x));
+            }
+        }
+
+        buttons
+    }
+
+    /// [WindowFrameButtonMode] of a button area.
+    fn button_mode(&self, area : WaylandFrameArea) -> WindowFrameButtonMode {
+        match area {
+            WaylandFrameArea::Min => self.min_button,
+            WaylandFrameArea::Max => self.max_button,
+            WaylandFrameArea::Close => self.close_button,
+            _ => WindowFrameButtonMode::Hidden,
+        }
+    }
+
+    /// Act on a button clicked according to its [WindowFrameButtonMode].
+    fn button_action(&mut self, area : WaylandFrameArea) {
+        let event = match area {
+            WaylandFrameArea::Min => WindowEvent::MinimizeButtonPressed,
+            WaylandFrameArea::Max => WindowEvent::MaximizeButtonPressed,
+            WaylandFrameArea::Close => WindowEvent::CloseButtonPressed,
+            _ => return,
+        };
+
+        match (self.button_mode(area), area) {
+            (WindowFrameButtonMode::Overriden, _) => self.events.borrow_mut().push_back(WindowManagerEvent::new(self.handle, event)),
+            (WindowFrameButtonMode::Default, WaylandFrameArea::Min) => self.request(self.toplevel, XDG_TOPLEVEL_SET_MINIMIZED, vec![]),
+            (WindowFrameButtonMode::Default, WaylandFrameArea::Max) if self.maximized => self.request(self.toplevel, XDG_TOPLEVEL_UNSET_MAXIMIZED, vec![]),
+            (WindowFrameButtonMode::Default, WaylandFrameArea::Max) => self.request(self.toplevel, XDG_TOPLEVEL_SET_MAXIMIZED, vec![]),
+            // Same as the server-side close button.
+            (WindowFrameButtonMode::Default, _) => self.events.borrow_mut().push_back(WindowManagerEvent::new(self.handle, event)),
+            _ => {},
+        }
+    }
+
+    /// Ask the compositor to draw the frame if visible.
+    fn request_mode(&mut self) {
+        if self.decoration != 0 {
+            let mode = if self.visible { ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE } else { ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE };
+            self.request(self.decoration, ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE, vec![WaylandArg::Uint(mode)]);
+        }
+    }
+
+    /// Draw or remove the client-side frame according to mode and states.
+    fn update(&mut self) {
+        let client_side = self.client_side() && self.size.width > 0 && self.size.height > 0;
+        let drawn = self.frame_surface != 0;
+
+        if client_side {
+            self.draw_frame();
+        } else if drawn {
+            self.destroy_frame();
+        }
+
+        // Window geometry excludes resize borders. Geometry and frame position are applied with the window commit.
+        if client_side || drawn {
+            let title = if client_side { WL_CSD_TITLE_HEIGHT } else { 0 };
+            self.request(self.xdg_surface, XDG_SURFACE_SET_WINDOW_GEOMETRY, vec![WaylandArg::Int(0), WaylandArg::Int(-title), 
+                WaylandArg::Int(self.size.width as i32), WaylandArg::Int(self.size.height as i32 + title)]);
+            if self.shown {
+                self.request(self.surface, WL_SURFACE_COMMIT, vec![]);
+            }
+        }
+    }
+
+    /// Create the frame sub-surface if needed and attach a new buffer.
+    fn draw_frame(&mut self) {
+        let (width, height) = self.frame_size();
+        let pixels = self.pixels();
+        let mut conn = self.connection.borrow_mut();
+
+        if self.frame_surface == 0 {
+            let compositor_version = conn.version(self.globals.compositor);
+            self.frame_surface = conn.new_object(&WL_SURFACE, compositor_version);
+            conn.send(self.globals.compositor, WL_COMPOSITOR_CREATE_SURFACE, vec![WaylandArg::NewId(self.frame_surface)]);
+
+            self.subsurface = conn.new_object(&WL_SUBSURFACE, 1);
+            conn.send(self.globals.subcompositor, WL_SUBCOMPOSITOR_GET_SUBSURFACE, vec![WaylandArg::NewId(self.subsurface), 
+                WaylandArg::Object(self.frame_surface), WaylandArg::Object(self.surface)]);
+            conn.send(self.subsurface, WL_SUBSURFACE_SET_POSITION, vec![WaylandArg::Int(-WL_CSD_BORDER), WaylandArg::Int(-WL_CSD_BORDER - WL_CSD_TITLE_HEIGHT)]);
+            conn.send(self.subsurface, WL_SUBSURFACE_PLACE_BELOW, vec![WaylandArg::Object(self.surface)]);
+
+            // Frame is redrawn without waiting for the window commit.
+            conn.send(self.subsurface, WL_SUBSURFACE_SET_DESYNC, vec![]);
+        }
+
+        if let Some(buffer) = shm_buffer(&mut conn, self.globals.shm, width, height, &pixels) {
+            conn.send(self.frame_surface, WL_SURFACE_ATTACH, vec![WaylandArg::Object(buffer), WaylandArg::Int(0), WaylandArg::Int(0)]);
+            conn.send(self.frame_surface, WL_SURFACE_DAMAGE, vec![WaylandArg::Int(0), WaylandArg::Int(0), WaylandArg::Int(width), WaylandArg::Int(height)]);
+            conn.send(self.frame_surface, WL_SURFACE_COMMIT, vec![]);
+
+            // Previous buffer isn't used once the new one is committed.
+            if self.buffer != 0 {
+                conn.send(self.buffer, WL_BUFFER_DESTROY, vec![]);
+                conn.destroyed(self.buffer);
+            }
+            self.buffer = buffer;
+        }
+
+        // Lost connection is reported by the manager dispatch.
+        let _ = conn.flush();
+    }
+
+    /// Destroy the frame sub-surface, its surface and buffer.
+    fn destroy_frame(&mut self) {
+        if self.frame_surface != 0 {
+            let mut conn = self.connection.borrow_mut();
+            for (object, opcode) in [(self.subsurface, WL_SUBSURFACE_DESTROY), (self.frame_surface, WL_SURFACE_DESTROY), (self.buffer, WL_BUFFER_DESTROY)] {
+                if object != 0 {
+                    conn.send(object, opcode, vec![]);
+                    conn.destroyed(object);
+                }
+            }
+            let _ = conn.flush();
+
+            self.subsurface = 0;
+            self.frame_surface = 0;
+            self.buffer = 0;
+            self.hover = WaylandFrameArea::None;
+        }
+    }
+
+    /// Send a request and flush it.
+    fn request(&self, object : WaylandId, opcode : u16, args : Vec<WaylandArg>) {
+        let mut conn = self.connection.borrow_mut();
+        conn.send(object, opcode, args);
+
+        // Lost connection is reported by the manager dispatch.
+        let _ = conn.flush();
+    }
+}
+
+/// [WindowFrame](crate::frame::WindowFrame) backend of a Wayland window, changing the shared [WaylandDecoration].
+#[derive(Debug)]
+pub(crate) struct WaylandWindowFrame {
+    /// Decorations shared with the window
+    decoration : Rc<RefCell<WaylandDecoration>>,
+}
+
+impl PartialEq for WaylandWindowFrame {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.decoration, &other.decoration)
+    }
 }
 
 impl WaylandWindowFrame {
+    /// Create the frame backend of decorations.
+    pub(crate) fn new(decoration : Rc<RefCell<WaylandDecoration>>) -> WaylandWindowFrame {
+        WaylandWindowFrame { decoration }
+    }
+
     #[inline(always)]
     pub fn reset(&mut self) {
-        todo!()
+        self.decoration.borrow_mut().reset()
     }
 
     #[inline(always)]
     pub fn show(&mut self) {
-        todo!()
+        self.decoration.borrow_mut().show()
     }
 
     #[inline(always)]
     pub fn hide(&mut self) {
-        todo!()
+        self.decoration.borrow_mut().hide()
     }
 
     #[inline(always)]
     pub fn lock(&mut self) {
-        todo!()
+        self.decoration.borrow_mut().set_resizable(false)
     }
 
     #[inline(always)]
     pub fn unlock(&mut self) {
-        todo!()
+        self.decoration.borrow_mut().set_resizable(true)
     }
 
     #[inline(always)]
     pub fn set_button_min(&mut self, mode : WindowFrameButtonMode) {
-        todo!()
+        self.decoration.borrow_mut().set_button_min(mode)
     }
 
     #[inline(always)]
     pub fn set_button_max(&mut self, mode : WindowFrameButtonMode) {
-        todo!()
+        self.decoration.borrow_mut().set_button_max(mode)
     }
 
     #[inline(always)]
     pub fn set_button_close(&mut self, mode : WindowFrameButtonMode) {
-        todo!()
+        self.decoration.borrow_mut().set_button_close(mode)
+    }
+}
+
+/// Returns true if area is a button.
+#[inline(always)]
+fn is_button(area : WaylandFrameArea) -> bool {
+    matches!(area, WaylandFrameArea::Min | WaylandFrameArea::Max | WaylandFrameArea::Close)
+}
+
+/// Fill a rectangle of pixels in an image of `width`.
+fn fill_rect(pixels : &mut [u32], width : i32, x : i32, y : i32, w : i32, h : i32, color : u32) {
+    for py in y.max(0)..y + h {
+        for px in x.max(0)..(x + w).min(width) {
+            if let Some(pixel) = pixels.get_mut((py * width + px) as usize) {
+                *pixel = color;
+            }
+        }
+    }
+}
+
+/// Draw the glyph of a button with its top left corner at x, y.
+fn draw_glyph(pixels : &mut [u32], width : i32, area : WaylandFrameArea, x : i32, y : i32, color : u32) {
+    let last = WL_CSD_GLYPH_SIZE - 1;
+    for i in 0..WL_CSD_GLYPH_SIZE {
+        let points : &[(i32, i32)] = match area {
+            WaylandFrameArea::Min => &[(i, last)],
+            WaylandFrameArea::Max => &[(i, 0), (i, last), (0, i), (last, i)],
+            WaylandFrameArea::Close => &[(i, i), (last - i, i)],
+            _ => &[],
+        };
+
+        for (px, py) in points {
+            fill_rect(pixels, width, x + px, y + py, 1, 1, color);
+        }
+    }
+}
+
+/// Create a wl_buffer of ARGB8888 pixels in a memfd shared with the compositor.
+/// 
+/// Returns None if the memfd can't be created.
+pub(crate) fn shm_buffer(conn : &mut WaylandConnection, shm : WaylandId, width : i32, height : i32, pixels : &[u32]) -> Option<WaylandId> {
+    let bytes : Vec<u8> = pixels.iter().flat_map(|p| p.to_ne_bytes()).collect();
+
+    let fd = unsafe { libc::memfd_create(c"nswindow-shm".as_ptr(), libc::MFD_CLOEXEC) };
+    if fd < 0 {
+        return None;
+    }
+
+    let mut file = unsafe { File::from_raw_fd(fd) };
+    file.write_all(&bytes).ok()?;
+
+    let pool = conn.new_object(&WL_SHM_POOL, 1);
+    conn.send(shm, WL_SHM_CREATE_POOL, vec![WaylandArg::NewId(pool), WaylandArg::Fd(OwnedFd::from(file)), WaylandArg::Int(bytes.len() as i32)]);
+
+    let buffer = conn.new_object(&WL_BUFFER, 1);
+    conn.send(pool, WL_SHM_POOL_CREATE_BUFFER, vec![WaylandArg::NewId(buffer), WaylandArg::Int(0), WaylandArg::Int(width), 
+        WaylandArg::Int(height), WaylandArg::Int(width * 4), WaylandArg::Uint(WL_SHM_FORMAT_ARGB8888)]);
+
+    // Buffer keeps the memory once the pool is destroyed.
+    conn.send(pool, WL_SHM_POOL_DESTROY, vec![]);
+    conn.destroyed(pool);
+
+    Some(buffer)
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Client-side frame tests
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::VecDeque, os::unix::net::UnixStream, rc::Rc};
+
+    use crate::{frame::{WindowFrame, WindowFrameButtonMode}, WindowHandle, WindowSize};
+
+    use super::{WaylandDecoration, WaylandDecorationGlobals, WaylandFrameArea, super::wlib::WaylandConnection, WL_CSD_BORDER, WL_CSD_BUTTON_WIDTH, 
+        WL_CSD_COLOR_DISABLED, WL_CSD_COLOR_GLYPH, WL_CSD_COLOR_TITLE, WL_CSD_TITLE_HEIGHT, XDG_TOPLEVEL_RESIZE_EDGE_BOTTOM, XDG_TOPLEVEL_RESIZE_EDGE_LEFT, 
+        XDG_TOPLEVEL_RESIZE_EDGE_TOP};
+
+    /// Client-side decoration of a 200x100 window without compositor.
+    fn decoration(frame : &WindowFrame) -> WaylandDecoration {
+        let (client, _) = UnixStream::pair().unwrap();
+        let mut decoration = WaylandDecoration::new(Rc::new(RefCell::new(WaylandConnection::from_stream(client, false))), 
+            Rc::new(RefCell::new(VecDeque::new())), WindowHandle::NULL, WaylandDecorationGlobals::default(), 3, 4, 5, frame);
+        decoration.size = WindowSize::new(200, 100);
+        decoration
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn wayland_frame_area() {
+        let mut frame = WindowFrame::new();
+        frame.min_button = WindowFrameButtonMode::Hidden;
+        let mut decoration = decoration(&frame);
+
+        let right = WL_CSD_BORDER + 200;
+        let title = WL_CSD_BORDER + WL_CSD_TITLE_HEIGHT / 2;
+        assert_eq!(decoration.frame_size(), (200 + 2 * WL_CSD_BORDER, 100 + WL_CSD_TITLE_HEIGHT + 2 * WL_CSD_BORDER));
+        assert_eq!(decoration.area(right - 1, title), WaylandFrameArea::Close);
+        assert_eq!(decoration.area(right - WL_CSD_BUTTON_WIDTH - 1, title), WaylandFrameArea::Max);
+
+        // Hidden min button leaves the title bar
+        assert_eq!(decoration.area(right - 2 * WL_CSD_BUTTON_WIDTH - 1, title), WaylandFrameArea::Title);
+        assert_eq!(decoration.area(WL_CSD_BORDER, title), WaylandFrameArea::Title);
+
+        // Window surface covers the frame below the title bar
+        assert_eq!(decoration.area(100, WL_CSD_BORDER + WL_CSD_TITLE_HEIGHT + 10), WaylandFrameArea::None);
+        assert_eq!(decoration.area(-1, 0), WaylandFrameArea::None);
+
+        assert_eq!(decoration.area(0, 0), WaylandFrameArea::Edge(XDG_TOPLEVEL_RESIZE_EDGE_TOP | XDG_TOPLEVEL_RESIZE_EDGE_LEFT));
+        assert_eq!(decoration.area(100, decoration.frame_size().1 - 1), WaylandFrameArea::Edge(XDG_TOPLEVEL_RESIZE_EDGE_BOTTOM));
+
+        // Locked or maximized frame can't resize
+        decoration.set_resizable(false);
+        assert_eq!(decoration.area(0, 0), WaylandFrameArea::None);
+        decoration.set_resizable(true);
+        decoration.set_states(true, false);
+        assert_eq!(decoration.area(0, 0), WaylandFrameArea::None);
+    }
+
+    #[test]
+    fn wayland_frame_pixels() {
+        let mut frame = WindowFrame::new();
+        frame.max_button = WindowFrameButtonMode::Disable;
+        let decoration = decoration(&frame);
+
+        let (width, height) = decoration.frame_size();
+        let pixels = decoration.pixels();
+        assert_eq!(pixels.len(), (width * height) as usize);
+
+        // Borders are transparent
+        assert_eq!(pixels[0], 0);
+        assert_eq!(pixels[(WL_CSD_BORDER * width + WL_CSD_BORDER) as usize], WL_CSD_COLOR_TITLE);
+
+        // Glyphs of close and disabled max buttons
+        let row = |y : i32| &pixels[(y * width) as usize..((y + 1) * width) as usize];
+        let glyphs : Vec<u32> = (WL_CSD_BORDER..WL_CSD_BORDER + WL_CSD_TITLE_HEIGHT).flat_map(|y| row(y).to_vec()).collect();
+        assert!(glyphs.contains(&WL_CSD_COLOR_GLYPH));
+        assert!(glyphs.contains(&WL_CSD_COLOR_DISABLED));
+    }
+}
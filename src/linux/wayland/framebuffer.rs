@@ -0,0 +1,256 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Software framebuffer presented with wl_shm buffers.
+//! 
+//! Pixels are drawn directly in memory shared with the compositor. Buffers are swapped so that the client draws 
+//! in one while the compositor reads another until it releases it, the released buffer receiving the pixels changed since.
+//! 
+//! Reference(s)
+//! <https://wayland.app/protocols/wayland#wl_shm>
+
+use std::{cell::RefCell, fs::File, os::fd::{FromRawFd, OwnedFd}, rc::Rc};
+
+use crate::{WindowPosition, WindowSize};
+
+use super::{protocol::{WL_BUFFER, WL_BUFFER_DESTROY, WL_SHM_CREATE_POOL, WL_SHM_FORMAT_XRGB8888, WL_SHM_POOL, WL_SHM_POOL_CREATE_BUFFER, 
+    WL_SHM_POOL_DESTROY, WL_SURFACE_ATTACH, WL_SURFACE_COMMIT, WL_SURFACE_DAMAGE, WL_SURFACE_DAMAGE_BUFFER}, 
+    wlib::{WaylandArg, WaylandConnection, WaylandId}};
+
+/// Buffer shared with the compositor, its pixels mapped in client memory.
+#[derive(Debug)]
+struct WaylandShmBuffer {
+    /// wl_buffer object
+    buffer : WaylandId,
+
+    /// XRGB8888 pixels mapped with mmap
+    pixels : *mut u32,
+
+    /// Count of pixels
+    len : usize,
+
+    /// Attached and not released by the compositor yet.
+    busy : bool,
+}
+
+/// Buffers of a window software framebuffer.
+#[derive(Debug)]
+pub(crate) struct WaylandFramebuffer {
+    /// Connection shared with the manager
+    connection : Rc<RefCell<WaylandConnection>>,
+
+    /// wl_shm global, 0 if not advertised.
+    shm : WaylandId,
+
+    /// wl_surface buffers are attached to
+    surface : WaylandId,
+
+    /// Size in pixels of buffers
+    size : WindowSize,
+
+    /// Buffers allocated for the current size
+    buffers : Vec<WaylandShmBuffer>,
+
+    /// Index of buffer drawn by client
+    back : Option<usize>,
+
+    /// Index of the last buffer presented
+    front : Option<usize>,
+
+    /// Index of the buffer presented before front, missing only the damage of front.
+    previous : Option<usize>,
+
+    /// Damage presented with front
+    damage : Vec<(WindowPosition, WindowSize)>,
+}
+
+impl WaylandFramebuffer {
+
+    /// Create a framebuffer for surface. Buffers are allocated when first drawn.
+    pub(crate) fn new(connection : Rc<RefCell<WaylandConnection>>, shm : WaylandId, surface : WaylandId) -> WaylandFramebuffer {
+        WaylandFramebuffer { connection, shm, surface, size : WindowSize::new(0, 0), buffers : Vec::new(), back : None, front : None, 
+            previous : None, damage : Vec::new() }
+    }
+
+    /// Returns the pixels of the back buffer of `size`, reallocating buffers if size changed.
+    /// 
+    /// Returns None if wl_shm isn't available or memory can't be shared.
+    pub(crate) fn pixels(&mut self, size : WindowSize) -> Option<&mut [u32]> {
+        if size != self.size {
+            self.destroy();
+            self.size = size;
+        }
+
+        let back = match self.back {
+            Some(back) => back,
+            None => self.acquire()?,
+        };
+        self.back = Some(back);
+
+        let buffer = &self.buffers[back];
+        Some(unsafe { std::slice::from_raw_parts_mut(buffer.pixels, buffer.len) })
+    }
+
+    /// Attach the back buffer of `size` with the damage rectangles, clipped to size, and commit it.
+    /// 
+    /// Returns false if the back buffer couldn't be allocated.
+    pub(crate) fn present(&mut self, size : WindowSize, damage : &[(WindowPosition, WindowSize)]) -> bool {
+        if self.pixels(size).is_none() {
+            return false;
+        }
+        let Some(back) = self.back.take() else {
+            return false;
+        };
+
+        {
+            let mut conn = self.connection.borrow_mut();
+            conn.send(self.surface, WL_SURFACE_ATTACH, vec![WaylandArg::Object(self.buffers[back].buffer), WaylandArg::Int(0), WaylandArg::Int(0)]);
+
+            // wl_surface.damage_buffer exists since version 4, surface coordinates are unknown to the framebuffer before.
+            if conn.version(self.surface) >= 4 {
+                for (position, size) in damage {
+                    conn.send(self.surface, WL_SURFACE_DAMAGE_BUFFER, vec![WaylandArg::Int(position.x), WaylandArg::Int(position.y), 
+                        WaylandArg::Int(size.width as i32), WaylandArg::Int(size.height as i32)]);
+                }
+            } else {
+                conn.send(self.surface, WL_SURFACE_DAMAGE, vec![WaylandArg::Int(0), WaylandArg::Int(0), WaylandArg::Int(i32::MAX), WaylandArg::Int(i32::MAX)]);
+            }
+            conn.send(self.surface, WL_SURFACE_COMMIT, vec![]);
+
+            // Lost connection is reported by the manager dispatch.
+            let _ = conn.flush();
+        }
+
+        self.buffers[back].busy = true;
+        self.previous = if self.front == Some(back) { None } else { self.front };
+        self.front = Some(back);
+        self.damage = damage.to_vec();
+        true
+    }
+
+    /// Compositor released a wl_buffer. Returns true if it belongs to this framebuffer.
+    pub(crate) fn release(&mut self, buffer : WaylandId) -> bool {
+        match self.buffers.iter_mut().find(|b| b.buffer == buffer) {
+            Some(b) => {
+                b.busy = false;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Destroy all buffers. Content of the framebuffer is lost.
+    pub(crate) fn destroy(&mut self) {
+        let mut conn = self.connection.borrow_mut();
+        for buffer in self.buffers.drain(..) {
+            if conn.alive(buffer.buffer) {
+                conn.send(buffer.buffer, WL_BUFFER_DESTROY, vec![]);
+                conn.destroyed(buffer.buffer);
+            }
+            unsafe { libc::munmap(buffer.pixels as *mut libc::c_void, buffer.len * 4) };
+        }
+        self.back = None;
+        self.front = None;
+        self.previous = None;
+        self.damage.clear();
+    }
+
+    /// Pick a buffer released by the compositor to draw in, with the pixels of front.
+    fn acquire(&mut self) -> Option<usize> {
+        let released = |index : Option<usize>| index.filter(|i| !self.buffers[*i].busy);
+
+        // Front released already has the pixels and previous only misses the damage of front.
+        if let Some(front) = released(self.front) {
+            return Some(front);
+        }
+        if let (Some(previous), Some(front)) = (released(self.previous), self.front) {
+            self.copy(front, previous, &self.damage.clone());
+            return Some(previous);
+        }
+
+        let index = match self.buffers.iter().position(|b| !b.busy) {
+            Some(index) => index,
+            None => {
+                let buffer = self.allocate()?;
+                self.buffers.push(buffer);
+                self.buffers.len() - 1
+            },
+        };
+        if let Some(front) = self.front {
+            self.copy(front, index, &[(WindowPosition::new(0, 0), self.size)]);
+        }
+        if self.previous == Some(index) {
+            self.previous = None;
+        }
+        Some(index)
+    }
+
+    /// Copy the pixels within rectangles from a buffer to another.
+    fn copy(&self, from : usize, to : usize, rects : &[(WindowPosition, WindowSize)]) {
+        let width = self.size.width as usize;
+        let (from, to) = (self.buffers[from].pixels, self.buffers[to].pixels);
+
+        for (position, size) in rects {
+            for row in position.y as usize..(position.y as usize + size.height as usize) {
+                let start = row * width + position.x as usize;
+                unsafe { std::ptr::copy_nonoverlapping(from.add(start), to.add(start), size.width as usize) };
+            }
+        }
+    }
+
+    /// Create a wl_buffer of XRGB8888 pixels of the framebuffer size, mapped in a memfd shared with the compositor.
+    fn allocate(&self) -> Option<WaylandShmBuffer> {
+        if self.shm == 0 || self.size.width == 0 || self.size.height == 0 {
+            return None;
+        }
+        let (width, height) = (self.size.width as i32, self.size.height as i32);
+        let len = self.size.width as usize * self.size.height as usize;
+
+        let fd = unsafe { libc::memfd_create(c"nswindow-framebuffer".as_ptr(), libc::MFD_CLOEXEC) };
+        if fd < 0 {
+            return None;
+        }
+        let file = unsafe { File::from_raw_fd(fd) };
+        file.set_len(len as u64 * 4).ok()?;
+
+        let pixels = unsafe { libc::mmap(std::ptr::null_mut(), len * 4, libc::PROT_READ | libc::PROT_WRITE, libc::MAP_SHARED, fd, 0) };
+        if pixels == libc::MAP_FAILED {
+            return None;
+        }
+
+        let mut conn = self.connection.borrow_mut();
+        let pool = conn.new_object(&WL_SHM_POOL, 1);
+        conn.send(self.shm, WL_SHM_CREATE_POOL, vec![WaylandArg::NewId(pool), WaylandArg::Fd(OwnedFd::from(file)), WaylandArg::Int(len as i32 * 4)]);
+
+        let buffer = conn.new_object(&WL_BUFFER, 1);
+        conn.send(pool, WL_SHM_POOL_CREATE_BUFFER, vec![WaylandArg::NewId(buffer), WaylandArg::Int(0), WaylandArg::Int(width), 
+            WaylandArg::Int(height), WaylandArg::Int(width * 4), WaylandArg::Uint(WL_SHM_FORMAT_XRGB8888)]);
+
+        // Buffer keeps the memory once the pool is destroyed.
+        conn.send(pool, WL_SHM_POOL_DESTROY, vec![]);
+        conn.destroyed(pool);
+
+        Some(WaylandShmBuffer { buffer, pixels : pixels as *mut u32, len, busy : false })
+    }
+}
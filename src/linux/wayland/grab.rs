@@ -0,0 +1,96 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Exclusive input grab of windows with pointer-constraints and keyboard-shortcuts-inhibit.
+//! 
+//! Wayland clients can't grab the seat. Instead the pointer is locked on the window surface and compositor 
+//! shortcuts are inhibited, so that all inputs go to the window while it has the focus.
+//! 
+//! Reference(s)
+//! <https://wayland.app/protocols/pointer-constraints-unstable-v1>
+//! <https://wayland.app/protocols/keyboard-shortcuts-inhibit-unstable-v1>
+
+use std::{cell::RefCell, rc::Rc};
+
+use super::{pointer::WaylandPointerState, protocol::{ZWP_KEYBOARD_SHORTCUTS_INHIBITOR_V1, ZWP_KEYBOARD_SHORTCUTS_INHIBITOR_V1_DESTROY, 
+    ZWP_KEYBOARD_SHORTCUTS_INHIBIT_MANAGER_V1_INHIBIT_SHORTCUTS}, wlib::{WaylandArg, WaylandConnection, WaylandId}};
+
+/// Input grab state of the seat, shared with windows.
+#[derive(Debug)]
+pub(crate) struct WaylandGrab {
+    /// Connection with the compositor
+    connection : Rc<RefCell<WaylandConnection>>,
+
+    /// Pointer state of the seat, locking the pointer of grabbed surfaces.
+    pointer : Rc<RefCell<WaylandPointerState>>,
+
+    /// zwp_keyboard_shortcuts_inhibit_manager_v1 global, 0 if not advertised.
+    pub shortcuts_manager : WaylandId,
+
+    /// wl_seat global
+    pub seat : WaylandId,
+
+    /// Shortcuts inhibitors as (wl_surface, zwp_keyboard_shortcuts_inhibitor_v1) of grabbed surfaces.
+    inhibitors : Vec<(WaylandId, WaylandId)>,
+}
+
+impl WaylandGrab {
+
+    /// Create the grab state without globals.
+    pub fn new(connection : Rc<RefCell<WaylandConnection>>, pointer : Rc<RefCell<WaylandPointerState>>) -> WaylandGrab {
+        WaylandGrab { connection, pointer, shortcuts_manager : 0, seat : 0, inhibitors : Vec::new() }
+    }
+
+    /// Lock the pointer on `surface` and inhibit compositor shortcuts of the seat. 
+    /// 
+    /// Returns false if the compositor lacks pointer-constraints or keyboard-shortcuts-inhibit, or if there is no seat.
+    pub fn grab(&mut self, surface : WaylandId) -> bool {
+        if self.shortcuts_manager == 0 || self.seat == 0 || !self.pointer.borrow_mut().grab(surface, true) {
+            return false;
+        }
+
+        if !self.inhibitors.iter().any(|(s, _)| *s == surface) {
+            let mut conn = self.connection.borrow_mut();
+            let inhibitor = conn.new_object(&ZWP_KEYBOARD_SHORTCUTS_INHIBITOR_V1, 1);
+            conn.send(self.shortcuts_manager, ZWP_KEYBOARD_SHORTCUTS_INHIBIT_MANAGER_V1_INHIBIT_SHORTCUTS, vec![WaylandArg::NewId(inhibitor), 
+                WaylandArg::Object(surface), WaylandArg::Object(self.seat)]);
+            let _ = conn.flush();
+            self.inhibitors.push((surface, inhibitor));
+        }
+        true
+    }
+
+    /// Give back the pointer constraint requested by the window and its shortcuts to the compositor. Does nothing if `surface` isn't grabbed.
+    pub fn ungrab(&mut self, surface : WaylandId) {
+        if let Some(index) = self.inhibitors.iter().position(|(s, _)| *s == surface) {
+            let (_, inhibitor) = self.inhibitors.remove(index);
+            self.pointer.borrow_mut().grab(surface, false);
+
+            let mut conn = self.connection.borrow_mut();
+            conn.send(inhibitor, ZWP_KEYBOARD_SHORTCUTS_INHIBITOR_V1_DESTROY, vec![]);
+            conn.destroyed(inhibitor);
+            let _ = conn.flush();
+        }
+    }
+}
@@ -22,27 +22,192 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use crate::keyboard::WindowKeyboardMode;
+use std::{cell::RefCell, rc::Rc, time::{Duration, Instant}};
 
+use crate::{event::{KeyCode, WindowCoordinate}, keyboard::{KeyLocks, KeyModifiers, KeyboardLayout, WindowKeyboardMode}, WindowHandle, WindowSize};
+
+use super::{text_input::WaylandTextInputState, wlib::WaylandId, xkb::{WaylandKeymap, WL_XKB_MOD_1, WL_XKB_MOD_2, WL_XKB_MOD_4, WL_XKB_MOD_CONTROL, WL_XKB_MOD_LOCK, WL_XKB_MOD_SHIFT}};
+
+/// Keysym of the Scroll Lock key. Scroll Lock isn't a modifier so it is toggled when pressed.
+const WL_KEYSYM_SCROLL_LOCK : u32 = 0xff14;
+
+/// Offset between linux evdev codes sent by wl_keyboard and xkb keycodes.
+pub(crate) const WL_EVDEV_OFFSET : KeyCode = 8;
+
+/// Key repeated client-side while pressed down.
 #[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct WaylandKeyRepeat {
+    /// [WindowHandle] of the window with keyboard focus
+    pub window : WindowHandle,
+
+    /// Key repeated
+    pub key : KeyCode,
+
+    /// Time of the next repeat
+    pub next : Instant,
+}
+
+/// Keyboard state of the seat, updated by the window manager from wl_keyboard events.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct WaylandKeyboardState {
+    /// Keymap sent by the compositor
+    pub keymap : Option<WaylandKeymap>,
+
+    /// wl_surface with keyboard focus, 0 if none.
+    pub focus : WaylandId,
+
+    /// Keys currently pressed down
+    pub pressed : Vec<KeyCode>,
+
+    /// Effective modifiers mask (depressed, latched and locked)
+    pub modifiers : u32,
+
+    /// Locked modifiers mask
+    pub locked : u32,
+
+    /// Active group (layout)
+    pub group : u32,
+
+    /// Scroll Lock state, toggled client-side.
+    pub scroll_lock : bool,
+
+    /// Repeat rate in characters per second. 0 disables repeat.
+    pub repeat_rate : i32,
+
+    /// Delay in milliseconds before a key starts repeating.
+    pub repeat_delay : i32,
+
+    /// Key currently repeating
+    pub repeat : Option<WaylandKeyRepeat>,
+}
+
+impl WaylandKeyboardState {
+
+    /// Track a key pressed or released.
+    pub fn key(&mut self, key : KeyCode, pressed : bool) {
+        if pressed {
+            if !self.pressed.contains(&key) {
+                self.pressed.push(key);
+            }
+            if self.keymap.as_ref().map(|k| k.keysym(key, self.group, 0)) == Some(WL_KEYSYM_SCROLL_LOCK) {
+                self.scroll_lock = !self.scroll_lock;
+            }
+        } else {
+            self.pressed.retain(|k| *k != key);
+            if matches!(self.repeat, Some(r) if r.key == key) {
+                self.repeat = None;
+            }
+        }
+    }
+
+    /// Start repeating a key after the repeat delay. Keys declared as not repeating like modifiers are ignored.
+    pub fn start_repeat(&mut self, window : WindowHandle, key : KeyCode) {
+        let repeats = self.keymap.as_ref().map(|k| k.repeats(key)).unwrap_or(true);
+        self.repeat = match repeats && self.repeat_rate > 0 {
+            true => Some(WaylandKeyRepeat { window, key, next : Instant::now() + Duration::from_millis(self.repeat_delay.max(0) as u64) }),
+            false => None,
+        };
+    }
+
+    /// Returns the repeated key if its repeat is due and schedule the next one.
+    pub fn next_repeat(&mut self) -> Option<WaylandKeyRepeat> {
+        let interval = Duration::from_millis(1000 / self.repeat_rate.max(1) as u64);
+        let now = Instant::now();
+
+        match &mut self.repeat {
+            Some(repeat) if repeat.next <= now => {
+                let due = *repeat;
+                // Late repeats are skipped instead of sent in burst.
+                repeat.next = (repeat.next + interval).max(now);
+                Some(due)
+            },
+            _ => None,
+        }
+    }
+
+    /// Time left before the next key repeat. None if no key is repeating.
+    pub fn repeat_timeout(&self) -> Option<Duration> {
+        self.repeat.map(|r| r.next.saturating_duration_since(Instant::now()))
+    }
+
+    /// Active [KeyboardLayout] from keymap group names.
+    pub fn layout(&self) -> KeyboardLayout {
+        match &self.keymap {
+            Some(keymap) => keymap.layout(self.group),
+            None => KeyboardLayout::default(),
+        }
+    }
+
+    /// [KeyModifiers] from effective modifiers.
+    pub fn key_modifiers(&self) -> KeyModifiers {
+        KeyModifiers::new(self.modifiers & WL_XKB_MOD_SHIFT != 0, self.modifiers & WL_XKB_MOD_CONTROL != 0, 
+            self.modifiers & WL_XKB_MOD_1 != 0, self.modifiers & WL_XKB_MOD_4 != 0)
+    }
+
+    /// [KeyLocks] from locked modifiers.
+    pub fn key_locks(&self) -> KeyLocks {
+        KeyLocks { caps : self.locked & WL_XKB_MOD_LOCK != 0, num : self.locked & WL_XKB_MOD_2 != 0, scroll : self.scroll_lock }
+    }
+}
+
+/// Keyboard of a Wayland window. State is shared with the window manager since wl_keyboard is per seat.
+#[derive(Debug, Clone)]
 pub struct WaylandKeyboard {
-    
+    /// wl_surface of the window
+    pub(crate) surface : WaylandId,
+
+    /// Seat keyboard state
+    pub(crate) state : Rc<RefCell<WaylandKeyboardState>>,
+
+    /// Seat text input state, enabled for the window in [WindowKeyboardMode::Text].
+    pub(crate) text_input : Rc<RefCell<WaylandTextInputState>>,
+}
+
+impl PartialEq for WaylandKeyboard {
+    fn eq(&self, other : &Self) -> bool {
+        self.surface == other.surface && Rc::ptr_eq(&self.state, &other.state)
+    }
 }
 
 impl WaylandKeyboard {
 
+    /// Create the keyboard of a window surface sharing the seat keyboard and text input states.
+    pub(crate) fn new(surface : WaylandId, mode : WindowKeyboardMode, state : Rc<RefCell<WaylandKeyboardState>>, 
+        text_input : Rc<RefCell<WaylandTextInputState>>) -> WaylandKeyboard {
+        text_input.borrow_mut().set_enabled(surface, mode == WindowKeyboardMode::Text);
+        WaylandKeyboard { surface, state, text_input }
+    }
+
+    /// Text input is enabled in [WindowKeyboardMode::Text] while the window has the text input focus.
     #[inline(always)]
     pub fn set_mode(&mut self, mode : WindowKeyboardMode) {
-        todo!()
+        self.text_input.borrow_mut().set_enabled(self.surface, mode == WindowKeyboardMode::Text);
+    }
+
+    /// Surrounding text is sent to the input method when text input is enabled.
+    #[inline(always)]
+    pub fn set_surrounding_text(&mut self, text : &str, cursor : usize, anchor : usize) {
+        self.text_input.borrow_mut().set_surrounding(self.surface, text, cursor, anchor);
     }
 
+    /// Cursor rectangle is sent to the input method when text input is enabled.
     #[inline(always)]
-    pub fn enable_repeat(&mut self) {
-        todo!()
+    pub fn set_text_cursor(&mut self, position : WindowCoordinate, size : WindowSize) {
+        self.text_input.borrow_mut().set_cursor(self.surface, position.x, position.y, size.width as i32, size.height as i32);
     }
 
+    /// Repeat is done client-side by the window manager which reads [WindowKeyboard::repeat](crate::keyboard::WindowKeyboard::repeat).
+    #[inline(always)]
+    pub fn enable_repeat(&mut self) {}
+
+    /// Repeat is done client-side by the window manager which reads [WindowKeyboard::repeat](crate::keyboard::WindowKeyboard::repeat).
     #[inline(always)]
-    pub fn disable_repeat(&mut self) {
-        todo!()
+    pub fn disable_repeat(&mut self) {}
+
+    /// Keys pressed, modifiers and locks from the seat keyboard state.
+    #[inline(always)]
+    pub fn query_state(&self) -> (Vec<KeyCode>, KeyModifiers, KeyLocks) {
+        let state = self.state.borrow();
+        (state.pressed.clone(), state.key_modifiers(), state.key_locks())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,582 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Wayland connection owned by libwayland-client, loaded at runtime.
+//! 
+//! Messages keep being described by [super::protocol] and handled as [WaylandMessage]. Proxies are created with the 
+//! `wl_interface` built from those descriptions, requests are sent with `wl_proxy_marshal_array` and events are decoded 
+//! by a dispatcher queuing them until the window manager handles them. Proxies have the version of `wl_display` since 
+//! libwayland doesn't let it be set, versions are tracked by [super::wlib::WaylandConnection].
+//! 
+//! Reference(s)
+//! <https://wayland.freedesktop.org/docs/html/apb.html#Client-classwl__proxy>
+//! <https://wayland.freedesktop.org/docs/html/apb.html#Client-classwl__display>
+
+use std::{collections::VecDeque, ffi::{c_char, c_int, c_void, CStr, CString}, io::{Error, ErrorKind}, os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd}};
+
+use crate::WindowError;
+
+use super::{protocol::{WaylandInterface, WL_DISPLAY_ID}, wlib::{WaylandArg, WaylandId, WaylandMessage}};
+use crate::linux::library::Library;
+
+/// `struct wl_message`
+#[repr(C)]
+#[derive(Debug)]
+struct WlMessage {
+    name : *const c_char,
+    signature : *const c_char,
+    types : *const *const WlInterface,
+}
+
+/// `struct wl_interface`
+#[repr(C)]
+#[derive(Debug)]
+struct WlInterface {
+    name : *const c_char,
+    version : c_int,
+    method_count : c_int,
+    methods : *const WlMessage,
+    event_count : c_int,
+    events : *const WlMessage,
+}
+
+/// `struct wl_array`
+#[repr(C)]
+#[derive(Debug)]
+struct WlArray {
+    size : usize,
+    alloc : usize,
+    data : *mut c_void,
+}
+
+/// `union wl_argument`
+#[repr(C)]
+#[derive(Clone, Copy)]
+union WlArgument {
+    i : i32,
+    u : u32,
+    f : i32,
+    s : *const c_char,
+    o : *mut c_void,
+    a : *mut WlArray,
+    h : RawFd,
+}
+
+/// `wl_dispatcher_func_t`
+type WlDispatcher = unsafe extern "C" fn(*const c_void, *mut c_void, u32, *const WlMessage, *mut WlArgument) -> c_int;
+
+/// Functions of libwayland-client.
+#[derive(Debug)]
+struct Libwayland {
+    display_connect : unsafe extern "C" fn(*const c_char) -> *mut c_void,
+    display_connect_to_fd : unsafe extern "C" fn(c_int) -> *mut c_void,
+    display_disconnect : unsafe extern "C" fn(*mut c_void),
+    display_get_fd : unsafe extern "C" fn(*mut c_void) -> c_int,
+    display_flush : unsafe extern "C" fn(*mut c_void) -> c_int,
+    display_prepare_read : unsafe extern "C" fn(*mut c_void) -> c_int,
+    display_read_events : unsafe extern "C" fn(*mut c_void) -> c_int,
+    display_cancel_read : unsafe extern "C" fn(*mut c_void),
+    display_dispatch_pending : unsafe extern "C" fn(*mut c_void) -> c_int,
+    proxy_create : unsafe extern "C" fn(*mut c_void, *const WlInterface) -> *mut c_void,
+    proxy_destroy : unsafe extern "C" fn(*mut c_void),
+    proxy_get_id : unsafe extern "C" fn(*mut c_void) -> u32,
+    proxy_add_dispatcher : unsafe extern "C" fn(*mut c_void, WlDispatcher, *const c_void, *mut c_void) -> c_int,
+    proxy_marshal_array : unsafe extern "C" fn(*mut c_void, u32, *mut WlArgument),
+
+    /// Functions are valid while library is open.
+    _library : Library,
+}
+
+impl Libwayland {
+    /// Load libwayland-client. None if the library or one of its functions is missing.
+    fn load() -> Option<Libwayland> {
+        let library = Library::open(&[c"libwayland-client.so.0", c"libwayland-client.so"])?;
+        unsafe {
+            Some(Libwayland {
+                display_connect : library.symbol(c"wl_display_connect")?,
+                display_connect_to_fd : library.symbol(c"wl_display_connect_to_fd")?,
+                display_disconnect : library.symbol(c"wl_display_disconnect")?,
+                display_get_fd : library.symbol(c"wl_display_get_fd")?,
+                display_flush : library.symbol(c"wl_display_flush")?,
+                display_prepare_read : library.symbol(c"wl_display_prepare_read")?,
+                display_read_events : library.symbol(c"wl_display_read_events")?,
+                display_cancel_read : library.symbol(c"wl_display_cancel_read")?,
+                display_dispatch_pending : library.symbol(c"wl_display_dispatch_pending")?,
+                proxy_create : library.symbol(c"wl_proxy_create")?,
+                proxy_destroy : library.symbol(c"wl_proxy_destroy")?,
+                proxy_get_id : library.symbol(c"wl_proxy_get_id")?,
+                proxy_add_dispatcher : library.symbol(c"wl_proxy_add_dispatcher")?,
+                proxy_marshal_array : library.symbol(c"wl_proxy_marshal_array")?,
+                _library : library,
+            })
+        }
+    }
+}
+
+/// `wl_interface` of a [WaylandInterface], with the memory its pointers refer to.
+#[derive(Debug)]
+struct WaylandLibInterface {
+    /// Interface described
+    desc : &'static WaylandInterface,
+
+    /// `wl_interface` given to libwayland, boxed to keep its address.
+    interface : Box<WlInterface>,
+
+    /// Requests followed by events
+    _messages : Vec<WlMessage>,
+
+    /// Names and signatures
+    _strings : Vec<CString>,
+
+    /// Null interface of each argument. Objects received aren't type checked since they are checked when handled.
+    _types : Vec<*const WlInterface>,
+}
+
+impl WaylandLibInterface {
+    /// Build the `wl_interface` of an interface description.
+    fn new(desc : &'static WaylandInterface) -> WaylandLibInterface {
+        let mut strings : Vec<CString> = Vec::new();
+        let mut cstr = |s : &str| -> *const c_char {
+            // Moving a CString doesn't move its characters.
+            strings.push(CString::new(s).unwrap_or_default());
+            strings.last().unwrap().as_ptr()
+        };
+
+        let count = desc.requests.iter().chain(desc.events).map(|m| m.signature.len()).max().unwrap_or(0);
+        let types : Vec<*const WlInterface> = vec![std::ptr::null(); count.max(1)];
+
+        let messages : Vec<WlMessage> = desc.requests.iter().chain(desc.events)
+            .map(|m| WlMessage { name : cstr(m.name), signature : cstr(m.signature), types : types.as_ptr() }).collect();
+
+        let interface = Box::new(WlInterface { name : cstr(desc.name), version : desc.version as c_int, 
+            method_count : desc.requests.len() as c_int, methods : messages.as_ptr(), 
+            event_count : desc.events.len() as c_int, events : messages[desc.requests.len()..].as_ptr() });
+
+        WaylandLibInterface { desc, interface, _messages : messages, _strings : strings, _types : types }
+    }
+}
+
+/// Events dispatched by libwayland and not handled yet.
+#[derive(Debug)]
+struct WaylandLibInbox {
+    /// `wl_proxy_get_id` used to give objects as ids
+    proxy_get_id : unsafe extern "C" fn(*mut c_void) -> u32,
+
+    /// Events in the order received
+    messages : VecDeque<WaylandMessage>,
+}
+
+/// Connected libwayland `wl_display` with the proxies of the objects created by nswindow.
+#[derive(Debug)]
+pub(crate) struct WaylandLibDisplay {
+    /// `struct wl_display`
+    display : *mut c_void,
+
+    /// Proxies indexed by object id, null if none.
+    proxies : Vec<*mut c_void>,
+
+    /// `wl_interface` of proxies created
+    interfaces : Vec<WaylandLibInterface>,
+
+    /// Events dispatched, freed when dropped.
+    inbox : *mut WaylandLibInbox,
+
+    /// libwayland-client
+    lib : Libwayland,
+}
+
+// libwayland locks the display for each call, so the display and its proxies can move to another thread.
+unsafe impl Send for WaylandLibDisplay {}
+
+impl Drop for WaylandLibDisplay {
+    fn drop(&mut self) {
+        unsafe {
+            for proxy in self.proxies.iter().skip(WL_DISPLAY_ID as usize + 1).filter(|p| !p.is_null()) {
+                (self.lib.proxy_destroy)(*proxy);
+            }
+            (self.lib.display_disconnect)(self.display);
+
+            // Events not handled close their file descriptors.
+            drop(Box::from_raw(self.inbox));
+        }
+    }
+}
+
+impl WaylandLibDisplay {
+
+    /// Connect to the compositor with `wl_display_connect`, which uses `$WAYLAND_SOCKET` or `$WAYLAND_DISPLAY`.
+    /// 
+    /// Returns None if libwayland-client is missing or compositor can't be connected.
+    pub fn connect() -> Option<WaylandLibDisplay> {
+        WaylandLibDisplay::with_display(|lib| unsafe { (lib.display_connect)(std::ptr::null()) })
+    }
+
+    /// Connect with `wl_display_connect_to_fd` on a socket already connected.
+    #[cfg(test)]
+    pub fn connect_to_fd(fd : OwnedFd) -> Option<WaylandLibDisplay> {
+        use std::os::fd::IntoRawFd;
+        WaylandLibDisplay::with_display(|lib| unsafe { (lib.display_connect_to_fd)(fd.into_raw_fd()) })
+    }
+
+    /// Load libwayland and connect the display with `connect`.
+    fn with_display(connect : impl FnOnce(&Libwayland) -> *mut c_void) -> Option<WaylandLibDisplay> {
+        let lib = Libwayland::load()?;
+        let display = connect(&lib);
+        if display.is_null() {
+            return None;
+        }
+
+        let inbox = Box::into_raw(Box::new(WaylandLibInbox { proxy_get_id : lib.proxy_get_id, messages : VecDeque::new() }));
+
+        // Id 0 is null and wl_display proxy is the display itself.
+        Some(WaylandLibDisplay { display, proxies : vec![std::ptr::null_mut(), display], interfaces : Vec::new(), inbox, lib })
+    }
+
+    /// `struct wl_display` pointer, given to Vulkan and EGL.
+    pub fn display(&self) -> *mut c_void {
+        self.display
+    }
+
+    /// `struct wl_proxy` pointer of an object, null if it doesn't exist.
+    pub fn proxy(&self, id : WaylandId) -> *mut c_void {
+        self.proxies.get(id as usize).copied().unwrap_or(std::ptr::null_mut())
+    }
+
+    /// File descriptor of the compositor socket.
+    pub fn fd(&self) -> RawFd {
+        unsafe { (self.lib.display_get_fd)(self.display) }
+    }
+
+    /// Create the proxy of a new object and returns its id, allocated by libwayland. Nothing is sent until the request creating 
+    /// the object is.
+    /// 
+    /// Returns 0 if the proxy couldn't be allocated, which make the request creating it fail.
+    pub fn create(&mut self, interface : &'static WaylandInterface) -> WaylandId {
+        let index = match self.interfaces.iter().position(|i| i.desc == interface) {
+            Some(index) => index,
+            None => {
+                self.interfaces.push(WaylandLibInterface::new(interface));
+                self.interfaces.len() - 1
+            },
+        };
+
+        unsafe {
+            let proxy = (self.lib.proxy_create)(self.display, &*self.interfaces[index].interface);
+            if proxy.is_null() {
+                return 0;
+            }
+            (self.lib.proxy_add_dispatcher)(proxy, wayland_lib_dispatch, self.inbox as *const c_void, std::ptr::null_mut());
+
+            let id = (self.lib.proxy_get_id)(proxy);
+            if self.proxies.len() <= id as usize {
+                self.proxies.resize(id as usize + 1, std::ptr::null_mut());
+            }
+            self.proxies[id as usize] = proxy;
+            id
+        }
+    }
+
+    /// Destroy the proxy of an object. libwayland frees the id once the compositor deletes it.
+    pub fn destroy(&mut self, id : WaylandId) {
+        if id > WL_DISPLAY_ID {
+            if let Some(proxy) = self.proxies.get_mut(id as usize).filter(|p| !p.is_null()) {
+                unsafe { (self.lib.proxy_destroy)(*proxy) };
+                *proxy = std::ptr::null_mut();
+            }
+        }
+    }
+
+    /// Marshal a request. Requests of objects without proxy are dropped. File descriptors are duplicated by libwayland.
+    pub fn send(&mut self, sender : WaylandId, opcode : u16, args : Vec<WaylandArg>) {
+        let proxy = self.proxy(sender);
+        if proxy.is_null() {
+            return;
+        }
+
+        // Memory pointed by arguments must stay valid until marshalled. Arrays are never reallocated.
+        let mut strings : Vec<CString> = Vec::new();
+        let mut arrays : Vec<WlArray> = Vec::with_capacity(args.len());
+        let mut owned : Vec<WaylandArg> = Vec::new();
+        let mut raw : Vec<WlArgument> = Vec::with_capacity(args.len());
+
+        for arg in args {
+            raw.push(match &arg {
+                WaylandArg::Int(v) => WlArgument { i : *v },
+                WaylandArg::Uint(v) => WlArgument { u : *v },
+                WaylandArg::Fixed(v) => WlArgument { f : (*v * 256.0) as i32 },
+                WaylandArg::Str(None) => WlArgument { s : std::ptr::null() },
+                WaylandArg::Str(Some(v)) => {
+                    strings.push(CString::new(v.as_str()).unwrap_or_default());
+                    WlArgument { s : strings.last().unwrap().as_ptr() }
+                },
+                WaylandArg::Object(id) | WaylandArg::NewId(id) => WlArgument { o : self.proxy(*id) },
+                WaylandArg::Array(v) => {
+                    arrays.push(WlArray { size : v.len(), alloc : v.len(), data : v.as_ptr() as *mut c_void });
+                    WlArgument { a : arrays.last_mut().unwrap() as *mut WlArray }
+                },
+                WaylandArg::Fd(fd) => WlArgument { h : fd.as_raw_fd() },
+            });
+            owned.push(arg);
+        }
+
+        unsafe { (self.lib.proxy_marshal_array)(proxy, opcode as u32, raw.as_mut_ptr()) };
+    }
+
+    /// Send requests marshalled, waiting while the socket is full.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowManagerConnectionLost`]) if connection is lost or a protocol error occurred.
+    pub fn flush(&mut self) -> Result<(), WindowError> {
+        loop {
+            if unsafe { (self.lib.display_flush)(self.display) } >= 0 {
+                return Ok(());
+            }
+            match Error::last_os_error().kind() {
+                ErrorKind::WouldBlock => { poll_fd(self.fd(), libc::POLLOUT, -1); },
+                ErrorKind::Interrupted => {},
+                _ => return Err(WindowError::WindowManagerConnectionLost),
+            }
+        }
+    }
+
+    /// Read events from the socket and dispatch them to the inbox. If `blocking` is false, returns Ok(false) when nothing was 
+    /// dispatched.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowManagerConnectionLost`]) if connection is lost or a protocol error occurred.
+    pub fn read(&mut self, blocking : bool) -> Result<bool, WindowError> {
+        unsafe {
+            // Events already read, by Vulkan or EGL on their own queue, are dispatched before reading.
+            while (self.lib.display_prepare_read)(self.display) != 0 {
+                if (self.lib.display_dispatch_pending)(self.display) < 0 {
+                    return Err(WindowError::WindowManagerConnectionLost);
+                }
+            }
+            (self.lib.display_flush)(self.display);
+
+            if poll_fd(self.fd(), libc::POLLIN, if blocking { -1 } else { 0 }) {
+                if (self.lib.display_read_events)(self.display) < 0 {
+                    return Err(WindowError::WindowManagerConnectionLost);
+                }
+            } else {
+                (self.lib.display_cancel_read)(self.display);
+            }
+
+            if (self.lib.display_dispatch_pending)(self.display) < 0 {
+                return Err(WindowError::WindowManagerConnectionLost);
+            }
+            Ok(!(*self.inbox).messages.is_empty())
+        }
+    }
+
+    /// Wait until events are available or timeout in milliseconds expire. Negative timeout wait forever.
+    /// 
+    /// Returns true if events are available.
+    pub fn poll(&self, timeout : i32) -> bool {
+        unsafe {
+            if !(*self.inbox).messages.is_empty() || (self.lib.display_prepare_read)(self.display) != 0 {
+                return true;
+            }
+            (self.lib.display_cancel_read)(self.display);
+        }
+        poll_fd(self.fd(), libc::POLLIN, timeout)
+    }
+
+    /// Take the next event dispatched.
+    pub fn next_message(&mut self) -> Option<WaylandMessage> {
+        unsafe { (*self.inbox).messages.pop_front() }
+    }
+}
+
+/// Wait until `events` are ready on `fd` or timeout in milliseconds expire.
+fn poll_fd(fd : RawFd, events : libc::c_short, timeout : i32) -> bool {
+    let mut pfd = libc::pollfd { fd, events, revents : 0 };
+    unsafe { libc::poll(&mut pfd, 1, timeout) > 0 }
+}
+
+/// Dispatcher of every proxy, queuing events in the [WaylandLibInbox] given as implementation.
+/// 
+/// Events of the protocol never create objects, so new ids aren't expected.
+unsafe extern "C" fn wayland_lib_dispatch(inbox : *const c_void, proxy : *mut c_void, opcode : u32, message : *const WlMessage, 
+    args : *mut WlArgument) -> c_int {
+    let inbox = &mut *(inbox as *mut WaylandLibInbox);
+    let proxy_get_id = inbox.proxy_get_id;
+    let get_id = |object : *mut c_void| if object.is_null() { 0 } else { proxy_get_id(object) };
+
+    let mut decoded : Vec<WaylandArg> = Vec::new();
+    let signature = CStr::from_ptr((*message).signature).to_bytes();
+    for (index, c) in signature.iter().filter(|c| c.is_ascii_alphabetic()).enumerate() {
+        let arg = *args.add(index);
+        decoded.push(match c {
+            b'i' => WaylandArg::Int(arg.i),
+            b'u' => WaylandArg::Uint(arg.u),
+            b'f' => WaylandArg::Fixed(arg.f as f64 / 256.0),
+            b's' if arg.s.is_null() => WaylandArg::Str(None),
+            b's' => WaylandArg::Str(Some(CStr::from_ptr(arg.s).to_string_lossy().to_string())),
+            b'o' => WaylandArg::Object(get_id(arg.o)),
+            b'n' => WaylandArg::NewId(get_id(arg.o)),
+            b'a' if arg.a.is_null() || (*arg.a).size == 0 => WaylandArg::Array(Vec::new()),
+            b'a' => WaylandArg::Array(std::slice::from_raw_parts((*arg.a).data as *const u8, (*arg.a).size).to_vec()),
+            // File descriptors of events dispatched belong to the client.
+            b'h' => WaylandArg::Fd(OwnedFd::from_raw_fd(arg.h)),
+            _ => return -1,
+        });
+    }
+
+    inbox.messages.push_back(WaylandMessage { sender : get_id(proxy), opcode : opcode as u16, args : decoded });
+    0
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// libwayland interfaces and dispatcher tests
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, ffi::{c_void, CStr}, fs::File, os::{fd::IntoRawFd, unix::net::UnixStream}};
+
+    use super::{wayland_lib_dispatch, WaylandLibDisplay, WaylandLibInbox, WaylandLibInterface, WlArgument, WlArray};
+    use super::super::{protocol::{WL_DISPLAY_ERROR, WL_DISPLAY_GET_REGISTRY, WL_DISPLAY_ID, WL_KEYBOARD, WL_KEYBOARD_ENTER, WL_KEYBOARD_KEYMAP, 
+        WL_REGISTRY, WL_REGISTRY_BIND, WL_REGISTRY_GLOBAL, WL_SHM, WL_SHM_CREATE_POOL, WL_SHM_POOL, WL_SURFACE, ZWP_TEXT_INPUT_V3, 
+        ZWP_TEXT_INPUT_V3_COMMIT_STRING}, wlib::{WaylandArg, WaylandConnection}};
+
+    /// Proxies of tests are the id they point to.
+    unsafe extern "C" fn proxy_get_id(proxy : *mut c_void) -> u32 {
+        *(proxy as *const u32)
+    }
+
+    #[test]
+    fn wayland_libwayland_interface() {
+        let interface = WaylandLibInterface::new(&WL_SURFACE);
+
+        unsafe {
+            assert_eq!(CStr::from_ptr(interface.interface.name).to_str().unwrap(), "wl_surface");
+            assert_eq!(interface.interface.version, WL_SURFACE.version as i32);
+            assert_eq!(interface.interface.method_count as usize, WL_SURFACE.requests.len());
+            assert_eq!(interface.interface.event_count as usize, WL_SURFACE.events.len());
+
+            // Opcodes index requests and events.
+            let attach = &*interface.interface.methods.add(1);
+            assert_eq!(CStr::from_ptr(attach.name).to_str().unwrap(), "attach");
+            assert_eq!(CStr::from_ptr(attach.signature).to_str().unwrap(), "?oii");
+            assert!((*attach.types.add(2)).is_null());
+
+            let enter = &*interface.interface.events;
+            assert_eq!(CStr::from_ptr(enter.name).to_str().unwrap(), "enter");
+        }
+    }
+
+    #[test]
+    fn wayland_libwayland_dispatch() {
+        let mut inbox = WaylandLibInbox { proxy_get_id, messages : VecDeque::new() };
+        let inbox_ptr = &mut inbox as *mut WaylandLibInbox as *const c_void;
+        let mut keyboard : u32 = 5;
+        let mut surface : u32 = 9;
+        let keyboard_ptr = &mut keyboard as *mut u32 as *mut c_void;
+        let keyboards = WaylandLibInterface::new(&WL_KEYBOARD);
+        let text_inputs = WaylandLibInterface::new(&ZWP_TEXT_INPUT_V3);
+
+        unsafe {
+            // Objects are given as ids and arrays are copied.
+            let mut keys = [30u32, 31u32];
+            let mut array = WlArray { size : 8, alloc : 8, data : keys.as_mut_ptr() as *mut c_void };
+            let mut args = [WlArgument { u : 12 }, WlArgument { o : &mut surface as *mut u32 as *mut c_void }, WlArgument { a : &mut array as *mut WlArray }];
+            let message = keyboards.interface.events.add(WL_KEYBOARD_ENTER as usize);
+            assert_eq!(wayland_lib_dispatch(inbox_ptr, keyboard_ptr, WL_KEYBOARD_ENTER as u32, message, args.as_mut_ptr()), 0);
+
+            // File descriptors are owned
+            let fd = File::open("/dev/null").unwrap().into_raw_fd();
+            let mut args = [WlArgument { u : 1 }, WlArgument { h : fd }, WlArgument { u : 64 }];
+            let message = keyboards.interface.events.add(WL_KEYBOARD_KEYMAP as usize);
+            wayland_lib_dispatch(inbox_ptr, keyboard_ptr, WL_KEYBOARD_KEYMAP as u32, message, args.as_mut_ptr());
+
+            // Nullable strings
+            let mut args = [WlArgument { s : std::ptr::null() }];
+            let message = text_inputs.interface.events.add(ZWP_TEXT_INPUT_V3_COMMIT_STRING as usize);
+            wayland_lib_dispatch(inbox_ptr, keyboard_ptr, ZWP_TEXT_INPUT_V3_COMMIT_STRING as u32, message, args.as_mut_ptr());
+        }
+
+        let enter = inbox.messages.pop_front().unwrap();
+        assert_eq!((enter.sender, enter.opcode, enter.uint(0), enter.uint(1)), (5, WL_KEYBOARD_ENTER, 12, 9));
+        assert_eq!(enter.array(2), [30u32.to_ne_bytes(), 31u32.to_ne_bytes()].concat());
+
+        let mut keymap = inbox.messages.pop_front().unwrap();
+        assert!(keymap.take_fd(1).is_some());
+        assert_eq!(keymap.uint(2), 64);
+
+        let commit = inbox.messages.pop_front().unwrap();
+        assert!(matches!(commit.args[0], WaylandArg::Str(None)));
+        assert!(inbox.messages.is_empty());
+    }
+
+    #[test]
+    fn wayland_libwayland_connection() {
+        let (client, server) = UnixStream::pair().unwrap();
+
+        // libwayland-client may not be installed.
+        let mut client = match WaylandLibDisplay::connect_to_fd(client.into()) {
+            Some(client) => client,
+            None => return,
+        };
+        let mut server = WaylandConnection::from_stream(server, true);
+
+        // Ids are allocated by libwayland and requests are marshalled with their file descriptors.
+        let registry = client.create(&WL_REGISTRY);
+        client.send(WL_DISPLAY_ID, WL_DISPLAY_GET_REGISTRY, vec![WaylandArg::NewId(registry)]);
+        let shm = client.create(&WL_SHM);
+        client.send(registry, WL_REGISTRY_BIND, vec![WaylandArg::Uint(7), WaylandArg::Str(Some("wl_shm".to_string())), 
+            WaylandArg::Uint(1), WaylandArg::NewId(shm)]);
+        let pool = client.create(&WL_SHM_POOL);
+        client.send(shm, WL_SHM_CREATE_POOL, vec![WaylandArg::NewId(pool), WaylandArg::Fd(File::open("/dev/null").unwrap().into()), 
+            WaylandArg::Int(4096)]);
+        client.flush().unwrap();
+        assert_eq!((registry, shm, pool), (2, 3, 4));
+
+        let mut requests = Vec::new();
+        while requests.len() < 3 {
+            match server.next_message() {
+                Some(message) => requests.push(message.unwrap()),
+                None => assert!(server.read(true).unwrap()),
+            }
+        }
+        assert_eq!((requests[1].sender, requests[1].string(1), requests[1].uint(3)), (registry, "wl_shm", shm));
+        assert!(matches!(requests[2].args[1], WaylandArg::Fd(_)));
+
+        // Events are dispatched to the inbox
+        server.send(registry, WL_REGISTRY_GLOBAL, vec![WaylandArg::Uint(3), WaylandArg::Str(Some("wl_seat".to_string())), WaylandArg::Uint(9)]);
+        server.flush().unwrap();
+        assert!(client.poll(1000));
+        assert!(client.read(true).unwrap());
+
+        let global = client.next_message().unwrap();
+        assert_eq!((global.sender, global.opcode, global.uint(0), global.string(1), global.uint(2)), (registry, WL_REGISTRY_GLOBAL, 3, "wl_seat", 9));
+        assert!(client.next_message().is_none());
+
+        // Protocol errors are fatal
+        server.send(WL_DISPLAY_ID, WL_DISPLAY_ERROR, vec![WaylandArg::Object(shm), WaylandArg::Uint(0), WaylandArg::Str(Some("error".to_string()))]);
+        server.flush().unwrap();
+        assert!(client.read(true).is_err());
+    }
+}
@@ -22,42 +22,2303 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use crate::{display::Displays, event::WindowManagerEvent, Window, WindowBuilder, WindowError, WindowHandle};
+use std::{cell::RefCell, collections::VecDeque, rc::Rc, time::Duration};
 
+use crate::{device::{InputDevice, InputDeviceId, InputDeviceKind, InputDevices}, display::Displays, event::{KeyCode, PointerAcceleration, WindowCoordinate, 
+    WindowEvent, WindowKeyboardEvent, WindowManagerEvent, WindowPointerEvent}, keyboard::{HotkeyId, KeyModifiers, KeyboardLayout}, 
+    linux::{frame::LinuxWindowFrame, keyboard::LinuxKeyboard, pointer::LinuxPointer, window::LinuxWindow}, pointer::WindowPointerMode, window::WindowHandles, Window, WindowBuilder, WindowError, WindowHandle};
+
+#[cfg(not(feature = "single_opt"))]
+use super::popup::WaylandPositioner;
+
+use super::{activation::{WaylandActivation, WL_ACTIVATION_TOKEN_ENV}, display::{wayland_displays, WaylandOutput}, frame::{WaylandDecorationGlobals, WaylandWindowFrame}, grab::WaylandGrab, 
+    keyboard::{WaylandKeyboard, WaylandKeyboardState, WL_EVDEV_OFFSET}, pointer::{wayland_pointer_button, WaylandPointerGlobals, WaylandPointer, WaylandPointerState}, 
+    protocol::{WaylandInterface, ZWP_KEYBOARD_SHORTCUTS_INHIBIT_MANAGER_V1, WL_CALLBACK, WL_CALLBACK_DONE, WL_COMPOSITOR, WL_DISPLAY_DELETE_ID, 
+    WL_DISPLAY_ERROR, WL_DISPLAY_GET_REGISTRY, WL_DISPLAY_ID, WL_DISPLAY_SYNC, WL_OUTPUT, WL_OUTPUT_DESCRIPTION, WL_OUTPUT_DONE, WL_OUTPUT_GEOMETRY, 
+    WL_OUTPUT_MODE, WL_OUTPUT_NAME, WL_OUTPUT_RELEASE, WL_OUTPUT_SCALE, WL_REGISTRY, WL_REGISTRY_BIND, WL_REGISTRY_GLOBAL, WL_REGISTRY_GLOBAL_REMOVE, 
+    WL_KEYBOARD, WL_KEYBOARD_ENTER, WL_KEYBOARD_KEY, WL_KEYBOARD_KEYMAP, WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1, WL_KEYBOARD_KEY_STATE_PRESSED, 
+    WL_KEYBOARD_LEAVE, WL_KEYBOARD_MODIFIERS, WL_KEYBOARD_RELEASE, WL_KEYBOARD_REPEAT_INFO, 
+    WL_POINTER_AXIS, WL_POINTER_AXIS_DISCRETE, WL_POINTER_AXIS_HORIZONTAL_SCROLL, WL_POINTER_AXIS_VALUE120, WL_POINTER_BUTTON, 
+    WL_POINTER_BUTTON_STATE_PRESSED, WL_POINTER_ENTER, WL_POINTER_FRAME, WL_POINTER_LEAVE, WL_POINTER_MOTION, WL_SEAT, 
+    WL_SEAT_CAPABILITIES, WL_SEAT_CAPABILITY_KEYBOARD, WL_SEAT_CAPABILITY_POINTER, WL_SEAT_GET_KEYBOARD, WL_SEAT_NAME, WL_SHM, WL_SUBCOMPOSITOR, 
+    WL_SURFACE_ENTER, WL_SURFACE_LEAVE, WL_SURFACE_PREFERRED_BUFFER_SCALE, XDG_SURFACE_CONFIGURE, XDG_TOPLEVEL_CLOSE, XDG_TOPLEVEL_CONFIGURE, XDG_WM_BASE, XDG_WM_BASE_PING, XDG_WM_BASE_PONG, 
+    XDG_POPUP_CONFIGURE, XDG_POPUP_POPUP_DONE, XDG_POSITIONER_DESTROY, WL_BUFFER_RELEASE, 
+    ZXDG_OUTPUT_MANAGER_V1, ZXDG_OUTPUT_MANAGER_V1_GET_XDG_OUTPUT, ZXDG_OUTPUT_V1, ZXDG_OUTPUT_V1_DESCRIPTION, ZXDG_OUTPUT_V1_DESTROY, ZXDG_OUTPUT_V1_DONE, 
+    ZXDG_OUTPUT_V1_LOGICAL_POSITION, ZXDG_OUTPUT_V1_LOGICAL_SIZE, ZXDG_OUTPUT_V1_NAME, ZXDG_DECORATION_MANAGER_V1, ZXDG_TOPLEVEL_DECORATION_V1_CONFIGURE, 
+    WP_CURSOR_SHAPE_MANAGER_V1, ZWP_CONFINED_POINTER_V1_CONFINED, ZWP_CONFINED_POINTER_V1_UNCONFINED, ZWP_LOCKED_POINTER_V1_LOCKED, 
+    ZWP_LOCKED_POINTER_V1_UNLOCKED, ZWP_POINTER_CONSTRAINTS_V1, ZWP_RELATIVE_POINTER_MANAGER_V1, ZWP_RELATIVE_POINTER_V1_RELATIVE_MOTION, 
+    WP_FRACTIONAL_SCALE_MANAGER_V1, WP_FRACTIONAL_SCALE_V1_PREFERRED_SCALE, WP_VIEWPORTER, ZWP_TEXT_INPUT_MANAGER_V3, ZWP_TEXT_INPUT_V3_COMMIT_STRING, 
+    ZWP_TEXT_INPUT_V3_DELETE_SURROUNDING_TEXT, ZWP_TEXT_INPUT_V3_DONE, ZWP_TEXT_INPUT_V3_ENTER, ZWP_TEXT_INPUT_V3_LEAVE, ZWP_TEXT_INPUT_V3_PREEDIT_STRING, 
+    XDG_ACTIVATION_TOKEN_V1_DONE, XDG_ACTIVATION_V1, ZWP_IDLE_INHIBIT_MANAGER_V1}, 
+    text_input::WaylandTextInputState, window::{WaylandScaleGlobals, WaylandWindow, WaylandWindowGlobals}, wlib::{WaylandArg, WaylandConnection, WaylandId, WaylandMessage}, xkb::WaylandKeymap};
+
+/// Global object advertised by the compositor registry.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WaylandGlobal {
+    /// Numeric name of the global
+    pub name : u32,
+
+    /// Interface name of the global
+    pub interface : String,
+
+    /// Highest version supported by the compositor
+    pub version : u32,
+}
 
 pub struct WaylandWindowManager {
-    
+
+    /// Connection with the compositor, shared with windows.
+    connection : Rc<RefCell<WaylandConnection>>,
+
+    /// wl_registry object id
+    registry : WaylandId,
+
+    /// Globals advertised by the compositor
+    globals : Vec<WaylandGlobal>,
+
+    /// wl_compositor global
+    compositor : WaylandId,
+
+    /// xdg_wm_base global
+    wm_base : WaylandId,
+
+    /// Globals used to decorate windows
+    decorations : WaylandDecorationGlobals,
+
+    /// Globals used to scale windows
+    scaling : WaylandScaleGlobals,
+
+    /// wl_seat global, 0 if not advertised.
+    seat : WaylandId,
+
+    /// Pointer state of the seat, shared with windows pointers.
+    pointer : Rc<RefCell<WaylandPointerState>>,
+
+    /// wl_keyboard of the seat, 0 if seat has no keyboard.
+    keyboard : WaylandId,
+
+    /// Keyboard state of the seat, shared with windows keyboards.
+    keyboard_state : Rc<RefCell<WaylandKeyboardState>>,
+
+    /// Text input state of the seat, shared with windows keyboards.
+    text_input : Rc<RefCell<WaylandTextInputState>>,
+
+    /// Activation state of the seat, shared with windows.
+    activation : Rc<RefCell<WaylandActivation>>,
+
+    /// Activation token given by the launcher, used once by the first window shown.
+    launch_token : Option<String>,
+
+    /// Input grab state of the seat, shared with windows.
+    grab : Rc<RefCell<WaylandGrab>>,
+
+    /// Active keyboard layout
+    layout : KeyboardLayout,
+
+    /// Name of the seat like `seat0`
+    seat_name : String,
+
+    /// Pointer and keyboard of the seat
+    devices : InputDevices,
+
+    /// zxdg_output_manager_v1 global, 0 if not advertised.
+    xdg_output_manager : WaylandId,
+
+    /// wl_output bound
+    outputs : Vec<WaylandOutput>,
+
+    /// wl_output ids indexed by [DisplayHandle](crate::display::DisplayHandle), shared with windows.
+    display_outputs : Rc<RefCell<Vec<WaylandId>>>,
+
+    /// wl_display.sync callbacks not done yet
+    syncs : Vec<WaylandId>,
+
+    /// [WindowManagerEvent] generated by dispatch and windows, kept in queue until fetched.
+    queue : Rc<RefCell<VecDeque<WindowManagerEvent>>>,
+
+    /// Last [WindowManagerEvent] fetched
+    event : Option<WindowManagerEvent>,
+
+    /// List of display
+    displays : Rc<Displays>,
+
+    /// [Window] collection of this manager.
+    windows : Vec<Window>,
+
+    /// Handles given to windows
+    handles : WindowHandles,
+
+    /// xdg_toplevel of each [WindowHandle], shared with windows to set parents.
+    toplevels : Rc<RefCell<Vec<(WindowHandle, WaylandId)>>>,
+
+    /// False during initialization. Devices and layout found then don't generate events.
+    ready : bool,
 }
 
 impl WaylandWindowManager {
+
+    /// Connect to the compositor and fetch globals.
+    /// 
+    /// # Errors
+    /// Returns the errors of [WaylandConnection::connect] if compositor socket can't be connected.
+    /// Returns Err([`WindowError::WindowManagerMissingProtocol`]) if compositor lacks wl_compositor or xdg-shell.
+    /// Returns Err([`WindowError::WindowManagerConnectionLost`]) if compositor close the connection.
+    pub fn new() -> Result<WaylandWindowManager, WindowError> {
+        let mut wm = WaylandWindowManager::from_connection(WaylandConnection::connect()?)?;
+
+        // Token is kept by the manager, the environment of the process is left untouched.
+        wm.launch_token = std::env::var(WL_ACTIVATION_TOKEN_ENV).ok();
+        Ok(wm)
+    }
+
+    /// Create the manager from an established connection, get the registry, wait for globals and bind them.
+    pub(crate) fn from_connection(mut connection : WaylandConnection) -> Result<WaylandWindowManager, WindowError> {
+
+        let registry = connection.new_object(&WL_REGISTRY, 1);
+        connection.send(WL_DISPLAY_ID, WL_DISPLAY_GET_REGISTRY, vec![WaylandArg::NewId(registry)]);
+
+        let connection = Rc::new(RefCell::new(connection));
+        let pointer = Rc::new(RefCell::new(WaylandPointerState::new(connection.clone())));
+        let mut wm = WaylandWindowManager { 
+            grab : Rc::new(RefCell::new(WaylandGrab::new(connection.clone(), pointer.clone()))),
+            pointer,
+            text_input : Rc::new(RefCell::new(WaylandTextInputState::new(connection.clone()))),
+            activation : Rc::new(RefCell::new(WaylandActivation::new(connection.clone()))),
+            launch_token : None,
+            connection, 
+            registry, 
+            globals : Vec::new(), 
+            compositor : 0,
+            wm_base : 0,
+            decorations : WaylandDecorationGlobals::default(),
+            scaling : WaylandScaleGlobals::default(),
+            seat : 0,
+            keyboard : 0,
+            keyboard_state : Rc::new(RefCell::new(WaylandKeyboardState::default())),
+            layout : KeyboardLayout::default(),
+            seat_name : String::new(),
+            devices : InputDevices::create(Vec::new()),
+            xdg_output_manager : 0,
+            outputs : Vec::new(),
+            display_outputs : Rc::new(RefCell::new(Vec::new())),
+            syncs : Vec::new(),
+            queue : Rc::new(RefCell::new(VecDeque::new())), 
+            event : None,
+            displays : Rc::new(Displays::create(Vec::new(), None)),
+            windows : Vec::new(),
+            handles : WindowHandles::default(),
+            toplevels : Rc::new(RefCell::new(Vec::new())),
+            ready : false,
+        };
+
+        // Globals are all sent before the sync callback is done.
+        wm.roundtrip()?;
+
+        // Windows can't be created without compositor and xdg-shell.
+        match (wm.bind(&WL_COMPOSITOR, WL_COMPOSITOR.version), wm.bind(&XDG_WM_BASE, XDG_WM_BASE.version)) {
+            (Some(compositor), Some(wm_base)) => {
+                wm.compositor = compositor;
+                wm.wm_base = wm_base;
+            },
+            (None, _) => return Err(WindowError::WindowManagerMissingProtocol(WL_COMPOSITOR.name.to_string())),
+            (_, None) => return Err(WindowError::WindowManagerMissingProtocol(XDG_WM_BASE.name.to_string())),
+        }
+
+        // Server-side decorations are used if available, otherwise the frame is drawn with sub-surfaces.
+        wm.decorations = WaylandDecorationGlobals { 
+            compositor : wm.compositor, 
+            subcompositor : wm.bind(&WL_SUBCOMPOSITOR, WL_SUBCOMPOSITOR.version).unwrap_or(0), 
+            shm : wm.bind(&WL_SHM, WL_SHM.version).unwrap_or(0), 
+            manager : wm.bind(&ZXDG_DECORATION_MANAGER_V1, ZXDG_DECORATION_MANAGER_V1.version).unwrap_or(0),
+        };
+        // Cursors are drawn by the compositor with cursor-shape, otherwise from the Xcursor theme.
+        wm.pointer.borrow_mut().globals = WaylandPointerGlobals { 
+            compositor : wm.compositor, 
+            shm : wm.decorations.shm, 
+            shape_manager : wm.bind(&WP_CURSOR_SHAPE_MANAGER_V1, WP_CURSOR_SHAPE_MANAGER_V1.version).unwrap_or(0),
+            constraints : wm.bind(&ZWP_POINTER_CONSTRAINTS_V1, ZWP_POINTER_CONSTRAINTS_V1.version).unwrap_or(0),
+            relative_manager : wm.bind(&ZWP_RELATIVE_POINTER_MANAGER_V1, ZWP_RELATIVE_POINTER_MANAGER_V1.version).unwrap_or(0),
+        };
+        // Fractional scales need both globals, otherwise integer buffer scales are used.
+        wm.scaling = WaylandScaleGlobals {
+            fractional_manager : wm.bind(&WP_FRACTIONAL_SCALE_MANAGER_V1, WP_FRACTIONAL_SCALE_MANAGER_V1.version).unwrap_or(0),
+            viewporter : wm.bind(&WP_VIEWPORTER, WP_VIEWPORTER.version).unwrap_or(0),
+        };
+        wm.seat = wm.bind(&WL_SEAT, WL_SEAT.version).unwrap_or(0);
+
+        // Windows activate each other with tokens and inhibit idle if available.
+        {
+            let (activation, idle_manager) = (wm.bind(&XDG_ACTIVATION_V1, XDG_ACTIVATION_V1.version).unwrap_or(0), 
+                wm.bind(&ZWP_IDLE_INHIBIT_MANAGER_V1, ZWP_IDLE_INHIBIT_MANAGER_V1.version).unwrap_or(0));
+            let mut state = wm.activation.borrow_mut();
+            state.activation = activation;
+            state.idle_manager = idle_manager;
+            state.seat = wm.seat;
+        }
+
+        // Input is grabbed with a pointer lock and compositor shortcuts inhibited.
+        {
+            let shortcuts_manager = wm.bind(&ZWP_KEYBOARD_SHORTCUTS_INHIBIT_MANAGER_V1, ZWP_KEYBOARD_SHORTCUTS_INHIBIT_MANAGER_V1.version).unwrap_or(0);
+            let mut grab = wm.grab.borrow_mut();
+            grab.shortcuts_manager = shortcuts_manager;
+            grab.seat = wm.seat;
+        }
+
+        // Input methods are used with text-input-v3 when the seat has one.
+        if let (Some(manager), true) = (wm.bind(&ZWP_TEXT_INPUT_MANAGER_V3, ZWP_TEXT_INPUT_MANAGER_V3.version), wm.seat != 0) {
+            wm.text_input.borrow_mut().create(manager, wm.seat);
+        }
+
+        // Outputs advertised later are bound when their global is received.
+        wm.xdg_output_manager = wm.bind(&ZXDG_OUTPUT_MANAGER_V1, ZXDG_OUTPUT_MANAGER_V1.version).unwrap_or(0);
+        let outputs : Vec<u32> = wm.globals.iter().filter(|g| g.interface == WL_OUTPUT.name).map(|g| g.name).collect();
+        for global in outputs {
+            wm.add_output(global);
+        }
+
+        wm.roundtrip()?;
+
+        // Keymap is sent once the keyboard requested with seat capabilities is created.
+        if wm.keyboard != 0 {
+            wm.roundtrip()?;
+        }
+
+        wm.ready = true;
+        Ok(wm)
+    }
+
+    /// Poll an event. Pending messages from compositor are dispatched when queue is empty.
+    /// 
+    /// A lost connection returns no event. [WaylandWindowManager::event_wait] returns [WindowEvent::NoEvent] instead.
     #[inline(always)]
-    pub fn new() -> WaylandWindowManager {
-        WaylandWindowManager {}
+    pub fn event(&mut self) -> Option<&WindowManagerEvent> {
+        if self.queue.borrow().is_empty() {
+            let _ = self.dispatch(false);
+        }
+
+        // Repeats come after compositor events since a key may have been released.
+        if self.queue.borrow().is_empty() {
+            self.key_repeat();
+        }
+
+        self.next_event();
+        self.event.as_ref()
     }
 
+    /// Wait for an event, blocking on the compositor socket. Returns [WindowEvent::NoEvent] if connection with the compositor 
+    /// is lost since no event will ever come.
     #[inline(always)]
-    pub fn event(&self) -> Option<&WindowManagerEvent> {
-        todo!()
+    pub fn event_wait(&mut self) -> &WindowManagerEvent {
+        while self.queue.borrow().is_empty() {
+            let timeout = self.keyboard_state.borrow().repeat_timeout();
+            let result = match timeout {
+                // Wait for messages until the next key repeat.
+                Some(timeout) => match self.connection.borrow_mut().flush() {
+                    Ok(_) => Ok(self.connection.borrow().poll(timeout.as_micros().div_ceil(1000) as i32)),
+                    Err(err) => Err(err),
+                },
+                None => self.dispatch(true).map(|_| false),
+            };
+
+            match result {
+                Ok(true) => if self.dispatch(false).is_err() {
+                    break;
+                },
+                Ok(false) => self.key_repeat(),
+                Err(_) => break,
+            }
+        }
+
+        self.next_event();
+        self.event.get_or_insert(WindowManagerEvent::new(WindowHandle::NULL, WindowEvent::NoEvent))
+    }
+
+    /// Send all requests and wait until the compositor processed them.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowManagerConnectionLost`]) if connection is lost or a protocol error occurred.
+    pub(crate) fn roundtrip(&mut self) -> Result<(), WindowError> {
+        let callback = {
+            let mut conn = self.connection.borrow_mut();
+            let callback = conn.new_object(&WL_CALLBACK, 1);
+            conn.send(WL_DISPLAY_ID, WL_DISPLAY_SYNC, vec![WaylandArg::NewId(callback)]);
+            callback
+        };
+        self.syncs.push(callback);
+
+        while self.syncs.contains(&callback) {
+            self.dispatch(true)?;
+        }
+
+        // Requests sent while handling events are sent with the roundtrip.
+        self.connection.borrow_mut().flush()?;
+        Ok(())
+    }
+
+    /// Flush requests then read and handle messages from compositor. If `blocking`, wait until at least one message is handled.
+    /// 
+    /// Returns count of messages handled.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowManagerConnectionLost`]) if connection is lost or a protocol error occurred.
+    pub(crate) fn dispatch(&mut self, blocking : bool) -> Result<usize, WindowError> {
+        self.connection.borrow_mut().flush()?;
+        self.connection.borrow_mut().read(false)?;
+
+        let mut count = self.dispatch_pending()?;
+        while blocking && count == 0 {
+            self.connection.borrow_mut().read(true)?;
+            count = self.dispatch_pending()?;
+        }
+
+        Ok(count)
+    }
+
+    /// Bind a global by interface with the highest version supported by both sides, up to `version`.
+    /// 
+    /// Returns Some(WaylandId) of the bound object or None if compositor doesn't advertise the interface.
+    pub(crate) fn bind(&mut self, interface : &'static WaylandInterface, version : u32) -> Option<WaylandId> {
+        let name = self.globals.iter().find(|g| g.interface == interface.name)?.name;
+        self.bind_global(name, interface, version)
+    }
+
+    /// Bind a global by name, used for interfaces advertised many times like wl_output.
+    /// 
+    /// Returns Some(WaylandId) of the bound object or None if global doesn't exists.
+    pub(crate) fn bind_global(&mut self, name : u32, interface : &'static WaylandInterface, version : u32) -> Option<WaylandId> {
+        let global = self.globals.iter().find(|g| g.name == name && g.interface == interface.name)?;
+        let version = version.min(interface.version).min(global.version);
+
+        let mut conn = self.connection.borrow_mut();
+        let id = conn.new_object(interface, version);
+        conn.send(self.registry, WL_REGISTRY_BIND, vec![WaylandArg::Uint(name), 
+            WaylandArg::Str(Some(interface.name.to_string())), WaylandArg::Uint(version), WaylandArg::NewId(id)]);
+        Some(id)
     }
 
+    /// Globals advertised by the compositor.
     #[inline(always)]
+    pub(crate) fn globals(&self) -> &[WaylandGlobal] {
+        &self.globals
+    }
+
+    /// Bind a wl_output global and get its xdg-output if available.
+    fn add_output(&mut self, global : u32) {
+        if let Some(output) = self.bind_global(global, &WL_OUTPUT, WL_OUTPUT.version) {
+            let xdg_output = match self.xdg_output_manager {
+                0 => 0,
+                manager => {
+                    let mut conn = self.connection.borrow_mut();
+                    let version = conn.version(manager);
+                    let xdg_output = conn.new_object(&ZXDG_OUTPUT_V1, version);
+                    conn.send(manager, ZXDG_OUTPUT_MANAGER_V1_GET_XDG_OUTPUT, vec![WaylandArg::NewId(xdg_output), WaylandArg::Object(output)]);
+                    xdg_output
+                },
+            };
+
+            self.outputs.push(WaylandOutput::new(global, output, xdg_output));
+        }
+    }
+
+    /// Release an output removed by the compositor and refresh displays.
+    fn remove_output(&mut self, global : u32) {
+        if let Some(index) = self.outputs.iter().position(|o| o.global == global) {
+            let output = self.outputs.remove(index);
+
+            {
+                let mut conn = self.connection.borrow_mut();
+                if output.xdg_output != 0 {
+                    conn.send(output.xdg_output, ZXDG_OUTPUT_V1_DESTROY, vec![]);
+                    conn.destroyed(output.xdg_output);
+                }
+
+                // wl_output can only be released since version 3.
+                if conn.version(output.output) >= 3 {
+                    conn.send(output.output, WL_OUTPUT_RELEASE, vec![]);
+                    conn.destroyed(output.output);
+                }
+            }
+
+            for window in &mut self.windows {
+                if let LinuxWindow::Wayland(lw) = &mut window.window {
+                    lw.output_enter(output.output, false);
+                }
+            }
+
+            self.refresh_displays();
+            self.update_output_scales();
+        }
+    }
+
+    /// Handle wl_output and zxdg_output_v1 events. Displays are refreshed once all informations are received.
+    fn output_event(&mut self, message : &WaylandMessage, xdg : bool) {
+        let output = match self.outputs.iter_mut().find(|o| if xdg { o.xdg_output == message.sender } else { o.output == message.sender }) {
+            Some(output) => output,
+            None => return,
+        };
+
+        match (xdg, message.opcode) {
+            (false, WL_OUTPUT_GEOMETRY) => {
+                output.position = (message.int(0), message.int(1));
+                output.size_mm = (message.int(2), message.int(3));
+                output.make = message.string(5).to_string();
+                output.model = message.string(6).to_string();
+                output.transform = message.int(7);
+            },
+            (false, WL_OUTPUT_MODE) => output.set_mode(message.uint(0), message.int(1), message.int(2), message.int(3)),
+            (false, WL_OUTPUT_SCALE) => output.scale = message.int(0),
+            (false, WL_OUTPUT_NAME) | (true, ZXDG_OUTPUT_V1_NAME) => output.name = message.string(0).to_string(),
+            (false, WL_OUTPUT_DESCRIPTION) | (true, ZXDG_OUTPUT_V1_DESCRIPTION) => output.description = message.string(0).to_string(),
+            (true, ZXDG_OUTPUT_V1_LOGICAL_POSITION) => output.logical_position = Some((message.int(0), message.int(1))),
+            (true, ZXDG_OUTPUT_V1_LOGICAL_SIZE) => output.logical_size = Some((message.int(0), message.int(1))),
+            (false, WL_OUTPUT_DONE) | (true, ZXDG_OUTPUT_V1_DONE) => {
+                output.done = true;
+                self.refresh_displays();
+                self.update_output_scales();
+            },
+            _ => {},
+        }
+    }
+
+    /// Update the integer scale of windows from the outputs they entered, used when the compositor doesn't tell the preferred scale, 
+    /// and their refresh interval from the display of the current output.
+    fn update_output_scales(&mut self) {
+        let display_outputs = self.display_outputs.borrow();
+
+        for window in &mut self.windows {
+            if let LinuxWindow::Wayland(lw) = &mut window.window {
+                let scale = self.outputs.iter().filter(|o| lw.entered().contains(&o.output)).map(|o| o.scale).max().unwrap_or(1);
+                lw.set_output_scale(scale);
+
+                let display = lw.entered().last().and_then(|o| display_outputs.iter().position(|d| d == o)).and_then(|i| self.displays.list.get(i));
+                lw.set_refresh_interval(display.map_or(Duration::ZERO, |d| d.refresh_interval()));
+            }
+        }
+    }
+
+    /// Create [Displays] from outputs and share them with windows.
+    fn refresh_displays(&mut self) {
+        self.displays = Rc::new(wayland_displays(&self.outputs));
+        *self.display_outputs.borrow_mut() = self.outputs.iter().filter(|o| o.done).map(|o| o.output).collect();
+
+        for window in &mut self.windows {
+            window.displays = self.displays.clone();
+        }
+    }
+
+    /// Pop the next event from queue and update the [Window] it refers to.
+    fn next_event(&mut self) {
+        let event = self.queue.borrow_mut().pop_front();
+
+        if let Some(event) = &event {
+            if let Some(index) = self.windows.iter().position(|w| w.handle == event.window) {
+                if self.windows[index].handle_event(&event.event) {
+                    self.queue.borrow_mut().push_back(WindowManagerEvent::new(event.window, WindowEvent::InputGrabReleased));
+                }
+
+                // Closed windows are removed from collection with their pointer constraint, text field and handle.
+                if event.event == WindowEvent::Closed {
+                    self.handles.remove(event.window);
+                    self.toplevels.borrow_mut().retain(|(handle, _)| *handle != event.window);
+                    if let LinuxWindow::Wayland(lw) = &self.windows.remove(index).window {
+                        self.pointer.borrow_mut().constrain(lw.surface, None);
+                        self.text_input.borrow_mut().remove(lw.surface);
+                    }
+                }
+            }
+        }
+
+        self.event = event;
+    }
+
+    /// Handle all complete messages received.
+    fn dispatch_pending(&mut self) -> Result<usize, WindowError> {
+        let mut count : usize = 0;
+
+        loop {
+            // Connection must not stay borrowed while handling.
+            let message = self.connection.borrow_mut().next_message();
+            match message {
+                Some(message) => self.handle(message?)?,
+                None => return Ok(count),
+            }
+            count += 1;
+        }
+    }
+
+    /// Handle a message received from compositor.
+    fn handle(&mut self, message : WaylandMessage) -> Result<(), WindowError> {
+
+        // Messages of destroyed objects are ignored.
+        let interface = {
+            let conn = self.connection.borrow();
+            match conn.interface(message.sender) {
+                Some(interface) if conn.alive(message.sender) => interface,
+                _ => return Ok(()),
+            }
+        };
+
+        match (interface.name, message.opcode) {
+            // Protocol errors are fatal.
+            ("wl_display", WL_DISPLAY_ERROR) => return Err(WindowError::WindowManagerConnectionLost),
+            ("wl_display", WL_DISPLAY_DELETE_ID) => self.connection.borrow_mut().delete(message.uint(0)),
+
+            ("wl_registry", WL_REGISTRY_GLOBAL) => {
+                self.globals.push(WaylandGlobal { name : message.uint(0), interface : message.string(1).to_string(), version : message.uint(2) });
+
+                // Outputs advertised during initialization are bound once xdg-output is known.
+                if self.compositor != 0 && message.string(1) == WL_OUTPUT.name {
+                    self.add_output(message.uint(0));
+                }
+            },
+            ("wl_registry", WL_REGISTRY_GLOBAL_REMOVE) => {
+                self.remove_output(message.uint(0));
+                self.globals.retain(|g| g.name != message.uint(0));
+            },
+
+            ("wl_callback", WL_CALLBACK_DONE) => {
+                self.syncs.retain(|id| *id != message.sender);
+                // Callback is destroyed by compositor after done.
+                self.connection.borrow_mut().destroyed(message.sender);
+
+                if let Some((_, lw)) = self.wayland_window(|lw| lw.frame_callback == message.sender) {
+                    lw.frame_done(message.uint(0));
+                }
+            },
+
+            ("wl_output", _) => self.output_event(&message, false),
+            ("zxdg_output_v1", _) => self.output_event(&message, true),
+
+            ("wl_seat", WL_SEAT_CAPABILITIES) => self.seat_capabilities(message.uint(0)),
+            ("wl_seat", WL_SEAT_NAME) => {
+                self.seat_name = message.string(0).to_string();
+                for device in &mut self.devices.list {
+                    device.name = wayland_device_name(&self.seat_name, device.kind);
+                }
+            },
+
+            ("wl_keyboard", _) => self.keyboard_event(message),
+            ("zwp_text_input_v3", _) => self.text_input_event(&message),
+            ("xdg_activation_token_v1", XDG_ACTIVATION_TOKEN_V1_DONE) => self.activation.borrow_mut().done(message.sender, message.string(0)),
+
+            ("wl_pointer", _) => self.pointer_event(&message),
+            ("zwp_relative_pointer_v1", ZWP_RELATIVE_POINTER_V1_RELATIVE_MOTION) => self.relative_motion(&message),
+            ("zwp_locked_pointer_v1", ZWP_LOCKED_POINTER_V1_LOCKED) | ("zwp_confined_pointer_v1", ZWP_CONFINED_POINTER_V1_CONFINED) => 
+                self.pointer.borrow_mut().constraint_active(message.sender, true),
+            ("zwp_locked_pointer_v1", ZWP_LOCKED_POINTER_V1_UNLOCKED) | ("zwp_confined_pointer_v1", ZWP_CONFINED_POINTER_V1_UNCONFINED) => 
+                self.pointer.borrow_mut().constraint_active(message.sender, false),
+
+            ("zxdg_toplevel_decoration_v1", ZXDG_TOPLEVEL_DECORATION_V1_CONFIGURE) => 
+                if let Some((_, lw)) = self.wayland_window(|lw| lw.decoration.borrow().decoration == message.sender) {
+                    lw.decoration.borrow_mut().configure(message.uint(0));
+                },
+
+            ("wl_surface", WL_SURFACE_ENTER | WL_SURFACE_LEAVE) => if let Some((_, lw)) = self.wayland_window(|lw| lw.surface == message.sender) {
+                lw.output_enter(message.uint(0), message.opcode == WL_SURFACE_ENTER);
+                self.update_output_scales();
+            },
+            ("wl_surface", WL_SURFACE_PREFERRED_BUFFER_SCALE) => if let Some((_, lw)) = self.wayland_window(|lw| lw.surface == message.sender) {
+                lw.set_preferred_scale(message.int(0));
+            },
+            ("wp_fractional_scale_v1", WP_FRACTIONAL_SCALE_V1_PREFERRED_SCALE) => 
+                if let Some((_, lw)) = self.wayland_window(|lw| lw.fractional_scale == message.sender) {
+                    lw.set_fractional_scale(message.uint(0));
+                },
+
+            // Compositor checks that client is responsive.
+            ("xdg_wm_base", XDG_WM_BASE_PING) => self.connection.borrow_mut().send(self.wm_base, XDG_WM_BASE_PONG, vec![WaylandArg::Uint(message.uint(0))]),
+
+            ("xdg_toplevel", XDG_TOPLEVEL_CONFIGURE) => if let Some((_, lw)) = self.wayland_window(|lw| lw.toplevel == message.sender) {
+                lw.toplevel_configure(message.int(0), message.int(1), message.array(2));
+            },
+            ("xdg_toplevel", XDG_TOPLEVEL_CLOSE) => if let Some((handle, _)) = self.wayland_window(|lw| lw.toplevel == message.sender) {
+                self.queue.borrow_mut().push_back(WindowManagerEvent::new(handle, WindowEvent::CloseButtonPressed));
+            },
+            // Framebuffer buffers can be drawn again once released.
+            ("wl_buffer", WL_BUFFER_RELEASE) => for window in self.windows.iter_mut() {
+                if let LinuxWindow::Wayland(lw) = &mut window.window {
+                    if lw.framebuffer.release(message.sender) {
+                        break;
+                    }
+                }
+            },
+            ("xdg_popup", XDG_POPUP_CONFIGURE) => if let Some((_, lw)) = self.wayland_window(|lw| lw.popup == message.sender) {
+                lw.popup_configure(message.int(2), message.int(3));
+            },
+            // Popup dismissed by compositor, usually by clicking outside of a grabbing popup.
+            ("xdg_popup", XDG_POPUP_POPUP_DONE) => if let Some((_, lw)) = self.wayland_window(|lw| lw.popup == message.sender) {
+                lw.close();
+            },
+            ("xdg_surface", XDG_SURFACE_CONFIGURE) => if let Some(window) = self.windows.iter_mut().find(|w| 
+                matches!(&w.window, LinuxWindow::Wayland(lw) if lw.xdg_surface == message.sender)) {
+
+                let size = window.size;
+                if let LinuxWindow::Wayland(lw) = &mut window.window {
+                    for event in lw.surface_configure(message.uint(0), size) {
+                        self.queue.borrow_mut().push_back(WindowManagerEvent::new(window.handle, event));
+                    }
+                }
+            },
+
+            _ => {},
+        }
+
+        Ok(())
+    }
+
+    /// Get or release the seat pointer and keyboard when capabilities change. Their devices are added or removed accordingly.
+    fn seat_capabilities(&mut self, capabilities : u32) {
+        let version = self.connection.borrow().version(self.seat);
+
+        let pointer = self.pointer.borrow().pointer;
+        if capabilities & WL_SEAT_CAPABILITY_POINTER != 0 && pointer == 0 {
+            let pointer = self.pointer.borrow_mut().create(self.seat);
+            self.add_device(pointer, InputDeviceKind::Mouse);
+        } else if capabilities & WL_SEAT_CAPABILITY_POINTER == 0 && pointer != 0 {
+            self.pointer.borrow_mut().release();
+            self.remove_device(pointer);
+        }
+
+        if capabilities & WL_SEAT_CAPABILITY_KEYBOARD != 0 && self.keyboard == 0 {
+            self.keyboard = self.connection.borrow_mut().new_object(&WL_KEYBOARD, version);
+            self.connection.borrow_mut().send(self.seat, WL_SEAT_GET_KEYBOARD, vec![WaylandArg::NewId(self.keyboard)]);
+            self.add_device(self.keyboard, InputDeviceKind::Keyboard);
+        } else if capabilities & WL_SEAT_CAPABILITY_KEYBOARD == 0 && self.keyboard != 0 {
+            // wl_keyboard can only be released since version 3.
+            if version >= 3 {
+                let mut conn = self.connection.borrow_mut();
+                conn.send(self.keyboard, WL_KEYBOARD_RELEASE, vec![]);
+                conn.destroyed(self.keyboard);
+            }
+            self.remove_device(self.keyboard);
+            self.keyboard = 0;
+
+            let mut state = self.keyboard_state.borrow_mut();
+            state.focus = 0;
+            state.pressed.clear();
+            state.repeat = None;
+        }
+    }
+
+    /// Add the device of a seat pointer or keyboard and queue [WindowEvent::DeviceAdded].
+    fn add_device(&mut self, id : InputDeviceId, kind : InputDeviceKind) {
+        let device = InputDevice { id, name : wayland_device_name(&self.seat_name, kind), kind, capabilities : Default::default(), enabled : true };
+        if self.devices.add(device) && self.ready {
+            self.queue.borrow_mut().push_back(WindowManagerEvent::new(WindowHandle::NULL, WindowEvent::DeviceAdded(id)));
+        }
+    }
+
+    /// Remove the device of a seat pointer or keyboard and queue [WindowEvent::DeviceRemoved].
+    fn remove_device(&mut self, id : InputDeviceId) {
+        if self.devices.remove(id) {
+            self.queue.borrow_mut().push_back(WindowManagerEvent::new(WindowHandle::NULL, WindowEvent::DeviceRemoved(id)));
+        }
+    }
+
+    /// Handle wl_keyboard events. Keys are sent to the window with keyboard focus.
+    fn keyboard_event(&mut self, mut message : WaylandMessage) {
+        match message.opcode {
+            WL_KEYBOARD_KEYMAP => if let Some(fd) = message.take_fd(1) {
+                if message.uint(0) == WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1 {
+                    if let Some(keymap) = WaylandKeymap::from_fd(fd, message.uint(2)) {
+                        self.keyboard_state.borrow_mut().keymap = Some(keymap);
+                        self.update_layout();
+                    }
+                }
+            },
+            WL_KEYBOARD_ENTER => {
+                {
+                    let mut activation = self.activation.borrow_mut();
+                    activation.serial = message.uint(0);
+                    activation.focus = message.uint(1);
+
+                    let mut state = self.keyboard_state.borrow_mut();
+                    state.focus = message.uint(1);
+                    state.pressed = message.array(2).chunks_exact(4)
+                        .map(|key| u32::from_ne_bytes([key[0], key[1], key[2], key[3]]) + WL_EVDEV_OFFSET).collect();
+                }
+
+                if let Some((handle, _)) = self.wayland_window(|lw| lw.surface == message.uint(1)) {
+                    self.queue.borrow_mut().push_back(WindowManagerEvent::new(handle, WindowEvent::Focus));
+                }
+            },
+            WL_KEYBOARD_LEAVE => {
+                {
+                    self.activation.borrow_mut().focus = 0;
+
+                    let mut state = self.keyboard_state.borrow_mut();
+                    state.focus = 0;
+                    state.pressed.clear();
+                    state.repeat = None;
+                }
+
+                if let Some((handle, _)) = self.wayland_window(|lw| lw.surface == message.uint(1)) {
+                    self.queue.borrow_mut().push_back(WindowManagerEvent::new(handle, WindowEvent::Blur));
+                }
+            },
+            WL_KEYBOARD_KEY => {
+                self.activation.borrow_mut().serial = message.uint(0);
+                let key = message.uint(2) + WL_EVDEV_OFFSET;
+                let pressed = message.uint(3) == WL_KEYBOARD_KEY_STATE_PRESSED;
+                let (focus, modifiers, locks) = {
+                    let mut state = self.keyboard_state.borrow_mut();
+                    state.key(key, pressed);
+                    (state.focus, state.key_modifiers(), state.key_locks())
+                };
+
+                if let Some(window) = self.windows.iter_mut().find(|w| matches!(&w.window, LinuxWindow::Wayland(lw) if lw.surface == focus)) {
+                    // Scroll Lock may have been toggled by the key.
+                    window.keyboard.set_modifiers(modifiers, locks);
+
+                    if pressed {
+                        self.queue.borrow_mut().push_back(WindowManagerEvent::new(window.handle, WindowEvent::Keyboard(WindowKeyboardEvent::KeyDown(key))));
+                        if window.keyboard.repeat() {
+                            self.keyboard_state.borrow_mut().start_repeat(window.handle, key);
+                        }
+                    } else {
+                        self.queue.borrow_mut().push_back(WindowManagerEvent::new(window.handle, WindowEvent::Keyboard(WindowKeyboardEvent::KeyUp(key))));
+                    }
+                }
+            },
+            WL_KEYBOARD_MODIFIERS => {
+                let (focus, modifiers, locks, group) = {
+                    let mut state = self.keyboard_state.borrow_mut();
+                    state.modifiers = message.uint(1) | message.uint(2) | message.uint(3);
+                    state.locked = message.uint(3);
+                    state.group = message.uint(4);
+                    (state.focus, state.key_modifiers(), state.key_locks(), state.group)
+                };
+
+                if let Some(window) = self.windows.iter_mut().find(|w| matches!(&w.window, LinuxWindow::Wayland(lw) if lw.surface == focus)) {
+                    window.keyboard.set_modifiers(modifiers, locks);
+                }
+                if group != self.layout.group {
+                    self.update_layout();
+                }
+            },
+            WL_KEYBOARD_REPEAT_INFO => {
+                let mut state = self.keyboard_state.borrow_mut();
+                state.repeat_rate = message.int(0);
+                state.repeat_delay = message.int(1);
+            },
+            _ => {},
+        }
+    }
+
+    /// Handle zwp_text_input_v3 events. Input method changes are sent to the focused window once done.
+    fn text_input_event(&mut self, message : &WaylandMessage) {
+        let mut text_input = self.text_input.borrow_mut();
+        match message.opcode {
+            ZWP_TEXT_INPUT_V3_ENTER => text_input.enter(message.uint(0)),
+            ZWP_TEXT_INPUT_V3_LEAVE => text_input.leave(message.uint(0)),
+            ZWP_TEXT_INPUT_V3_PREEDIT_STRING => text_input.preedit = Some((message.string(0).to_string(), message.int(1), message.int(2))),
+            ZWP_TEXT_INPUT_V3_COMMIT_STRING => text_input.commit = Some(message.string(0).to_string()),
+            ZWP_TEXT_INPUT_V3_DELETE_SURROUNDING_TEXT => text_input.delete = (message.uint(0), message.uint(1)),
+            ZWP_TEXT_INPUT_V3_DONE => {
+                let events = text_input.done();
+                let focus = text_input.focus;
+                drop(text_input);
+
+                if let Some((handle, _)) = self.wayland_window(|lw| focus != 0 && lw.surface == focus) {
+                    self.queue.borrow_mut().extend(events.into_iter().map(|e| WindowManagerEvent::new(handle, WindowEvent::Keyboard(e))));
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Update the keyboard layout from keyboard state and queue [WindowEvent::KeyboardLayoutChanged] if it changed.
+    fn update_layout(&mut self) {
+        let layout = self.keyboard_state.borrow().layout();
+        if layout != self.layout {
+            self.layout = layout;
+            if self.ready {
+                self.queue.borrow_mut().push_back(WindowManagerEvent::new(WindowHandle::NULL, WindowEvent::KeyboardLayoutChanged));
+            }
+        }
+    }
+
+    /// Queue a [WindowEvent::KeyDown] of the key repeating if due. Repeat stops if the window disabled it or is gone.
+    fn key_repeat(&mut self) {
+        let repeat = self.keyboard_state.borrow_mut().next_repeat();
+        if let Some(repeat) = repeat {
+            match self.windows.iter().find(|w| w.handle == repeat.window) {
+                Some(window) if window.keyboard.repeat() => 
+                    self.queue.borrow_mut().push_back(WindowManagerEvent::new(repeat.window, WindowEvent::Keyboard(WindowKeyboardEvent::KeyDown(repeat.key)))),
+                _ => self.keyboard_state.borrow_mut().repeat = None,
+            }
+        }
+    }
+
+    /// Handle wl_pointer events. Events over windows are grouped until wl_pointer.frame while those over 
+    /// client-side frames are handled at once.
+    fn pointer_event(&mut self, message : &WaylandMessage) {
+        if message.opcode == WL_POINTER_ENTER {
+            let mut state = self.pointer.borrow_mut();
+            state.serial = message.uint(0);
+            state.focus = message.uint(1);
+        } else if message.opcode == WL_POINTER_BUTTON {
+            // Button serial lets windows request activation.
+            self.activation.borrow_mut().serial = message.uint(0);
+        }
+        let focus = self.pointer.borrow().focus;
+
+        let (seat, pointer) = (self.seat, self.pointer.clone());
+        if let Some((_, lw)) = self.wayland_window(|lw| focus != 0 && lw.decoration.borrow().frame_surface == focus) {
+            let mut decoration = lw.decoration.borrow_mut();
+            let cursor = decoration.cursor();
+            match message.opcode {
+                WL_POINTER_ENTER => decoration.pointer_motion(message.fixed(2), message.fixed(3)),
+                WL_POINTER_LEAVE => decoration.pointer_leave(),
+                WL_POINTER_MOTION => decoration.pointer_motion(message.fixed(1), message.fixed(2)),
+                WL_POINTER_BUTTON => decoration.pointer_button(seat, message.uint(0), message.uint(2), message.uint(3) == WL_POINTER_BUTTON_STATE_PRESSED),
+                _ => {},
+            }
+
+            // Borders show resize cursors.
+            if message.opcode == WL_POINTER_ENTER || decoration.cursor() != cursor {
+                pointer.borrow_mut().set_cursor(Some(decoration.cursor()));
+            }
+        }
+
+        if let Some(window) = self.windows.iter().find(|w| matches!(&w.window, LinuxWindow::Wayland(lw) if lw.surface == focus)) {
+            let mut state = self.pointer.borrow_mut();
+            let position = state.position;
+            let coordinate = |(x, y) : (f64, f64)| WindowCoordinate { x : x as i32, y : y as i32 };
+
+            let events = match message.opcode {
+                WL_POINTER_ENTER => {
+                    state.position = (message.fixed(2), message.fixed(3));
+                    state.set_cursor(window.pointer.visible().then_some(window.pointer.cursor()));
+                    match window.pointer.mode() {
+                        WindowPointerMode::Cursor => vec![WindowEvent::PointerEnter, WindowEvent::Pointer(WindowPointerEvent::Move(coordinate(state.position)))],
+                        WindowPointerMode::Acceleration => vec![WindowEvent::PointerEnter],
+                    }
+                },
+                WL_POINTER_LEAVE => vec![WindowEvent::PointerLeave],
+                WL_POINTER_MOTION => {
+                    state.position = (message.fixed(1), message.fixed(2));
+                    match window.pointer.mode() {
+                        WindowPointerMode::Cursor => vec![WindowEvent::Pointer(WindowPointerEvent::Move(coordinate(state.position)))],
+                        // Acceleration comes from relative motion when available.
+                        WindowPointerMode::Acceleration if state.relative_pointer != 0 => vec![],
+                        WindowPointerMode::Acceleration => vec![WindowEvent::Pointer(WindowPointerEvent::Acceleration(PointerAcceleration { 
+                            x : (state.position.0 - position.0) as i32, y : (state.position.1 - position.1) as i32 }))],
+                    }
+                },
+                WL_POINTER_BUTTON => {
+                    let button = wayland_pointer_button(message.uint(2));
+                    vec![WindowEvent::Pointer(match message.uint(3) == WL_POINTER_BUTTON_STATE_PRESSED {
+                        true => WindowPointerEvent::ButtonDown(button, coordinate(position)),
+                        false => WindowPointerEvent::ButtonUp(button, coordinate(position)),
+                    })]
+                },
+                WL_POINTER_AXIS => {
+                    match message.uint(1) {
+                        WL_POINTER_AXIS_HORIZONTAL_SCROLL => state.scroll.0 += message.fixed(2),
+                        _ => state.scroll.1 += message.fixed(2),
+                    }
+                    vec![]
+                },
+                WL_POINTER_AXIS_DISCRETE | WL_POINTER_AXIS_VALUE120 => {
+                    // Discrete steps of version 5 to 7 were replaced by 1/120 of steps.
+                    let value = if message.opcode == WL_POINTER_AXIS_DISCRETE { message.int(1) * 120 } else { message.int(1) };
+                    match message.uint(0) {
+                        WL_POINTER_AXIS_HORIZONTAL_SCROLL => state.scroll120.0 += value,
+                        _ => state.scroll120.1 += value,
+                    }
+                    vec![]
+                },
+                WL_POINTER_FRAME => state.take_scroll().map(|scroll| WindowEvent::Pointer(WindowPointerEvent::Scroll(scroll))).into_iter().collect(),
+                _ => vec![],
+            };
+            state.frame.extend(events.into_iter().map(|event| WindowManagerEvent::new(window.handle, event)));
+        }
+
+        if message.opcode == WL_POINTER_FRAME {
+            self.pointer_frame();
+        } else {
+            self.pointer_frame_end();
+        }
+
+        if message.opcode == WL_POINTER_LEAVE {
+            self.pointer.borrow_mut().focus = 0;
+        }
+    }
+
+    /// Queue the unaccelerated motion of zwp_relative_pointer_v1 as [WindowPointerEvent::Acceleration] if the window under 
+    /// the pointer is in [WindowPointerMode::Acceleration]. Relative motion is part of the wl_pointer frame.
+    fn relative_motion(&mut self, message : &WaylandMessage) {
+        let mut state = self.pointer.borrow_mut();
+        let focus = state.focus;
+        if let Some(window) = self.windows.iter().find(|w| matches!(&w.window, LinuxWindow::Wayland(lw) if lw.surface == focus)) {
+            if window.pointer.mode() == WindowPointerMode::Acceleration {
+                let acceleration = state.relative_motion(message.fixed(4), message.fixed(5));
+                if acceleration.x != 0 || acceleration.y != 0 {
+                    state.frame.push(WindowManagerEvent::new(window.handle, WindowEvent::Pointer(WindowPointerEvent::Acceleration(acceleration))));
+                }
+            }
+        }
+        drop(state);
+
+        self.pointer_frame_end();
+    }
+
+    /// Queue the events of the current wl_pointer frame.
+    fn pointer_frame(&mut self) {
+        let mut state = self.pointer.borrow_mut();
+        self.queue.borrow_mut().extend(state.frame.drain(..));
+    }
+
+    /// Events are sent without wl_pointer.frame before version 5 and are queued at once.
+    fn pointer_frame_end(&mut self) {
+        let pointer = self.pointer.borrow().pointer;
+        if self.connection.borrow().version(pointer) < 5 {
+            self.pointer_frame();
+        }
+    }
+
+    /// Find a [WaylandWindow] matching predicate.
+    /// 
+    /// Returns Some(([WindowHandle], [WaylandWindow])) if found.
+    fn wayland_window<P : Fn(&WaylandWindow) -> bool>(&mut self, predicate : P) -> Option<(WindowHandle, &mut WaylandWindow)> {
+        self.windows.iter_mut().find_map(|w| match &mut w.window {
+            LinuxWindow::Wayland(lw) if predicate(lw) => Some((w.handle, lw)),
+            _ => None,
+        })
+    }
+
+    /// Create a xdg_toplevel [Window] from builder properties.
+    /// 
+    /// Sub windows placed relative to their parent are created as xdg_popup instead.
     pub(crate) fn build(&mut self, builder : &WindowBuilder) -> Result<WindowHandle, WindowError> {
-        todo!()
+
+        // Builder is validated before any object is created, xdg_toplevel.set_min_size above max is a protocol error.
+        if builder.min_size > builder.max_size {
+            return Err(WindowError::WindowMinSizeBiggerThanMax);
+        }
+        if builder.size > builder.max_size || builder.size < builder.min_size {
+            return Err(WindowError::WindowSizeOOB);
+        }
+
+        // Compositors place toplevels, position is only checked once outputs gave the desktop bounds. 
+        // Positions relative to a parent are placed by the popup positioner.
+        #[cfg(not(feature = "single_opt"))]
+        let relative = builder.parent.is_some();
+        #[cfg(feature = "single_opt")]
+        let relative = false;
+        if !relative && self.displays.desktop.is_some() {
+            Window::get_window_desktop_position(builder.size, self.displays.clone(), builder.position.clone(), None, true)?;
+        }
+
+        // Parent toplevel and popup positioner, if any.
+        #[cfg(not(feature = "single_opt"))]
+        let (parent, positioner) = match builder.parent {
+            Some(parent) => {
+                let Some(window) = self.windows.iter().find(|w| w.handle == parent) else {
+                    return Err(WindowError::InvalidWindowHandle);
+                };
+                let LinuxWindow::Wayland(plw) = &window.window else {
+                    return Err(WindowError::InvalidWindowHandle);
+                };
+                let title = plw.decoration.borrow().title_height();
+                let positioner = WaylandPositioner::from_sub(&builder.position, &builder.sub.anchors, builder.size, window.size, title)
+                    .map(|p| (plw.xdg_surface, p.create(&mut self.connection.borrow_mut(), self.wm_base)));
+                (plw.toplevel, positioner)
+            },
+            None => (0, None),
+        };
+        #[cfg(feature = "single_opt")]
+        let positioner = None;
+
+        let globals = WaylandWindowGlobals { wm_base : self.wm_base, decorations : self.decorations, scaling : self.scaling };
+        let mut lw = WaylandWindow::new(self.connection.clone(), self.queue.clone(), self.handles.insert(), self.display_outputs.clone(), globals, positioner, 
+            &builder.frame);
+        lw.set_activation(self.activation.clone());
+        lw.set_grab(self.grab.clone());
+        lw.set_toplevels(self.toplevels.clone());
+        if lw.toplevel != 0 {
+            self.toplevels.borrow_mut().push((lw.handle(), lw.toplevel));
+        }
+
+        // Positioner is only needed to create the popup.
+        if let Some((_, positioner)) = positioner {
+            let mut conn = self.connection.borrow_mut();
+            conn.send(positioner, XDG_POSITIONER_DESTROY, vec![]);
+            conn.destroyed(positioner);
+        }
+        let handle = lw.handle();
+        let surface = lw.surface;
+        let frame = WaylandWindowFrame::new(lw.decoration.clone());
+        let mut window = Window::new(builder, LinuxWindow::Wayland(lw), self.displays.clone(), handle);
+        window.frame.frame = Some(LinuxWindowFrame::Wayland(frame));
+        window.keyboard.keyboard = Some(LinuxKeyboard::Wayland(WaylandKeyboard::new(surface, window.keyboard.mode(), self.keyboard_state.clone(), 
+            self.text_input.clone())));
+        window.pointer.pointer = Some(LinuxPointer::Wayland(WaylandPointer::new(surface, &window.pointer, self.pointer.clone())));
+
+        // Initial states are applied with the initial commit done by show.
+        let initial = window.window.set_size(window.size)
+            .and_then(|_| window.window.set_title(&window.title))
+            .and_then(|_| window.window.set_size_min(window.min_size))
+            .and_then(|_| window.window.set_size_max(window.max_size));
+        if let Err(err) = initial {
+            self.discard(window);
+            return Err(err);
+        }
+
+        #[cfg(not(feature = "single_opt"))]
+        if let LinuxWindow::Wayland(lw) = &mut window.window {
+            if lw.popup == 0 && parent != 0 {
+                lw.set_parent_toplevel(parent);
+            } else if builder.sub.grab_input {
+                lw.popup_grab();
+            }
+        }
+
+        if builder.maximized {
+            window.window.maximize();
+        }
+        if let Some(fsmode) = &builder.fsmode {
+            window.window.set_fullscreen(fsmode.clone());
+        }
+        if builder.visible {
+            window.window.show();
+
+            // Token of the launcher is used once, by the first window shown.
+            if let Some(token) = self.launch_token.take() {
+                self.activation.borrow_mut().activate(&token, surface);
+            }
+        }
+
+        // Minimize is ignored by compositors before the window is mapped.
+        if builder.minimized {
+            window.window.minimize();
+        }
+
+        self.windows.push(window);
+        Ok(handle)
+    }
+
+    /// Destroy a [Window] refused by [WaylandWindowManager::build], releasing its handle and toplevel.
+    fn discard(&mut self, mut window : Window) {
+        if let LinuxWindow::Wayland(lw) = &mut window.window {
+            lw.close();
+        }
+
+        // Window was never returned, its Closed event is dropped.
+        self.queue.borrow_mut().retain(|e| e.window != window.handle);
+        self.toplevels.borrow_mut().retain(|(handle, _)| *handle != window.handle);
+        self.handles.remove(window.handle);
     }
 
     #[inline(always)]
-    pub fn window(&mut self, window : WindowHandle) -> Result<&Window, WindowError> {
-        todo!()
+    pub fn window(&self, window : WindowHandle) -> Result<&Window, WindowError> {
+        match self.windows.iter().find(|w| w.handle == window) {
+            Some(window) => Ok(window),
+            None => Err(WindowError::InvalidWindowHandle),
+        }
     } 
 
     #[inline(always)]
     pub fn window_mut(&mut self, window : WindowHandle) -> Result<&mut Window, WindowError> {
-        todo!()
+        match self.windows.iter_mut().find(|w| w.handle == window) {
+            Some(window) => Ok(window),
+            None => Err(WindowError::InvalidWindowHandle),
+        }
     }
 
 
     #[inline(always)]
     pub fn displays(&self) -> &Displays {
-        todo!()
+        &self.displays
+    }
+
+    /// Wayland display handle of the libwayland wl_display. 
+    /// 
+    /// Returns Err(HandleError::NotSupported) if libwayland-client is missing, since the native wire protocol has no wl_display.
+    #[cfg(feature = "raw_window_handle")]
+    pub(crate) fn raw_display_handle(&self) -> Result<raw_window_handle::RawDisplayHandle, raw_window_handle::HandleError> {
+        match std::ptr::NonNull::new(self.connection.borrow().display_ptr()) {
+            Some(display) => Ok(raw_window_handle::WaylandDisplayHandle::new(display).into()),
+            None => Err(raw_window_handle::HandleError::NotSupported),
+        }
+    }
+
+    /// Instance extensions needed by VK_KHR_wayland_surface.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowVulkanUnsupported`]) if libwayland-client is missing, since the native wire protocol 
+    /// has no wl_display.
+    #[cfg(feature = "vulkan")]
+    pub(crate) fn vulkan_required_extensions(&self) -> Result<&'static [&'static std::ffi::CStr], WindowError> {
+        match self.connection.borrow().display_ptr().is_null() {
+            true => Err(WindowError::WindowVulkanUnsupported),
+            false => Ok(super::vulkan::WAYLAND_VULKAN_EXTENSIONS),
+        }
+    }
+
+    #[inline(always)]
+    pub fn input_devices(&self) -> &InputDevices {
+        &self.devices
+    }
+
+    #[inline(always)]
+    pub fn keyboard_layout(&self) -> &KeyboardLayout {
+        &self.layout
+    }
+
+    #[inline(always)]
+    pub fn key_label(&self, key : KeyCode) -> Option<String> {
+        self.keyboard_state.borrow().keymap.as_ref()?.key_label(key, self.layout.group)
+    }
+
+    /// Wayland has no protocol for clients to grab keys outside of their surfaces. System-wide hotkeys are 
+    /// configured in the compositor instead.
+    /// 
+    /// # Errors
+    /// Always returns Err([`WindowError::HotkeyUnavailable`]).
+    #[inline(always)]
+    pub fn register_hotkey(&mut self, _key : KeyCode, _modifiers : KeyModifiers) -> Result<HotkeyId, WindowError> {
+        Err(WindowError::HotkeyUnavailable)
+    }
+
+    /// No hotkey can be registered on Wayland, always returns false.
+    #[inline(always)]
+    pub fn unregister_hotkey(&mut self, _hotkey : HotkeyId) -> bool {
+        false
+    }
+}
+
+/// Name of a seat device like `seat0 keyboard`.
+fn wayland_device_name(seat : &str, kind : InputDeviceKind) -> String {
+    let device = match kind {
+        InputDeviceKind::Keyboard => "keyboard",
+        _ => "pointer",
+    };
+
+    match seat {
+        "" => device.to_string(),
+        seat => format!("{} {}", seat, device),
+    }
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Wayland window manager tests with mock compositor
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::{event::{PointerAcceleration, PointerScroll, WindowCoordinate, WindowEvent, WindowKeyboardEvent, WindowPointerEvent}, device::InputDeviceKind, frame::WindowFrameButtonMode, 
+        keyboard::{KeyModifiers, WindowKeyboardMode}, pointer::{WindowCursor, WindowPointerMode}, WindowBuilder, WindowError, WindowFullScreenMode, WindowHandle, WindowPosition, WindowSize};
+    #[cfg(not(feature = "single_opt"))]
+    use crate::{sub::SubWindow, WindowRelativePosition};
+
+    use super::WaylandWindowManager;
+    use super::super::{frame::{WL_CSD_BORDER, WL_CSD_BUTTON_WIDTH, WL_CSD_TITLE_HEIGHT}, mock::{mock_compositor, MockOutput, MOCK_GLOBALS, MOCK_REPEAT}, 
+        protocol::{BTN_RIGHT, WL_REGISTRY, WL_SEAT_CAPABILITY_KEYBOARD, WL_SEAT_CAPABILITY_POINTER, WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_DEFAULT, WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_TEXT, ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE, ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE}};
+    #[cfg(not(feature = "single_opt"))]
+    use super::super::protocol::{XDG_POSITIONER_ANCHOR_BOTTOM_RIGHT, XDG_POSITIONER_ANCHOR_TOP_LEFT, XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_FLIP_Y};
+
+    /// Roundtrip then returns all events fetched.
+    fn events(wm : &mut WaylandWindowManager) -> Vec<WindowEvent> {
+        wm.roundtrip().unwrap();
+
+        let mut events : Vec<WindowEvent> = Vec::new();
+        while let Some(event) = wm.event() {
+            events.push(event.event.clone());
+        }
+        events
+    }
+
+    #[test]
+    fn wayland_manager_registry() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("wl_seat", 9), ("xdg_wm_base", 5)]);
+        let wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        assert_eq!(wm.globals().len(), 3);
+        assert_eq!((wm.globals()[0].name, wm.globals()[0].interface.as_str(), wm.globals()[0].version), (1, "wl_compositor", 6));
+        assert_eq!((wm.globals()[1].name, wm.globals()[1].interface.as_str(), wm.globals()[1].version), (2, "wl_seat", 9));
+        assert!(mock.run(|m| m.received("wl_display.get_registry") && m.received("wl_display.sync")));
+        assert!(mock.run(|m| m.bound("wl_compositor").is_some() && m.bound("xdg_wm_base").is_some()));
+    }
+
+    #[test]
+    fn wayland_manager_xdg_shell_missing() {
+        let (connection, _mock) = mock_compositor(&[("wl_compositor", 6)]);
+        assert_eq!(WaylandWindowManager::from_connection(connection).err(), Some(WindowError::WindowManagerMissingProtocol("xdg_wm_base".to_string())));
+
+        let (connection, _mock) = mock_compositor(&[("xdg_wm_base", 5)]);
+        assert_eq!(WaylandWindowManager::from_connection(connection).err(), Some(WindowError::WindowManagerMissingProtocol("wl_compositor".to_string())));
+    }
+
+    #[test]
+    fn wayland_manager_global_remove() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        mock.run(|m| m.remove_global(3));
+        wm.roundtrip().unwrap();
+
+        assert_eq!(wm.globals().len(), 2);
+        assert!(wm.globals().iter().all(|g| g.interface != "wl_seat"));
+
+        // Interface not advertised can't be bound
+        assert!(wm.bind(&WL_REGISTRY, 1).is_none());
+    }
+
+    #[test]
+    fn wayland_manager_event() {
+        let (connection, _mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        assert!(wm.event().is_none());
+        assert!(events(&mut wm).is_empty());
+    }
+
+    #[test]
+    fn wayland_manager_ping() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        mock.run(|m| m.ping(42));
+        assert!(events(&mut wm).is_empty());
+        assert!(mock.run(|m| m.received("xdg_wm_base.pong")));
+    }
+
+    #[test]
+    fn wayland_manager_hotkey() {
+        let (connection, _mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        // Hotkeys are left to the compositor.
+        assert_eq!(wm.register_hotkey(65, KeyModifiers::new(false, true, false, false)), Err(WindowError::HotkeyUnavailable));
+        assert!(!wm.unregister_hotkey(0));
+    }
+
+    #[test]
+    fn wayland_manager_protocol_error() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        mock.run(|m| m.protocol_error(1, 0, "invalid object"));
+        assert_eq!(wm.roundtrip(), Err(WindowError::WindowManagerConnectionLost));
+    }
+
+    #[test]
+    fn wayland_manager_connection_lost() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        drop(mock);
+        assert_eq!(wm.roundtrip(), Err(WindowError::WindowManagerConnectionLost));
+        assert!(wm.event().is_none());
+        assert_eq!(wm.event_wait().event, WindowEvent::NoEvent);
+    }
+
+    #[test]
+    fn wayland_manager_build() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let mut builder = WindowBuilder::new();
+        builder.title("Wayland").size_min(WindowSize::new(100, 50)).size_max(WindowSize::new(1000, 500));
+        let handle = wm.build(&builder).unwrap();
+
+        // Initial commit is answered with a configure, which requests the first frame.
+        assert!(matches!(events(&mut wm)[..], [WindowEvent::Shown, WindowEvent::RedrawRequested(_)]));
+        assert!(wm.window(handle).unwrap().visible());
+
+        let toplevel = mock.run(|m| m.toplevels[0].clone());
+        assert_eq!(toplevel.title, "Wayland");
+        assert_eq!(toplevel.min_size, (100, 50));
+        assert_eq!(toplevel.max_size, (1000, 500));
+        assert!(mock.run(|m| m.received("xdg_surface.ack_configure")));
+
+        wm.window_mut(handle).unwrap().set_title("Renamed").unwrap();
+        wm.roundtrip().unwrap();
+        assert_eq!(mock.run(|m| m.toplevels[0].title.clone()), "Renamed");
+    }
+
+    #[test]
+    fn wayland_manager_build_invalid() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        // Refused builders create nothing and don't use a handle.
+        assert_eq!(wm.build(WindowBuilder::new().size_min(WindowSize::new(500, 500)).size_max(WindowSize::new(400, 400))).err(), 
+            Some(WindowError::WindowMinSizeBiggerThanMax));
+        assert_eq!(wm.build(WindowBuilder::new().size(WindowSize::new(2000, 480))).err(), Some(WindowError::WindowSizeOOB));
+        #[cfg(not(feature = "single_opt"))]
+        assert_eq!(wm.build(WindowBuilder::new().parent(Some(WindowHandle::new(7, 1)))).err(), Some(WindowError::InvalidWindowHandle));
+        assert!(events(&mut wm).is_empty());
+        assert!(mock.run(|m| m.toplevels.is_empty()));
+
+        // Position is checked against the desktop once outputs are known.
+        mock.run(|m| m.add_output(MockOutput::new("DP-1", (0, 0), (1920, 1080), 1, (1920, 1080))));
+        events(&mut wm);
+        let position = crate::WindowRelativePosition::Desktop(WindowPosition::new(1800, 0));
+        assert_eq!(wm.build(WindowBuilder::new().position(position)).err(), Some(WindowError::WindowRelativePositionOOB));
+        assert!(mock.run(|m| m.toplevels.is_empty()));
+
+        assert_eq!(wm.build(&WindowBuilder::new()).unwrap(), WindowHandle::new(0, 1));
+    }
+
+    #[test]
+    fn wayland_manager_size_limits() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_subcompositor", 1), ("wl_shm", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        // Limits include the title bar, except 0 which means no limit.
+        wm.build(WindowBuilder::new().size_min(WindowSize::new(0, 0)).size_max(WindowSize::new(1000, 500))).unwrap();
+        events(&mut wm);
+        let toplevel = mock.run(|m| m.toplevels[0].clone());
+        assert_eq!(toplevel.min_size, (0, 0));
+        assert_eq!(toplevel.max_size, (1000, 500 + WL_CSD_TITLE_HEIGHT));
+    }
+
+    #[test]
+    fn wayland_manager_build_hidden() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().hide()).unwrap();
+        assert!(events(&mut wm).is_empty());
+        assert!(!mock.run(|m| m.toplevels[0].mapped));
+
+        wm.window_mut(handle).unwrap().show();
+        assert!(matches!(events(&mut wm)[..], [WindowEvent::Shown, WindowEvent::RedrawRequested(_)]));
+
+        wm.window_mut(handle).unwrap().hide();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Hidden]);
+        assert!(!wm.window(handle).unwrap().visible());
+        assert!(!mock.run(|m| m.toplevels[0].mapped));
+    }
+
+    #[test]
+    fn wayland_manager_configure() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+
+        mock.run(|m| m.configure(0, 800, 600));
+        assert_eq!(events(&mut wm), vec![WindowEvent::Resized(WindowSize::new(800, 600))]);
+        assert_eq!(wm.window(handle).unwrap().size(), WindowSize::new(800, 600));
+
+        // Same size doesn't resize
+        mock.run(|m| m.configure(0, 800, 600));
+        assert!(events(&mut wm).is_empty());
+
+        wm.window_mut(handle).unwrap().maximize();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Maximized]);
+        assert!(wm.window_mut(handle).unwrap().maximized());
+
+        wm.window_mut(handle).unwrap().set_fullscreen(WindowFullScreenMode::Current);
+        assert_eq!(events(&mut wm), vec![WindowEvent::Fullscreen]);
+        assert!(wm.window(handle).unwrap().fullscreen());
+
+        // Compositor configures once per state removed.
+        wm.window_mut(handle).unwrap().restore();
+        assert_eq!(events(&mut wm).last(), Some(&WindowEvent::Restored));
+        assert!(!wm.window(handle).unwrap().fullscreen());
+        assert!(!wm.window_mut(handle).unwrap().maximized());
+
+        wm.window_mut(handle).unwrap().minimize();
+        wm.roundtrip().unwrap();
+        assert!(mock.run(|m| m.received("xdg_toplevel.set_minimized")));
+    }
+
+    #[test]
+    fn wayland_manager_close() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+
+        mock.run(|m| m.close(0));
+        assert_eq!(events(&mut wm), vec![WindowEvent::CloseButtonPressed]);
+
+        wm.window_mut(handle).unwrap().close();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Closed]);
+        assert_eq!(wm.window(handle).err(), Some(WindowError::InvalidWindowHandle));
+        assert!(mock.run(|m| m.toplevels.is_empty()));
+    }
+
+    #[test]
+    fn wayland_manager_outputs() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("zxdg_output_manager_v1", 3)]);
+        mock.run(|m| {
+            m.add_output(MockOutput::new("DP-1", (0, 0), (2560, 1440), 2, (1280, 720)));
+            m.add_output(MockOutput::new("HDMI-A-1", (1280, 0), (1920, 1080), 1, (1920, 1080)));
+        });
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let displays = wm.displays();
+        assert_eq!(displays.list.len(), 2);
+        assert_eq!((displays.list[0].identifier.as_str(), displays.list[0].description()), ("DP-1", "Mock DP-1"));
+        assert_eq!((displays.list[0].resolution.width, displays.list[0].resolution.height, displays.list[0].refresh_rate), (2560, 1440, 6000));
+        assert_eq!((displays.list[0].size.width, displays.list[0].size.height, displays.list[0].scale()), (600, 340, 2.0));
+        assert!(displays.primary().is_some_and(|d| d.handle == 0));
+        assert_eq!((displays.list[1].identifier.as_str(), displays.list[1].position.x), ("HDMI-A-1", 1280));
+        assert_eq!(displays.desktop.as_ref().map(|d| (d.current.width, d.current.height)), Some((3200, 1080)));
+
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+
+        // Removed output is released and displays refreshed, windows included.
+        let global = mock.run(|m| m.outputs[0].global);
+        mock.run(move |m| m.remove_global(global));
+        events(&mut wm);
+        assert!(mock.run(|m| m.received("wl_output.release") && m.received("zxdg_output_v1.destroy")));
+        assert_eq!(wm.displays().list.len(), 1);
+        assert_eq!(wm.displays().list[0].identifier, "HDMI-A-1");
+        assert_eq!(wm.window(handle).unwrap().displays.list.len(), 1);
+
+        // Output added later is bound
+        mock.run(|m| m.add_output(MockOutput::new("DP-2", (-1920, 0), (1920, 1200), 1, (1920, 1200))));
+        events(&mut wm);
+        assert_eq!(wm.displays().list.len(), 2);
+        assert_eq!((wm.displays().list[1].identifier.as_str(), wm.displays().list[1].position.x), ("DP-2", -1920));
+        assert_eq!(wm.displays().desktop.as_ref().map(|d| (d.current.width, d.current.height)), Some((5120, 1200)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn wayland_manager_fullscreen_output() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        mock.run(|m| {
+            m.add_output(MockOutput::new("DP-1", (0, 0), (1920, 1080), 1, (1920, 1080)));
+            m.add_output(MockOutput::new("DP-2", (1920, 0), (1920, 1080), 1, (1920, 1080)));
+        });
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        // Without xdg-output, logical informations come from wl_output.
+        assert_eq!(wm.displays().list.len(), 2);
+        assert_eq!(wm.displays().list[1].position.x, 1920);
+
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+        let outputs = mock.run(|m| (m.outputs[0].output, m.outputs[1].output));
+
+        wm.window_mut(handle).unwrap().set_fullscreen(WindowFullScreenMode::Display(1));
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.toplevels[0].fullscreen_output), outputs.1);
+
+        wm.window_mut(handle).unwrap().restore();
+        wm.window_mut(handle).unwrap().set_fullscreen(WindowFullScreenMode::Primary);
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.toplevels[0].fullscreen_output), outputs.0);
+
+        // Current is the output the surface entered last.
+        mock.run(|m| m.enter(0, 1));
+        wm.window_mut(handle).unwrap().restore();
+        events(&mut wm);
+        wm.window_mut(handle).unwrap().set_fullscreen(WindowFullScreenMode::Current);
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.toplevels[0].fullscreen_output), outputs.1);
+
+        wm.window_mut(handle).unwrap().restore();
+        events(&mut wm);
+        wm.window_mut(handle).unwrap().set_fullscreen(WindowFullScreenMode::Desktop);
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.toplevels[0].fullscreen_output), 0);
+    }
+
+    #[test]
+    fn wayland_manager_server_decorations() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_subcompositor", 1), ("wl_shm", 1), 
+            ("zxdg_decoration_manager_v1", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.toplevels[0].decoration_mode), ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE);
+        assert!(mock.run(|m| m.frame(0)).is_none());
+
+        // Hidden frame is left to the client, which draws nothing.
+        wm.window_mut(handle).unwrap().frame_mut().hide();
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.toplevels[0].decoration_mode), ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE);
+        assert!(mock.run(|m| m.frame(0)).is_none());
+
+        wm.window_mut(handle).unwrap().frame_mut().show();
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.toplevels[0].decoration_mode), ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE);
+
+        wm.window_mut(handle).unwrap().close();
+        events(&mut wm);
+        assert!(mock.run(|m| m.received("zxdg_toplevel_decoration_v1.destroy")));
+    }
+
+    #[test]
+    fn wayland_manager_client_decorations() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_subcompositor", 1), ("wl_shm", 1), ("wl_seat", 9)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+        let (frame, width, height) = mock.run(|m| m.frame(0)).unwrap();
+        assert_eq!((width, height), (640 + 2 * WL_CSD_BORDER, 480 + WL_CSD_TITLE_HEIGHT + 2 * WL_CSD_BORDER));
+        assert!(mock.run(|m| m.received("xdg_surface.set_window_geometry")));
+
+        // Compositor size includes the title bar
+        mock.run(|m| m.configure(0, 800, 600 + WL_CSD_TITLE_HEIGHT));
+        assert_eq!(events(&mut wm), vec![WindowEvent::Resized(WindowSize::new(800, 600))]);
+        assert_eq!(mock.run(|m| m.frame(0)).map(|f| (f.1, f.2)), Some((800 + 2 * WL_CSD_BORDER, 600 + WL_CSD_TITLE_HEIGHT + 2 * WL_CSD_BORDER)));
+
+        // Buttons from right to left are close, max and min.
+        let title = (WL_CSD_BORDER + WL_CSD_TITLE_HEIGHT / 2) as f64;
+        let button = |index : i32| (WL_CSD_BORDER + 800 - (index + 1) * WL_CSD_BUTTON_WIDTH + WL_CSD_BUTTON_WIDTH / 2) as f64;
+
+        mock.run(move |m| { m.pointer_enter(frame, button(0), title); m.pointer_click(); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::CloseButtonPressed]);
+
+        // Maximize request is sent while handling the click, compositor answers after.
+        mock.run(move |m| { m.pointer_motion(button(1), title); m.pointer_click(); });
+        wm.roundtrip().unwrap();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Maximized]);
+        assert!(mock.run(|m| m.received("xdg_toplevel.set_maximized")));
+
+        wm.window_mut(handle).unwrap().frame_mut().set_button_min(WindowFrameButtonMode::Overriden);
+        mock.run(move |m| { m.pointer_motion(button(2), title); m.pointer_click(); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::MinimizeButtonPressed]);
+
+        wm.window_mut(handle).unwrap().frame_mut().set_button_min(WindowFrameButtonMode::Disable);
+        mock.run(move |m| m.pointer_click());
+        assert!(events(&mut wm).is_empty());
+        assert!(!mock.run(|m| m.received("xdg_toplevel.set_minimized")));
+
+        mock.run(move |m| { m.pointer_motion(WL_CSD_BORDER as f64 + 10.0, title); m.pointer_click(); });
+        events(&mut wm);
+        assert!(mock.run(|m| m.received("xdg_toplevel.move")));
+
+        // Hidden frame is removed
+        wm.window_mut(handle).unwrap().frame_mut().hide();
+        events(&mut wm);
+        assert!(mock.run(|m| m.frame(0)).is_none());
+        assert!(mock.run(|m| m.received("wl_subsurface.destroy")));
+    }
+
+    #[test]
+    fn wayland_manager_keyboard() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+        assert_eq!(wm.keyboard_layout().name, "English (US)");
+        assert_eq!(wm.key_label(24), Some("Q".to_string()));
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+
+        // Keys already pressed on enter are tracked. Evdev codes are offset by 8.
+        mock.run(|m| { m.keyboard_enter(0, &[30]); m.modifiers(0x01, 0x02, 0); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::Focus]);
+        let keyboard = wm.window(handle).unwrap().keyboard();
+        assert!(keyboard.is_pressed(38));
+        assert!(keyboard.modifiers().shift && keyboard.locks().caps && !keyboard.locks().num);
+
+        mock.run(|m| { m.key(16, true); m.key(16, false); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::Keyboard(WindowKeyboardEvent::KeyDown(24)), 
+            WindowEvent::Keyboard(WindowKeyboardEvent::KeyUp(24))]);
+
+        // Scroll Lock is toggled client-side.
+        mock.run(|m| { m.key(70, true); m.key(70, false); });
+        events(&mut wm);
+        assert!(wm.window(handle).unwrap().keyboard().locks().scroll);
+
+        // Group change switches the layout.
+        mock.run(|m| m.modifiers(0, 0, 1));
+        assert_eq!(events(&mut wm), vec![WindowEvent::KeyboardLayoutChanged]);
+        assert_eq!((wm.keyboard_layout().group, wm.keyboard_layout().name.as_str()), (1, "French"));
+        assert_eq!(wm.key_label(24), Some("A".to_string()));
+        assert_eq!(wm.key_label(9), Some("Escape".to_string()));
+
+        mock.run(|m| m.keyboard_leave(0));
+        assert_eq!(events(&mut wm), vec![WindowEvent::Blur]);
+        assert!(!wm.window(handle).unwrap().keyboard().is_pressed(38));
+    }
+
+    #[test]
+    fn wayland_manager_text_input() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9), ("zwp_text_input_manager_v3", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+
+        // Text input stays disabled in direct mode.
+        mock.run(|m| m.text_enter(0, true));
+        events(&mut wm);
+        assert!(!mock.run(|m| m.text_enabled));
+
+        // Text mode enables it with the text field state.
+        let keyboard = wm.window_mut(handle).unwrap().keyboard_mut();
+        keyboard.set_surrounding_text("Hello", 5, 5);
+        keyboard.set_text_cursor(WindowCoordinate { x : 10, y : 20 }, WindowSize::new(2, 16));
+        keyboard.set_mode(WindowKeyboardMode::Text);
+        events(&mut wm);
+        assert_eq!(mock.run(|m| (m.text_enabled, m.surrounding.clone(), m.text_cursor)), 
+            (true, Some(("Hello".to_string(), 5, 5)), Some((10, 20, 2, 16))));
+
+        // Pre-edit is replaced and cleared when text is committed.
+        mock.run(|m| { m.text_preedit("wo", 2, 2); m.text_done(); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::Keyboard(WindowKeyboardEvent::Preedit("wo".to_string(), Some((2, 2))))]);
+        mock.run(|m| { m.text_preedit("wor", -1, -1); m.text_done(); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::Keyboard(WindowKeyboardEvent::Preedit("wor".to_string(), None))]);
+        mock.run(|m| { m.text_delete(1, 0); m.text_commit(" world"); m.text_done(); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::Keyboard(WindowKeyboardEvent::DeleteSurrounding(1, 0)), 
+            WindowEvent::Keyboard(WindowKeyboardEvent::Text(" world".to_string())), 
+            WindowEvent::Keyboard(WindowKeyboardEvent::Preedit(String::new(), None))]);
+
+        // Leaving disables text input, entering enables it again.
+        mock.run(|m| m.text_enter(0, false));
+        events(&mut wm);
+        assert!(!mock.run(|m| m.text_enabled));
+        mock.run(|m| m.text_enter(0, true));
+        events(&mut wm);
+        assert_eq!(mock.run(|m| (m.text_enabled, m.surrounding.clone())), (true, Some(("Hello".to_string(), 5, 5))));
+
+        wm.window_mut(handle).unwrap().keyboard_mut().set_mode(WindowKeyboardMode::Direct);
+        events(&mut wm);
+        assert!(!mock.run(|m| m.text_enabled));
+    }
+
+    #[test]
+    fn wayland_manager_launch_token() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("xdg_activation_v1", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+        wm.launch_token = Some("launcher".to_string());
+
+        // Hidden windows don't use the token, the first window shown does.
+        wm.build(WindowBuilder::new().hide()).unwrap();
+        wm.build(&WindowBuilder::new()).unwrap();
+        wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+
+        let surface = mock.run(|m| m.toplevels[1].surface);
+        assert_eq!(mock.run(|m| m.activated.clone()), vec![("launcher".to_string(), surface)]);
+        assert!(wm.launch_token.is_none());
+    }
+
+    #[test]
+    fn wayland_manager_activation() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9), ("xdg_activation_v1", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let first = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        let second = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+
+        // Token is requested with the serial of the last input and the focused surface.
+        let serial = mock.run(|m| { m.keyboard_enter(0, &[]); m.key(16, true); m.serial });
+        events(&mut wm);
+        let surfaces = mock.run(|m| (m.toplevels[0].surface, m.toplevels[1].surface));
+        wm.window_mut(second).unwrap().activate();
+        events(&mut wm);
+
+        let (tokens, activated) = mock.run(|m| (m.tokens.clone(), m.activated.clone()));
+        assert_eq!(tokens, vec![(tokens[0].0, serial, surfaces.0)]);
+        assert_eq!(activated, vec![(format!("token-{}", tokens[0].0), surfaces.1)]);
+        assert!(mock.run(|m| m.received("xdg_activation_token_v1.destroy")));
+
+        // Token done after the window closed doesn't activate it.
+        wm.window_mut(first).unwrap().activate();
+        wm.window_mut(first).unwrap().close();
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.activated.len()), 1);
+    }
+
+    #[test]
+    #[cfg(not(feature = "single_opt"))]
+    fn wayland_manager_popup() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9), ("wl_subcompositor", 1), ("wl_shm", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let parent = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+
+        // Menu opened with a key grabs with its serial. Position is relative to content, below the client-side title bar.
+        let serial = mock.run(|m| { m.keyboard_enter(0, &[]); m.key(16, true); m.serial });
+        events(&mut wm);
+        let mut sub = SubWindow::new();
+        sub.grab_input = true;
+        let menu = wm.build(WindowBuilder::new().size(WindowSize::new(100, 50)).parent(Some(parent))
+            .position(WindowRelativePosition::Parent(WindowPosition::new(10, 20))).subwindow(sub)).unwrap();
+        events(&mut wm);
+
+        let (popup, parent_surface) = mock.run(|m| (m.toplevels[1].clone(), m.toplevels[0].xdg_surface));
+        assert_eq!((popup.toplevel, popup.parent, popup.grab, popup.mapped), (0, parent_surface, serial, true));
+        assert_eq!((popup.positioner.size, popup.positioner.anchor_rect), ((100, 50), (10, 20 + WL_CSD_TITLE_HEIGHT, 1, 1)));
+        assert_eq!((popup.positioner.anchor, popup.positioner.gravity), (XDG_POSITIONER_ANCHOR_TOP_LEFT, XDG_POSITIONER_ANCHOR_BOTTOM_RIGHT));
+        assert_ne!(popup.positioner.adjustment & XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_FLIP_Y, 0);
+        assert!(mock.run(|m| m.positioners.is_empty() && m.frame(1).is_none()));
+
+        // Clicking outside dismisses the popup.
+        mock.run(|m| m.popup_done(1));
+        assert!(events(&mut wm).contains(&WindowEvent::Closed));
+        assert_eq!(wm.window(menu).err(), Some(WindowError::InvalidWindowHandle));
+        assert!(mock.run(|m| m.received("xdg_popup.destroy") && m.toplevels.len() == 1));
+
+        // Anchors dock the popup to the parent, stretched between opposite anchors, without grab.
+        let mut sub = SubWindow::new();
+        sub.anchors.set(None, None, Some(WindowPosition::new(5, 5)), Some(WindowPosition::new(-5, 5)));
+        wm.build(WindowBuilder::new().size(WindowSize::new(100, 50)).parent(Some(parent)).subwindow(sub)).unwrap();
+        events(&mut wm);
+
+        let popup = mock.run(|m| m.toplevels[1].clone());
+        assert_eq!((popup.positioner.size, popup.positioner.offset, popup.grab), ((630, 50), (5, 5), 0));
+        assert_eq!(popup.positioner.anchor_rect, (0, WL_CSD_TITLE_HEIGHT, 640, 480));
+        assert_eq!((popup.positioner.anchor, popup.positioner.gravity), (XDG_POSITIONER_ANCHOR_TOP_LEFT, XDG_POSITIONER_ANCHOR_BOTTOM_RIGHT));
+
+        // Sub windows placed on desktop stay toplevels.
+        wm.build(WindowBuilder::new().size(WindowSize::new(100, 50)).parent(Some(parent))).unwrap();
+        events(&mut wm);
+        assert!(mock.run(|m| m.toplevels[2].toplevel != 0 && m.toplevels[2].popup == 0));
+    }
+
+    #[cfg(not(feature = "single_opt"))]
+    #[test]
+    fn wayland_manager_parent() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let parent = wm.build(&WindowBuilder::new()).unwrap();
+        let child = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+
+        // Parent is set on the toplevel after build and removed with None.
+        assert_eq!(wm.window_mut(child).unwrap().set_parent(Some(parent)), Ok(true));
+        wm.roundtrip().unwrap();
+        assert_eq!(mock.run(|m| m.toplevels[1].parent), mock.run(|m| m.toplevels[0].toplevel));
+        assert_eq!(wm.window_mut(child).unwrap().set_parent(None), Ok(true));
+        wm.roundtrip().unwrap();
+        assert_eq!(mock.run(|m| m.toplevels[1].parent), 0);
+
+        // Closed windows can't be parents.
+        wm.window_mut(parent).unwrap().close();
+        events(&mut wm);
+        assert_eq!(wm.window_mut(child).unwrap().set_parent(Some(parent)), Err(WindowError::InvalidWindowHandle));
+
+        // Position, icon and taskbar are left to the compositor.
+        let window = wm.window_mut(child).unwrap();
+        window.set_taskbar(false);
+        window.set_icon(None);
+        assert!(window.set_position_unchecked(WindowRelativePosition::Desktop(WindowPosition::new(0, 0))).is_ok());
+        wm.roundtrip().unwrap();
+    }
+
+    #[test]
+    fn wayland_manager_idle_inhibit() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("zwp_idle_inhibit_manager_v1", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+
+        let window = wm.window_mut(handle).unwrap();
+        window.set_idle_inhibit(true);
+        assert!(window.idle_inhibit());
+        events(&mut wm);
+        let surface = mock.run(|m| m.toplevels[0].surface);
+        assert_eq!(mock.run(|m| m.idle_inhibitors.iter().map(|(_, s)| *s).collect::<Vec<_>>()), vec![surface]);
+
+        wm.window_mut(handle).unwrap().set_idle_inhibit(false);
+        events(&mut wm);
+        assert!(mock.run(|m| m.idle_inhibitors.is_empty()));
+
+        // Inhibitor is destroyed with the window.
+        wm.window_mut(handle).unwrap().set_idle_inhibit(true);
+        wm.window_mut(handle).unwrap().close();
+        events(&mut wm);
+        assert!(mock.run(|m| m.idle_inhibitors.is_empty()));
+    }
+
+    #[test]
+    fn wayland_manager_key_repeat() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        mock.run(|m| m.keyboard_enter(0, &[]));
+        events(&mut wm);
+
+        // Count key down events received during 10 repeat intervals.
+        let repeats = |wm : &mut WaylandWindowManager, key : u32| -> usize {
+            mock.run(move |m| m.key(key, true));
+            wm.roundtrip().unwrap();
+
+            let mut count = 0;
+            let end = Instant::now() + Duration::from_millis(MOCK_REPEAT.1 as u64 + 10 * 1000 / MOCK_REPEAT.0 as u64);
+            while Instant::now() < end {
+                if let Some(WindowEvent::Keyboard(WindowKeyboardEvent::KeyDown(_))) = wm.event().map(|e| e.event.clone()) {
+                    count += 1;
+                }
+            }
+
+            mock.run(move |m| m.key(key, false));
+            events(wm);
+            count
+        };
+
+        // Repeat is disabled by default.
+        assert_eq!(repeats(&mut wm, 16), 1);
+
+        wm.window_mut(handle).unwrap().keyboard_mut().enable_repeat();
+        assert!(repeats(&mut wm, 16) > 3);
+
+        // Modifiers don't repeat.
+        assert_eq!(repeats(&mut wm, 42), 1);
+
+        // Repeat stops when key is released.
+        std::thread::sleep(Duration::from_millis(MOCK_REPEAT.1 as u64 * 2));
+        assert!(events(&mut wm).is_empty());
+    }
+
+    #[test]
+    fn wayland_manager_pointer() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+        let surface = mock.run(|m| m.toplevels[0].surface);
+        assert!(wm.window(handle).unwrap().pointer().position().is_none());
+
+        mock.run(move |m| m.pointer_enter(surface, 10.5, 20.0));
+        assert_eq!(events(&mut wm), vec![WindowEvent::PointerEnter, WindowEvent::Pointer(WindowPointerEvent::Move(WindowCoordinate { x : 10, y : 20 }))]);
+        assert_eq!(wm.window(handle).unwrap().pointer().position().map(|p| (p.x, p.y)), Some((10, 20)));
+
+        // Buttons are numbered like X11 buttons.
+        mock.run(|m| { m.pointer_motion(30.0, 40.0); m.pointer_button(BTN_RIGHT); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::Pointer(WindowPointerEvent::Move(WindowCoordinate { x : 30, y : 40 })), 
+            WindowEvent::Pointer(WindowPointerEvent::ButtonDown(3, WindowCoordinate { x : 30, y : 40 })), 
+            WindowEvent::Pointer(WindowPointerEvent::ButtonUp(3, WindowCoordinate { x : 30, y : 40 }))]);
+
+        // Axis events of a frame are a single scroll, in wheel steps.
+        mock.run(|m| m.pointer_scroll(-240));
+        assert_eq!(events(&mut wm), vec![WindowEvent::Pointer(WindowPointerEvent::Scroll(PointerScroll { x : 0.0, y : -2.0 }))]);
+
+        mock.run(move |m| m.pointer_leave(surface));
+        assert_eq!(events(&mut wm), vec![WindowEvent::PointerLeave]);
+        assert!(wm.window(handle).unwrap().pointer().position().is_none());
+    }
+
+    #[test]
+    fn wayland_manager_cursor() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9), ("wp_cursor_shape_manager_v1", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+        assert!(mock.run(|m| m.shape_device != 0));
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+        let surface = mock.run(|m| m.toplevels[0].surface);
+
+        // Cursor is set on enter.
+        mock.run(move |m| m.pointer_enter(surface, 10.0, 10.0));
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.shape), WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_DEFAULT);
+
+        wm.window_mut(handle).unwrap().pointer_mut().set_cursor(WindowCursor::Select);
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.shape), WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_TEXT);
+
+        // Hidden cursor has a null surface.
+        wm.window_mut(handle).unwrap().pointer_mut().hide();
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.cursor_surface), Some(0));
+
+        wm.window_mut(handle).unwrap().pointer_mut().show();
+        events(&mut wm);
+        assert!(mock.run(|m| m.requests.iter().filter(|r| *r == "wp_cursor_shape_device_v1.set_shape").count() == 3));
+    }
+
+    #[test]
+    fn wayland_manager_pointer_lock() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9), ("zwp_pointer_constraints_v1", 1), 
+            ("zwp_relative_pointer_manager_v1", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+        assert!(mock.run(|m| m.relative_pointer != 0));
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+        let surface = mock.run(|m| m.toplevels[0].surface);
+        mock.run(move |m| m.pointer_enter(surface, 10.0, 10.0));
+        events(&mut wm);
+
+        // Lock is active once the compositor grants it.
+        wm.window_mut(handle).unwrap().pointer_mut().set_mode(WindowPointerMode::Acceleration);
+        events(&mut wm);
+        assert!(mock.run(move |m| m.constraints.len() == 1 && m.constraints[0].1 == surface && m.constraints[0].2));
+        wm.roundtrip().unwrap();
+        assert!(wm.window(handle).unwrap().pointer().locked());
+
+        // Accelerations are unaccelerated deltas, fractions are kept for the next motion.
+        mock.run(|m| { m.relative_motion(1.5, -2.0); m.relative_motion(1.5, 0.25); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::Pointer(WindowPointerEvent::Acceleration(PointerAcceleration { x : 1, y : -2 })), 
+            WindowEvent::Pointer(WindowPointerEvent::Acceleration(PointerAcceleration { x : 2, y : 0 }))]);
+
+        // Dropped then granted again by the compositor.
+        mock.run(|m| m.constraint(0, false));
+        events(&mut wm);
+        assert!(!wm.window(handle).unwrap().pointer().locked());
+        mock.run(|m| m.constraint(0, true));
+        events(&mut wm);
+        assert!(wm.window(handle).unwrap().pointer().locked());
+
+        // Lock supersedes confinement.
+        wm.window_mut(handle).unwrap().pointer_mut().confine();
+        events(&mut wm);
+        assert!(mock.run(|m| m.constraints.len() == 1 && m.constraints[0].2));
+        wm.window_mut(handle).unwrap().pointer_mut().set_mode(WindowPointerMode::Cursor);
+        events(&mut wm);
+        assert!(mock.run(|m| m.received("zwp_locked_pointer_v1.destroy") && m.constraints.len() == 1 && !m.constraints[0].2));
+        assert!(!wm.window(handle).unwrap().pointer().locked());
+
+        // Constraint is requested again for a new pointer.
+        mock.run(|m| m.capabilities(WL_SEAT_CAPABILITY_KEYBOARD));
+        events(&mut wm);
+        assert!(mock.run(|m| m.constraints.is_empty()));
+        mock.run(|m| m.capabilities(WL_SEAT_CAPABILITY_POINTER | WL_SEAT_CAPABILITY_KEYBOARD));
+        events(&mut wm);
+        assert!(mock.run(|m| m.constraints.len() == 1));
+
+        wm.window_mut(handle).unwrap().close();
+        events(&mut wm);
+        assert!(mock.run(|m| m.constraints.is_empty()));
+    }
+
+    #[test]
+    fn wayland_manager_grab() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9), ("zwp_pointer_constraints_v1", 1), 
+            ("zwp_keyboard_shortcuts_inhibit_manager_v1", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+        let surface = mock.run(|m| m.toplevels[0].surface);
+
+        // Pointer is locked and shortcuts inhibited on the surface.
+        assert_eq!(wm.window_mut(handle).unwrap().grab_input(), Ok(true));
+        events(&mut wm);
+        assert!(mock.run(move |m| m.constraints.len() == 1 && m.constraints[0].1 == surface && m.constraints[0].2));
+        assert_eq!(mock.run(|m| m.shortcuts_inhibitors.iter().map(|(_, s)| *s).collect::<Vec<_>>()), vec![surface]);
+
+        // Confinement requested while grabbed is applied once released.
+        wm.window_mut(handle).unwrap().pointer_mut().confine();
+        events(&mut wm);
+        assert!(mock.run(|m| m.constraints.len() == 1 && m.constraints[0].2));
+        wm.window_mut(handle).unwrap().ungrab_input();
+        events(&mut wm);
+        assert!(mock.run(|m| m.shortcuts_inhibitors.is_empty() && m.constraints.len() == 1 && !m.constraints[0].2));
+
+        // Grab is released with the window.
+        wm.window_mut(handle).unwrap().grab_input().unwrap();
+        wm.window_mut(handle).unwrap().close();
+        events(&mut wm);
+        assert!(mock.run(|m| m.shortcuts_inhibitors.is_empty() && m.constraints.is_empty()));
+    }
+
+    #[test]
+    fn wayland_manager_grab_unsupported() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9), ("zwp_pointer_constraints_v1", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+
+        // Shortcuts can't be inhibited, nothing is grabbed.
+        assert_eq!(wm.window_mut(handle).unwrap().grab_input(), Err(WindowError::WindowInputGrabFailed));
+        assert!(!wm.window(handle).unwrap().input_grabbed());
+        wm.window_mut(handle).unwrap().ungrab_input();
+        events(&mut wm);
+        assert!(mock.run(|m| m.constraints.is_empty()));
+    }
+
+    #[test]
+    fn wayland_manager_integer_scale() {
+        let (connection, mock) = mock_compositor(MOCK_GLOBALS);
+        mock.run(|m| {
+            m.add_output(MockOutput::new("DP-1", (0, 0), (1920, 1080), 1, (1920, 1080)));
+            m.add_output(MockOutput::new("DP-2", (1920, 0), (3840, 2160), 2, (1920, 1080)));
+        });
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        events(&mut wm);
+        assert_eq!(wm.window(handle).unwrap().scale(), 1.0);
+
+        // Highest scale of outputs entered is used.
+        mock.run(|m| { m.enter(0, 0); m.enter(0, 1); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::ScaleChanged(2.0)]);
+        assert_eq!(wm.window(handle).unwrap().scale(), 2.0);
+        assert_eq!(mock.run(|m| m.toplevels[0].buffer_scale), 2);
+
+        mock.run(|m| m.leave(0, 1));
+        assert_eq!(events(&mut wm), vec![WindowEvent::ScaleChanged(1.0)]);
+
+        // Preferred buffer scale replaces outputs scales.
+        mock.run(|m| { m.preferred_buffer_scale(0, 3); m.enter(0, 1); });
+        assert_eq!(events(&mut wm), vec![WindowEvent::ScaleChanged(3.0)]);
+        assert_eq!(mock.run(|m| m.toplevels[0].buffer_scale), 3);
+    }
+
+    #[test]
+    fn wayland_manager_fractional_scale() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wp_fractional_scale_manager_v1", 1), ("wp_viewporter", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(640, 480))).unwrap();
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.toplevels[0].viewport_size), (640, 480));
+
+        mock.run(|m| m.preferred_scale(0, 180));
+        assert_eq!(events(&mut wm), vec![WindowEvent::ScaleChanged(1.5)]);
+        assert_eq!(wm.window(handle).unwrap().scale(), 1.5);
+        assert!(!mock.run(|m| m.received("wl_surface.set_buffer_scale")));
+
+        // Viewport follows the window size.
+        mock.run(|m| m.configure(0, 800, 600));
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.toplevels[0].viewport_size), (800, 600));
+
+        wm.window_mut(handle).unwrap().close();
+        events(&mut wm);
+        assert!(mock.run(|m| m.received("wp_fractional_scale_v1.destroy") && m.received("wp_viewport.destroy")));
+    }
+
+    #[test]
+    fn wayland_manager_seat_devices() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_seat", 9)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let devices = &wm.input_devices().list;
+        assert_eq!(devices.len(), 2);
+        assert!(devices.iter().any(|d| d.kind == InputDeviceKind::Mouse && d.name == "seat0 pointer"));
+        assert!(devices.iter().any(|d| d.kind == InputDeviceKind::Keyboard && d.name == "seat0 keyboard"));
+        let keyboard = wm.input_devices().of_kind(InputDeviceKind::Keyboard).next().unwrap().id;
+
+        mock.run(|m| m.capabilities(WL_SEAT_CAPABILITY_POINTER));
+        assert_eq!(events(&mut wm), vec![WindowEvent::DeviceRemoved(keyboard)]);
+        assert!(mock.run(|m| m.received("wl_keyboard.release")));
+        assert!(wm.input_devices().of_kind(InputDeviceKind::Keyboard).next().is_none());
+    }
+
+    #[test]
+    fn wayland_manager_framebuffer() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_shm", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(4, 2))).unwrap();
+        events(&mut wm);
+
+        // First present attaches a buffer damaged whole.
+        let window = wm.window_mut(handle).unwrap();
+        assert_eq!(window.framebuffer_size(), WindowSize::new(4, 2));
+        window.framebuffer().unwrap().fill(0x00FF0000);
+        window.present(&[]).unwrap();
+        events(&mut wm);
+        let (first, damage) = mock.run(|m| (m.toplevels[0].committed, m.toplevels[0].buffer_damage.clone()));
+        assert_eq!(mock.run(|m| m.buffers.clone()), vec![(first, 4, 2)]);
+        assert_eq!(damage, vec![(0, 0, 4, 2)]);
+
+        // First buffer is still busy, second is a copy of it.
+        let window = wm.window_mut(handle).unwrap();
+        let pixels = window.framebuffer().unwrap();
+        assert!(pixels.iter().all(|p| *p == 0x00FF0000));
+        pixels[1] = 0x0000FF00;
+        window.present(&[(WindowPosition::new(1, 0), WindowSize::new(1, 1))]).unwrap();
+        events(&mut wm);
+        let (second, damage) = mock.run(|m| (m.toplevels[0].committed, m.toplevels[0].buffer_damage.clone()));
+        assert_ne!(first, second);
+        assert_eq!(damage, vec![(1, 0, 1, 1)]);
+
+        // First buffer released by the commit is reused with the damage of the second.
+        let window = wm.window_mut(handle).unwrap();
+        let pixels = window.framebuffer().unwrap();
+        assert_eq!((pixels[0], pixels[1]), (0x00FF0000, 0x0000FF00));
+        window.present(&[(WindowPosition::new(-2, -2), WindowSize::new(3, 3))]).unwrap();
+        events(&mut wm);
+        let (third, damage) = mock.run(|m| (m.toplevels[0].committed, m.toplevels[0].buffer_damage.clone()));
+        assert_eq!(third, first);
+        assert_eq!(damage, vec![(0, 0, 1, 1)]);
+        assert_eq!(mock.run(|m| m.buffers.len()), 2);
+
+        // Resize reallocates buffers of the new size.
+        mock.run(|m| m.configure(0, 8, 4));
+        assert!(events(&mut wm).contains(&WindowEvent::Resized(WindowSize::new(8, 4))));
+        let window = wm.window_mut(handle).unwrap();
+        assert_eq!(window.framebuffer().unwrap().len(), 32);
+        window.present(&[]).unwrap();
+        events(&mut wm);
+        assert!(mock.run(|m| m.received("wl_buffer.destroy")));
+        assert_eq!(mock.run(|m| (m.buffers.len(), m.buffers[2].1, m.buffers[2].2)), (3, 8, 4));
+    }
+
+    #[test]
+    #[cfg(feature = "raw_window_handle")]
+    fn wayland_manager_raw_handles() {
+        use raw_window_handle::{HandleError, HasWindowHandle, RawDisplayHandle, RawWindowHandle};
+
+        let (connection, _mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+
+        // Native wire protocol has no libwayland objects to give.
+        assert!(matches!(wm.raw_display_handle(), Err(HandleError::NotSupported)));
+        assert!(matches!(wm.window(handle).unwrap().window_handle(), Err(HandleError::NotSupported)));
+
+        // libwayland-client may not be installed.
+        let (connection, _mock) = match super::super::mock::mock_compositor_libwayland(MOCK_GLOBALS) {
+            Some(mock) => mock,
+            None => return,
+        };
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+
+        let display = wm.connection.borrow().display_ptr();
+        assert!(matches!(wm.raw_display_handle(), Ok(RawDisplayHandle::Wayland(h)) if h.display.as_ptr() == display));
+
+        let surface = wm.wayland_window(|_| true).unwrap().1.surface;
+        let surface = wm.connection.borrow().proxy_ptr(surface);
+        assert!(!surface.is_null());
+        assert!(matches!(wm.window(handle).unwrap().window_handle().map(|h| h.as_raw()), 
+            Ok(RawWindowHandle::Wayland(h)) if h.surface.as_ptr() == surface));
+    }
+
+    #[test]
+    #[cfg(feature = "vulkan")]
+    fn wayland_manager_vulkan() {
+        use crate::vulkan::{VkInstance, VkVoidFunction};
+
+        unsafe extern "system" fn loader(_instance : VkInstance, _name : *const std::ffi::c_char) -> Option<VkVoidFunction> {
+            None
+        }
+
+        let (connection, _mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+
+        // VK_KHR_wayland_surface needs libwayland objects.
+        assert_eq!(wm.vulkan_required_extensions(), Err(WindowError::WindowVulkanUnsupported));
+        assert_eq!(unsafe { wm.window(handle).unwrap().create_vulkan_surface(std::ptr::null_mut(), loader) }, 
+            Err(WindowError::WindowVulkanUnsupported));
+
+        // libwayland-client may not be installed.
+        let (connection, _mock) = match super::super::mock::mock_compositor_libwayland(MOCK_GLOBALS) {
+            Some(mock) => mock,
+            None => return,
+        };
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+        assert_eq!(wm.vulkan_required_extensions(), Ok(super::super::vulkan::WAYLAND_VULKAN_EXTENSIONS));
+
+        // Loader without VK_KHR_wayland_surface
+        assert_eq!(unsafe { wm.window(handle).unwrap().create_vulkan_surface(std::ptr::null_mut(), loader) }, 
+            Err(WindowError::WindowVulkanSurfaceFailed(crate::vulkan::VK_ERROR_EXTENSION_NOT_PRESENT)));
+    }
+
+    #[test]
+    #[cfg(feature = "cairo")]
+    fn wayland_manager_cairo() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_shm", 1)]);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(4, 2))).unwrap();
+        events(&mut wm);
+
+        let window = wm.window_mut(handle).unwrap();
+        let first = match window.cairo_surface() {
+            // libcairo isn't installed.
+            Err(WindowError::WindowCairoUnsupported) => return,
+            surface => surface.unwrap(),
+        };
+        assert_eq!(window.cairo_surface(), Ok(first));
+
+        // Surface is presented with the framebuffer.
+        window.cairo_present(&[]).unwrap();
+        events(&mut wm);
+        assert_eq!(mock.run(|m| m.toplevels[0].buffer_damage.clone()), vec![(0, 0, 4, 2)]);
+
+        // Resize follows the framebuffer.
+        mock.run(|m| m.configure(0, 8, 4));
+        events(&mut wm);
+        let window = wm.window_mut(handle).unwrap();
+        assert!(window.cairo_surface().is_ok());
+        assert_eq!(window.framebuffer().unwrap().len(), 32);
+    }
+
+    #[test]
+    #[cfg(feature = "opengl")]
+    fn wayland_manager_opengl() {
+        use crate::opengl::{GLApi, GLConfig};
+
+        let (connection, _mock) = mock_compositor(MOCK_GLOBALS);
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+
+        // wl_egl_window needs libwayland objects.
+        assert!(matches!(wm.window(handle).unwrap().create_gl_context(&GLConfig::default(), None), Err(WindowError::WindowOpenGLUnsupported)));
+
+        // libwayland-client may not be installed.
+        let (connection, _mock) = match super::super::mock::mock_compositor_libwayland(MOCK_GLOBALS) {
+            Some(mock) => mock,
+            None => return,
+        };
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+        let handle = wm.build(&WindowBuilder::new()).unwrap();
+
+        // Mock has no buffer sharing for drivers, EGL may be missing or fail. Wayland contexts are always EGL.
+        match wm.window(handle).unwrap().create_gl_context(&GLConfig::default(), None) {
+            Ok(context) => assert_eq!(context.config().api, GLApi::Egl),
+            Err(error) => assert!(matches!(error, WindowError::WindowOpenGLUnsupported | WindowError::WindowOpenGLContextFailed)),
+        }
+    }
+
+    #[test]
+    fn wayland_manager_redraw() {
+        let (connection, mock) = mock_compositor(&[("wl_compositor", 6), ("xdg_wm_base", 5), ("wl_shm", 1)]);
+        mock.run(|m| { m.add_output(MockOutput::new("DP-1", (0, 0), (1920, 1080), 1, (1920, 1080))); });
+        let mut wm = WaylandWindowManager::from_connection(connection).unwrap();
+
+        let handle = wm.build(WindowBuilder::new().size(WindowSize::new(4, 2))).unwrap();
+        events(&mut wm);
+        mock.run(|m| m.enter(0, 0));
+        events(&mut wm);
+
+        // Redraws requested while the first frame is pending are merged.
+        let window = wm.window_mut(handle).unwrap();
+        window.request_redraw();
+        window.request_redraw();
+        assert!(events(&mut wm).is_empty());
+        wm.window_mut(handle).unwrap().present(&[]).unwrap();
+
+        // Redraw is sent once the frame is done, targeting the next refresh of the output.
+        let before = Instant::now();
+        mock.run(|m| m.frame_done(crate::linux::monotonic_time().as_millis() as u32));
+        let events_done = events(&mut wm);
+        let interval = Duration::from_nanos(16_666_666);
+        match events_done[..] {
+            [WindowEvent::RedrawRequested(timing)] => {
+                assert_eq!(timing.interval, interval);
+                assert!(timing.target > before && timing.target <= Instant::now() + interval);
+            },
+            _ => panic!("Expected one RedrawRequested, got {:?}", events_done),
+        }
+
+        // Frame done without request sends nothing and the next request is sent right away.
+        mock.run(|m| m.frame_done(0));
+        assert!(events(&mut wm).is_empty());
+        wm.window_mut(handle).unwrap().request_redraw();
+        assert!(matches!(events(&mut wm)[..], [WindowEvent::RedrawRequested(_)]));
+
+        // Hidden windows aren't redrawn until shown again.
+        wm.window_mut(handle).unwrap().hide();
+        wm.window_mut(handle).unwrap().request_redraw();
+        assert_eq!(events(&mut wm), vec![WindowEvent::Hidden]);
+        wm.window_mut(handle).unwrap().show();
+        assert!(matches!(events(&mut wm)[..], [WindowEvent::Shown, WindowEvent::RedrawRequested(_)]));
+    }
+}
@@ -0,0 +1,976 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Mock Wayland compositor used by unit tests.
+//! 
+//! The compositor runs on a thread at the other end of a socket pair and answers requests like a real compositor would.
+//! Tests can run closures on the compositor thread to send events or inspect requests received.
+
+use std::{fs::File, io::Write, os::{fd::{FromRawFd, OwnedFd}, unix::net::UnixStream}, sync::mpsc::{channel, Receiver, Sender, TryRecvError}, thread::JoinHandle};
+
+use super::{protocol::{WL_CALLBACK_DONE, WL_DISPLAY_DELETE_ID, WL_DISPLAY_ERROR, WL_DISPLAY_GET_REGISTRY, WL_DISPLAY_ID, WL_DISPLAY_SYNC, 
+    BTN_LEFT, WL_OUTPUT_DESCRIPTION, WL_OUTPUT_DONE, WL_OUTPUT_GEOMETRY, WL_OUTPUT_MODE, WL_OUTPUT_MODE_CURRENT, WL_OUTPUT_NAME, WL_OUTPUT_SCALE, 
+    WL_REGISTRY_BIND, WL_REGISTRY_GLOBAL, WL_REGISTRY_GLOBAL_REMOVE, WL_SURFACE_ATTACH, WL_SURFACE_COMMIT, WL_SURFACE_DAMAGE_BUFFER, WL_SURFACE_FRAME, WL_BUFFER_RELEASE, WL_SURFACE_ENTER, WL_POINTER_AXIS, WL_POINTER_AXIS_VALUE120, WL_POINTER_AXIS_VERTICAL_SCROLL, WL_POINTER_BUTTON, WL_POINTER_ENTER, 
+    WL_POINTER_FRAME, WL_POINTER_LEAVE, WL_POINTER_MOTION, WL_POINTER_SET_CURSOR, WP_CURSOR_SHAPE_DEVICE_V1_SET_SHAPE, WP_CURSOR_SHAPE_MANAGER_V1_GET_POINTER, 
+    ZWP_CONFINED_POINTER_V1_CONFINED, ZWP_CONFINED_POINTER_V1_UNCONFINED, ZWP_LOCKED_POINTER_V1_LOCKED, ZWP_LOCKED_POINTER_V1_UNLOCKED, 
+    ZWP_POINTER_CONSTRAINTS_V1_CONFINE_POINTER, ZWP_POINTER_CONSTRAINTS_V1_LOCK_POINTER, ZWP_RELATIVE_POINTER_MANAGER_V1_GET_RELATIVE_POINTER, 
+    ZWP_RELATIVE_POINTER_V1_RELATIVE_MOTION, WL_SURFACE_LEAVE, WL_SURFACE_PREFERRED_BUFFER_SCALE, WL_SURFACE_SET_BUFFER_SCALE, 
+    WP_FRACTIONAL_SCALE_MANAGER_V1_GET_FRACTIONAL_SCALE, WP_FRACTIONAL_SCALE_V1_PREFERRED_SCALE, WP_VIEWPORTER_GET_VIEWPORT, WP_VIEWPORT_SET_DESTINATION, 
+    WL_KEYBOARD_ENTER, WL_KEYBOARD_KEY, WL_KEYBOARD_KEYMAP, WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1, WL_KEYBOARD_LEAVE, WL_KEYBOARD_MODIFIERS, WL_KEYBOARD_REPEAT_INFO, 
+    WL_SEAT_CAPABILITIES, WL_SEAT_CAPABILITY_KEYBOARD, WL_SEAT_CAPABILITY_POINTER, WL_SEAT_GET_KEYBOARD, WL_SEAT_GET_POINTER, WL_SEAT_NAME, WL_SHM_POOL_CREATE_BUFFER, WL_SUBCOMPOSITOR_GET_SUBSURFACE, XDG_SURFACE_CONFIGURE, XDG_SURFACE_GET_TOPLEVEL, 
+    XDG_TOPLEVEL_CLOSE, XDG_TOPLEVEL_CONFIGURE, XDG_TOPLEVEL_SET_FULLSCREEN, XDG_TOPLEVEL_SET_MAXIMIZED, XDG_TOPLEVEL_SET_MAX_SIZE, XDG_TOPLEVEL_SET_MIN_SIZE, 
+    XDG_TOPLEVEL_SET_PARENT, XDG_TOPLEVEL_SET_TITLE, XDG_TOPLEVEL_STATE_FULLSCREEN, XDG_TOPLEVEL_STATE_MAXIMIZED, XDG_TOPLEVEL_UNSET_FULLSCREEN, XDG_TOPLEVEL_UNSET_MAXIMIZED, 
+    XDG_WM_BASE_GET_XDG_SURFACE, XDG_WM_BASE_PING, ZXDG_OUTPUT_MANAGER_V1_GET_XDG_OUTPUT, ZXDG_OUTPUT_V1_LOGICAL_POSITION, ZXDG_OUTPUT_V1_LOGICAL_SIZE, 
+    ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION, ZXDG_TOPLEVEL_DECORATION_V1_CONFIGURE, ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE, 
+    ZWP_TEXT_INPUT_MANAGER_V3_GET_TEXT_INPUT, ZWP_TEXT_INPUT_V3_COMMIT, ZWP_TEXT_INPUT_V3_COMMIT_STRING, ZWP_TEXT_INPUT_V3_DELETE_SURROUNDING_TEXT, 
+    ZWP_TEXT_INPUT_V3_DISABLE, ZWP_TEXT_INPUT_V3_DONE, ZWP_TEXT_INPUT_V3_ENABLE, ZWP_TEXT_INPUT_V3_ENTER, ZWP_TEXT_INPUT_V3_LEAVE, 
+    ZWP_TEXT_INPUT_V3_PREEDIT_STRING, ZWP_TEXT_INPUT_V3_SET_CURSOR_RECTANGLE, ZWP_TEXT_INPUT_V3_SET_SURROUNDING_TEXT, 
+    XDG_ACTIVATION_TOKEN_V1_COMMIT, XDG_ACTIVATION_TOKEN_V1_DONE, XDG_ACTIVATION_TOKEN_V1_SET_SERIAL, XDG_ACTIVATION_TOKEN_V1_SET_SURFACE, 
+    XDG_ACTIVATION_V1_ACTIVATE, XDG_ACTIVATION_V1_GET_ACTIVATION_TOKEN, ZWP_IDLE_INHIBITOR_V1_DESTROY, ZWP_IDLE_INHIBIT_MANAGER_V1_CREATE_INHIBITOR, 
+    ZWP_KEYBOARD_SHORTCUTS_INHIBITOR_V1_DESTROY, ZWP_KEYBOARD_SHORTCUTS_INHIBIT_MANAGER_V1_INHIBIT_SHORTCUTS, 
+    XDG_POPUP_CONFIGURE, XDG_POPUP_GRAB, XDG_POPUP_POPUP_DONE, XDG_POSITIONER_SET_ANCHOR, XDG_POSITIONER_SET_ANCHOR_RECT, 
+    XDG_POSITIONER_SET_CONSTRAINT_ADJUSTMENT, XDG_POSITIONER_SET_GRAVITY, XDG_POSITIONER_SET_OFFSET, XDG_POSITIONER_SET_SIZE, XDG_SURFACE_GET_POPUP, 
+    XDG_WM_BASE_CREATE_POSITIONER}, wlib::{WaylandArg, WaylandConnection, WaylandId, WaylandMessage}};
+
+/// Globals needed by the window manager.
+pub(crate) const MOCK_GLOBALS : &[(&str, u32)] = &[("wl_compositor", 6), ("xdg_wm_base", 5)];
+
+/// Keymap sent to keyboards, in the format serialized by xkbcommon.
+pub(crate) const MOCK_KEYMAP : &str = r#"xkb_keymap {
+xkb_keycodes "evdev+aliases(qwerty)" {
+    minimum = 8;
+    maximum = 255;
+    <ESC>                = 9;
+    <AE01>               = 10;
+    <AE05>               = 14;
+    <AD01>               = 24;
+    <LFSH>               = 50;
+    <SPCE>               = 65;
+    <CAPS>               = 66;
+    <SCLK>               = 78;
+    <KP1>                = 87;
+    alias <LatQ>         = <AD01>;
+    alias <CAPL>         = <CAPS>;
+    indicator 1 = "Caps Lock";
+};
+
+xkb_types "complete" {
+    virtual_modifiers NumLock,Alt,LevelThree,LevelFive,Meta,Super,Hyper,ScrollLock;
+
+    type "ONE_LEVEL" {
+        modifiers= none;
+        level_name[Level1]= "Any";
+    };
+    type "TWO_LEVEL" {
+        modifiers= Shift;
+        map[Shift]= Level2;
+        level_name[Level1]= "Base";
+        level_name[Level2]= "Shift";
+    };
+    type "ALPHABETIC" {
+        modifiers= Shift+Lock;
+        map[Shift]= Level2;
+        map[Lock]= Level2;
+        level_name[Level1]= "Base";
+        level_name[Level2]= "Caps";
+    };
+    type "KEYPAD" {
+        modifiers= Shift+NumLock;
+        map[NumLock]= Level2;
+        level_name[Level1]= "Base";
+        level_name[Level2]= "Number";
+    };
+    type "FOUR_LEVEL" {
+        modifiers= Shift+LevelThree;
+        map[Shift]= Level2;
+        map[LevelThree]= Level3;
+        map[Shift+LevelThree]= Level4;
+        level_name[Level1]= "Base";
+        level_name[Level2]= "Shift";
+        level_name[Level3]= "Alt Base";
+        level_name[Level4]= "Shift Alt";
+    };
+};
+
+xkb_compatibility "complete" {
+    virtual_modifiers NumLock,Alt,LevelThree,LevelFive,Meta,Super,Hyper,ScrollLock;
+
+    interpret Shift_L+AnyOf(all) {
+        action= SetMods(modifiers=Shift,clearLocks);
+    };
+};
+
+xkb_symbols "pc+us+fr:2" {
+    name[Group1]="English (US)";
+    name[Group2]="French";
+
+    key <ESC>                {	[          Escape ] };
+    key <AE01>               {	type= "TWO_LEVEL", symbols[Group1]= [               1,          exclam ] };
+    key <AE05>               {	type[Group1]= "FOUR_LEVEL", symbols[Group1]= [ 5, percent, U20AC, NoSymbol ] };
+    key <LatQ>               {	[               q,               Q ], [ a, A ] };
+    key <LFSH>               {	repeat= No, [         Shift_L ] };
+    key <SPCE>               {	[           space ] };
+    key <CAPL>               {	[       Caps_Lock ] };
+    key <SCLK>               {	[     Scroll_Lock ] };
+    key <KP1>                {	[          KP_End,            KP_1 ] };
+    modifier_map Shift { <LFSH> };
+    modifier_map Lock { <CAPS> };
+};
+
+};
+"#;
+
+/// Repeat rate and delay sent to keyboards, short to keep tests fast.
+pub(crate) const MOCK_REPEAT : (i32, i32) = (100, 20);
+
+/// Command executed on the compositor thread.
+type MockCommand = Box<dyn FnOnce(&mut MockCompositor) + Send>;
+
+/// Positioner created by client, copied to the popups created with it.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct MockPositioner {
+    /// xdg_positioner id
+    pub positioner : WaylandId,
+
+    /// Size of popup
+    pub size : (i32, i32),
+
+    /// Anchor rectangle as (x, y, width, height)
+    pub anchor_rect : (i32, i32, i32, i32),
+
+    /// Anchor edge or corner
+    pub anchor : u32,
+
+    /// Gravity edge or corner
+    pub gravity : u32,
+
+    /// Offset from anchor
+    pub offset : (i32, i32),
+
+    /// Constraint adjustment flags
+    pub adjustment : u32,
+}
+
+/// Toplevel window created by client. Popups are toplevels with a xdg_popup instead of a xdg_toplevel.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct MockToplevel {
+    /// wl_surface id
+    pub surface : WaylandId,
+
+    /// xdg_surface id
+    pub xdg_surface : WaylandId,
+
+    /// xdg_toplevel id
+    pub toplevel : WaylandId,
+
+    /// xdg_popup id
+    pub popup : WaylandId,
+
+    /// Parent xdg_surface of popup, or parent xdg_toplevel of toplevel
+    pub parent : WaylandId,
+
+    /// Positioner of popup
+    pub positioner : MockPositioner,
+
+    /// Serial of xdg_popup.grab, 0 if not grabbed.
+    pub grab : u32,
+
+    /// Title set by client
+    pub title : String,
+
+    /// Minimum size set by client
+    pub min_size : (i32, i32),
+
+    /// Maximum size set by client
+    pub max_size : (i32, i32),
+
+    /// Toplevel is maximized
+    pub maximized : bool,
+
+    /// Toplevel is fullscreen
+    pub fullscreen : bool,
+
+    /// wl_output given with set_fullscreen
+    pub fullscreen_output : WaylandId,
+
+    /// zxdg_toplevel_decoration_v1 id, 0 if not created.
+    pub decoration : WaylandId,
+
+    /// Decoration mode requested by client
+    pub decoration_mode : u32,
+
+    /// Initial commit was done
+    pub mapped : bool,
+
+    /// Null buffer attached, unmapping surface on commit
+    pub unmapping : bool,
+
+    /// Scale set with wl_surface.set_buffer_scale, 0 if never set.
+    pub buffer_scale : i32,
+
+    /// wp_fractional_scale_v1 id, 0 if not created.
+    pub fractional_scale : WaylandId,
+
+    /// wp_viewport id, 0 if not created.
+    pub viewport : WaylandId,
+
+    /// Destination size set with wp_viewport.set_destination
+    pub viewport_size : (i32, i32),
+
+    /// Rectangles given with wl_surface.damage_buffer since the last attach as (x, y, width, height)
+    pub buffer_damage : Vec<(i32, i32, i32, i32)>,
+
+    /// wl_buffer of the last commit, released when another is committed.
+    pub committed : WaylandId,
+}
+
+/// Output advertised by the compositor.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct MockOutput {
+    /// Registry name of the wl_output global
+    pub global : u32,
+
+    /// wl_output id bound by client, 0 if not bound.
+    pub output : WaylandId,
+
+    /// Name like `DP-1`
+    pub name : String,
+
+    /// Logical position on desktop
+    pub position : (i32, i32),
+
+    /// Current mode as (width, height, refresh in mHz)
+    pub mode : (i32, i32, i32),
+
+    /// Integer scale
+    pub scale : i32,
+
+    /// Logical size sent with xdg-output
+    pub logical_size : (i32, i32),
+}
+
+impl MockOutput {
+    /// Create an output at position with a 60hz mode and its logical size.
+    pub fn new(name : &str, position : (i32, i32), mode : (i32, i32), scale : i32, logical_size : (i32, i32)) -> MockOutput {
+        MockOutput { name : name.to_string(), position, mode : (mode.0, mode.1, 60000), scale, logical_size, ..Default::default() }
+    }
+}
+
+/// State of the mock compositor.
+pub(crate) struct MockCompositor {
+    /// Server side of the connection
+    pub connection : WaylandConnection,
+
+    /// Globals advertised as (name, interface, version). Name is the index + 1.
+    pub globals : Vec<(u32, String, u32)>,
+
+    /// Registries created by client
+    pub registries : Vec<WaylandId>,
+
+    /// Globals bound by client as (interface, id)
+    pub bound : Vec<(String, WaylandId)>,
+
+    /// Toplevels created by client
+    pub toplevels : Vec<MockToplevel>,
+
+    /// Outputs advertised
+    pub outputs : Vec<MockOutput>,
+
+    /// Positioners created by client
+    pub positioners : Vec<MockPositioner>,
+
+    /// Sub-surfaces as (wl_subsurface, wl_surface, parent wl_surface)
+    pub subsurfaces : Vec<(WaylandId, WaylandId, WaylandId)>,
+
+    /// Buffers created as (wl_buffer, width, height)
+    pub buffers : Vec<(WaylandId, i32, i32)>,
+
+    /// Buffers attached as (wl_surface, wl_buffer)
+    pub attached : Vec<(WaylandId, WaylandId)>,
+
+    /// Frame callbacks requested as (wl_callback, wl_surface)
+    pub frames : Vec<(WaylandId, WaylandId)>,
+
+    /// wl_pointer created by client
+    pub pointer : WaylandId,
+
+    /// wl_keyboard created by client
+    pub keyboard : WaylandId,
+
+    /// Cursor surface set with wl_pointer.set_cursor, 0 if hidden
+    pub cursor_surface : Option<WaylandId>,
+
+    /// wp_cursor_shape_device_v1 created by client
+    pub shape_device : WaylandId,
+
+    /// Last shape set with wp_cursor_shape_device_v1.set_shape
+    pub shape : u32,
+
+    /// Pointer constraints as (object, wl_surface, true if lock), activated when created.
+    pub constraints : Vec<(WaylandId, WaylandId, bool)>,
+
+    /// zwp_relative_pointer_v1 created by client
+    pub relative_pointer : WaylandId,
+
+    /// zwp_text_input_v3 created by client
+    pub text_input : WaylandId,
+
+    /// Text input enabled, applied on commit
+    pub text_enabled : bool,
+
+    /// Surrounding text as (text, cursor, anchor)
+    pub surrounding : Option<(String, i32, i32)>,
+
+    /// Cursor rectangle as (x, y, width, height)
+    pub text_cursor : Option<(i32, i32, i32, i32)>,
+
+    /// Number of zwp_text_input_v3.commit received, also the serial of done
+    pub text_commits : u32,
+
+    /// Idle inhibitors as (zwp_idle_inhibitor_v1, wl_surface)
+    pub idle_inhibitors : Vec<(WaylandId, WaylandId)>,
+
+    /// Keyboard shortcuts inhibitors as (zwp_keyboard_shortcuts_inhibitor_v1, wl_surface)
+    pub shortcuts_inhibitors : Vec<(WaylandId, WaylandId)>,
+
+    /// Activation tokens as (xdg_activation_token_v1, serial, wl_surface), done with name `token-<id>` when committed.
+    pub tokens : Vec<(WaylandId, u32, WaylandId)>,
+
+    /// Surfaces activated as (token name, wl_surface)
+    pub activated : Vec<(String, WaylandId)>,
+
+    /// Requests received as `interface.request`
+    pub requests : Vec<String>,
+
+    /// Serial of the last event sent
+    pub serial : u32,
+}
+
+impl MockCompositor {
+
+    /// Returns true if request `interface.request` was received.
+    pub fn received(&self, request : &str) -> bool {
+        self.requests.iter().any(|r| r == request)
+    }
+
+    /// Returns the next serial.
+    pub fn next_serial(&mut self) -> u32 {
+        self.serial += 1;
+        self.serial
+    }
+
+    /// Advertise a new wl_output global and notify registries.
+    /// 
+    /// Returns the global name of the output.
+    pub fn add_output(&mut self, mut output : MockOutput) -> u32 {
+        output.global = self.globals.iter().map(|(n, _, _)| *n).max().unwrap_or(0) + 1;
+        self.globals.push((output.global, "wl_output".to_string(), 4));
+        for registry in self.registries.clone() {
+            self.connection.send(registry, WL_REGISTRY_GLOBAL, vec![WaylandArg::Uint(output.global), 
+                WaylandArg::Str(Some("wl_output".to_string())), WaylandArg::Uint(4)]);
+        }
+
+        self.outputs.push(output);
+        self.outputs.last().unwrap().global
+    }
+
+    /// Remove a global and notify registries.
+    pub fn remove_global(&mut self, name : u32) {
+        self.globals.retain(|(n, _, _)| *n != name);
+        self.outputs.retain(|o| o.global != name);
+        for registry in self.registries.clone() {
+            self.connection.send(registry, WL_REGISTRY_GLOBAL_REMOVE, vec![WaylandArg::Uint(name)]);
+        }
+    }
+
+    /// Returns the id of a bound global.
+    pub fn bound(&self, interface : &str) -> Option<WaylandId> {
+        self.bound.iter().find(|(i, _)| i == interface).map(|(_, id)| *id)
+    }
+
+    /// Send a xdg_wm_base.ping.
+    pub fn ping(&mut self, serial : u32) {
+        let wm_base = self.bound("xdg_wm_base").unwrap();
+        self.connection.send(wm_base, XDG_WM_BASE_PING, vec![WaylandArg::Uint(serial)]);
+    }
+
+    /// Send a configure sequence to toplevel at index with a size and its states.
+    /// 
+    /// Popups are placed at their anchor rectangle plus offset with the size of their positioner if 0.
+    pub fn configure(&mut self, index : usize, width : i32, height : i32) {
+        let toplevel = self.toplevels[index].clone();
+        if toplevel.popup != 0 {
+            let p = toplevel.positioner;
+            let (width, height) = if width > 0 && height > 0 { (width, height) } else { p.size };
+            self.connection.send(toplevel.popup, XDG_POPUP_CONFIGURE, vec![WaylandArg::Int(p.anchor_rect.0 + p.offset.0), 
+                WaylandArg::Int(p.anchor_rect.1 + p.offset.1), WaylandArg::Int(width), WaylandArg::Int(height)]);
+            let serial = self.next_serial();
+            self.connection.send(toplevel.xdg_surface, XDG_SURFACE_CONFIGURE, vec![WaylandArg::Uint(serial)]);
+            return;
+        }
+
+        let mut states : Vec<u8> = Vec::new();
+        if toplevel.maximized {
+            states.extend_from_slice(&XDG_TOPLEVEL_STATE_MAXIMIZED.to_ne_bytes());
+        }
+        if toplevel.fullscreen {
+            states.extend_from_slice(&XDG_TOPLEVEL_STATE_FULLSCREEN.to_ne_bytes());
+        }
+
+        self.connection.send(toplevel.toplevel, XDG_TOPLEVEL_CONFIGURE, vec![WaylandArg::Int(width), WaylandArg::Int(height), WaylandArg::Array(states)]);
+        let serial = self.next_serial();
+        self.connection.send(toplevel.xdg_surface, XDG_SURFACE_CONFIGURE, vec![WaylandArg::Uint(serial)]);
+    }
+
+    /// Send wl_surface.enter of output at index to toplevel at index.
+    pub fn enter(&mut self, index : usize, output : usize) {
+        let (surface, output) = (self.toplevels[index].surface, self.outputs[output].output);
+        self.connection.send(surface, WL_SURFACE_ENTER, vec![WaylandArg::Object(output)]);
+    }
+
+    /// Send wl_surface.leave of output to toplevel at index.
+    pub fn leave(&mut self, index : usize, output : usize) {
+        let (surface, output) = (self.toplevels[index].surface, self.outputs[output].output);
+        self.connection.send(surface, WL_SURFACE_LEAVE, vec![WaylandArg::Object(output)]);
+    }
+
+    /// Send wl_surface.preferred_buffer_scale to toplevel at index.
+    pub fn preferred_buffer_scale(&mut self, index : usize, scale : i32) {
+        self.connection.send(self.toplevels[index].surface, WL_SURFACE_PREFERRED_BUFFER_SCALE, vec![WaylandArg::Int(scale)]);
+    }
+
+    /// Send wp_fractional_scale_v1.preferred_scale, in 1/120 of scale, to toplevel at index.
+    pub fn preferred_scale(&mut self, index : usize, scale : u32) {
+        self.connection.send(self.toplevels[index].fractional_scale, WP_FRACTIONAL_SCALE_V1_PREFERRED_SCALE, vec![WaylandArg::Uint(scale)]);
+    }
+
+    /// Returns the (wl_surface, width, height) of the sub-surface frame of toplevel at index if a buffer is attached.
+    pub fn frame(&self, index : usize) -> Option<(WaylandId, i32, i32)> {
+        let parent = self.toplevels[index].surface;
+        let (_, surface, _) = self.subsurfaces.iter().find(|(_, _, p)| *p == parent)?;
+        let (_, buffer) = self.attached.iter().find(|(s, _)| s == surface)?;
+        self.buffers.iter().find(|(b, _, _)| b == buffer).map(|(_, width, height)| (*surface, *width, *height))
+    }
+
+    /// Send wl_pointer.enter on surface.
+    pub fn pointer_enter(&mut self, surface : WaylandId, x : f64, y : f64) {
+        let serial = self.next_serial();
+        self.connection.send(self.pointer, WL_POINTER_ENTER, vec![WaylandArg::Uint(serial), WaylandArg::Object(surface), WaylandArg::Fixed(x), WaylandArg::Fixed(y)]);
+        self.connection.send(self.pointer, WL_POINTER_FRAME, vec![]);
+    }
+
+    /// Send wl_pointer.leave of surface.
+    pub fn pointer_leave(&mut self, surface : WaylandId) {
+        let serial = self.next_serial();
+        self.connection.send(self.pointer, WL_POINTER_LEAVE, vec![WaylandArg::Uint(serial), WaylandArg::Object(surface)]);
+        self.connection.send(self.pointer, WL_POINTER_FRAME, vec![]);
+    }
+
+    /// Send wl_pointer.motion.
+    pub fn pointer_motion(&mut self, x : f64, y : f64) {
+        self.connection.send(self.pointer, WL_POINTER_MOTION, vec![WaylandArg::Uint(0), WaylandArg::Fixed(x), WaylandArg::Fixed(y)]);
+        self.connection.send(self.pointer, WL_POINTER_FRAME, vec![]);
+    }
+
+    /// Press and release a button, each in its own frame.
+    pub fn pointer_button(&mut self, button : u32) {
+        for state in [1, 0] {
+            let serial = self.next_serial();
+            self.connection.send(self.pointer, WL_POINTER_BUTTON, vec![WaylandArg::Uint(serial), WaylandArg::Uint(0), WaylandArg::Uint(button), WaylandArg::Uint(state)]);
+            self.connection.send(self.pointer, WL_POINTER_FRAME, vec![]);
+        }
+    }
+
+    /// Press and release the left button.
+    pub fn pointer_click(&mut self) {
+        self.pointer_button(BTN_LEFT);
+    }
+
+    /// Scroll vertically of `value120` 1/120 of wheel steps, 15 pixels per step.
+    pub fn pointer_scroll(&mut self, value120 : i32) {
+        self.connection.send(self.pointer, WL_POINTER_AXIS, vec![WaylandArg::Uint(0), WaylandArg::Uint(WL_POINTER_AXIS_VERTICAL_SCROLL), 
+            WaylandArg::Fixed(value120 as f64 * 15.0 / 120.0)]);
+        self.connection.send(self.pointer, WL_POINTER_AXIS_VALUE120, vec![WaylandArg::Uint(WL_POINTER_AXIS_VERTICAL_SCROLL), WaylandArg::Int(value120)]);
+        self.connection.send(self.pointer, WL_POINTER_FRAME, vec![]);
+    }
+
+    /// Send zwp_relative_pointer_v1.relative_motion, accelerated motion being twice the unaccelerated one.
+    pub fn relative_motion(&mut self, dx : f64, dy : f64) {
+        self.connection.send(self.relative_pointer, ZWP_RELATIVE_POINTER_V1_RELATIVE_MOTION, vec![WaylandArg::Uint(0), WaylandArg::Uint(0), 
+            WaylandArg::Fixed(dx * 2.0), WaylandArg::Fixed(dy * 2.0), WaylandArg::Fixed(dx), WaylandArg::Fixed(dy)]);
+        self.connection.send(self.pointer, WL_POINTER_FRAME, vec![]);
+    }
+
+    /// Activate or deactivate the constraint at index, like compositors do when surface gains or loses focus.
+    pub fn constraint(&mut self, index : usize, active : bool) {
+        let (object, _, lock) = self.constraints[index];
+        let event = match (lock, active) {
+            (true, true) => ZWP_LOCKED_POINTER_V1_LOCKED,
+            (true, false) => ZWP_LOCKED_POINTER_V1_UNLOCKED,
+            (false, true) => ZWP_CONFINED_POINTER_V1_CONFINED,
+            (false, false) => ZWP_CONFINED_POINTER_V1_UNCONFINED,
+        };
+        self.connection.send(object, event, vec![]);
+    }
+
+    /// Send wl_keyboard.enter on toplevel at index with evdev keys already pressed.
+    pub fn keyboard_enter(&mut self, index : usize, keys : &[u32]) {
+        let serial = self.next_serial();
+        let keys : Vec<u8> = keys.iter().flat_map(|k| k.to_ne_bytes()).collect();
+        self.connection.send(self.keyboard, WL_KEYBOARD_ENTER, vec![WaylandArg::Uint(serial), WaylandArg::Object(self.toplevels[index].surface), 
+            WaylandArg::Array(keys)]);
+    }
+
+    /// Send wl_keyboard.leave of toplevel at index.
+    pub fn keyboard_leave(&mut self, index : usize) {
+        let serial = self.next_serial();
+        self.connection.send(self.keyboard, WL_KEYBOARD_LEAVE, vec![WaylandArg::Uint(serial), WaylandArg::Object(self.toplevels[index].surface)]);
+    }
+
+    /// Send wl_keyboard.key of an evdev key.
+    pub fn key(&mut self, key : u32, pressed : bool) {
+        let serial = self.next_serial();
+        self.connection.send(self.keyboard, WL_KEYBOARD_KEY, vec![WaylandArg::Uint(serial), WaylandArg::Uint(0), WaylandArg::Uint(key), 
+            WaylandArg::Uint(pressed as u32)]);
+    }
+
+    /// Send wl_keyboard.modifiers with depressed and locked modifiers masks and the active group.
+    pub fn modifiers(&mut self, depressed : u32, locked : u32, group : u32) {
+        let serial = self.next_serial();
+        self.connection.send(self.keyboard, WL_KEYBOARD_MODIFIERS, vec![WaylandArg::Uint(serial), WaylandArg::Uint(depressed), WaylandArg::Uint(0), 
+            WaylandArg::Uint(locked), WaylandArg::Uint(group)]);
+    }
+
+    /// Send zwp_text_input_v3.enter or leave of toplevel at index.
+    pub fn text_enter(&mut self, index : usize, enter : bool) {
+        let event = if enter { ZWP_TEXT_INPUT_V3_ENTER } else { ZWP_TEXT_INPUT_V3_LEAVE };
+        self.connection.send(self.text_input, event, vec![WaylandArg::Object(self.toplevels[index].surface)]);
+    }
+
+    /// Send zwp_text_input_v3.preedit_string with cursor range in bytes.
+    pub fn text_preedit(&mut self, text : &str, begin : i32, end : i32) {
+        self.connection.send(self.text_input, ZWP_TEXT_INPUT_V3_PREEDIT_STRING, vec![WaylandArg::Str(Some(text.to_string())), 
+            WaylandArg::Int(begin), WaylandArg::Int(end)]);
+    }
+
+    /// Send zwp_text_input_v3.commit_string.
+    pub fn text_commit(&mut self, text : &str) {
+        self.connection.send(self.text_input, ZWP_TEXT_INPUT_V3_COMMIT_STRING, vec![WaylandArg::Str(Some(text.to_string()))]);
+    }
+
+    /// Send zwp_text_input_v3.delete_surrounding_text with bytes before and after the cursor.
+    pub fn text_delete(&mut self, before : u32, after : u32) {
+        self.connection.send(self.text_input, ZWP_TEXT_INPUT_V3_DELETE_SURROUNDING_TEXT, vec![WaylandArg::Uint(before), WaylandArg::Uint(after)]);
+    }
+
+    /// Send zwp_text_input_v3.done with the number of commits received as serial.
+    pub fn text_done(&mut self) {
+        self.connection.send(self.text_input, ZWP_TEXT_INPUT_V3_DONE, vec![WaylandArg::Uint(self.text_commits)]);
+    }
+
+    /// Send wl_seat.capabilities to the seat bound.
+    pub fn capabilities(&mut self, capabilities : u32) {
+        let seat = self.bound("wl_seat").unwrap();
+        self.connection.send(seat, WL_SEAT_CAPABILITIES, vec![WaylandArg::Uint(capabilities)]);
+    }
+
+    /// Send xdg_toplevel.close to toplevel at index.
+    pub fn close(&mut self, index : usize) {
+        let toplevel = self.toplevels[index].toplevel;
+        self.connection.send(toplevel, XDG_TOPLEVEL_CLOSE, vec![]);
+    }
+
+    /// Send xdg_popup.popup_done to popup at index, like when clicking outside of it.
+    pub fn popup_done(&mut self, index : usize) {
+        let popup = self.toplevels[index].popup;
+        self.connection.send(popup, XDG_POPUP_POPUP_DONE, vec![]);
+    }
+
+    /// Send wl_callback.done with `time` in milliseconds to all frame callbacks requested, like when the compositor is ready for another frame.
+    pub fn frame_done(&mut self, time : u32) {
+        for (callback, _) in std::mem::take(&mut self.frames) {
+            self.connection.send(callback, WL_CALLBACK_DONE, vec![WaylandArg::Uint(time)]);
+            self.connection.send(WL_DISPLAY_ID, WL_DISPLAY_DELETE_ID, vec![WaylandArg::Uint(callback)]);
+            self.connection.delete(callback);
+        }
+    }
+
+    /// Send a fatal protocol error on object.
+    pub fn protocol_error(&mut self, object : WaylandId, code : u32, message : &str) {
+        self.connection.send(WL_DISPLAY_ID, WL_DISPLAY_ERROR, vec![WaylandArg::Object(object), WaylandArg::Uint(code), 
+            WaylandArg::Str(Some(message.to_string()))]);
+    }
+
+    /// Answer a request from client.
+    fn handle(&mut self, message : WaylandMessage) {
+        let interface = match self.connection.interface(message.sender) {
+            Some(interface) => interface,
+            None => return,
+        };
+        let request = interface.requests[message.opcode as usize].name;
+        self.requests.push(format!("{}.{}", interface.name, request));
+
+        let toplevel = self.toplevels.iter().position(|t| t.toplevel == message.sender || t.surface == message.sender);
+
+        match (interface.name, message.opcode) {
+            ("wl_display", WL_DISPLAY_SYNC) => {
+                let callback = message.uint(0);
+                let serial = self.next_serial();
+                self.connection.send(callback, WL_CALLBACK_DONE, vec![WaylandArg::Uint(serial)]);
+                self.connection.send(WL_DISPLAY_ID, WL_DISPLAY_DELETE_ID, vec![WaylandArg::Uint(callback)]);
+                self.connection.delete(callback);
+            },
+            ("wl_display", WL_DISPLAY_GET_REGISTRY) => {
+                let registry = message.uint(0);
+                self.registries.push(registry);
+                for (name, interface, version) in self.globals.clone() {
+                    self.connection.send(registry, WL_REGISTRY_GLOBAL, vec![WaylandArg::Uint(name), 
+                        WaylandArg::Str(Some(interface)), WaylandArg::Uint(version)]);
+                }
+            },
+            ("wl_registry", WL_REGISTRY_BIND) => {
+                self.bound.push((message.string(1).to_string(), message.uint(3)));
+
+                if message.string(1) == "wl_seat" {
+                    self.connection.send(message.uint(3), WL_SEAT_CAPABILITIES, vec![WaylandArg::Uint(WL_SEAT_CAPABILITY_POINTER | WL_SEAT_CAPABILITY_KEYBOARD)]);
+                    self.connection.send(message.uint(3), WL_SEAT_NAME, vec![WaylandArg::Str(Some("seat0".to_string()))]);
+                }
+
+                // Outputs send their informations when bound.
+                if let Some(output) = self.outputs.iter_mut().find(|o| o.global == message.uint(0)) {
+                    output.output = message.uint(3);
+                    let output = output.clone();
+                    self.connection.send(output.output, WL_OUTPUT_GEOMETRY, vec![WaylandArg::Int(output.position.0), WaylandArg::Int(output.position.1), 
+                        WaylandArg::Int(600), WaylandArg::Int(340), WaylandArg::Int(0), WaylandArg::Str(Some("Mock".to_string())), 
+                        WaylandArg::Str(Some(output.name.clone())), WaylandArg::Int(0)]);
+                    self.connection.send(output.output, WL_OUTPUT_MODE, vec![WaylandArg::Uint(WL_OUTPUT_MODE_CURRENT), 
+                        WaylandArg::Int(output.mode.0), WaylandArg::Int(output.mode.1), WaylandArg::Int(output.mode.2)]);
+                    self.connection.send(output.output, WL_OUTPUT_SCALE, vec![WaylandArg::Int(output.scale)]);
+                    self.connection.send(output.output, WL_OUTPUT_NAME, vec![WaylandArg::Str(Some(output.name.clone()))]);
+                    self.connection.send(output.output, WL_OUTPUT_DESCRIPTION, vec![WaylandArg::Str(Some(format!("Mock {}", output.name)))]);
+                    self.connection.send(output.output, WL_OUTPUT_DONE, vec![]);
+                }
+            },
+            // xdg-output v3 informations are followed by wl_output.done.
+            ("zxdg_output_manager_v1", ZXDG_OUTPUT_MANAGER_V1_GET_XDG_OUTPUT) => if let Some(output) = self.outputs.iter().find(|o| o.output == message.uint(1)).cloned() {
+                let xdg_output = message.uint(0);
+                self.connection.send(xdg_output, ZXDG_OUTPUT_V1_LOGICAL_POSITION, vec![WaylandArg::Int(output.position.0), WaylandArg::Int(output.position.1)]);
+                self.connection.send(xdg_output, ZXDG_OUTPUT_V1_LOGICAL_SIZE, vec![WaylandArg::Int(output.logical_size.0), WaylandArg::Int(output.logical_size.1)]);
+                self.connection.send(output.output, WL_OUTPUT_DONE, vec![]);
+            },
+            ("wl_seat", WL_SEAT_GET_POINTER) => self.pointer = message.uint(0),
+            ("wl_pointer", WL_POINTER_SET_CURSOR) => self.cursor_surface = Some(message.uint(1)),
+            ("wp_cursor_shape_manager_v1", WP_CURSOR_SHAPE_MANAGER_V1_GET_POINTER) => self.shape_device = message.uint(0),
+            ("wp_cursor_shape_device_v1", WP_CURSOR_SHAPE_DEVICE_V1_SET_SHAPE) => self.shape = message.uint(1),
+            ("wl_surface", WL_SURFACE_SET_BUFFER_SCALE) => if let Some(index) = toplevel {
+                self.toplevels[index].buffer_scale = message.int(0);
+            },
+            ("wl_surface", WL_SURFACE_FRAME) => self.frames.push((message.uint(0), message.sender)),
+            ("wl_surface", WL_SURFACE_DAMAGE_BUFFER) => if let Some(index) = toplevel {
+                self.toplevels[index].buffer_damage.push((message.int(0), message.int(1), message.int(2), message.int(3)));
+            },
+            ("wp_fractional_scale_manager_v1", WP_FRACTIONAL_SCALE_MANAGER_V1_GET_FRACTIONAL_SCALE) => 
+                if let Some(t) = self.toplevels.iter_mut().find(|t| t.surface == message.uint(1)) {
+                    t.fractional_scale = message.uint(0);
+                },
+            ("wp_viewporter", WP_VIEWPORTER_GET_VIEWPORT) => if let Some(t) = self.toplevels.iter_mut().find(|t| t.surface == message.uint(1)) {
+                t.viewport = message.uint(0);
+            },
+            ("wp_viewport", WP_VIEWPORT_SET_DESTINATION) => if let Some(t) = self.toplevels.iter_mut().find(|t| t.viewport == message.sender) {
+                t.viewport_size = (message.int(0), message.int(1));
+            },
+            ("zwp_relative_pointer_manager_v1", ZWP_RELATIVE_POINTER_MANAGER_V1_GET_RELATIVE_POINTER) => self.relative_pointer = message.uint(0),
+            ("zwp_text_input_manager_v3", ZWP_TEXT_INPUT_MANAGER_V3_GET_TEXT_INPUT) => self.text_input = message.uint(0),
+            ("zwp_text_input_v3", ZWP_TEXT_INPUT_V3_ENABLE) => self.text_enabled = true,
+            // Disable resets the state of the text input.
+            ("zwp_text_input_v3", ZWP_TEXT_INPUT_V3_DISABLE) => {
+                self.text_enabled = false;
+                self.surrounding = None;
+                self.text_cursor = None;
+            },
+            ("zwp_text_input_v3", ZWP_TEXT_INPUT_V3_SET_SURROUNDING_TEXT) => 
+                self.surrounding = Some((message.string(0).to_string(), message.int(1), message.int(2))),
+            ("zwp_text_input_v3", ZWP_TEXT_INPUT_V3_SET_CURSOR_RECTANGLE) => 
+                self.text_cursor = Some((message.int(0), message.int(1), message.int(2), message.int(3))),
+            ("zwp_text_input_v3", ZWP_TEXT_INPUT_V3_COMMIT) => self.text_commits += 1,
+            ("zwp_idle_inhibit_manager_v1", ZWP_IDLE_INHIBIT_MANAGER_V1_CREATE_INHIBITOR) => self.idle_inhibitors.push((message.uint(0), message.uint(1))),
+            ("zwp_idle_inhibitor_v1", ZWP_IDLE_INHIBITOR_V1_DESTROY) => self.idle_inhibitors.retain(|(i, _)| *i != message.sender),
+            ("zwp_keyboard_shortcuts_inhibit_manager_v1", ZWP_KEYBOARD_SHORTCUTS_INHIBIT_MANAGER_V1_INHIBIT_SHORTCUTS) => 
+                self.shortcuts_inhibitors.push((message.uint(0), message.uint(1))),
+            ("zwp_keyboard_shortcuts_inhibitor_v1", ZWP_KEYBOARD_SHORTCUTS_INHIBITOR_V1_DESTROY) => 
+                self.shortcuts_inhibitors.retain(|(i, _)| *i != message.sender),
+            ("xdg_activation_v1", XDG_ACTIVATION_V1_GET_ACTIVATION_TOKEN) => self.tokens.push((message.uint(0), 0, 0)),
+            ("xdg_activation_v1", XDG_ACTIVATION_V1_ACTIVATE) => self.activated.push((message.string(0).to_string(), message.uint(1))),
+            ("xdg_activation_token_v1", XDG_ACTIVATION_TOKEN_V1_SET_SERIAL) => if let Some(t) = self.tokens.iter_mut().find(|t| t.0 == message.sender) {
+                t.1 = message.uint(0);
+            },
+            ("xdg_activation_token_v1", XDG_ACTIVATION_TOKEN_V1_SET_SURFACE) => if let Some(t) = self.tokens.iter_mut().find(|t| t.0 == message.sender) {
+                t.2 = message.uint(0);
+            },
+            ("xdg_activation_token_v1", XDG_ACTIVATION_TOKEN_V1_COMMIT) => 
+                self.connection.send(message.sender, XDG_ACTIVATION_TOKEN_V1_DONE, vec![WaylandArg::Str(Some(format!("token-{}", message.sender)))]),
+            ("zwp_pointer_constraints_v1", ZWP_POINTER_CONSTRAINTS_V1_LOCK_POINTER) | ("zwp_pointer_constraints_v1", ZWP_POINTER_CONSTRAINTS_V1_CONFINE_POINTER) => {
+                self.constraints.push((message.uint(0), message.uint(1), message.opcode == ZWP_POINTER_CONSTRAINTS_V1_LOCK_POINTER));
+                self.constraint(self.constraints.len() - 1, true);
+            },
+            // Keymap is shared in a memfd followed by repeat informations.
+            ("wl_seat", WL_SEAT_GET_KEYBOARD) => {
+                self.keyboard = message.uint(0);
+                let fd = unsafe { libc::memfd_create(c"nswindow-mock-keymap".as_ptr(), libc::MFD_CLOEXEC) };
+                let mut file = unsafe { File::from_raw_fd(fd) };
+                file.write_all(MOCK_KEYMAP.as_bytes()).unwrap();
+                file.write_all(&[0]).unwrap();
+
+                self.connection.send(self.keyboard, WL_KEYBOARD_KEYMAP, vec![WaylandArg::Uint(WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1), 
+                    WaylandArg::Fd(OwnedFd::from(file)), WaylandArg::Uint(MOCK_KEYMAP.len() as u32 + 1)]);
+                self.connection.send(self.keyboard, WL_KEYBOARD_REPEAT_INFO, vec![WaylandArg::Int(MOCK_REPEAT.0), WaylandArg::Int(MOCK_REPEAT.1)]);
+            },
+            ("wl_subcompositor", WL_SUBCOMPOSITOR_GET_SUBSURFACE) => self.subsurfaces.push((message.uint(0), message.uint(1), message.uint(2))),
+            ("wl_shm_pool", WL_SHM_POOL_CREATE_BUFFER) => self.buffers.push((message.uint(0), message.int(2), message.int(3))),
+            // Compositor answers with the mode requested.
+            ("zxdg_decoration_manager_v1", ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION) => 
+                if let Some(t) = self.toplevels.iter_mut().find(|t| t.toplevel == message.uint(1)) {
+                    t.decoration = message.uint(0);
+                },
+            ("zxdg_toplevel_decoration_v1", ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE) => 
+                if let Some(t) = self.toplevels.iter_mut().find(|t| t.decoration == message.sender) {
+                    t.decoration_mode = message.uint(0);
+                    self.connection.send(message.sender, ZXDG_TOPLEVEL_DECORATION_V1_CONFIGURE, vec![WaylandArg::Uint(message.uint(0))]);
+                },
+            ("xdg_wm_base", XDG_WM_BASE_GET_XDG_SURFACE) => self.toplevels.push(MockToplevel { xdg_surface : message.uint(0), 
+                surface : message.uint(1), ..Default::default() }),
+            ("xdg_surface", XDG_SURFACE_GET_TOPLEVEL) => if let Some(t) = self.toplevels.iter_mut().find(|t| t.xdg_surface == message.sender) {
+                t.toplevel = message.uint(0);
+            },
+            ("xdg_wm_base", XDG_WM_BASE_CREATE_POSITIONER) => self.positioners.push(MockPositioner { positioner : message.uint(0), ..Default::default() }),
+            ("xdg_positioner", _) => if let Some(p) = self.positioners.iter_mut().find(|p| p.positioner == message.sender) {
+                match message.opcode {
+                    XDG_POSITIONER_SET_SIZE => p.size = (message.int(0), message.int(1)),
+                    XDG_POSITIONER_SET_ANCHOR_RECT => p.anchor_rect = (message.int(0), message.int(1), message.int(2), message.int(3)),
+                    XDG_POSITIONER_SET_ANCHOR => p.anchor = message.uint(0),
+                    XDG_POSITIONER_SET_GRAVITY => p.gravity = message.uint(0),
+                    XDG_POSITIONER_SET_CONSTRAINT_ADJUSTMENT => p.adjustment = message.uint(0),
+                    XDG_POSITIONER_SET_OFFSET => p.offset = (message.int(0), message.int(1)),
+                    _ => {},
+                }
+            },
+            ("xdg_surface", XDG_SURFACE_GET_POPUP) => {
+                let positioner = self.positioners.iter().find(|p| p.positioner == message.uint(2)).copied().unwrap_or_default();
+                if let Some(t) = self.toplevels.iter_mut().find(|t| t.xdg_surface == message.sender) {
+                    t.popup = message.uint(0);
+                    t.parent = message.uint(1);
+                    t.positioner = positioner;
+                }
+            },
+            ("xdg_popup", XDG_POPUP_GRAB) => if let Some(t) = self.toplevels.iter_mut().find(|t| t.popup == message.sender) {
+                t.grab = message.uint(1);
+            },
+            ("xdg_toplevel", XDG_TOPLEVEL_SET_PARENT) => self.toplevels[toplevel.unwrap()].parent = message.uint(0),
+            ("xdg_toplevel", XDG_TOPLEVEL_SET_TITLE) => self.toplevels[toplevel.unwrap()].title = message.string(0).to_string(),
+            ("xdg_toplevel", XDG_TOPLEVEL_SET_MIN_SIZE) => self.toplevels[toplevel.unwrap()].min_size = (message.int(0), message.int(1)),
+            ("xdg_toplevel", XDG_TOPLEVEL_SET_MAX_SIZE) => self.toplevels[toplevel.unwrap()].max_size = (message.int(0), message.int(1)),
+            ("xdg_toplevel", XDG_TOPLEVEL_SET_MAXIMIZED) | ("xdg_toplevel", XDG_TOPLEVEL_UNSET_MAXIMIZED) => {
+                let index = toplevel.unwrap();
+                self.toplevels[index].maximized = message.opcode == XDG_TOPLEVEL_SET_MAXIMIZED;
+                if self.toplevels[index].mapped {
+                    self.configure(index, 0, 0);
+                }
+            },
+            ("xdg_toplevel", XDG_TOPLEVEL_SET_FULLSCREEN) | ("xdg_toplevel", XDG_TOPLEVEL_UNSET_FULLSCREEN) => {
+                let index = toplevel.unwrap();
+                self.toplevels[index].fullscreen = message.opcode == XDG_TOPLEVEL_SET_FULLSCREEN;
+                self.toplevels[index].fullscreen_output = message.uint(0);
+                if self.toplevels[index].mapped {
+                    self.configure(index, 0, 0);
+                }
+            },
+            // Null buffer unmaps the surface on commit.
+            ("wl_surface", WL_SURFACE_ATTACH) => {
+                self.attached.retain(|(s, _)| *s != message.sender);
+                self.attached.push((message.sender, message.uint(0)));
+                if let Some(index) = toplevel {
+                    self.toplevels[index].unmapping = message.uint(0) == 0;
+                    self.toplevels[index].buffer_damage.clear();
+                }
+            },
+            // Initial commit is answered with a configure. Buffer committed before is released.
+            ("wl_surface", WL_SURFACE_COMMIT) => if let Some(index) = toplevel {
+                let buffer = self.attached.iter().find(|(s, _)| *s == message.sender).map_or(0, |(_, b)| *b);
+                let committed = self.toplevels[index].committed;
+                if committed != buffer && committed != 0 && self.connection.alive(committed) {
+                    self.connection.send(committed, WL_BUFFER_RELEASE, vec![]);
+                }
+                self.toplevels[index].committed = buffer;
+
+                if self.toplevels[index].unmapping {
+                    self.toplevels[index].unmapping = false;
+                    self.toplevels[index].mapped = false;
+                } else if !self.toplevels[index].mapped && (self.toplevels[index].toplevel != 0 || self.toplevels[index].popup != 0) {
+                    self.toplevels[index].mapped = true;
+                    self.configure(index, 0, 0);
+                }
+            },
+            _ => {},
+        }
+
+        // Ids of destroyed objects are released.
+        if request == "destroy" || request == "release" {
+            self.connection.send(WL_DISPLAY_ID, WL_DISPLAY_DELETE_ID, vec![WaylandArg::Uint(message.sender)]);
+            self.connection.delete(message.sender);
+            self.toplevels.retain(|t| t.surface != message.sender);
+            self.subsurfaces.retain(|(s, _, _)| *s != message.sender);
+            self.frames.retain(|(_, s)| *s != message.sender);
+            self.attached.retain(|(s, b)| *s != message.sender && *b != message.sender);
+            self.constraints.retain(|(c, _, _)| *c != message.sender);
+            self.positioners.retain(|p| p.positioner != message.sender);
+        }
+    }
+}
+
+/// Handle on the mock compositor thread. Dropping it stops the compositor and close the connection.
+pub(crate) struct MockHandle {
+    /// Commands sent to compositor thread
+    commands : Option<Sender<MockCommand>>,
+
+    /// Compositor thread
+    thread : Option<JoinHandle<()>>,
+}
+
+impl MockHandle {
+    /// Run a closure on the compositor thread and returns its result once executed. 
+    /// 
+    /// Events sent by the closure are flushed right after.
+    pub fn run<R : Send + 'static>(&self, f : impl FnOnce(&mut MockCompositor) -> R + Send + 'static) -> R {
+        let (sender, receiver) = channel::<R>();
+        self.commands.as_ref().unwrap().send(Box::new(move |mock| { let _ = sender.send(f(mock)); })).unwrap();
+        receiver.recv().unwrap()
+    }
+}
+
+impl Drop for MockHandle {
+    fn drop(&mut self) {
+        // Disconnecting the channel stops the thread.
+        drop(self.commands.take());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Start a mock compositor advertising `globals` as (interface, version).
+/// 
+/// Returns the client connection and the [MockHandle] of the compositor.
+pub(crate) fn mock_compositor(globals : &[(&str, u32)]) -> (WaylandConnection, MockHandle) {
+    let (client, mock) = mock_start(globals);
+    (WaylandConnection::from_stream(client, false), mock)
+}
+
+/// Start a mock compositor like [mock_compositor], with a client connection using libwayland.
+/// 
+/// Returns None if libwayland-client is missing.
+#[cfg(any(feature = "raw_window_handle", feature = "vulkan", feature = "opengl"))]
+pub(crate) fn mock_compositor_libwayland(globals : &[(&str, u32)]) -> Option<(WaylandConnection, MockHandle)> {
+    let (client, mock) = mock_start(globals);
+    WaylandConnection::from_libwayland(client).map(|connection| (connection, mock))
+}
+
+/// Start the compositor thread and returns the client end of the socket.
+fn mock_start(globals : &[(&str, u32)]) -> (UnixStream, MockHandle) {
+    let (client, server) = UnixStream::pair().unwrap();
+    let (sender, receiver) = channel::<MockCommand>();
+
+    let mut mock = MockCompositor { 
+        connection : WaylandConnection::from_stream(server, true), 
+        globals : globals.iter().enumerate().map(|(i, (interface, version))| (i as u32 + 1, interface.to_string(), *version)).collect(), 
+        registries : Vec::new(),
+        bound : Vec::new(),
+        toplevels : Vec::new(),
+        outputs : Vec::new(),
+        positioners : Vec::new(),
+        subsurfaces : Vec::new(),
+        buffers : Vec::new(),
+        attached : Vec::new(),
+        frames : Vec::new(),
+        pointer : 0,
+        keyboard : 0,
+        cursor_surface : None,
+        shape_device : 0,
+        shape : 0,
+        constraints : Vec::new(),
+        relative_pointer : 0,
+        text_input : 0,
+        text_enabled : false,
+        surrounding : None,
+        text_cursor : None,
+        text_commits : 0,
+        idle_inhibitors : Vec::new(),
+        shortcuts_inhibitors : Vec::new(),
+        tokens : Vec::new(),
+        activated : Vec::new(),
+        requests : Vec::new(), 
+        serial : 0 
+    };
+
+    let thread = std::thread::spawn(move || mock_run(&mut mock, receiver));
+
+    (client, MockHandle { commands : Some(sender), thread : Some(thread) })
+}
+
+/// Compositor thread loop, running until client disconnect or handle is dropped.
+fn mock_run(mock : &mut MockCompositor, commands : Receiver<MockCommand>) {
+    loop {
+        loop {
+            match commands.try_recv() {
+                // Requests already sent by client are handled before the command.
+                Ok(command) => match mock_receive(mock, 0) {
+                    true => command(mock),
+                    false => return,
+                },
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => return,
+            }
+        }
+
+        if !mock_receive(mock, 5) || mock.connection.flush().is_err() {
+            return;
+        }
+    }
+}
+
+/// Handle requests received within `timeout` milliseconds.
+/// 
+/// Returns false if client disconnected.
+fn mock_receive(mock : &mut MockCompositor, timeout : i32) -> bool {
+    if mock.connection.poll(timeout) {
+        match mock.connection.read(false) {
+            Ok(_) => {
+                while let Some(Ok(message)) = mock.connection.next_message() {
+                    mock.handle(message);
+                }
+            },
+            Err(_) => return false,
+        }
+    }
+    true
+}
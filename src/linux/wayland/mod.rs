@@ -22,23 +22,27 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-
-
-use crate::{event::WindowManagerEvent, display::Displays};
-
-
 pub(crate) mod manager;
 pub(crate) mod window;
+pub(crate) mod display;
 pub(crate) mod keyboard;
+pub(crate) mod text_input;
+pub(crate) mod activation;
+pub(crate) mod grab;
+#[cfg(not(feature = "single_opt"))]
+pub(crate) mod popup;
+pub(crate) mod cursor;
 pub(crate) mod pointer;
 pub(crate) mod frame;
-
-
-/// This function spawn a new thread and try to connect to wayland server to see if available.
-/// 
-/// TODO:Create thread
-/// 
-/// Return true if wayland server is available and supported. False otherwise.
-pub fn wayland_supported() -> bool { 
-    false
-}
\ No newline at end of file
+pub(crate) mod framebuffer;
+pub(crate) mod protocol;
+pub(crate) mod xkb;
+pub(crate) mod wlib;
+pub(crate) mod libwayland;
+#[cfg(feature = "vulkan")]
+pub(crate) mod vulkan;
+#[cfg(feature = "opengl")]
+pub(crate) mod opengl;
+
+#[cfg(test)]
+pub(crate) mod mock;
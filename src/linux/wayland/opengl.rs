@@ -0,0 +1,215 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! OpenGL contexts with EGL on a wl_egl_window of the libwayland wl_surface, loaded at runtime from libEGL and libwayland-egl.
+//! 
+//! Reference(s)
+//! <https://registry.khronos.org/EGL/extensions/KHR/EGL_KHR_platform_wayland.txt>
+//! <https://gitlab.freedesktop.org/wayland/wayland/-/blob/main/egl/wayland-egl-core.h>
+
+use std::{cell::{Cell, RefCell}, ffi::{c_int, c_void, CStr}, mem::ManuallyDrop, rc::Rc};
+
+use crate::{linux::{egl::{Egl, EglContext, EGL_PLATFORM_WAYLAND_KHR}, library::Library}, opengl::{GLApi, GLConfig}, WindowError, WindowSize};
+
+use super::wlib::WaylandConnection;
+
+/// wl_egl_window functions of libwayland-egl.
+#[derive(Debug)]
+struct WaylandEgl {
+    window_create : unsafe extern "C" fn(*mut c_void, c_int, c_int) -> *mut c_void,
+    window_resize : unsafe extern "C" fn(*mut c_void, c_int, c_int, c_int, c_int),
+    window_destroy : unsafe extern "C" fn(*mut c_void),
+
+    /// Functions are valid while library is open.
+    _library : Library,
+}
+
+impl WaylandEgl {
+    /// Load wl_egl_window functions from libwayland-egl. None if libwayland-egl or one of its functions is missing.
+    fn load() -> Option<WaylandEgl> {
+        let library = Library::open(&[c"libwayland-egl.so.1", c"libwayland-egl.so"])?;
+        unsafe {
+            Some(WaylandEgl {
+                window_create : library.symbol(c"wl_egl_window_create")?,
+                window_resize : library.symbol(c"wl_egl_window_resize")?,
+                window_destroy : library.symbol(c"wl_egl_window_destroy")?,
+                _library : library,
+            })
+        }
+    }
+}
+
+/// Size in pixels of the wl_egl_window of a window of `size` and `scale`, like [Window::framebuffer_size()](crate::Window::framebuffer_size).
+fn wayland_egl_size(size : WindowSize, scale : f64) -> WindowSize {
+    WindowSize::new(((size.width as f64 * scale).round() as u32).max(1), ((size.height as f64 * scale).round() as u32).max(1))
+}
+
+/// OpenGL context of a Wayland window, drawing with EGL on a wl_egl_window.
+/// 
+/// wl_egl_window follows the size and scale of the window, shared with the [WaylandWindow](super::window::WaylandWindow), 
+/// when the context is made current and after buffers are swapped.
+#[derive(Debug)]
+pub(crate) struct WaylandGLContext {
+    /// libwayland wl_display pointer
+    display : *mut c_void,
+
+    /// libwayland-egl, shared with contexts sharing objects.
+    wayland_egl : Rc<WaylandEgl>,
+
+    /// wl_egl_window of the wl_surface
+    window : *mut c_void,
+
+    /// Window size and scale, shared with the window.
+    size : Rc<Cell<(WindowSize, f64)>>,
+
+    /// Size in pixels of the wl_egl_window
+    resized : Cell<WindowSize>,
+
+    /// EGL context drawing on the wl_egl_window, destroyed before it.
+    context : ManuallyDrop<EglContext>,
+
+    /// Connection kept open while the context exists, since contexts may outlive their manager.
+    _connection : Rc<RefCell<WaylandConnection>>,
+}
+
+impl Drop for WaylandGLContext {
+    fn drop(&mut self) {
+        unsafe {
+            ManuallyDrop::drop(&mut self.context);
+            (self.wayland_egl.window_destroy)(self.window);
+        }
+    }
+}
+
+impl WaylandGLContext {
+    /// Create an EGL context of `config` on a wl_egl_window of the libwayland `surface`, sharing objects with `shared` if given. 
+    /// Wayland has no GLX, so contexts are created with EGL whatever [GLApi] is requested.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowOpenGLUnsupported`]) if libEGL or libwayland-egl is missing, or the display can't be 
+    /// initialized, and Err([`WindowError::WindowOpenGLContextFailed`]) if no framebuffer matches config, creation failed 
+    /// or `shared` uses another display.
+    /// 
+    /// # Safety
+    /// `display` and `surface` must be the libwayland `wl_display` of `connection` and a `wl_surface` outliving the context.
+    pub(crate) unsafe fn new(connection : Rc<RefCell<WaylandConnection>>, display : *mut c_void, surface : *mut c_void, 
+        size : Rc<Cell<(WindowSize, f64)>>, config : &GLConfig, shared : Option<&WaylandGLContext>) -> Result<WaylandGLContext, WindowError> {
+        let (egl, wayland_egl) = match shared {
+            Some(shared) if shared.display != display => return Err(WindowError::WindowOpenGLContextFailed),
+            Some(shared) => (shared.context.egl.clone(), shared.wayland_egl.clone()),
+            None => (Rc::new(Egl::load().ok_or(WindowError::WindowOpenGLUnsupported)?), 
+                Rc::new(WaylandEgl::load().ok_or(WindowError::WindowOpenGLUnsupported)?)),
+        };
+
+        let (window_size, scale) = size.get();
+        let resized = wayland_egl_size(window_size, scale);
+        let window = (wayland_egl.window_create)(surface, resized.width as c_int, resized.height as c_int);
+        if window.is_null() {
+            return Err(WindowError::WindowOpenGLContextFailed);
+        }
+
+        let config = GLConfig { api : GLApi::Egl, ..*config };
+        let egl_display = egl.platform_display(EGL_PLATFORM_WAYLAND_KHR, "EGL_EXT_platform_wayland", display);
+        match EglContext::new(egl, egl_display, window as usize, None, &config, shared.map(|shared| &*shared.context)) {
+            Ok(context) => Ok(WaylandGLContext { display, wayland_egl, window, size, resized : Cell::new(resized), 
+                context : ManuallyDrop::new(context), _connection : connection }),
+            Err(error) => {
+                (wayland_egl.window_destroy)(window);
+                Err(error)
+            },
+        }
+    }
+
+    /// Resize the wl_egl_window if the window size or scale changed. Next buffer drawn gets the new size.
+    fn resize(&self) {
+        let (size, scale) = self.size.get();
+        let size = wayland_egl_size(size, scale);
+        if size != self.resized.get() {
+            self.resized.set(size);
+            unsafe { (self.wayland_egl.window_resize)(self.window, size.width as c_int, size.height as c_int, 0, 0) };
+        }
+    }
+
+    /// Make the context current on the calling thread.
+    pub(crate) fn make_current(&self) -> Result<(), WindowError> {
+        self.resize();
+        match self.context.make_current() {
+            true => Ok(()),
+            false => Err(WindowError::WindowOpenGLContextFailed),
+        }
+    }
+
+    /// Swap buffers with eglSwapBuffers, which attaches and commits the buffer to the wl_surface.
+    pub(crate) fn swap_buffers(&self) -> Result<(), WindowError> {
+        let swapped = self.context.swap_buffers();
+        self.resize();
+        match swapped {
+            true => Ok(()),
+            false => Err(WindowError::WindowOpenGLContextFailed),
+        }
+    }
+
+    /// Set swap interval with eglSwapInterval.
+    pub(crate) fn set_swap_interval(&self, interval : i32) -> Result<(), WindowError> {
+        match self.context.set_swap_interval(interval) {
+            true => Ok(()),
+            false => Err(WindowError::WindowOpenGLContextFailed),
+        }
+    }
+
+    /// Get an OpenGL function with eglGetProcAddress.
+    #[inline(always)]
+    pub(crate) fn get_proc_address(&self, name : &CStr) -> *const c_void {
+        self.context.get_proc_address(name)
+    }
+
+    /// Config with the sizes of the chosen framebuffer.
+    #[inline(always)]
+    pub(crate) fn config(&self) -> &GLConfig {
+        self.context.config()
+    }
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Wayland OpenGL context tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wayland_opengl_egl_size() {
+        assert_eq!(wayland_egl_size(WindowSize::new(800, 600), 1.0), WindowSize::new(800, 600));
+        assert_eq!(wayland_egl_size(WindowSize::new(800, 600), 2.0), WindowSize::new(1600, 1200));
+
+        // Fractional scales are rounded like the framebuffer size.
+        assert_eq!(wayland_egl_size(WindowSize::new(801, 601), 1.25), WindowSize::new(1001, 751));
+
+        // wl_egl_window can't be empty.
+        assert_eq!(wayland_egl_size(WindowSize::new(0, 0), 1.0), WindowSize::new(1, 1));
+    }
+}
@@ -22,54 +22,515 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use crate::{pointer::{WindowCursor, WindowPointerMode, WindowPointerPosition}, WindowError};
+use std::{cell::RefCell, rc::Rc};
 
+use crate::{event::{PointerAcceleration, PointerButton, PointerScroll, WindowManagerEvent}, pointer::{WindowCursor, WindowPointer, WindowPointerMode, WindowPointerPosition}, WindowError};
 
+use super::{cursor::{cursor_shape, WaylandCursorTheme}, frame::shm_buffer, protocol::{BTN_EXTRA, BTN_LEFT, BTN_MIDDLE, BTN_RIGHT, BTN_SIDE, 
+    WL_BUFFER_DESTROY, WL_COMPOSITOR_CREATE_SURFACE, WL_POINTER, WL_POINTER_RELEASE, WL_POINTER_SET_CURSOR, WL_SEAT_GET_POINTER, WL_SURFACE, 
+    WL_SURFACE_ATTACH, WL_SURFACE_COMMIT, WL_SURFACE_DAMAGE, WL_SURFACE_DESTROY, WP_CURSOR_SHAPE_DEVICE_V1, WP_CURSOR_SHAPE_DEVICE_V1_DESTROY, 
+    WP_CURSOR_SHAPE_DEVICE_V1_SET_SHAPE, WP_CURSOR_SHAPE_MANAGER_V1_GET_POINTER, 
+    ZWP_CONFINED_POINTER_V1, ZWP_CONFINED_POINTER_V1_DESTROY, ZWP_LOCKED_POINTER_V1, ZWP_LOCKED_POINTER_V1_DESTROY, ZWP_LOCKED_POINTER_V1_SET_CURSOR_POSITION_HINT, 
+    ZWP_POINTER_CONSTRAINTS_V1_CONFINE_POINTER, ZWP_POINTER_CONSTRAINTS_V1_LIFETIME_PERSISTENT, ZWP_POINTER_CONSTRAINTS_V1_LOCK_POINTER, 
+    ZWP_RELATIVE_POINTER_MANAGER_V1_GET_RELATIVE_POINTER, ZWP_RELATIVE_POINTER_V1, ZWP_RELATIVE_POINTER_V1_DESTROY}, wlib::{WaylandArg, WaylandConnection, WaylandId}};
+
+/// Pixels scrolled with a touchpad equivalent to a wheel step.
+const WL_SCROLL_PIXELS_PER_STEP : f64 = 10.0;
+
+/// Value of a wheel step in wl_pointer.axis_value120 events.
+const WL_SCROLL_VALUE120_PER_STEP : f64 = 120.0;
+
+/// Globals used to show cursors and constrain the pointer.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct WaylandPointerGlobals {
+    /// wl_compositor global, creating cursor surfaces
+    pub compositor : WaylandId,
+
+    /// wl_shm global, creating cursor buffers
+    pub shm : WaylandId,
+
+    /// wp_cursor_shape_manager_v1 global, 0 if not advertised.
+    pub shape_manager : WaylandId,
+
+    /// zwp_pointer_constraints_v1 global, 0 if not advertised.
+    pub constraints : WaylandId,
+
+    /// zwp_relative_pointer_manager_v1 global, 0 if not advertised.
+    pub relative_manager : WaylandId,
+}
+
+/// Kind of pointer constraint requested by a window.
 #[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum WaylandConstraintKind {
+    /// Pointer is locked in place, used with [WindowPointerMode::Acceleration].
+    Lock,
+
+    /// Pointer can't leave the window surface.
+    Confine,
+}
+
+/// Pointer constraint of a window surface. Constraint object is re-created when the seat pointer is.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct WaylandConstraint {
+    /// wl_surface of the window
+    pub surface : WaylandId,
+
+    /// Kind of constraint
+    pub kind : WaylandConstraintKind,
+
+    /// zwp_locked_pointer_v1 or zwp_confined_pointer_v1, 0 if seat has no pointer.
+    pub object : WaylandId,
+
+    /// True while the compositor applies the constraint.
+    pub active : bool,
+}
+
+/// Pointer state of the seat, updated by the window manager from wl_pointer events and shared with windows pointers.
+#[derive(Debug)]
+pub(crate) struct WaylandPointerState {
+    /// Connection with the compositor
+    connection : Rc<RefCell<WaylandConnection>>,
+
+    /// Globals used to show cursors
+    pub globals : WaylandPointerGlobals,
+
+    /// wl_pointer of the seat, 0 if seat has no pointer.
+    pub pointer : WaylandId,
+
+    /// wp_cursor_shape_device_v1 of the pointer, 0 if compositor lacks cursor-shape.
+    shape_device : WaylandId,
+
+    /// zwp_relative_pointer_v1 of the pointer, 0 if compositor lacks relative pointer.
+    pub relative_pointer : WaylandId,
+
+    /// Constraints applied, one per surface
+    pub constraints : Vec<WaylandConstraint>,
+
+    /// Constraints requested by windows pointers as (wl_surface, kind)
+    requested : Vec<(WaylandId, WaylandConstraintKind)>,
+
+    /// wl_surface of windows with input grabbed, pointer is locked on them whatever is requested.
+    grabbed : Vec<WaylandId>,
+
+    /// Fraction of relative motion not sent yet since accelerations are integers.
+    relative_remainder : (f64, f64),
+
+    /// wl_surface under the pointer, 0 if none.
+    pub focus : WaylandId,
+
+    /// Serial of the last wl_pointer.enter, needed to set the cursor.
+    pub serial : u32,
+
+    /// Position of the pointer on the focused surface
+    pub position : (f64, f64),
+
+    /// Events of the current wl_pointer.frame
+    pub frame : Vec<WindowManagerEvent>,
+
+    /// Pixels scrolled during the current frame
+    pub scroll : (f64, f64),
+
+    /// Wheel steps scrolled during the current frame, in 1/120 of step.
+    pub scroll120 : (i32, i32),
+
+    /// wl_surface showing Xcursor images, created on first use.
+    cursor_surface : WaylandId,
+
+    /// wl_buffer attached to the cursor surface
+    cursor_buffer : WaylandId,
+
+    /// Xcursor theme, loaded on first use.
+    theme : Option<WaylandCursorTheme>,
+}
+
+impl WaylandPointerState {
+
+    /// Create the state of a seat without pointer.
+    pub fn new(connection : Rc<RefCell<WaylandConnection>>) -> WaylandPointerState {
+        WaylandPointerState { connection, globals : WaylandPointerGlobals::default(), pointer : 0, shape_device : 0, relative_pointer : 0, 
+            constraints : Vec::new(), requested : Vec::new(), grabbed : Vec::new(), relative_remainder : (0.0, 0.0), focus : 0, serial : 0, 
+            position : (0.0, 0.0), frame : Vec::new(), scroll : (0.0, 0.0), scroll120 : (0, 0), cursor_surface : 0, cursor_buffer : 0, theme : None }
+    }
+
+    /// Get the wl_pointer of `seat`, its cursor shape device and relative pointer if available. Constraints
+    /// requested by windows are applied to the new pointer.
+    /// 
+    /// Returns the wl_pointer id.
+    pub fn create(&mut self, seat : WaylandId) -> WaylandId {
+        let mut conn = self.connection.borrow_mut();
+        let version = conn.version(seat);
+        self.pointer = conn.new_object(&WL_POINTER, version);
+        conn.send(seat, WL_SEAT_GET_POINTER, vec![WaylandArg::NewId(self.pointer)]);
+
+        if self.globals.shape_manager != 0 {
+            self.shape_device = conn.new_object(&WP_CURSOR_SHAPE_DEVICE_V1, 1);
+            conn.send(self.globals.shape_manager, WP_CURSOR_SHAPE_MANAGER_V1_GET_POINTER, vec![WaylandArg::NewId(self.shape_device), 
+                WaylandArg::Object(self.pointer)]);
+        }
+
+        if self.globals.relative_manager != 0 {
+            self.relative_pointer = conn.new_object(&ZWP_RELATIVE_POINTER_V1, 1);
+            conn.send(self.globals.relative_manager, ZWP_RELATIVE_POINTER_MANAGER_V1_GET_RELATIVE_POINTER, 
+                vec![WaylandArg::NewId(self.relative_pointer), WaylandArg::Object(self.pointer)]);
+        }
+
+        for constraint in self.constraints.iter_mut() {
+            constraint.object = constraint_object(&mut conn, self.globals.constraints, self.pointer, constraint.surface, constraint.kind);
+        }
+
+        self.pointer
+    }
+
+    /// Release the wl_pointer removed from seat with its cursor objects.
+    pub fn release(&mut self) {
+        let mut conn = self.connection.borrow_mut();
+
+        if self.shape_device != 0 {
+            conn.send(self.shape_device, WP_CURSOR_SHAPE_DEVICE_V1_DESTROY, vec![]);
+            conn.destroyed(self.shape_device);
+        }
+        if self.relative_pointer != 0 {
+            conn.send(self.relative_pointer, ZWP_RELATIVE_POINTER_V1_DESTROY, vec![]);
+            conn.destroyed(self.relative_pointer);
+        }
+
+        // Constraints are kept to be applied on the next pointer.
+        for constraint in self.constraints.iter_mut() {
+            constraint_destroy(&mut conn, constraint);
+        }
+        if self.cursor_buffer != 0 {
+            conn.send(self.cursor_buffer, WL_BUFFER_DESTROY, vec![]);
+            conn.destroyed(self.cursor_buffer);
+        }
+        if self.cursor_surface != 0 {
+            conn.send(self.cursor_surface, WL_SURFACE_DESTROY, vec![]);
+            conn.destroyed(self.cursor_surface);
+        }
+
+        // wl_pointer can only be released since version 3.
+        if conn.version(self.pointer) >= 3 {
+            conn.send(self.pointer, WL_POINTER_RELEASE, vec![]);
+            conn.destroyed(self.pointer);
+        }
+
+        self.pointer = 0;
+        self.shape_device = 0;
+        self.relative_pointer = 0;
+        self.cursor_surface = 0;
+        self.cursor_buffer = 0;
+        self.focus = 0;
+        self.frame.clear();
+    }
+
+    /// Show a [WindowCursor] on the focused surface, or hide the cursor if None.
+    /// 
+    /// Cursor shape is used if available, otherwise the image of the Xcursor theme is shown with a cursor surface.
+    pub fn set_cursor(&mut self, cursor : Option<WindowCursor>) {
+        if self.pointer == 0 || self.focus == 0 {
+            return;
+        }
+
+        let mut conn = self.connection.borrow_mut();
+        match cursor {
+            Some(cursor) if self.shape_device != 0 => conn.send(self.shape_device, WP_CURSOR_SHAPE_DEVICE_V1_SET_SHAPE, 
+                vec![WaylandArg::Uint(self.serial), WaylandArg::Uint(cursor_shape(cursor))]),
+            Some(cursor) => {
+                if self.globals.compositor == 0 || self.globals.shm == 0 {
+                    return;
+                }
+                let image = match self.theme.get_or_insert_with(WaylandCursorTheme::from_env).image(cursor) {
+                    Some(image) => image.clone(),
+                    None => return,
+                };
+                let (width, height) = (image.width as i32, image.height as i32);
+
+                if self.cursor_surface == 0 {
+                    let version = conn.version(self.globals.compositor);
+                    self.cursor_surface = conn.new_object(&WL_SURFACE, version);
+                    conn.send(self.globals.compositor, WL_COMPOSITOR_CREATE_SURFACE, vec![WaylandArg::NewId(self.cursor_surface)]);
+                }
+
+                if let Some(buffer) = shm_buffer(&mut conn, self.globals.shm, width, height, &image.pixels) {
+                    conn.send(self.cursor_surface, WL_SURFACE_ATTACH, vec![WaylandArg::Object(buffer), WaylandArg::Int(0), WaylandArg::Int(0)]);
+                    conn.send(self.cursor_surface, WL_SURFACE_DAMAGE, vec![WaylandArg::Int(0), WaylandArg::Int(0), WaylandArg::Int(width), WaylandArg::Int(height)]);
+                    conn.send(self.cursor_surface, WL_SURFACE_COMMIT, vec![]);
+
+                    // Previous image is replaced once committed.
+                    if self.cursor_buffer != 0 {
+                        conn.send(self.cursor_buffer, WL_BUFFER_DESTROY, vec![]);
+                        conn.destroyed(self.cursor_buffer);
+                    }
+                    self.cursor_buffer = buffer;
+                }
+
+                conn.send(self.pointer, WL_POINTER_SET_CURSOR, vec![WaylandArg::Uint(self.serial), WaylandArg::Object(self.cursor_surface), 
+                    WaylandArg::Int(image.hotspot.0 as i32), WaylandArg::Int(image.hotspot.1 as i32)]);
+            },
+            None => conn.send(self.pointer, WL_POINTER_SET_CURSOR, vec![WaylandArg::Uint(self.serial), WaylandArg::Object(0), 
+                WaylandArg::Int(0), WaylandArg::Int(0)]),
+        }
+    }
+
+    /// Request or remove the pointer constraint of a window surface. Only one constraint can exist per surface.
+    pub fn constrain(&mut self, surface : WaylandId, kind : Option<WaylandConstraintKind>) {
+        self.requested.retain(|(s, _)| *s != surface);
+        if let Some(kind) = kind {
+            self.requested.push((surface, kind));
+        }
+        self.apply(surface);
+    }
+
+    /// Lock the pointer on a window surface while its input is grabbed, or give back the constraint requested by its pointer.
+    /// 
+    /// Returns false if the compositor lacks pointer constraints.
+    pub fn grab(&mut self, surface : WaylandId, grabbed : bool) -> bool {
+        if self.globals.constraints == 0 {
+            return false;
+        }
+
+        self.grabbed.retain(|s| *s != surface);
+        if grabbed {
+            self.grabbed.push(surface);
+        }
+        self.apply(surface);
+        true
+    }
+
+    /// Apply the constraint of a surface. Grabbed surfaces are locked, others get the constraint requested.
+    fn apply(&mut self, surface : WaylandId) {
+        let kind = match self.grabbed.contains(&surface) {
+            true => Some(WaylandConstraintKind::Lock),
+            false => self.requested.iter().find(|(s, _)| *s == surface).map(|(_, kind)| *kind),
+        };
+
+        let current = self.constraints.iter().position(|c| c.surface == surface);
+        if current.map(|index| self.constraints[index].kind) == kind {
+            return;
+        }
+
+        let mut conn = self.connection.borrow_mut();
+        if let Some(index) = current {
+            constraint_destroy(&mut conn, &mut self.constraints.remove(index));
+        }
+
+        if let Some(kind) = kind {
+            let object = constraint_object(&mut conn, self.globals.constraints, self.pointer, surface, kind);
+            self.constraints.push(WaylandConstraint { surface, kind, object, active : false });
+        }
+    }
+
+    /// Update the constraint activated or deactivated by the compositor. Persistent constraints are
+    /// activated again by the compositor once the surface has the pointer back.
+    pub fn constraint_active(&mut self, object : WaylandId, active : bool) {
+        if let Some(constraint) = self.constraints.iter_mut().find(|c| c.object == object) {
+            constraint.active = active;
+        }
+    }
+
+    /// Returns true if the pointer lock of surface is currently active.
+    pub fn locked(&self, surface : WaylandId) -> bool {
+        self.constraints.iter().any(|c| c.surface == surface && c.kind == WaylandConstraintKind::Lock && c.active)
+    }
+
+    /// Get the [PointerAcceleration] of an unaccelerated relative motion. Fractions are kept for the next motion.
+    pub fn relative_motion(&mut self, dx : f64, dy : f64) -> PointerAcceleration {
+        let motion = (self.relative_remainder.0 + dx, self.relative_remainder.1 + dy);
+        self.relative_remainder = (motion.0.fract(), motion.1.fract());
+        PointerAcceleration { x : motion.0.trunc() as i32, y : motion.1.trunc() as i32 }
+    }
+
+    /// Take the scroll of the current frame. Wheel steps are preferred over pixels when the device has a wheel.
+    /// 
+    /// Returns None if nothing was scrolled.
+    pub fn take_scroll(&mut self) -> Option<PointerScroll> {
+        let scroll = std::mem::take(&mut self.scroll);
+        let scroll120 = std::mem::take(&mut self.scroll120);
+
+        let axis = |pixels : f64, value120 : i32| match value120 {
+            0 => pixels / WL_SCROLL_PIXELS_PER_STEP,
+            value120 => value120 as f64 / WL_SCROLL_VALUE120_PER_STEP,
+        };
+
+        match (axis(scroll.0, scroll120.0), axis(scroll.1, scroll120.1)) {
+            (x, y) if x == 0.0 && y == 0.0 => None,
+            (x, y) => Some(PointerScroll { x, y }),
+        }
+    }
+}
+
+/// Create the zwp_locked_pointer_v1 or zwp_confined_pointer_v1 of a constraint. 
+/// 
+/// Returns 0 if there is no pointer or the compositor lacks pointer constraints.
+fn constraint_object(conn : &mut WaylandConnection, constraints : WaylandId, pointer : WaylandId, surface : WaylandId, kind : WaylandConstraintKind) -> WaylandId {
+    if constraints == 0 || pointer == 0 {
+        return 0;
+    }
+
+    let (interface, request) = match kind {
+        WaylandConstraintKind::Lock => (&ZWP_LOCKED_POINTER_V1, ZWP_POINTER_CONSTRAINTS_V1_LOCK_POINTER),
+        WaylandConstraintKind::Confine => (&ZWP_CONFINED_POINTER_V1, ZWP_POINTER_CONSTRAINTS_V1_CONFINE_POINTER),
+    };
+    let object = conn.new_object(interface, 1);
+    conn.send(constraints, request, vec![WaylandArg::NewId(object), WaylandArg::Object(surface), WaylandArg::Object(pointer), 
+        WaylandArg::Object(0), WaylandArg::Uint(ZWP_POINTER_CONSTRAINTS_V1_LIFETIME_PERSISTENT)]);
+    object
+}
+
+/// Destroy the object of a constraint, which becomes inactive.
+fn constraint_destroy(conn : &mut WaylandConnection, constraint : &mut WaylandConstraint) {
+    if constraint.object != 0 {
+        let destroy = match constraint.kind {
+            WaylandConstraintKind::Lock => ZWP_LOCKED_POINTER_V1_DESTROY,
+            WaylandConstraintKind::Confine => ZWP_CONFINED_POINTER_V1_DESTROY,
+        };
+        conn.send(constraint.object, destroy, vec![]);
+        conn.destroyed(constraint.object);
+    }
+    constraint.object = 0;
+    constraint.active = false;
+}
+
+/// Get the [PointerButton] of a linux evdev button code, numbered like X11 buttons. Unknown buttons keep their evdev code.
+pub(crate) fn wayland_pointer_button(code : u32) -> PointerButton {
+    match code {
+        BTN_LEFT => 1,
+        BTN_MIDDLE => 2,
+        BTN_RIGHT => 3,
+        BTN_SIDE => 8,
+        BTN_EXTRA => 9,
+        code => code,
+    }
+}
+
+/// Pointer of a Wayland window. State is shared with the window manager since wl_pointer is per seat.
+#[derive(Debug, Clone)]
 pub struct WaylandPointer {
-    
+    /// wl_surface of the window
+    pub(crate) surface : WaylandId,
+
+    /// Cursor shown when pointer is over the window
+    pub(crate) cursor : WindowCursor,
+
+    /// False if cursor is hidden over the window
+    pub(crate) visible : bool,
+
+    /// Mode of the window pointer, locking the pointer in [WindowPointerMode::Acceleration].
+    pub(crate) mode : WindowPointerMode,
+
+    /// True if pointer is confined to the window
+    pub(crate) confined : bool,
+
+    /// Seat pointer state
+    pub(crate) state : Rc<RefCell<WaylandPointerState>>,
+}
+
+impl PartialEq for WaylandPointer {
+    fn eq(&self, other : &Self) -> bool {
+        self.surface == other.surface && Rc::ptr_eq(&self.state, &other.state)
+    }
 }
 
 impl WaylandPointer {
 
+    /// Create the pointer of a window surface sharing the seat pointer state and request the constraint of `properties`.
+    pub(crate) fn new(surface : WaylandId, properties : &WindowPointer, state : Rc<RefCell<WaylandPointerState>>) -> WaylandPointer {
+        let pointer = WaylandPointer { surface, cursor : properties.cursor(), visible : properties.visible(), mode : properties.mode(), 
+            confined : properties.confined(), state };
+        pointer.update_constraint();
+        pointer
+    }
+
+    /// Pointer is locked in [WindowPointerMode::Acceleration]. Motion is translated by the window manager.
     #[inline(always)]
     pub fn set_mode(&mut self, mode : WindowPointerMode) {
-        todo!()
+        self.mode = mode;
+        self.update_constraint();
     }
 
     #[inline(always)]
     pub fn show(&mut self) {
-        todo!()
+        self.visible = true;
+        self.update();
     }
 
     #[inline(always)]
     pub fn hide(&mut self) {
-        todo!()
+        self.visible = false;
+        self.update();
     }
 
     #[inline(always)]
     pub fn confine(&mut self) {
-        todo!()
+        self.confined = true;
+        self.update_constraint();
     }
 
     #[inline(always)]
     pub fn release(&mut self) {
-        todo!()
+        self.confined = false;
+        self.update_constraint();
+    }
+
+    /// True while the compositor locks the pointer.
+    #[inline(always)]
+    pub fn locked(&self) -> bool {
+        self.state.borrow().locked(self.surface)
     }
 
     #[inline(always)]
     pub fn set_cursor(&mut self, cursor : WindowCursor) {
-        todo!()
+        self.cursor = cursor;
+        self.update();
     }
 
+    /// Position of the pointer if over the window surface.
     #[inline(always)]
     pub fn position(&self) -> Option<WindowPointerPosition> {
-        todo!()
+        let state = self.state.borrow();
+        match state.focus == self.surface {
+            true => Some(WindowPointerPosition { x : state.position.0.max(0.0) as u32, y : state.position.1.max(0.0) as u32 }),
+            false => None,
+        }
     }
 
+    /// Compositors don't let clients move the pointer. While locked, position is given as hint where the pointer 
+    /// appears when unlocked.
     #[inline(always)]
     pub fn set_position(&mut self, position : WindowPointerPosition) -> Result<bool, WindowError> {
-        todo!()
+        let state = self.state.borrow();
+        if state.focus != self.surface {
+            return Err(WindowError::WindowpointerOOB);
+        }
+
+        match state.constraints.iter().find(|c| c.surface == self.surface && c.kind == WaylandConstraintKind::Lock && c.active) {
+            Some(constraint) => {
+                // Hint is applied on the next commit of surface.
+                let mut conn = state.connection.borrow_mut();
+                conn.send(constraint.object, ZWP_LOCKED_POINTER_V1_SET_CURSOR_POSITION_HINT, 
+                    vec![WaylandArg::Fixed(position.x as f64), WaylandArg::Fixed(position.y as f64)]);
+                conn.send(self.surface, WL_SURFACE_COMMIT, vec![]);
+                Ok(true)
+            },
+            None => Ok(false),
+        }
+    }
+
+    /// Request the lock in [WindowPointerMode::Acceleration], which supersedes confinement.
+    fn update_constraint(&self) {
+        let kind = match (self.mode, self.confined) {
+            (WindowPointerMode::Acceleration, _) => Some(WaylandConstraintKind::Lock),
+            (WindowPointerMode::Cursor, true) => Some(WaylandConstraintKind::Confine),
+            (WindowPointerMode::Cursor, false) => None,
+        };
+        self.state.borrow_mut().constrain(self.surface, kind);
     }
 
-}
\ No newline at end of file
+    /// Apply the cursor if the pointer is over the window.
+    fn update(&self) {
+        let mut state = self.state.borrow_mut();
+        if state.focus == self.surface {
+            state.set_cursor(self.visible.then_some(self.cursor));
+        }
+    }
+
+}
@@ -0,0 +1,154 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! Popups of sub windows, positioned relative to their parent with a xdg_positioner.
+//! 
+//! Compositors don't let clients position surfaces absolutely. Menus, dropdowns and tooltips are created as 
+//! xdg_popup placed from an anchor rectangle of the parent and moved by the compositor if they would be constrained.
+//! 
+//! Reference(s)
+//! <https://wayland.app/protocols/xdg-shell#xdg_positioner>
+
+use crate::{sub::SubWindowAnchor, WindowPosition, WindowRelativePosition, WindowSize};
+
+use super::{protocol::{XDG_POSITIONER, XDG_POSITIONER_ANCHOR_BOTTOM, XDG_POSITIONER_ANCHOR_BOTTOM_LEFT, XDG_POSITIONER_ANCHOR_BOTTOM_RIGHT, 
+    XDG_POSITIONER_ANCHOR_LEFT, XDG_POSITIONER_ANCHOR_NONE, XDG_POSITIONER_ANCHOR_RIGHT, XDG_POSITIONER_ANCHOR_TOP, XDG_POSITIONER_ANCHOR_TOP_LEFT, 
+    XDG_POSITIONER_ANCHOR_TOP_RIGHT, XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_FLIP_X, XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_FLIP_Y, 
+    XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_RESIZE_X, XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_RESIZE_Y, XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_SLIDE_X, 
+    XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_SLIDE_Y, XDG_POSITIONER_SET_ANCHOR, XDG_POSITIONER_SET_ANCHOR_RECT, XDG_POSITIONER_SET_CONSTRAINT_ADJUSTMENT, 
+    XDG_POSITIONER_SET_GRAVITY, XDG_POSITIONER_SET_OFFSET, XDG_POSITIONER_SET_SIZE, XDG_WM_BASE_CREATE_POSITIONER}, 
+    wlib::{WaylandArg, WaylandConnection, WaylandId}};
+
+/// Popup moves and resizes along both axis to stay visible.
+const WL_POPUP_SLIDE_RESIZE : u32 = XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_SLIDE_X | XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_SLIDE_Y | 
+    XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_RESIZE_X | XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_RESIZE_Y;
+
+/// Popup flips to the other side of its anchor before sliding, like menus do.
+const WL_POPUP_FLIP : u32 = XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_FLIP_X | XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_FLIP_Y;
+
+/// Placement of a popup relative to the window geometry of its parent.
+/// 
+/// Gravity uses the same values as anchors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct WaylandPositioner {
+    /// Size of the popup
+    pub size : (i32, i32),
+
+    /// Rectangle of the parent the popup is anchored to as (x, y, width, height)
+    pub anchor_rect : (i32, i32, i32, i32),
+
+    /// Edge or corner of the anchor rectangle
+    pub anchor : u32,
+
+    /// Direction the popup extends from the anchor
+    pub gravity : u32,
+
+    /// Offset of the popup from the anchor
+    pub offset : (i32, i32),
+
+    /// Adjustments allowed when the popup would be constrained
+    pub adjustment : u32,
+}
+
+impl WaylandPositioner {
+
+    /// Derive the placement of a sub window from its position and anchors.
+    /// 
+    /// [SubWindowAnchor] dock the popup corners to the parent corners, the popup being resized between opposite anchors. 
+    /// Otherwise [WindowRelativePosition::Parent] places the popup top-left corner at a point of the parent content. 
+    /// `title` is the height of the parent client-side title bar, part of its window geometry.
+    /// 
+    /// Returns None if the sub window isn't placed relative to its parent and is a toplevel.
+    pub fn from_sub(position : &WindowRelativePosition, anchors : &SubWindowAnchor, size : WindowSize, parent : WindowSize, 
+        title : i32) -> Option<WaylandPositioner> {
+
+        let size = (size.width.max(1) as i32, size.height.max(1) as i32);
+        let parent = (parent.width as i32, parent.height as i32);
+
+        let left = anchors.top_left.or(anchors.bottom_left);
+        let right = anchors.top_right.or(anchors.bottom_right);
+        let top = anchors.top_left.or(anchors.top_right);
+        let bottom = anchors.bottom_left.or(anchors.bottom_right);
+
+        if left.is_some() || right.is_some() {
+            // Each axis is docked to one edge, the left and top ones when opposite anchors stretch the popup.
+            let (horizontal, x, width) = wayland_dock(left, right, parent.0, size.0, |p| p.x);
+            let (vertical, y, height) = wayland_dock(top, bottom, parent.1, size.1, |p| p.y);
+            let anchor = wayland_anchor(horizontal, vertical);
+
+            return Some(WaylandPositioner { size : (width, height), anchor_rect : (0, title, parent.0.max(1), parent.1.max(1)), anchor, 
+                gravity : wayland_anchor(-horizontal, -vertical), offset : (x, y), adjustment : WL_POPUP_SLIDE_RESIZE });
+        }
+
+        match position {
+            WindowRelativePosition::Parent(position) => Some(WaylandPositioner { size, anchor_rect : (position.x, position.y + title, 1, 1), 
+                anchor : XDG_POSITIONER_ANCHOR_TOP_LEFT, gravity : XDG_POSITIONER_ANCHOR_BOTTOM_RIGHT, offset : (0, 0), 
+                adjustment : WL_POPUP_FLIP | WL_POPUP_SLIDE_RESIZE }),
+            _ => None,
+        }
+    }
+
+    /// Create the xdg_positioner, to destroy once the popup is created.
+    pub fn create(&self, conn : &mut WaylandConnection, wm_base : WaylandId) -> WaylandId {
+        let positioner = conn.new_object(&XDG_POSITIONER, conn.version(wm_base));
+        conn.send(wm_base, XDG_WM_BASE_CREATE_POSITIONER, vec![WaylandArg::NewId(positioner)]);
+        conn.send(positioner, XDG_POSITIONER_SET_SIZE, vec![WaylandArg::Int(self.size.0), WaylandArg::Int(self.size.1)]);
+        conn.send(positioner, XDG_POSITIONER_SET_ANCHOR_RECT, vec![WaylandArg::Int(self.anchor_rect.0), WaylandArg::Int(self.anchor_rect.1), 
+            WaylandArg::Int(self.anchor_rect.2), WaylandArg::Int(self.anchor_rect.3)]);
+        conn.send(positioner, XDG_POSITIONER_SET_ANCHOR, vec![WaylandArg::Uint(self.anchor)]);
+        conn.send(positioner, XDG_POSITIONER_SET_GRAVITY, vec![WaylandArg::Uint(self.gravity)]);
+        conn.send(positioner, XDG_POSITIONER_SET_CONSTRAINT_ADJUSTMENT, vec![WaylandArg::Uint(self.adjustment)]);
+        conn.send(positioner, XDG_POSITIONER_SET_OFFSET, vec![WaylandArg::Int(self.offset.0), WaylandArg::Int(self.offset.1)]);
+        positioner
+    }
+}
+
+/// Dock an axis to the first (left or top) or second (right or bottom) edge of a parent of `length`.
+/// 
+/// Returns the edge as -1, 0 or 1, the offset from it and the size of the popup on this axis.
+fn wayland_dock<F : Fn(WindowPosition) -> i32>(first : Option<WindowPosition>, second : Option<WindowPosition>, length : i32, size : i32, 
+    axis : F) -> (i32, i32, i32) {
+    match (first.map(&axis), second.map(&axis)) {
+        (Some(first), Some(second)) => (-1, first, (length + second - first).max(1)),
+        (Some(first), None) => (-1, first, size),
+        (None, Some(second)) => (1, second, size),
+        (None, None) => (0, 0, size),
+    }
+}
+
+/// Anchor value from horizontal and vertical edges as -1, 0 or 1.
+fn wayland_anchor(horizontal : i32, vertical : i32) -> u32 {
+    match (horizontal, vertical) {
+        (-1, -1) => XDG_POSITIONER_ANCHOR_TOP_LEFT,
+        (1, -1) => XDG_POSITIONER_ANCHOR_TOP_RIGHT,
+        (-1, 1) => XDG_POSITIONER_ANCHOR_BOTTOM_LEFT,
+        (1, 1) => XDG_POSITIONER_ANCHOR_BOTTOM_RIGHT,
+        (-1, _) => XDG_POSITIONER_ANCHOR_LEFT,
+        (1, _) => XDG_POSITIONER_ANCHOR_RIGHT,
+        (_, -1) => XDG_POSITIONER_ANCHOR_TOP,
+        (_, 1) => XDG_POSITIONER_ANCHOR_BOTTOM,
+        _ => XDG_POSITIONER_ANCHOR_NONE,
+    }
+}
@@ -0,0 +1,572 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Wayland interfaces descriptions used to encode and decode messages.
+//! 
+//! Signatures use the same format as libwayland `wl_message` : `i` int, `u` uint, `f` fixed, `s` string, `o` object, 
+//! `n` new_id, `a` array and `h` file descriptor. A `?` prefix make the next string or object nullable.
+//! 
+//! Reference(s)
+//! <https://wayland.freedesktop.org/docs/html/ch04.html#sect-Protocol-Wire-Format>
+
+/// Description of a request or event of a [WaylandInterface].
+#[derive(Debug)]
+pub(crate) struct WaylandMessageDesc {
+    /// Name of the message
+    pub name : &'static str,
+
+    /// Signature of the message arguments
+    pub signature : &'static str,
+
+    /// Interface of the object created by the message new_id argument, if any. 
+    /// None for `wl_registry.bind` since interface is given as argument.
+    pub new_id : Option<&'static WaylandInterface>,
+}
+
+/// Description of a Wayland interface with its requests and events.
+#[derive(Debug)]
+pub(crate) struct WaylandInterface {
+    /// Name of the interface
+    pub name : &'static str,
+
+    /// Highest version of the interface supported by nswindow
+    pub version : u32,
+
+    /// Requests sent by client
+    pub requests : &'static [WaylandMessageDesc],
+
+    /// Events sent by server
+    pub events : &'static [WaylandMessageDesc],
+}
+
+impl PartialEq for WaylandInterface {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+/// Shortcut to create a [WaylandMessageDesc].
+macro_rules! msg {
+    ($name:literal, $signature:literal) => {
+        WaylandMessageDesc { name : $name, signature : $signature, new_id : None }
+    };
+    ($name:literal, $signature:literal, $new_id:ident) => {
+        WaylandMessageDesc { name : $name, signature : $signature, new_id : Some(&$new_id) }
+    };
+}
+
+/// Get a [WaylandInterface] description from its name.
+/// 
+/// Returns None if interface isn't supported by nswindow.
+pub(crate) fn interface_by_name(name : &str) -> Option<&'static WaylandInterface> {
+    INTERFACES.iter().find(|i| i.name == name).copied()
+}
+
+/// All interfaces supported by nswindow.
+static INTERFACES : &[&WaylandInterface] = &[&WL_DISPLAY, &WL_REGISTRY, &WL_CALLBACK, &WL_COMPOSITOR, &WL_SURFACE, &WL_REGION, 
+    &WL_OUTPUT, &XDG_WM_BASE, &XDG_POSITIONER, &XDG_SURFACE, &XDG_TOPLEVEL, &XDG_POPUP, &ZXDG_OUTPUT_MANAGER_V1, &ZXDG_OUTPUT_V1, &WL_SUBCOMPOSITOR, &WL_SUBSURFACE, &WL_SHM, &WL_SHM_POOL, &WL_BUFFER, &WL_SEAT, &WL_POINTER, &WL_KEYBOARD, 
+    &ZXDG_DECORATION_MANAGER_V1, &ZXDG_TOPLEVEL_DECORATION_V1, &WP_CURSOR_SHAPE_MANAGER_V1, &WP_CURSOR_SHAPE_DEVICE_V1, 
+    &ZWP_POINTER_CONSTRAINTS_V1, &ZWP_LOCKED_POINTER_V1, &ZWP_CONFINED_POINTER_V1, &ZWP_RELATIVE_POINTER_MANAGER_V1, &ZWP_RELATIVE_POINTER_V1, 
+    &WP_FRACTIONAL_SCALE_MANAGER_V1, &WP_FRACTIONAL_SCALE_V1, &WP_VIEWPORTER, &WP_VIEWPORT, 
+    &ZWP_TEXT_INPUT_MANAGER_V3, &ZWP_TEXT_INPUT_V3, &ZWP_IDLE_INHIBIT_MANAGER_V1, &ZWP_IDLE_INHIBITOR_V1, &XDG_ACTIVATION_V1, &XDG_ACTIVATION_TOKEN_V1, 
+    &ZWP_KEYBOARD_SHORTCUTS_INHIBIT_MANAGER_V1, &ZWP_KEYBOARD_SHORTCUTS_INHIBITOR_V1];
+
+/***********
+* CORE     *
+***********/
+
+/// Core global object. Always have id 1.
+pub(crate) static WL_DISPLAY : WaylandInterface = WaylandInterface { name : "wl_display", version : 1, 
+    requests : &[msg!("sync", "n", WL_CALLBACK), msg!("get_registry", "n", WL_REGISTRY)], 
+    events : &[msg!("error", "ous"), msg!("delete_id", "u")] };
+pub(crate) const WL_DISPLAY_ID : u32 = 1;
+pub(crate) const WL_DISPLAY_SYNC : u16 = 0;
+pub(crate) const WL_DISPLAY_GET_REGISTRY : u16 = 1;
+pub(crate) const WL_DISPLAY_ERROR : u16 = 0;
+pub(crate) const WL_DISPLAY_DELETE_ID : u16 = 1;
+
+/// Global registry object
+pub(crate) static WL_REGISTRY : WaylandInterface = WaylandInterface { name : "wl_registry", version : 1, 
+    requests : &[msg!("bind", "usun")], 
+    events : &[msg!("global", "usu"), msg!("global_remove", "u")] };
+pub(crate) const WL_REGISTRY_BIND : u16 = 0;
+pub(crate) const WL_REGISTRY_GLOBAL : u16 = 0;
+pub(crate) const WL_REGISTRY_GLOBAL_REMOVE : u16 = 1;
+
+/// Callback object
+pub(crate) static WL_CALLBACK : WaylandInterface = WaylandInterface { name : "wl_callback", version : 1, 
+    requests : &[], 
+    events : &[msg!("done", "u")] };
+pub(crate) const WL_CALLBACK_DONE : u16 = 0;
+
+
+/// Compositor global creating surfaces and regions
+pub(crate) static WL_COMPOSITOR : WaylandInterface = WaylandInterface { name : "wl_compositor", version : 6, 
+    requests : &[msg!("create_surface", "n", WL_SURFACE), msg!("create_region", "n", WL_REGION)], 
+    events : &[] };
+pub(crate) const WL_COMPOSITOR_CREATE_SURFACE : u16 = 0;
+
+/// Rectangular area displayed by the compositor
+pub(crate) static WL_SURFACE : WaylandInterface = WaylandInterface { name : "wl_surface", version : 6, 
+    requests : &[msg!("destroy", ""), msg!("attach", "?oii"), msg!("damage", "iiii"), msg!("frame", "n", WL_CALLBACK), 
+        msg!("set_opaque_region", "?o"), msg!("set_input_region", "?o"), msg!("commit", ""), msg!("set_buffer_transform", "i"), 
+        msg!("set_buffer_scale", "i"), msg!("damage_buffer", "iiii"), msg!("offset", "ii")], 
+    events : &[msg!("enter", "o"), msg!("leave", "o"), msg!("preferred_buffer_scale", "i"), msg!("preferred_buffer_transform", "u")] };
+pub(crate) const WL_SURFACE_DESTROY : u16 = 0;
+pub(crate) const WL_SURFACE_ATTACH : u16 = 1;
+pub(crate) const WL_SURFACE_DAMAGE : u16 = 2;
+pub(crate) const WL_SURFACE_FRAME : u16 = 3;
+pub(crate) const WL_SURFACE_COMMIT : u16 = 6;
+pub(crate) const WL_SURFACE_SET_BUFFER_SCALE : u16 = 8;
+pub(crate) const WL_SURFACE_DAMAGE_BUFFER : u16 = 9;
+pub(crate) const WL_SURFACE_ENTER : u16 = 0;
+pub(crate) const WL_SURFACE_LEAVE : u16 = 1;
+pub(crate) const WL_SURFACE_PREFERRED_BUFFER_SCALE : u16 = 2;
+
+/// Region used for opaque and input areas of surfaces
+pub(crate) static WL_REGION : WaylandInterface = WaylandInterface { name : "wl_region", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("add", "iiii"), msg!("subtract", "iiii")], 
+    events : &[] };
+
+/// Compositor output, usually a monitor
+pub(crate) static WL_OUTPUT : WaylandInterface = WaylandInterface { name : "wl_output", version : 4, 
+    requests : &[msg!("release", "")], 
+    events : &[msg!("geometry", "iiiiissi"), msg!("mode", "uiii"), msg!("done", ""), msg!("scale", "i"), msg!("name", "s"), msg!("description", "s")] };
+pub(crate) const WL_OUTPUT_RELEASE : u16 = 0;
+pub(crate) const WL_OUTPUT_GEOMETRY : u16 = 0;
+pub(crate) const WL_OUTPUT_MODE : u16 = 1;
+pub(crate) const WL_OUTPUT_DONE : u16 = 2;
+pub(crate) const WL_OUTPUT_SCALE : u16 = 3;
+pub(crate) const WL_OUTPUT_NAME : u16 = 4;
+pub(crate) const WL_OUTPUT_DESCRIPTION : u16 = 5;
+pub(crate) const WL_OUTPUT_MODE_CURRENT : u32 = 1;
+
+/// Global creating sub-surfaces
+pub(crate) static WL_SUBCOMPOSITOR : WaylandInterface = WaylandInterface { name : "wl_subcompositor", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("get_subsurface", "noo", WL_SUBSURFACE)], 
+    events : &[] };
+pub(crate) const WL_SUBCOMPOSITOR_GET_SUBSURFACE : u16 = 1;
+
+/// Surface positioned relative to a parent surface
+pub(crate) static WL_SUBSURFACE : WaylandInterface = WaylandInterface { name : "wl_subsurface", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("set_position", "ii"), msg!("place_above", "o"), msg!("place_below", "o"), msg!("set_sync", ""), msg!("set_desync", "")], 
+    events : &[] };
+pub(crate) const WL_SUBSURFACE_DESTROY : u16 = 0;
+pub(crate) const WL_SUBSURFACE_SET_POSITION : u16 = 1;
+pub(crate) const WL_SUBSURFACE_PLACE_BELOW : u16 = 3;
+pub(crate) const WL_SUBSURFACE_SET_DESYNC : u16 = 5;
+
+/// Shared memory buffers global
+pub(crate) static WL_SHM : WaylandInterface = WaylandInterface { name : "wl_shm", version : 1, 
+    requests : &[msg!("create_pool", "nhi", WL_SHM_POOL)], 
+    events : &[msg!("format", "u")] };
+pub(crate) const WL_SHM_CREATE_POOL : u16 = 0;
+pub(crate) const WL_SHM_FORMAT_ARGB8888 : u32 = 0;
+pub(crate) const WL_SHM_FORMAT_XRGB8888 : u32 = 1;
+
+/// Shared memory pool buffers are created from
+pub(crate) static WL_SHM_POOL : WaylandInterface = WaylandInterface { name : "wl_shm_pool", version : 1, 
+    requests : &[msg!("create_buffer", "niiiiu", WL_BUFFER), msg!("destroy", ""), msg!("resize", "i")], 
+    events : &[] };
+pub(crate) const WL_SHM_POOL_CREATE_BUFFER : u16 = 0;
+pub(crate) const WL_SHM_POOL_DESTROY : u16 = 1;
+
+/// Content of a surface
+pub(crate) static WL_BUFFER : WaylandInterface = WaylandInterface { name : "wl_buffer", version : 1, 
+    requests : &[msg!("destroy", "")], 
+    events : &[msg!("release", "")] };
+pub(crate) const WL_BUFFER_DESTROY : u16 = 0;
+pub(crate) const WL_BUFFER_RELEASE : u16 = 0;
+
+/// Group of input devices
+pub(crate) static WL_SEAT : WaylandInterface = WaylandInterface { name : "wl_seat", version : 9, 
+    requests : &[msg!("get_pointer", "n", WL_POINTER), msg!("get_keyboard", "n", WL_KEYBOARD), msg!("get_touch", "n"), msg!("release", "")], 
+    events : &[msg!("capabilities", "u"), msg!("name", "s")] };
+pub(crate) const WL_SEAT_GET_POINTER : u16 = 0;
+pub(crate) const WL_SEAT_GET_KEYBOARD : u16 = 1;
+pub(crate) const WL_SEAT_CAPABILITIES : u16 = 0;
+pub(crate) const WL_SEAT_NAME : u16 = 1;
+pub(crate) const WL_SEAT_CAPABILITY_POINTER : u32 = 1;
+pub(crate) const WL_SEAT_CAPABILITY_KEYBOARD : u32 = 2;
+
+/// Pointer of a seat
+pub(crate) static WL_POINTER : WaylandInterface = WaylandInterface { name : "wl_pointer", version : 9, 
+    requests : &[msg!("set_cursor", "u?oii"), msg!("release", "")], 
+    events : &[msg!("enter", "uoff"), msg!("leave", "uo"), msg!("motion", "uff"), msg!("button", "uuuu"), msg!("axis", "uuf"), msg!("frame", ""), 
+        msg!("axis_source", "u"), msg!("axis_stop", "uu"), msg!("axis_discrete", "ui"), msg!("axis_value120", "ui"), msg!("axis_relative_direction", "uu")] };
+pub(crate) const WL_POINTER_SET_CURSOR : u16 = 0;
+pub(crate) const WL_POINTER_ENTER : u16 = 0;
+pub(crate) const WL_POINTER_LEAVE : u16 = 1;
+pub(crate) const WL_POINTER_MOTION : u16 = 2;
+pub(crate) const WL_POINTER_BUTTON : u16 = 3;
+pub(crate) const WL_POINTER_AXIS : u16 = 4;
+pub(crate) const WL_POINTER_FRAME : u16 = 5;
+pub(crate) const WL_POINTER_AXIS_DISCRETE : u16 = 8;
+pub(crate) const WL_POINTER_AXIS_VALUE120 : u16 = 9;
+pub(crate) const WL_POINTER_AXIS_VERTICAL_SCROLL : u32 = 0;
+pub(crate) const WL_POINTER_AXIS_HORIZONTAL_SCROLL : u32 = 1;
+pub(crate) const WL_POINTER_RELEASE : u16 = 1;
+pub(crate) const WL_POINTER_BUTTON_STATE_PRESSED : u32 = 1;
+
+/// Keyboard of a seat
+pub(crate) static WL_KEYBOARD : WaylandInterface = WaylandInterface { name : "wl_keyboard", version : 9, 
+    requests : &[msg!("release", "")], 
+    events : &[msg!("keymap", "uhu"), msg!("enter", "uoa"), msg!("leave", "uo"), msg!("key", "uuuu"), msg!("modifiers", "uuuuu"), 
+        msg!("repeat_info", "ii")] };
+pub(crate) const WL_KEYBOARD_RELEASE : u16 = 0;
+pub(crate) const WL_KEYBOARD_KEYMAP : u16 = 0;
+pub(crate) const WL_KEYBOARD_ENTER : u16 = 1;
+pub(crate) const WL_KEYBOARD_LEAVE : u16 = 2;
+pub(crate) const WL_KEYBOARD_KEY : u16 = 3;
+pub(crate) const WL_KEYBOARD_MODIFIERS : u16 = 4;
+pub(crate) const WL_KEYBOARD_REPEAT_INFO : u16 = 5;
+pub(crate) const WL_KEYBOARD_KEYMAP_FORMAT_XKB_V1 : u32 = 1;
+pub(crate) const WL_KEYBOARD_KEY_STATE_PRESSED : u32 = 1;
+
+/// Linux evdev codes of mouse buttons
+pub(crate) const BTN_LEFT : u32 = 0x110;
+pub(crate) const BTN_RIGHT : u32 = 0x111;
+pub(crate) const BTN_MIDDLE : u32 = 0x112;
+pub(crate) const BTN_SIDE : u32 = 0x113;
+pub(crate) const BTN_EXTRA : u32 = 0x114;
+
+/************
+* XDG SHELL *
+************/
+
+/// Desktop windows global
+pub(crate) static XDG_WM_BASE : WaylandInterface = WaylandInterface { name : "xdg_wm_base", version : 5, 
+    requests : &[msg!("destroy", ""), msg!("create_positioner", "n", XDG_POSITIONER), msg!("get_xdg_surface", "no", XDG_SURFACE), msg!("pong", "u")], 
+    events : &[msg!("ping", "u")] };
+pub(crate) const XDG_WM_BASE_CREATE_POSITIONER : u16 = 1;
+pub(crate) const XDG_WM_BASE_GET_XDG_SURFACE : u16 = 2;
+pub(crate) const XDG_WM_BASE_PONG : u16 = 3;
+pub(crate) const XDG_WM_BASE_PING : u16 = 0;
+
+/// Placement rules of popups
+pub(crate) static XDG_POSITIONER : WaylandInterface = WaylandInterface { name : "xdg_positioner", version : 5, 
+    requests : &[msg!("destroy", ""), msg!("set_size", "ii"), msg!("set_anchor_rect", "iiii"), msg!("set_anchor", "u"), msg!("set_gravity", "u"), 
+        msg!("set_constraint_adjustment", "u"), msg!("set_offset", "ii"), msg!("set_reactive", ""), msg!("set_parent_size", "ii"), msg!("set_parent_configure", "u")], 
+    events : &[] };
+pub(crate) const XDG_POSITIONER_DESTROY : u16 = 0;
+pub(crate) const XDG_POSITIONER_SET_SIZE : u16 = 1;
+pub(crate) const XDG_POSITIONER_SET_ANCHOR_RECT : u16 = 2;
+pub(crate) const XDG_POSITIONER_SET_ANCHOR : u16 = 3;
+pub(crate) const XDG_POSITIONER_SET_GRAVITY : u16 = 4;
+pub(crate) const XDG_POSITIONER_SET_CONSTRAINT_ADJUSTMENT : u16 = 5;
+pub(crate) const XDG_POSITIONER_SET_OFFSET : u16 = 6;
+pub(crate) const XDG_POSITIONER_ANCHOR_NONE : u32 = 0;
+pub(crate) const XDG_POSITIONER_ANCHOR_TOP : u32 = 1;
+pub(crate) const XDG_POSITIONER_ANCHOR_BOTTOM : u32 = 2;
+pub(crate) const XDG_POSITIONER_ANCHOR_LEFT : u32 = 3;
+pub(crate) const XDG_POSITIONER_ANCHOR_RIGHT : u32 = 4;
+pub(crate) const XDG_POSITIONER_ANCHOR_TOP_LEFT : u32 = 5;
+pub(crate) const XDG_POSITIONER_ANCHOR_BOTTOM_LEFT : u32 = 6;
+pub(crate) const XDG_POSITIONER_ANCHOR_TOP_RIGHT : u32 = 7;
+pub(crate) const XDG_POSITIONER_ANCHOR_BOTTOM_RIGHT : u32 = 8;
+pub(crate) const XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_SLIDE_X : u32 = 1;
+pub(crate) const XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_SLIDE_Y : u32 = 2;
+pub(crate) const XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_FLIP_X : u32 = 4;
+pub(crate) const XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_FLIP_Y : u32 = 8;
+pub(crate) const XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_RESIZE_X : u32 = 16;
+pub(crate) const XDG_POSITIONER_CONSTRAINT_ADJUSTMENT_RESIZE_Y : u32 = 32;
+
+/// Desktop role of a wl_surface
+pub(crate) static XDG_SURFACE : WaylandInterface = WaylandInterface { name : "xdg_surface", version : 5, 
+    requests : &[msg!("destroy", ""), msg!("get_toplevel", "n", XDG_TOPLEVEL), msg!("get_popup", "n?oo", XDG_POPUP), msg!("set_window_geometry", "iiii"), msg!("ack_configure", "u")], 
+    events : &[msg!("configure", "u")] };
+pub(crate) const XDG_SURFACE_DESTROY : u16 = 0;
+pub(crate) const XDG_SURFACE_GET_TOPLEVEL : u16 = 1;
+pub(crate) const XDG_SURFACE_GET_POPUP : u16 = 2;
+pub(crate) const XDG_SURFACE_SET_WINDOW_GEOMETRY : u16 = 3;
+pub(crate) const XDG_SURFACE_ACK_CONFIGURE : u16 = 4;
+pub(crate) const XDG_SURFACE_CONFIGURE : u16 = 0;
+
+/// Short-lived surface positioned relative to its parent, like menus and tooltips
+pub(crate) static XDG_POPUP : WaylandInterface = WaylandInterface { name : "xdg_popup", version : 5, 
+    requests : &[msg!("destroy", ""), msg!("grab", "ou"), msg!("reposition", "ou")], 
+    events : &[msg!("configure", "iiii"), msg!("popup_done", ""), msg!("repositioned", "u")] };
+pub(crate) const XDG_POPUP_DESTROY : u16 = 0;
+pub(crate) const XDG_POPUP_GRAB : u16 = 1;
+pub(crate) const XDG_POPUP_CONFIGURE : u16 = 0;
+pub(crate) const XDG_POPUP_POPUP_DONE : u16 = 1;
+
+/// Top level desktop window
+pub(crate) static XDG_TOPLEVEL : WaylandInterface = WaylandInterface { name : "xdg_toplevel", version : 5, 
+    requests : &[msg!("destroy", ""), msg!("set_parent", "?o"), msg!("set_title", "s"), msg!("set_app_id", "s"), msg!("show_window_menu", "ouii"), 
+        msg!("move", "ou"), msg!("resize", "ouu"), msg!("set_max_size", "ii"), msg!("set_min_size", "ii"), msg!("set_maximized", ""), 
+        msg!("unset_maximized", ""), msg!("set_fullscreen", "?o"), msg!("unset_fullscreen", ""), msg!("set_minimized", "")], 
+    events : &[msg!("configure", "iia"), msg!("close", ""), msg!("configure_bounds", "ii"), msg!("wm_capabilities", "a")] };
+pub(crate) const XDG_TOPLEVEL_DESTROY : u16 = 0;
+pub(crate) const XDG_TOPLEVEL_SET_PARENT : u16 = 1;
+pub(crate) const XDG_TOPLEVEL_SET_TITLE : u16 = 2;
+pub(crate) const XDG_TOPLEVEL_MOVE : u16 = 5;
+pub(crate) const XDG_TOPLEVEL_RESIZE : u16 = 6;
+pub(crate) const XDG_TOPLEVEL_SET_MAX_SIZE : u16 = 7;
+pub(crate) const XDG_TOPLEVEL_SET_MIN_SIZE : u16 = 8;
+pub(crate) const XDG_TOPLEVEL_SET_MAXIMIZED : u16 = 9;
+pub(crate) const XDG_TOPLEVEL_UNSET_MAXIMIZED : u16 = 10;
+pub(crate) const XDG_TOPLEVEL_SET_FULLSCREEN : u16 = 11;
+pub(crate) const XDG_TOPLEVEL_UNSET_FULLSCREEN : u16 = 12;
+pub(crate) const XDG_TOPLEVEL_SET_MINIMIZED : u16 = 13;
+pub(crate) const XDG_TOPLEVEL_CONFIGURE : u16 = 0;
+pub(crate) const XDG_TOPLEVEL_CLOSE : u16 = 1;
+pub(crate) const XDG_TOPLEVEL_STATE_MAXIMIZED : u32 = 1;
+pub(crate) const XDG_TOPLEVEL_STATE_FULLSCREEN : u32 = 2;
+pub(crate) const XDG_TOPLEVEL_STATE_ACTIVATED : u32 = 4;
+pub(crate) const XDG_TOPLEVEL_RESIZE_EDGE_TOP : u32 = 1;
+pub(crate) const XDG_TOPLEVEL_RESIZE_EDGE_BOTTOM : u32 = 2;
+pub(crate) const XDG_TOPLEVEL_RESIZE_EDGE_LEFT : u32 = 4;
+pub(crate) const XDG_TOPLEVEL_RESIZE_EDGE_RIGHT : u32 = 8;
+
+/*************
+* XDG OUTPUT *
+*************/
+
+/// Global giving desktop logical informations of outputs
+pub(crate) static ZXDG_OUTPUT_MANAGER_V1 : WaylandInterface = WaylandInterface { name : "zxdg_output_manager_v1", version : 3, 
+    requests : &[msg!("destroy", ""), msg!("get_xdg_output", "no", ZXDG_OUTPUT_V1)], 
+    events : &[] };
+pub(crate) const ZXDG_OUTPUT_MANAGER_V1_GET_XDG_OUTPUT : u16 = 1;
+
+/// Logical position and size of an output on the desktop
+pub(crate) static ZXDG_OUTPUT_V1 : WaylandInterface = WaylandInterface { name : "zxdg_output_v1", version : 3, 
+    requests : &[msg!("destroy", "")], 
+    events : &[msg!("logical_position", "ii"), msg!("logical_size", "ii"), msg!("done", ""), msg!("name", "s"), msg!("description", "s")] };
+pub(crate) const ZXDG_OUTPUT_V1_DESTROY : u16 = 0;
+pub(crate) const ZXDG_OUTPUT_V1_LOGICAL_POSITION : u16 = 0;
+pub(crate) const ZXDG_OUTPUT_V1_LOGICAL_SIZE : u16 = 1;
+pub(crate) const ZXDG_OUTPUT_V1_DONE : u16 = 2;
+pub(crate) const ZXDG_OUTPUT_V1_NAME : u16 = 3;
+pub(crate) const ZXDG_OUTPUT_V1_DESCRIPTION : u16 = 4;
+
+/*****************
+* XDG DECORATION *
+*****************/
+
+/// Global negotiating who draws toplevels decorations
+pub(crate) static ZXDG_DECORATION_MANAGER_V1 : WaylandInterface = WaylandInterface { name : "zxdg_decoration_manager_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("get_toplevel_decoration", "no", ZXDG_TOPLEVEL_DECORATION_V1)], 
+    events : &[] };
+pub(crate) const ZXDG_DECORATION_MANAGER_V1_GET_TOPLEVEL_DECORATION : u16 = 1;
+
+/// Decoration mode of a toplevel
+pub(crate) static ZXDG_TOPLEVEL_DECORATION_V1 : WaylandInterface = WaylandInterface { name : "zxdg_toplevel_decoration_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("set_mode", "u"), msg!("unset_mode", "")], 
+    events : &[msg!("configure", "u")] };
+pub(crate) const ZXDG_TOPLEVEL_DECORATION_V1_DESTROY : u16 = 0;
+pub(crate) const ZXDG_TOPLEVEL_DECORATION_V1_SET_MODE : u16 = 1;
+pub(crate) const ZXDG_TOPLEVEL_DECORATION_V1_CONFIGURE : u16 = 0;
+pub(crate) const ZXDG_TOPLEVEL_DECORATION_V1_MODE_CLIENT_SIDE : u32 = 1;
+pub(crate) const ZXDG_TOPLEVEL_DECORATION_V1_MODE_SERVER_SIDE : u32 = 2;
+
+/***************
+* CURSOR SHAPE *
+***************/
+
+/// Global setting pointer cursors by shape name, drawn by the compositor
+pub(crate) static WP_CURSOR_SHAPE_MANAGER_V1 : WaylandInterface = WaylandInterface { name : "wp_cursor_shape_manager_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("get_pointer", "no", WP_CURSOR_SHAPE_DEVICE_V1), msg!("get_tablet_tool_v2", "no", WP_CURSOR_SHAPE_DEVICE_V1)], 
+    events : &[] };
+pub(crate) const WP_CURSOR_SHAPE_MANAGER_V1_GET_POINTER : u16 = 1;
+
+/// Cursor shape of a pointer
+pub(crate) static WP_CURSOR_SHAPE_DEVICE_V1 : WaylandInterface = WaylandInterface { name : "wp_cursor_shape_device_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("set_shape", "uu")], 
+    events : &[] };
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_DESTROY : u16 = 0;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SET_SHAPE : u16 = 1;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_DEFAULT : u32 = 1;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_POINTER : u32 = 4;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_PROGRESS : u32 = 5;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_WAIT : u32 = 6;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_CROSSHAIR : u32 = 8;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_TEXT : u32 = 9;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_MOVE : u32 = 13;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NOT_ALLOWED : u32 = 15;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_EW_RESIZE : u32 = 26;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NS_RESIZE : u32 = 27;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NESW_RESIZE : u32 = 28;
+pub(crate) const WP_CURSOR_SHAPE_DEVICE_V1_SHAPE_NWSE_RESIZE : u32 = 29;
+
+/**********************
+* POINTER CONSTRAINTS *
+**********************/
+
+/// Global locking or confining the pointer over a surface
+pub(crate) static ZWP_POINTER_CONSTRAINTS_V1 : WaylandInterface = WaylandInterface { name : "zwp_pointer_constraints_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("lock_pointer", "noo?ou", ZWP_LOCKED_POINTER_V1), msg!("confine_pointer", "noo?ou", ZWP_CONFINED_POINTER_V1)], 
+    events : &[] };
+pub(crate) const ZWP_POINTER_CONSTRAINTS_V1_LOCK_POINTER : u16 = 1;
+pub(crate) const ZWP_POINTER_CONSTRAINTS_V1_CONFINE_POINTER : u16 = 2;
+pub(crate) const ZWP_POINTER_CONSTRAINTS_V1_LIFETIME_PERSISTENT : u32 = 2;
+
+/// Pointer lock, pointer doesn't move while active
+pub(crate) static ZWP_LOCKED_POINTER_V1 : WaylandInterface = WaylandInterface { name : "zwp_locked_pointer_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("set_cursor_position_hint", "ff"), msg!("set_region", "?o")], 
+    events : &[msg!("locked", ""), msg!("unlocked", "")] };
+pub(crate) const ZWP_LOCKED_POINTER_V1_DESTROY : u16 = 0;
+pub(crate) const ZWP_LOCKED_POINTER_V1_SET_CURSOR_POSITION_HINT : u16 = 1;
+pub(crate) const ZWP_LOCKED_POINTER_V1_LOCKED : u16 = 0;
+pub(crate) const ZWP_LOCKED_POINTER_V1_UNLOCKED : u16 = 1;
+
+/// Pointer confinement, pointer can't leave the surface while active
+pub(crate) static ZWP_CONFINED_POINTER_V1 : WaylandInterface = WaylandInterface { name : "zwp_confined_pointer_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("set_region", "?o")], 
+    events : &[msg!("confined", ""), msg!("unconfined", "")] };
+pub(crate) const ZWP_CONFINED_POINTER_V1_DESTROY : u16 = 0;
+pub(crate) const ZWP_CONFINED_POINTER_V1_CONFINED : u16 = 0;
+pub(crate) const ZWP_CONFINED_POINTER_V1_UNCONFINED : u16 = 1;
+
+/*******************
+* RELATIVE POINTER *
+*******************/
+
+/// Global giving relative motion of pointers
+pub(crate) static ZWP_RELATIVE_POINTER_MANAGER_V1 : WaylandInterface = WaylandInterface { name : "zwp_relative_pointer_manager_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("get_relative_pointer", "no", ZWP_RELATIVE_POINTER_V1)], 
+    events : &[] };
+pub(crate) const ZWP_RELATIVE_POINTER_MANAGER_V1_GET_RELATIVE_POINTER : u16 = 1;
+
+/// Relative motion of a wl_pointer, sent even when pointer is locked
+pub(crate) static ZWP_RELATIVE_POINTER_V1 : WaylandInterface = WaylandInterface { name : "zwp_relative_pointer_v1", version : 1, 
+    requests : &[msg!("destroy", "")], 
+    events : &[msg!("relative_motion", "uuffff")] };
+pub(crate) const ZWP_RELATIVE_POINTER_V1_DESTROY : u16 = 0;
+pub(crate) const ZWP_RELATIVE_POINTER_V1_RELATIVE_MOTION : u16 = 0;
+
+/*******************
+* FRACTIONAL SCALE *
+*******************/
+
+/// Global giving the preferred fractional scale of surfaces
+pub(crate) static WP_FRACTIONAL_SCALE_MANAGER_V1 : WaylandInterface = WaylandInterface { name : "wp_fractional_scale_manager_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("get_fractional_scale", "no", WP_FRACTIONAL_SCALE_V1)], 
+    events : &[] };
+pub(crate) const WP_FRACTIONAL_SCALE_MANAGER_V1_GET_FRACTIONAL_SCALE : u16 = 1;
+
+/// Preferred scale of a surface, in 1/120 of scale
+pub(crate) static WP_FRACTIONAL_SCALE_V1 : WaylandInterface = WaylandInterface { name : "wp_fractional_scale_v1", version : 1, 
+    requests : &[msg!("destroy", "")], 
+    events : &[msg!("preferred_scale", "u")] };
+pub(crate) const WP_FRACTIONAL_SCALE_V1_DESTROY : u16 = 0;
+pub(crate) const WP_FRACTIONAL_SCALE_V1_PREFERRED_SCALE : u16 = 0;
+
+/*************
+* VIEWPORTER *
+*************/
+
+/// Global cropping and scaling surfaces
+pub(crate) static WP_VIEWPORTER : WaylandInterface = WaylandInterface { name : "wp_viewporter", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("get_viewport", "no", WP_VIEWPORT)], 
+    events : &[] };
+pub(crate) const WP_VIEWPORTER_GET_VIEWPORT : u16 = 1;
+
+/// Viewport of a surface, buffers being scaled to the destination size
+pub(crate) static WP_VIEWPORT : WaylandInterface = WaylandInterface { name : "wp_viewport", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("set_source", "ffff"), msg!("set_destination", "ii")], 
+    events : &[] };
+pub(crate) const WP_VIEWPORT_DESTROY : u16 = 0;
+pub(crate) const WP_VIEWPORT_SET_DESTINATION : u16 = 2;
+
+/*************
+* TEXT INPUT *
+*************/
+
+/// Global giving text inputs of seats, used by input methods
+pub(crate) static ZWP_TEXT_INPUT_MANAGER_V3 : WaylandInterface = WaylandInterface { name : "zwp_text_input_manager_v3", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("get_text_input", "no", ZWP_TEXT_INPUT_V3)], 
+    events : &[] };
+pub(crate) const ZWP_TEXT_INPUT_MANAGER_V3_GET_TEXT_INPUT : u16 = 1;
+
+/// Text input of a seat. Requests are double-buffered and applied with commit, events are applied with done.
+pub(crate) static ZWP_TEXT_INPUT_V3 : WaylandInterface = WaylandInterface { name : "zwp_text_input_v3", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("enable", ""), msg!("disable", ""), msg!("set_surrounding_text", "sii"), msg!("set_text_change_cause", "u"), 
+        msg!("set_content_type", "uu"), msg!("set_cursor_rectangle", "iiii"), msg!("commit", "")], 
+    events : &[msg!("enter", "o"), msg!("leave", "o"), msg!("preedit_string", "?sii"), msg!("commit_string", "?s"), 
+        msg!("delete_surrounding_text", "uu"), msg!("done", "u")] };
+pub(crate) const ZWP_TEXT_INPUT_V3_ENABLE : u16 = 1;
+pub(crate) const ZWP_TEXT_INPUT_V3_DISABLE : u16 = 2;
+pub(crate) const ZWP_TEXT_INPUT_V3_SET_SURROUNDING_TEXT : u16 = 3;
+pub(crate) const ZWP_TEXT_INPUT_V3_SET_CURSOR_RECTANGLE : u16 = 6;
+pub(crate) const ZWP_TEXT_INPUT_V3_COMMIT : u16 = 7;
+pub(crate) const ZWP_TEXT_INPUT_V3_ENTER : u16 = 0;
+pub(crate) const ZWP_TEXT_INPUT_V3_LEAVE : u16 = 1;
+pub(crate) const ZWP_TEXT_INPUT_V3_PREEDIT_STRING : u16 = 2;
+pub(crate) const ZWP_TEXT_INPUT_V3_COMMIT_STRING : u16 = 3;
+pub(crate) const ZWP_TEXT_INPUT_V3_DELETE_SURROUNDING_TEXT : u16 = 4;
+pub(crate) const ZWP_TEXT_INPUT_V3_DONE : u16 = 5;
+
+/***************
+* IDLE INHIBIT *
+***************/
+
+/// Global creating idle inhibitors of surfaces
+pub(crate) static ZWP_IDLE_INHIBIT_MANAGER_V1 : WaylandInterface = WaylandInterface { name : "zwp_idle_inhibit_manager_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("create_inhibitor", "no", ZWP_IDLE_INHIBITOR_V1)], 
+    events : &[] };
+pub(crate) const ZWP_IDLE_INHIBIT_MANAGER_V1_CREATE_INHIBITOR : u16 = 1;
+
+/// Prevents the screen from blanking while its surface is visible
+pub(crate) static ZWP_IDLE_INHIBITOR_V1 : WaylandInterface = WaylandInterface { name : "zwp_idle_inhibitor_v1", version : 1, 
+    requests : &[msg!("destroy", "")], 
+    events : &[] };
+pub(crate) const ZWP_IDLE_INHIBITOR_V1_DESTROY : u16 = 0;
+
+/*****************************
+* KEYBOARD SHORTCUTS INHIBIT *
+*****************************/
+
+/// Global inhibiting compositor shortcuts so that keys are sent to a surface
+pub(crate) static ZWP_KEYBOARD_SHORTCUTS_INHIBIT_MANAGER_V1 : WaylandInterface = WaylandInterface { name : "zwp_keyboard_shortcuts_inhibit_manager_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("inhibit_shortcuts", "noo", ZWP_KEYBOARD_SHORTCUTS_INHIBITOR_V1)], 
+    events : &[] };
+pub(crate) const ZWP_KEYBOARD_SHORTCUTS_INHIBIT_MANAGER_V1_INHIBIT_SHORTCUTS : u16 = 1;
+
+/// Compositor shortcuts inhibited for a surface and seat, while active
+pub(crate) static ZWP_KEYBOARD_SHORTCUTS_INHIBITOR_V1 : WaylandInterface = WaylandInterface { name : "zwp_keyboard_shortcuts_inhibitor_v1", version : 1, 
+    requests : &[msg!("destroy", "")], 
+    events : &[msg!("active", ""), msg!("inactive", "")] };
+pub(crate) const ZWP_KEYBOARD_SHORTCUTS_INHIBITOR_V1_DESTROY : u16 = 0;
+
+/*************
+* ACTIVATION *
+*************/
+
+/// Global activating surfaces with tokens
+pub(crate) static XDG_ACTIVATION_V1 : WaylandInterface = WaylandInterface { name : "xdg_activation_v1", version : 1, 
+    requests : &[msg!("destroy", ""), msg!("get_activation_token", "n", XDG_ACTIVATION_TOKEN_V1), msg!("activate", "so")], 
+    events : &[] };
+pub(crate) const XDG_ACTIVATION_V1_GET_ACTIVATION_TOKEN : u16 = 1;
+pub(crate) const XDG_ACTIVATION_V1_ACTIVATE : u16 = 2;
+
+/// Activation token request. Token is given with done once committed.
+pub(crate) static XDG_ACTIVATION_TOKEN_V1 : WaylandInterface = WaylandInterface { name : "xdg_activation_token_v1", version : 1, 
+    requests : &[msg!("set_serial", "uo"), msg!("set_app_id", "s"), msg!("set_surface", "o"), msg!("commit", ""), msg!("destroy", "")], 
+    events : &[msg!("done", "s")] };
+pub(crate) const XDG_ACTIVATION_TOKEN_V1_SET_SERIAL : u16 = 0;
+pub(crate) const XDG_ACTIVATION_TOKEN_V1_SET_SURFACE : u16 = 2;
+pub(crate) const XDG_ACTIVATION_TOKEN_V1_COMMIT : u16 = 3;
+pub(crate) const XDG_ACTIVATION_TOKEN_V1_DESTROY : u16 = 4;
+pub(crate) const XDG_ACTIVATION_TOKEN_V1_DONE : u16 = 0;
@@ -0,0 +1,242 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! Text input of the seat, used by input methods like fcitx5 and ibus with text-input-v3.
+//! 
+//! Text input is enabled while a window in [WindowKeyboardMode::Text](crate::keyboard::WindowKeyboardMode::Text) has
+//! the text input focus. Pre-edit, commit and deletion sent by the input method are applied together on done.
+//! 
+//! Reference(s)
+//! <https://wayland.app/protocols/text-input-unstable-v3>
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::event::WindowKeyboardEvent;
+
+use super::{protocol::{ZWP_TEXT_INPUT_MANAGER_V3_GET_TEXT_INPUT, ZWP_TEXT_INPUT_V3, ZWP_TEXT_INPUT_V3_COMMIT, ZWP_TEXT_INPUT_V3_DISABLE, 
+    ZWP_TEXT_INPUT_V3_ENABLE, ZWP_TEXT_INPUT_V3_SET_CURSOR_RECTANGLE, ZWP_TEXT_INPUT_V3_SET_SURROUNDING_TEXT}, wlib::{WaylandArg, WaylandConnection, WaylandId}};
+
+/// Maximum size in bytes of the surrounding text sent to the input method.
+const WL_SURROUNDING_TEXT_MAX : usize = 4000;
+
+/// Text entry of a window, sent to the input method while the window has the text input focus.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(crate) struct WaylandTextField {
+    /// wl_surface of the window
+    pub surface : WaylandId,
+
+    /// Window is in text mode
+    pub enabled : bool,
+
+    /// Text around the cursor with cursor and anchor in bytes
+    pub surrounding : Option<(String, i32, i32)>,
+
+    /// Text cursor area as (x, y, width, height) relative to the window
+    pub cursor : Option<(i32, i32, i32, i32)>,
+}
+
+/// Text input state of the seat, updated by the window manager from zwp_text_input_v3 events and shared with windows keyboards.
+#[derive(Debug)]
+pub(crate) struct WaylandTextInputState {
+    /// Connection with the compositor
+    connection : Rc<RefCell<WaylandConnection>>,
+
+    /// zwp_text_input_v3 of the seat, 0 if compositor lacks text input.
+    pub text_input : WaylandId,
+
+    /// wl_surface with the text input focus, 0 if none.
+    pub focus : WaylandId,
+
+    /// Text input is enabled on the focused surface
+    active : bool,
+
+    /// Text fields of windows
+    fields : Vec<WaylandTextField>,
+
+    /// Pre-edit text with cursor range received since the last done
+    pub preedit : Option<(String, i32, i32)>,
+
+    /// Text committed since the last done
+    pub commit : Option<String>,
+
+    /// Bytes to delete before and after the cursor received since the last done
+    pub delete : (u32, u32),
+
+    /// A pre-edit text is currently shown by the window
+    preedit_shown : bool,
+}
+
+impl WaylandTextInputState {
+
+    /// Create the state of a seat without text input.
+    pub fn new(connection : Rc<RefCell<WaylandConnection>>) -> WaylandTextInputState {
+        WaylandTextInputState { connection, text_input : 0, focus : 0, active : false, fields : Vec::new(), preedit : None, commit : None, 
+            delete : (0, 0), preedit_shown : false }
+    }
+
+    /// Get the zwp_text_input_v3 of `seat` from the text input `manager`.
+    pub fn create(&mut self, manager : WaylandId, seat : WaylandId) {
+        let mut conn = self.connection.borrow_mut();
+        self.text_input = conn.new_object(&ZWP_TEXT_INPUT_V3, 1);
+        conn.send(manager, ZWP_TEXT_INPUT_MANAGER_V3_GET_TEXT_INPUT, vec![WaylandArg::NewId(self.text_input), WaylandArg::Object(seat)]);
+    }
+
+    /// Surface got the text input focus.
+    pub fn enter(&mut self, surface : WaylandId) {
+        self.focus = surface;
+        self.sync();
+    }
+
+    /// Surface lost the text input focus. Text input is disabled.
+    pub fn leave(&mut self, surface : WaylandId) {
+        if self.focus == surface {
+            self.focus = 0;
+            self.sync();
+        }
+    }
+
+    /// Enable or disable text input of the window surface.
+    pub fn set_enabled(&mut self, surface : WaylandId, enabled : bool) {
+        self.field(surface).enabled = enabled;
+        self.sync();
+    }
+
+    /// Set the text around the cursor of the window surface, `cursor` and `anchor` being in bytes.
+    /// 
+    /// Text is ignored by input methods if longer than 4000 bytes.
+    pub fn set_surrounding(&mut self, surface : WaylandId, text : &str, cursor : usize, anchor : usize) {
+        self.field(surface).surrounding = match text.len() < WL_SURROUNDING_TEXT_MAX {
+            true => Some((text.to_string(), cursor as i32, anchor as i32)),
+            false => None,
+        };
+        self.update(surface);
+    }
+
+    /// Set the text cursor area of the window surface.
+    pub fn set_cursor(&mut self, surface : WaylandId, x : i32, y : i32, width : i32, height : i32) {
+        self.field(surface).cursor = Some((x, y, width, height));
+        self.update(surface);
+    }
+
+    /// Remove the text field of a closed window surface.
+    pub fn remove(&mut self, surface : WaylandId) {
+        self.fields.retain(|f| f.surface != surface);
+        if self.focus == surface {
+            self.focus = 0;
+            self.sync();
+        }
+    }
+
+    /// Apply the pending changes received with zwp_text_input_v3.done.
+    /// 
+    /// Returns the [WindowKeyboardEvent]s to send to the focused window, in the order they apply.
+    pub fn done(&mut self) -> Vec<WindowKeyboardEvent> {
+        let mut events : Vec<WindowKeyboardEvent> = Vec::new();
+
+        let (before, after) = std::mem::take(&mut self.delete);
+        if before > 0 || after > 0 {
+            events.push(WindowKeyboardEvent::DeleteSurrounding(before as usize, after as usize));
+        }
+
+        if let Some(text) = self.commit.take().filter(|t| !t.is_empty()) {
+            events.push(WindowKeyboardEvent::Text(text));
+        }
+
+        // Pre-edit is removed when not sent again.
+        match self.preedit.take().filter(|(text, _, _)| !text.is_empty()) {
+            Some((text, begin, end)) => {
+                let cursor = (begin >= 0 && end >= 0).then_some((begin as usize, end as usize));
+                events.push(WindowKeyboardEvent::Preedit(text, cursor));
+                self.preedit_shown = true;
+            },
+            None if self.preedit_shown => {
+                events.push(WindowKeyboardEvent::Preedit(String::new(), None));
+                self.preedit_shown = false;
+            },
+            None => {},
+        }
+
+        events
+    }
+
+    /// Get the text field of a window surface, created if missing.
+    fn field(&mut self, surface : WaylandId) -> &mut WaylandTextField {
+        let index = match self.fields.iter().position(|f| f.surface == surface) {
+            Some(index) => index,
+            None => {
+                self.fields.push(WaylandTextField { surface, ..Default::default() });
+                self.fields.len() - 1
+            },
+        };
+        &mut self.fields[index]
+    }
+
+    /// Enable text input if the focused window is in text mode, disable it otherwise.
+    fn sync(&mut self) {
+        let enabled = self.fields.iter().any(|f| f.surface == self.focus && f.enabled) && self.focus != 0;
+        if self.text_input == 0 || enabled == self.active {
+            return;
+        }
+        self.active = enabled;
+
+        match enabled {
+            // Enabling resets the state, which is sent again.
+            true => {
+                self.connection.borrow_mut().send(self.text_input, ZWP_TEXT_INPUT_V3_ENABLE, vec![]);
+                self.update(self.focus);
+            },
+            false => {
+                let mut conn = self.connection.borrow_mut();
+                conn.send(self.text_input, ZWP_TEXT_INPUT_V3_DISABLE, vec![]);
+                conn.send(self.text_input, ZWP_TEXT_INPUT_V3_COMMIT, vec![]);
+                let _ = conn.flush();
+            },
+        }
+    }
+
+    /// Send and flush the text field of surface if it has the enabled text input.
+    fn update(&mut self, surface : WaylandId) {
+        if !self.active || self.focus != surface {
+            return;
+        }
+
+        let field = match self.fields.iter().find(|f| f.surface == surface) {
+            Some(field) => field,
+            None => return,
+        };
+
+        let mut conn = self.connection.borrow_mut();
+        if let Some((text, cursor, anchor)) = &field.surrounding {
+            conn.send(self.text_input, ZWP_TEXT_INPUT_V3_SET_SURROUNDING_TEXT, vec![WaylandArg::Str(Some(text.clone())), 
+                WaylandArg::Int(*cursor), WaylandArg::Int(*anchor)]);
+        }
+        if let Some((x, y, width, height)) = field.cursor {
+            conn.send(self.text_input, ZWP_TEXT_INPUT_V3_SET_CURSOR_RECTANGLE, vec![WaylandArg::Int(x), WaylandArg::Int(y), 
+                WaylandArg::Int(width), WaylandArg::Int(height)]);
+        }
+        conn.send(self.text_input, ZWP_TEXT_INPUT_V3_COMMIT, vec![]);
+        let _ = conn.flush();
+    }
+}
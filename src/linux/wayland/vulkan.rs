@@ -0,0 +1,134 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Vulkan surfaces with VK_KHR_wayland_surface, loaded from the application instance.
+//! 
+//! Reference(s)
+//! <https://registry.khronos.org/vulkan/specs/latest/man/html/vkCreateWaylandSurfaceKHR.html>
+
+use std::ffi::{c_void, CStr};
+
+use crate::{vulkan::{VkGetInstanceProcAddr, VkInstance, VkResult, VkSurfaceKHR, VkVoidFunction, VK_ERROR_EXTENSION_NOT_PRESENT, VK_KHR_SURFACE, VK_KHR_WAYLAND_SURFACE, VK_SUCCESS}, WindowError};
+
+/// `VK_STRUCTURE_TYPE_WAYLAND_SURFACE_CREATE_INFO_KHR`
+const VK_STRUCTURE_TYPE_WAYLAND_SURFACE_CREATE_INFO_KHR : i32 = 1000006000;
+
+/// Instance extensions needed by [wayland_vulkan_surface].
+pub(crate) const WAYLAND_VULKAN_EXTENSIONS : &[&CStr] = &[VK_KHR_SURFACE, VK_KHR_WAYLAND_SURFACE];
+
+/// `VkWaylandSurfaceCreateInfoKHR`
+#[repr(C)]
+struct VkWaylandSurfaceCreateInfoKHR {
+    s_type : i32,
+    p_next : *const c_void,
+    flags : u32,
+    display : *mut c_void,
+    surface : *mut c_void,
+}
+
+/// `PFN_vkCreateWaylandSurfaceKHR`
+type VkCreateWaylandSurfaceKHR = unsafe extern "system" fn(instance : VkInstance, create_info : *const VkWaylandSurfaceCreateInfoKHR, 
+    allocator : *const c_void, surface : *mut VkSurfaceKHR) -> VkResult;
+
+/// Create a VkSurfaceKHR of the libwayland `wl_surface` with vkCreateWaylandSurfaceKHR.
+/// 
+/// # Safety
+/// `display` and `surface` must be libwayland `wl_display` and `wl_surface` pointers. `instance` must be a valid VkInstance 
+/// created with [WAYLAND_VULKAN_EXTENSIONS] and `get_instance_proc_addr` its loader.
+pub(crate) unsafe fn wayland_vulkan_surface(display : *mut c_void, surface : *mut c_void, instance : VkInstance, 
+    get_instance_proc_addr : VkGetInstanceProcAddr) -> Result<VkSurfaceKHR, WindowError> {
+
+    let create = match get_instance_proc_addr(instance, c"vkCreateWaylandSurfaceKHR".as_ptr()) {
+        // Signature is given by the Vulkan registry.
+        Some(function) => std::mem::transmute::<VkVoidFunction, VkCreateWaylandSurfaceKHR>(function),
+        None => return Err(WindowError::WindowVulkanSurfaceFailed(VK_ERROR_EXTENSION_NOT_PRESENT)),
+    };
+
+    let info = VkWaylandSurfaceCreateInfoKHR { s_type: VK_STRUCTURE_TYPE_WAYLAND_SURFACE_CREATE_INFO_KHR, p_next: std::ptr::null(), 
+        flags: 0, display, surface };
+    let mut vk_surface : VkSurfaceKHR = 0;
+
+    match create(instance, &info, std::ptr::null(), &mut vk_surface) {
+        VK_SUCCESS => Ok(vk_surface),
+        result => Err(WindowError::WindowVulkanSurfaceFailed(result)),
+    }
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Wayland Vulkan surface tests
+#[cfg(test)]
+mod tests {
+    use std::ffi::{c_char, CStr};
+
+    use crate::{vulkan::{VkInstance, VkResult, VkSurfaceKHR, VkVoidFunction, VK_ERROR_EXTENSION_NOT_PRESENT}, WindowError};
+
+    use super::{VkWaylandSurfaceCreateInfoKHR, VK_STRUCTURE_TYPE_WAYLAND_SURFACE_CREATE_INFO_KHR};
+
+    const INSTANCE : usize = 0x1000;
+    const DISPLAY : usize = 0x2000;
+
+    unsafe extern "system" fn create_surface(instance : VkInstance, create_info : *const VkWaylandSurfaceCreateInfoKHR, 
+        _allocator : *const std::ffi::c_void, surface : *mut VkSurfaceKHR) -> VkResult {
+        let info = &*create_info;
+        assert_eq!(instance as usize, INSTANCE);
+        assert_eq!(info.s_type, VK_STRUCTURE_TYPE_WAYLAND_SURFACE_CREATE_INFO_KHR);
+        assert_eq!(info.display as usize, DISPLAY);
+
+        if info.surface.is_null() {
+            // VK_ERROR_SURFACE_LOST_KHR
+            return -1000000000;
+        }
+
+        *surface = info.surface as VkSurfaceKHR;
+        0
+    }
+
+    unsafe extern "system" fn loader(_instance : VkInstance, name : *const c_char) -> Option<VkVoidFunction> {
+        (CStr::from_ptr(name) == c"vkCreateWaylandSurfaceKHR").then(|| 
+            std::mem::transmute::<super::VkCreateWaylandSurfaceKHR, VkVoidFunction>(create_surface))
+    }
+
+    unsafe extern "system" fn loader_empty(_instance : VkInstance, _name : *const c_char) -> Option<VkVoidFunction> {
+        None
+    }
+
+    #[test]
+    fn wayland_vulkan_surface_create() {
+        let display = DISPLAY as *mut _;
+        let instance = INSTANCE as VkInstance;
+
+        unsafe {
+            assert_eq!(super::wayland_vulkan_surface(display, 0x3000 as *mut _, instance, loader), Ok(0x3000));
+            assert_eq!(super::wayland_vulkan_surface(display, std::ptr::null_mut(), instance, loader), 
+                Err(WindowError::WindowVulkanSurfaceFailed(-1000000000)));
+
+            // Instance without VK_KHR_wayland_surface
+            assert_eq!(super::wayland_vulkan_surface(display, 0x3000 as *mut _, instance, loader_empty), 
+                Err(WindowError::WindowVulkanSurfaceFailed(VK_ERROR_EXTENSION_NOT_PRESENT)));
+        }
+    }
+}
@@ -22,7 +22,792 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-#[derive(Debug, PartialEq)]
+use std::{cell::RefCell, collections::VecDeque, rc::Rc, time::{Duration, Instant}};
+
+use crate::{event::{WindowEvent, WindowFrameTiming, WindowManagerEvent}, frame::WindowFrame, WindowError, WindowFullScreenMode, WindowHandle, WindowPosition, WindowSize};
+
+use super::{activation::WaylandActivation, grab::WaylandGrab, frame::{WaylandDecoration, WaylandDecorationGlobals}, framebuffer::WaylandFramebuffer, 
+    protocol::{WL_CALLBACK, WL_SURFACE, WL_COMPOSITOR_CREATE_SURFACE, WL_SURFACE_ATTACH, WL_SURFACE_COMMIT, WL_SURFACE_DESTROY, WL_SURFACE_FRAME, XDG_SURFACE, XDG_SURFACE_ACK_CONFIGURE, 
+    XDG_SURFACE_DESTROY, XDG_SURFACE_GET_TOPLEVEL, XDG_TOPLEVEL, XDG_TOPLEVEL_DESTROY, XDG_TOPLEVEL_SET_FULLSCREEN, XDG_TOPLEVEL_SET_MAXIMIZED, 
+    XDG_TOPLEVEL_SET_MAX_SIZE, XDG_TOPLEVEL_SET_MINIMIZED, XDG_TOPLEVEL_SET_MIN_SIZE, XDG_TOPLEVEL_SET_PARENT, XDG_TOPLEVEL_SET_TITLE, 
+    XDG_TOPLEVEL_STATE_FULLSCREEN, XDG_TOPLEVEL_STATE_MAXIMIZED, XDG_TOPLEVEL_UNSET_FULLSCREEN, XDG_TOPLEVEL_UNSET_MAXIMIZED, XDG_WM_BASE_GET_XDG_SURFACE, 
+    WL_SURFACE_SET_BUFFER_SCALE, WP_FRACTIONAL_SCALE_MANAGER_V1_GET_FRACTIONAL_SCALE, WP_FRACTIONAL_SCALE_V1, WP_FRACTIONAL_SCALE_V1_DESTROY, WP_VIEWPORT, 
+    WP_VIEWPORTER_GET_VIEWPORT, WP_VIEWPORT_DESTROY, WP_VIEWPORT_SET_DESTINATION, XDG_POPUP, XDG_POPUP_DESTROY, XDG_POPUP_GRAB, XDG_SURFACE_GET_POPUP, 
+    ZWP_IDLE_INHIBITOR_V1, ZWP_IDLE_INHIBITOR_V1_DESTROY, 
+    ZWP_IDLE_INHIBIT_MANAGER_V1_CREATE_INHIBITOR}, 
+    wlib::{WaylandArg, WaylandConnection, WaylandId}};
+
+/// States of a xdg_toplevel given by configure events.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct WaylandToplevelState {
+    /// Size suggested by compositor. None if client choose its size.
+    pub size : Option<WindowSize>,
+
+    /// Toplevel is maximized
+    pub maximized : bool,
+
+    /// Toplevel is fullscreen
+    pub fullscreen : bool,
+}
+
+/// Globals scaling the content of windows.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct WaylandScaleGlobals {
+    /// wp_fractional_scale_manager_v1 global, 0 if not advertised.
+    pub fractional_manager : WaylandId,
+
+    /// wp_viewporter global, 0 if not advertised.
+    pub viewporter : WaylandId,
+}
+
+/// Globals used to create windows.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct WaylandWindowGlobals {
+    /// xdg_wm_base global
+    pub wm_base : WaylandId,
+
+    /// Globals used to decorate windows
+    pub decorations : WaylandDecorationGlobals,
+
+    /// Globals used to scale windows
+    pub scaling : WaylandScaleGlobals,
+}
+
+/// Wayland window made of a wl_surface with the xdg_toplevel role, or the xdg_popup role for sub windows placed relative to their parent.
+/// 
+/// Requests are sent right away on the connection shared with the [WaylandWindowManager](super::manager::WaylandWindowManager).
+#[derive(Debug)]
 pub struct WaylandWindow {
-    
-}
\ No newline at end of file
+    /// Connection shared with the manager
+    connection : Rc<RefCell<WaylandConnection>>,
+
+    /// Event queue shared with the manager
+    events : Rc<RefCell<VecDeque<WindowManagerEvent>>>,
+
+    /// [WindowHandle] given by the manager
+    handle : WindowHandle,
+
+    /// wl_surface object
+    pub(crate) surface : WaylandId,
+
+    /// xdg_surface object
+    pub(crate) xdg_surface : WaylandId,
+
+    /// xdg_toplevel object, 0 if window is a popup.
+    pub(crate) toplevel : WaylandId,
+
+    /// xdg_popup object, 0 if window is a toplevel.
+    pub(crate) popup : WaylandId,
+
+    /// Initial commit was done and surface isn't hidden.
+    shown : bool,
+
+    /// Received a configure since shown.
+    configured : bool,
+
+    /// States received with xdg_toplevel.configure, applied with xdg_surface.configure.
+    pending : WaylandToplevelState,
+
+    /// States of the last configure acknowledged.
+    current : WaylandToplevelState,
+
+    /// wl_output ids indexed by [DisplayHandle](crate::display::DisplayHandle), shared with the manager.
+    outputs : Rc<RefCell<Vec<WaylandId>>>,
+
+    /// wl_output the surface entered, the last one is the current.
+    entered : Vec<WaylandId>,
+
+    /// Decorations, shared with the [WindowFrame] backend.
+    pub(crate) decoration : Rc<RefCell<WaylandDecoration>>,
+
+    /// wp_fractional_scale_v1 object, 0 if fractional scaling isn't available.
+    pub(crate) fractional_scale : WaylandId,
+
+    /// wp_viewport object mapping buffers to the window size, 0 if fractional scaling isn't available.
+    pub(crate) viewport : WaylandId,
+
+    /// Integer scale given by wl_surface.preferred_buffer_scale
+    preferred_scale : Option<i32>,
+
+    /// Highest integer scale of outputs entered
+    output_scale : i32,
+
+    /// Current scale factor
+    scale : f64,
+
+    /// Activation state of the seat, shared with the manager. None until set by the manager.
+    activation : Option<Rc<RefCell<WaylandActivation>>>,
+
+    /// Input grab state of the seat, shared with the manager. None until set by the manager.
+    grab : Option<Rc<RefCell<WaylandGrab>>>,
+
+    /// xdg_toplevel of each [WindowHandle], shared with the manager. Used to set a parent after build.
+    toplevels : Rc<RefCell<Vec<(WindowHandle, WaylandId)>>>,
+
+    /// zwp_idle_inhibitor_v1 object, 0 if idle isn't inhibited.
+    pub(crate) idle_inhibitor : WaylandId,
+
+    /// Software framebuffer attached to the surface
+    pub(crate) framebuffer : WaylandFramebuffer,
+
+    /// Cairo image surface of the framebuffer back buffer
+    #[cfg(feature = "cairo")]
+    cairo : crate::linux::cairo::LinuxCairoSurface,
+
+    /// Window size and scale, shared with OpenGL contexts resizing their wl_egl_window.
+    #[cfg(feature = "opengl")]
+    gl_size : Rc<std::cell::Cell<(WindowSize, f64)>>,
+
+    /// wl_callback of wl_surface.frame, 0 if no frame is pending.
+    pub(crate) frame_callback : WaylandId,
+
+    /// Redraw requested while a frame is pending
+    redraw : bool,
+
+    /// Refresh interval of the current output, zero if unknown.
+    refresh : Duration,
+}
+
+impl PartialEq for WaylandWindow {
+    fn eq(&self, other: &Self) -> bool {
+        self.surface == other.surface
+    }
+}
+
+impl WaylandWindow {
+
+    /// Create a wl_surface with the xdg_toplevel role decorated according to `frame`. Surface isn't shown until [WaylandWindow::show].
+    /// 
+    /// With `popup` as (parent xdg_surface, xdg_positioner), the surface gets the undecorated xdg_popup role instead.
+    pub(crate) fn new(connection : Rc<RefCell<WaylandConnection>>, events : Rc<RefCell<VecDeque<WindowManagerEvent>>>, handle : WindowHandle, 
+        outputs : Rc<RefCell<Vec<WaylandId>>>, globals : WaylandWindowGlobals, popup : Option<(WaylandId, WaylandId)>, 
+        frame : &WindowFrame) -> WaylandWindow {
+
+        let WaylandWindowGlobals { wm_base, mut decorations, scaling } = globals;
+        let compositor = decorations.compositor;
+
+        let (surface, xdg_surface, toplevel, popup, fractional_scale, viewport) = {
+            let mut conn = connection.borrow_mut();
+            let (compositor_version, wm_base_version) = (conn.version(compositor), conn.version(wm_base));
+
+            let surface = conn.new_object(&WL_SURFACE, compositor_version);
+            conn.send(compositor, WL_COMPOSITOR_CREATE_SURFACE, vec![WaylandArg::NewId(surface)]);
+
+            let xdg_surface = conn.new_object(&XDG_SURFACE, wm_base_version);
+            conn.send(wm_base, XDG_WM_BASE_GET_XDG_SURFACE, vec![WaylandArg::NewId(xdg_surface), WaylandArg::Object(surface)]);
+
+            let (toplevel, popup) = match popup {
+                // Popups are never decorated.
+                Some((parent, positioner)) => {
+                    decorations = WaylandDecorationGlobals { subcompositor : 0, manager : 0, ..decorations };
+                    let popup = conn.new_object(&XDG_POPUP, wm_base_version);
+                    conn.send(xdg_surface, XDG_SURFACE_GET_POPUP, vec![WaylandArg::NewId(popup), WaylandArg::Object(parent), 
+                        WaylandArg::Object(positioner)]);
+                    (0, popup)
+                },
+                None => {
+                    let toplevel = conn.new_object(&XDG_TOPLEVEL, wm_base_version);
+                    conn.send(xdg_surface, XDG_SURFACE_GET_TOPLEVEL, vec![WaylandArg::NewId(toplevel)]);
+                    (toplevel, 0)
+                },
+            };
+
+            // Fractional scales need a viewport since buffers sizes are integers.
+            let (fractional_scale, viewport) = match scaling {
+                WaylandScaleGlobals { fractional_manager, viewporter } if fractional_manager != 0 && viewporter != 0 => {
+                    let fractional_scale = conn.new_object(&WP_FRACTIONAL_SCALE_V1, 1);
+                    conn.send(fractional_manager, WP_FRACTIONAL_SCALE_MANAGER_V1_GET_FRACTIONAL_SCALE, vec![WaylandArg::NewId(fractional_scale), 
+                        WaylandArg::Object(surface)]);
+                    let viewport = conn.new_object(&WP_VIEWPORT, 1);
+                    conn.send(viewporter, WP_VIEWPORTER_GET_VIEWPORT, vec![WaylandArg::NewId(viewport), WaylandArg::Object(surface)]);
+                    (fractional_scale, viewport)
+                },
+                _ => (0, 0),
+            };
+
+            (surface, xdg_surface, toplevel, popup, fractional_scale, viewport)
+        };
+
+        let decoration = Rc::new(RefCell::new(WaylandDecoration::new(connection.clone(), events.clone(), handle, decorations, 
+            surface, xdg_surface, toplevel, frame)));
+        let framebuffer = WaylandFramebuffer::new(connection.clone(), decorations.shm, surface);
+
+        WaylandWindow { connection, events, handle, surface, xdg_surface, toplevel, popup, shown : false, configured : false, 
+            pending : WaylandToplevelState::default(), current : WaylandToplevelState::default(), outputs, entered : Vec::new(), decoration, 
+            fractional_scale, viewport, preferred_scale : None, output_scale : 1, scale : 1.0, activation : None, grab : None, 
+            toplevels : Rc::new(RefCell::new(Vec::new())), idle_inhibitor : 0, 
+            framebuffer, #[cfg(feature = "cairo")] cairo : crate::linux::cairo::LinuxCairoSurface::new(), 
+            #[cfg(feature = "opengl")] gl_size : Rc::new(std::cell::Cell::new((WindowSize::new(1, 1), 1.0))), frame_callback : 0, redraw : false, 
+            refresh : Duration::ZERO }
+    }
+
+    /// [WindowHandle] of the window given by the manager.
+    #[inline(always)]
+    pub(crate) fn handle(&self) -> WindowHandle {
+        self.handle
+    }
+
+    /// wl_surface object id
+    #[inline(always)]
+    pub fn native_handle(&self) -> usize {
+        self.surface as usize
+    }
+
+    /// Share the activation state of the seat, used to activate the window and inhibit idle.
+    pub(crate) fn set_activation(&mut self, activation : Rc<RefCell<WaylandActivation>>) {
+        self.activation = Some(activation);
+    }
+
+    /// Share the input grab state of the seat, used to grab input.
+    pub(crate) fn set_grab(&mut self, grab : Rc<RefCell<WaylandGrab>>) {
+        self.grab = Some(grab);
+    }
+
+    /// Share the xdg_toplevel of each [WindowHandle], used to set a parent after build.
+    pub(crate) fn set_toplevels(&mut self, toplevels : Rc<RefCell<Vec<(WindowHandle, WaylandId)>>>) {
+        self.toplevels = toplevels;
+    }
+
+    /// Set the parent toplevel of this toplevel. 0 remove the parent.
+    pub(crate) fn set_parent_toplevel(&mut self, parent : WaylandId) {
+        self.toplevel_request(XDG_TOPLEVEL_SET_PARENT, vec![WaylandArg::Object(parent)]);
+    }
+
+    /// Wayland clients can't know where windows are on the desktop. Returns an empty window at the origin.
+    #[inline(always)]
+    pub(crate) fn get_window_pos_size(&self, _handle : WindowHandle) -> (WindowPosition, WindowSize) {
+        (WindowPosition::new(0, 0), WindowSize::new(0, 0))
+    }
+
+    /// Toplevels get their parent with xdg_toplevel.set_parent. Popups keep the parent they were created with.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::InvalidWindowHandle`]) if the parent isn't a toplevel of this manager.
+    #[inline(always)]
+    pub fn set_parent(&mut self, parent : Option<WindowHandle>) -> Result<bool, WindowError> {
+        if self.popup != 0 {
+            return Ok(false);
+        }
+
+        let toplevel = match parent {
+            Some(parent) => match self.toplevels.borrow().iter().find(|(handle, _)| *handle == parent) {
+                Some((_, toplevel)) => *toplevel,
+                None => return Err(WindowError::InvalidWindowHandle),
+            },
+            None => 0,
+        };
+        self.set_parent_toplevel(toplevel);
+        Ok(true)
+    }
+
+    #[inline(always)]
+    pub fn set_title(&mut self, title : &str) -> Result<bool, WindowError> {
+        self.toplevel_request(XDG_TOPLEVEL_SET_TITLE, vec![WaylandArg::Str(Some(title.to_string()))]);
+        Ok(true)
+    }
+
+    /// Wayland clients choose their size, which is applied with the next buffer committed. Client-side frame is redrawn.
+    #[inline(always)]
+    pub fn set_size(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        self.decoration.borrow_mut().set_size(size);
+        self.set_viewport(size);
+        #[cfg(feature = "opengl")]
+        self.gl_size.set((size, self.scale));
+        Ok(true)
+    }
+
+    /// Size limits apply to the window geometry, which includes the client-side title bar.
+    #[inline(always)]
+    pub fn set_size_min(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        let (width, height) = self.size_limit(size);
+        self.toplevel_request(XDG_TOPLEVEL_SET_MIN_SIZE, vec![WaylandArg::Int(width), WaylandArg::Int(height)]);
+        self.commit();
+        Ok(true)
+    }
+
+    #[inline(always)]
+    pub fn set_size_max(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        let (width, height) = self.size_limit(size);
+        self.toplevel_request(XDG_TOPLEVEL_SET_MAX_SIZE, vec![WaylandArg::Int(width), WaylandArg::Int(height)]);
+        self.commit();
+        Ok(true)
+    }
+
+    /// Geometry of a size limit. A dimension of 0 means no limit and is kept as is.
+    #[inline(always)]
+    fn size_limit(&self, size : WindowSize) -> (i32, i32) {
+        match size.height {
+            0 => (size.width as i32, 0),
+            height => (size.width as i32, height as i32 + self.decoration.borrow().title_height()),
+        }
+    }
+
+    /// xdg-shell has no icon request, compositors take the icon of the desktop entry matching the app id.
+    #[inline(always)]
+    pub fn set_icon(&mut self, _icon : Option<&mut dyn std::io::Read>) {}
+
+    /// xdg-shell has no taskbar hint, toplevels are always listed by the compositor.
+    #[inline(always)]
+    pub fn set_taskbar(&mut self, _show : bool) {}
+
+    /// Compositors place windows, clients can't move them.
+    #[inline(always)]
+    pub fn set_position(&mut self, _position : WindowPosition) {}
+
+    /// Minimized state can't be removed by clients on Wayland.
+    #[inline(always)]
+    pub fn restore(&mut self) {
+        self.toplevel_request(XDG_TOPLEVEL_UNSET_FULLSCREEN, vec![]);
+        self.toplevel_request(XDG_TOPLEVEL_UNSET_MAXIMIZED, vec![]);
+    }
+
+    /// Do the initial commit. Compositor answers with a configure.
+    #[inline(always)]
+    pub fn show(&mut self) {
+        if !self.shown {
+            self.shown = true;
+            self.configured = false;
+            self.decoration.borrow_mut().shown = true;
+            self.request(self.surface, WL_SURFACE_COMMIT, vec![]);
+        }
+    }
+
+    /// Unmap the surface with a null buffer.
+    #[inline(always)]
+    pub fn hide(&mut self) {
+        if self.shown {
+            self.shown = false;
+            self.decoration.borrow_mut().shown = false;
+            self.request(self.surface, WL_SURFACE_ATTACH, vec![WaylandArg::Object(0), WaylandArg::Int(0), WaylandArg::Int(0)]);
+            self.request(self.surface, WL_SURFACE_COMMIT, vec![]);
+            self.cancel_frame();
+            self.events.borrow_mut().push_back(WindowManagerEvent::new(self.handle(), WindowEvent::Hidden));
+        }
+    }
+
+    /// Destroy the toplevel or popup, xdg_surface and wl_surface.
+    #[inline(always)]
+    pub fn close(&mut self) {
+        if self.connection.borrow().alive(self.surface) {
+            // Decoration must be destroyed before its toplevel.
+            self.decoration.borrow_mut().destroy();
+            #[cfg(feature = "cairo")]
+            self.cairo.destroy();
+            self.framebuffer.destroy();
+            self.cancel_frame();
+
+            self.set_idle_inhibit(false);
+            if let Some(activation) = &self.activation {
+                activation.borrow_mut().remove(self.surface);
+            }
+
+            if self.fractional_scale != 0 {
+                self.request(self.fractional_scale, WP_FRACTIONAL_SCALE_V1_DESTROY, vec![]);
+                self.request(self.viewport, WP_VIEWPORT_DESTROY, vec![]);
+                let mut conn = self.connection.borrow_mut();
+                conn.destroyed(self.fractional_scale);
+                conn.destroyed(self.viewport);
+            }
+
+            match self.popup {
+                0 => self.request(self.toplevel, XDG_TOPLEVEL_DESTROY, vec![]),
+                popup => self.request(popup, XDG_POPUP_DESTROY, vec![]),
+            }
+            self.request(self.xdg_surface, XDG_SURFACE_DESTROY, vec![]);
+            self.request(self.surface, WL_SURFACE_DESTROY, vec![]);
+
+            let mut conn = self.connection.borrow_mut();
+            conn.destroyed(self.toplevel.max(self.popup));
+            conn.destroyed(self.xdg_surface);
+            conn.destroyed(self.surface);
+            drop(conn);
+
+            self.events.borrow_mut().push_back(WindowManagerEvent::new(self.handle(), WindowEvent::Closed));
+        }
+    }
+
+    /// Idle is inhibited with a zwp_idle_inhibitor_v1 of the surface, only while the surface is visible. 
+    /// Does nothing if compositor lacks idle-inhibit.
+    #[inline(always)]
+    pub fn set_idle_inhibit(&mut self, inhibit : bool) {
+        let manager = self.activation.as_ref().map_or(0, |a| a.borrow().idle_manager);
+
+        if inhibit && self.idle_inhibitor == 0 && manager != 0 {
+            self.idle_inhibitor = self.connection.borrow_mut().new_object(&ZWP_IDLE_INHIBITOR_V1, 1);
+            self.request(manager, ZWP_IDLE_INHIBIT_MANAGER_V1_CREATE_INHIBITOR, vec![WaylandArg::NewId(self.idle_inhibitor), 
+                WaylandArg::Object(self.surface)]);
+        } else if !inhibit && self.idle_inhibitor != 0 {
+            self.request(self.idle_inhibitor, ZWP_IDLE_INHIBITOR_V1_DESTROY, vec![]);
+            self.connection.borrow_mut().destroyed(self.idle_inhibitor);
+            self.idle_inhibitor = 0;
+        }
+    }
+
+    /// Activation is requested with a xdg_activation_v1 token. Compositor may only mark the window as urgent 
+    /// if no window of the application has the focus.
+    #[inline(always)]
+    pub fn activate(&mut self) {
+        if let Some(activation) = &self.activation {
+            activation.borrow_mut().request(self.surface);
+        }
+    }
+
+    /// Redraw is sent right away unless a frame is pending, in which case it is sent once the compositor is done with that frame.
+    #[inline(always)]
+    pub fn request_redraw(&mut self) {
+        if let Some(event) = self.redraw(None) {
+            self.events.borrow_mut().push_back(WindowManagerEvent::new(self.handle(), event));
+        }
+    }
+
+    /// Compositor choose the output for [WindowFullScreenMode::Desktop] or if the output isn't found.
+    #[inline(always)]
+    pub fn set_fullscreen(&mut self, fsmode : WindowFullScreenMode) {
+        let output = match fsmode {
+            WindowFullScreenMode::Current => self.entered.last().copied(),
+            WindowFullScreenMode::Primary => self.outputs.borrow().first().copied(),
+            WindowFullScreenMode::Desktop => None,
+            WindowFullScreenMode::Display(handle) => self.outputs.borrow().get(handle).copied(),
+        };
+
+        self.toplevel_request(XDG_TOPLEVEL_SET_FULLSCREEN, vec![WaylandArg::Object(output.unwrap_or(0))]);
+    }
+
+    #[inline(always)]
+    pub fn minimize(&mut self) {
+        self.toplevel_request(XDG_TOPLEVEL_SET_MINIMIZED, vec![]);
+    }
+
+    #[inline(always)]
+    pub fn maximize(&mut self) {
+        self.toplevel_request(XDG_TOPLEVEL_SET_MAXIMIZED, vec![]);
+    }
+
+    /// Lock the pointer on the surface with zwp_pointer_constraints_v1 and inhibit compositor shortcuts with 
+    /// zwp_keyboard_shortcuts_inhibit_manager_v1. Compositor applies them while the window has the focus.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowInputGrabFailed`]) if the compositor lacks one of those protocols or has no seat.
+    #[inline(always)]
+    pub fn grab_input(&mut self) -> Result<bool, WindowError> {
+        match &self.grab {
+            Some(grab) if grab.borrow_mut().grab(self.surface) => Ok(true),
+            _ => Err(WindowError::WindowInputGrabFailed),
+        }
+    }
+
+    /// Release the pointer lock and shortcuts inhibitor of the grab. Does nothing if input isn't grabbed.
+    #[inline(always)]
+    pub fn ungrab_input(&mut self) {
+        if let Some(grab) = &self.grab {
+            grab.borrow_mut().ungrab(self.surface);
+        }
+    }
+
+    /// Surface entered (or left if `entered` is false) an output with wl_surface.enter and wl_surface.leave.
+    pub(crate) fn output_enter(&mut self, output : WaylandId, entered : bool) {
+        self.entered.retain(|o| *o != output);
+        if entered {
+            self.entered.push(output);
+        }
+    }
+
+    /// wl_output the surface entered, the last one is the current.
+    pub(crate) fn entered(&self) -> &[WaylandId] {
+        &self.entered
+    }
+
+    /// Set the highest integer scale of outputs entered, used if the compositor doesn't tell the preferred scale.
+    pub(crate) fn set_output_scale(&mut self, scale : i32) {
+        self.output_scale = scale.max(1);
+        if self.fractional_scale == 0 && self.preferred_scale.is_none() {
+            self.set_scale(self.output_scale as f64);
+        }
+    }
+
+    /// Set the refresh interval of the current output.
+    pub(crate) fn set_refresh_interval(&mut self, refresh : Duration) {
+        self.refresh = refresh;
+    }
+
+    /// Compositor is done with the pending frame, presented at `time` in milliseconds of CLOCK_MONOTONIC. 
+    /// Redraw requested meanwhile is sent.
+    pub(crate) fn frame_done(&mut self, time : u32) {
+        self.frame_callback = 0;
+
+        if self.redraw {
+            let elapsed = (super::super::monotonic_time().as_millis() as u32).wrapping_sub(time);
+            let presented = super::super::presentation_instant(Duration::from_millis(elapsed as u64));
+            self.request_redraw_at(presented);
+        }
+    }
+
+    /// Send the redraw requested after a frame presented at `presented`.
+    fn request_redraw_at(&mut self, presented : Instant) {
+        if let Some(event) = self.redraw(Some(presented)) {
+            self.events.borrow_mut().push_back(WindowManagerEvent::new(self.handle(), event));
+        }
+    }
+
+    /// Returns [WindowEvent::RedrawRequested] if the window can draw now, requesting a wl_surface.frame applied with the frame 
+    /// committed. Redraw waits for the pending frame or the first configure otherwise.
+    fn redraw(&mut self, presented : Option<Instant>) -> Option<WindowEvent> {
+        if self.frame_callback != 0 || !self.shown || !self.configured {
+            self.redraw = true;
+            return None;
+        }
+
+        self.redraw = false;
+        self.frame_callback = self.connection.borrow_mut().new_object(&WL_CALLBACK, 1);
+        self.request(self.surface, WL_SURFACE_FRAME, vec![WaylandArg::NewId(self.frame_callback)]);
+
+        let target = presented.unwrap_or_else(Instant::now) + self.refresh;
+        Some(WindowEvent::RedrawRequested(WindowFrameTiming { target, interval : self.refresh }))
+    }
+
+    /// Forget the pending frame, which may never be done once the surface is unmapped.
+    fn cancel_frame(&mut self) {
+        if self.frame_callback != 0 {
+            self.connection.borrow_mut().destroyed(self.frame_callback);
+            self.frame_callback = 0;
+        }
+        self.redraw = false;
+    }
+
+    /// Set the integer scale of wl_surface.preferred_buffer_scale, replacing the scale of outputs.
+    pub(crate) fn set_preferred_scale(&mut self, scale : i32) {
+        self.preferred_scale = Some(scale.max(1));
+        if self.fractional_scale == 0 {
+            self.set_scale(scale.max(1) as f64);
+        }
+    }
+
+    /// Set the scale of wp_fractional_scale_v1.preferred_scale, given in 1/120 of scale.
+    pub(crate) fn set_fractional_scale(&mut self, scale : u32) {
+        self.set_scale(scale as f64 / 120.0);
+    }
+
+    /// Apply a new scale factor and queue [WindowEvent::ScaleChanged]. Integer scales are applied with the buffer scale 
+    /// while fractional scales rely on the viewport. Both take effect with the next buffer committed, drawn at the new scale.
+    fn set_scale(&mut self, scale : f64) {
+        if scale != self.scale {
+            self.scale = scale;
+            #[cfg(feature = "opengl")]
+            self.gl_size.set((self.gl_size.get().0, scale));
+
+            // wl_surface.set_buffer_scale exists since version 3.
+            if self.viewport == 0 && self.connection.borrow().version(self.surface) >= 3 {
+                self.request(self.surface, WL_SURFACE_SET_BUFFER_SCALE, vec![WaylandArg::Int(scale as i32)]);
+            }
+            self.events.borrow_mut().push_back(WindowManagerEvent::new(self.handle(), WindowEvent::ScaleChanged(scale)));
+        }
+    }
+
+    /// Map buffers of any size to the window size, applied with the next commit.
+    fn set_viewport(&self, size : WindowSize) {
+        if self.viewport != 0 {
+            self.request(self.viewport, WP_VIEWPORT_SET_DESTINATION, vec![WaylandArg::Int(size.width as i32), WaylandArg::Int(size.height as i32)]);
+        }
+    }
+
+    /// Wayland window handle of the libwayland wl_surface.
+    /// 
+    /// Returns Err(HandleError::NotSupported) if libwayland-client is missing, since the native wire protocol has no wl_surface.
+    #[cfg(feature = "raw_window_handle")]
+    pub fn raw_window_handle(&self) -> Result<raw_window_handle::RawWindowHandle, raw_window_handle::HandleError> {
+        match std::ptr::NonNull::new(self.connection.borrow().proxy_ptr(self.surface)) {
+            Some(surface) => Ok(raw_window_handle::WaylandWindowHandle::new(surface).into()),
+            None => Err(raw_window_handle::HandleError::NotSupported),
+        }
+    }
+
+    /// Vulkan surface with VK_KHR_wayland_surface on the libwayland wl_surface.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowVulkanUnsupported`]) if libwayland-client is missing, since the native wire protocol 
+    /// has no wl_surface.
+    #[cfg(feature = "vulkan")]
+    pub unsafe fn create_vulkan_surface(&self, instance : crate::vulkan::VkInstance, 
+        get_instance_proc_addr : crate::vulkan::VkGetInstanceProcAddr) -> Result<crate::vulkan::VkSurfaceKHR, WindowError> {
+        let (display, surface) = {
+            let conn = self.connection.borrow();
+            (conn.display_ptr(), conn.proxy_ptr(self.surface))
+        };
+
+        if display.is_null() || surface.is_null() {
+            Err(WindowError::WindowVulkanUnsupported)
+        } else {
+            super::vulkan::wayland_vulkan_surface(display, surface, instance, get_instance_proc_addr)
+        }
+    }
+
+    /// OpenGL context with EGL on a wl_egl_window of the libwayland wl_surface, following the window size and scale.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowOpenGLUnsupported`]) if libwayland-client is missing, since the native wire protocol 
+    /// has no wl_surface.
+    #[cfg(feature = "opengl")]
+    pub fn create_gl_context(&self, config : &crate::opengl::GLConfig, shared : Option<&crate::opengl::GLContext>) -> Result<crate::opengl::GLContext, WindowError> {
+        use crate::linux::opengl::LinuxGLContext;
+
+        let (display, surface) = {
+            let conn = self.connection.borrow();
+            (conn.display_ptr(), conn.proxy_ptr(self.surface))
+        };
+        if display.is_null() || surface.is_null() {
+            return Err(WindowError::WindowOpenGLUnsupported);
+        }
+
+        let shared = match shared.map(|shared| &shared.context) {
+            Some(LinuxGLContext::Wayland(shared)) => Some(shared),
+            Some(_) => return Err(WindowError::WindowOpenGLContextFailed),
+            None => None,
+        };
+        let context = unsafe { super::opengl::WaylandGLContext::new(self.connection.clone(), display, surface, self.gl_size.clone(), 
+            config, shared)? };
+        Ok(crate::opengl::GLContext { context : LinuxGLContext::Wayland(context) })
+    }
+
+    /// Pixels of the software framebuffer of `size` in pixels.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowFramebufferFailed`]) if wl_shm isn't available or memory can't be shared.
+    #[inline(always)]
+    pub fn framebuffer(&mut self, size : WindowSize) -> Result<&mut [u32], WindowError> {
+        self.framebuffer.pixels(size).ok_or(WindowError::WindowFramebufferFailed)
+    }
+
+    /// Attach and commit the framebuffer with its damage. Buffers can't be attached before the first configure or while hidden.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowFramebufferFailed`]) if wl_shm isn't available or memory can't be shared.
+    #[inline(always)]
+    pub fn present(&mut self, size : WindowSize, damage : &[(WindowPosition, WindowSize)]) -> Result<(), WindowError> {
+        if !self.shown || !self.configured {
+            Ok(())
+        } else if self.framebuffer.present(size, damage) {
+            Ok(())
+        } else {
+            Err(WindowError::WindowFramebufferFailed)
+        }
+    }
+
+    /// Cairo image surface of the framebuffer back buffer of `size`. Surface changes with the back buffer.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowFramebufferFailed`]) if the back buffer couldn't be allocated.
+    #[cfg(feature = "cairo")]
+    #[inline(always)]
+    pub fn cairo_surface(&mut self, size : WindowSize) -> Result<crate::cairo::CairoSurface, WindowError> {
+        let pixels = self.framebuffer.pixels(size).ok_or(WindowError::WindowFramebufferFailed)?;
+        self.cairo.image(pixels, size)
+    }
+
+    /// Flush Cairo drawing and present the framebuffer.
+    #[cfg(feature = "cairo")]
+    #[inline(always)]
+    pub fn cairo_present(&mut self, size : WindowSize, damage : &[(WindowPosition, WindowSize)]) -> Result<(), WindowError> {
+        self.cairo.flush();
+        self.present(size, damage)
+    }
+
+    /// Take an explicit grab with the serial of the last input, dismissing the popup when clicking outside. 
+    /// Must be done before the popup is shown.
+    pub(crate) fn popup_grab(&mut self) {
+        let (seat, serial) = self.activation.as_ref().map_or((0, 0), |a| (a.borrow().seat, a.borrow().serial));
+        if self.popup != 0 && seat != 0 && serial != 0 {
+            self.request(self.popup, XDG_POPUP_GRAB, vec![WaylandArg::Object(seat), WaylandArg::Uint(serial)]);
+        }
+    }
+
+    /// Keep the size of a xdg_popup.configure until xdg_surface.configure. Position is chosen by the compositor.
+    pub(crate) fn popup_configure(&mut self, width : i32, height : i32) {
+        self.pending.size = (width > 0 && height > 0).then(|| WindowSize::new(width as u32, height as u32));
+    }
+
+    /// Keep states of a xdg_toplevel.configure until xdg_surface.configure.
+    pub(crate) fn toplevel_configure(&mut self, width : i32, height : i32, states : &[u8]) {
+        self.pending.size = if width > 0 && height > 0 {
+            Some(WindowSize::new(width as u32, height as u32))
+        } else {
+            None
+        };
+
+        let states : Vec<u32> = states.chunks_exact(4).map(|s| u32::from_ne_bytes(s.try_into().unwrap())).collect();
+        self.pending.maximized = states.contains(&XDG_TOPLEVEL_STATE_MAXIMIZED);
+        self.pending.fullscreen = states.contains(&XDG_TOPLEVEL_STATE_FULLSCREEN);
+    }
+
+    /// Acknowledge a xdg_surface.configure and apply pending states. `size` is the current size of the window.
+    /// 
+    /// Size suggested by the compositor includes the client-side title bar, which is removed from the window size.
+    /// 
+    /// Returns [WindowEvent]s generated by states changes.
+    pub(crate) fn surface_configure(&mut self, serial : u32, size : WindowSize) -> Vec<WindowEvent> {
+        let mut events : Vec<WindowEvent> = Vec::new();
+
+        self.request(self.xdg_surface, XDG_SURFACE_ACK_CONFIGURE, vec![WaylandArg::Uint(serial)]);
+
+        let content = {
+            let mut decoration = self.decoration.borrow_mut();
+            decoration.set_states(self.pending.maximized, self.pending.fullscreen);
+            let title = decoration.title_height() as u32;
+            self.pending.size.map(|s| WindowSize::new(s.width, s.height.saturating_sub(title).max(1)))
+        };
+
+        if !self.configured && self.shown {
+            self.configured = true;
+            events.push(WindowEvent::Shown);
+        }
+
+        // Leaving a state restore the window before entering the remaining one.
+        let restored = (self.current.maximized && !self.pending.maximized) || (self.current.fullscreen && !self.pending.fullscreen);
+        if restored {
+            events.push(WindowEvent::Restored);
+        }
+        if self.pending.maximized && (restored || !self.current.maximized) {
+            events.push(WindowEvent::Maximized);
+        }
+        if self.pending.fullscreen && (restored || !self.current.fullscreen) {
+            events.push(WindowEvent::Fullscreen);
+        }
+
+        match content {
+            Some(content) if content != size => events.push(WindowEvent::Resized(content)),
+            _ => {},
+        }
+
+        self.current = self.pending;
+        self.decoration.borrow_mut().set_size(content.unwrap_or(size));
+        self.set_viewport(content.unwrap_or(size));
+        #[cfg(feature = "opengl")]
+        self.gl_size.set((content.unwrap_or(size), self.scale));
+
+        // Configured surface must be drawn again, like an exposed window.
+        events.extend(self.redraw(None));
+        events
+    }
+
+    /// Commit surface states if shown. Before shown, states are applied with the initial commit.
+    fn commit(&mut self) {
+        if self.shown {
+            self.request(self.surface, WL_SURFACE_COMMIT, vec![]);
+        }
+    }
+
+    /// Send a request to the xdg_toplevel. Ignored by popups which have no toplevel states.
+    fn toplevel_request(&self, opcode : u16, args : Vec<WaylandArg>) {
+        if self.toplevel != 0 {
+            self.request(self.toplevel, opcode, args);
+        }
+    }
+
+    /// Send a request and flush it.
+    fn request(&self, object : WaylandId, opcode : u16, args : Vec<WaylandArg>) {
+        let mut conn = self.connection.borrow_mut();
+        conn.send(object, opcode, args);
+
+        // Lost connection is reported by the manager dispatch.
+        let _ = conn.flush();
+    }
+}
@@ -21,3 +21,814 @@ LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
+
+//! Wayland wire protocol client. 
+//! 
+//! Connections use libwayland-client when it can be loaded, since Vulkan, EGL and raw-window-handle users need a 
+//! libwayland `wl_display` and `wl_surface`. libwayland only carries the bytes : messages are described by [super::protocol] 
+//! and handled as [WaylandMessage] with either transport, so the window manager doesn't depend on generated bindings.
+//! 
+//! Without libwayland, messages are encoded and decoded in Rust over the compositor unix socket and file descriptors are 
+//! passed as SCM_RIGHTS ancillary data. The mock compositor of tests always uses this transport.
+//! 
+//! Reference(s)
+//! <https://wayland.freedesktop.org/docs/html/ch04.html>
+
+use std::{collections::VecDeque, ffi::c_void, io::{Error, ErrorKind}, os::{fd::{AsRawFd, FromRawFd, OwnedFd, RawFd}, unix::net::UnixStream}, path::PathBuf};
+
+use crate::WindowError;
+
+use super::{libwayland::WaylandLibDisplay, protocol::{interface_by_name, WaylandInterface, WL_DISPLAY, WL_DISPLAY_ID}};
+
+/// Id of a Wayland object.
+pub(crate) type WaylandId = u32;
+
+/// Size of a message header (sender id, size and opcode).
+const WL_HEADER_SIZE : usize = 8;
+
+/// Maximum size of a Wayland message.
+const WL_MAX_MESSAGE_SIZE : usize = 4096;
+
+/// Maximum file descriptors sent with one sendmsg, same as libwayland.
+const WL_MAX_FDS : usize = 28;
+
+/// First id of objects created by the server.
+pub(crate) const WL_SERVER_ID_START : WaylandId = 0xff00_0000;
+
+/// Argument of a Wayland message.
+#[derive(Debug)]
+pub(crate) enum WaylandArg {
+    /// Signed 32 bits integer
+    Int(i32),
+
+    /// Unsigned 32 bits integer
+    Uint(u32),
+
+    /// Signed 24.8 fixed decimal number
+    Fixed(f64),
+
+    /// String that can be null
+    Str(Option<String>),
+
+    /// Object id. 0 is null.
+    Object(WaylandId),
+
+    /// Id of a newly created object.
+    NewId(WaylandId),
+
+    /// Array of bytes
+    Array(Vec<u8>),
+
+    /// File descriptor
+    Fd(OwnedFd),
+}
+
+/// Message sent or received on a [WaylandConnection].
+#[derive(Debug)]
+pub(crate) struct WaylandMessage {
+    /// Object that sent or receive the message.
+    pub sender : WaylandId,
+
+    /// Opcode of the request or event.
+    pub opcode : u16,
+
+    /// Arguments of the message.
+    pub args : Vec<WaylandArg>,
+}
+
+impl WaylandMessage {
+    /// Returns argument at index as u32. Objects and new ids are returned as their id.
+    pub fn uint(&self, index : usize) -> u32 {
+        match self.args.get(index) {
+            Some(WaylandArg::Uint(v)) | Some(WaylandArg::Object(v)) | Some(WaylandArg::NewId(v)) => *v,
+            Some(WaylandArg::Int(v)) => *v as u32,
+            _ => 0,
+        }
+    }
+
+    /// Returns argument at index as i32.
+    pub fn int(&self, index : usize) -> i32 {
+        match self.args.get(index) {
+            Some(WaylandArg::Int(v)) => *v,
+            Some(WaylandArg::Uint(v)) => *v as i32,
+            _ => 0,
+        }
+    }
+
+    /// Returns fixed argument at index as f64.
+    pub fn fixed(&self, index : usize) -> f64 {
+        match self.args.get(index) {
+            Some(WaylandArg::Fixed(v)) => *v,
+            _ => 0.0,
+        }
+    }
+
+    /// Returns string argument at index. Null strings are empty.
+    pub fn string(&self, index : usize) -> &str {
+        match self.args.get(index) {
+            Some(WaylandArg::Str(Some(v))) => v,
+            _ => "",
+        }
+    }
+
+    /// Returns array argument at index.
+    pub fn array(&self, index : usize) -> &[u8] {
+        match self.args.get(index) {
+            Some(WaylandArg::Array(v)) => v,
+            _ => &[],
+        }
+    }
+
+    /// Take the file descriptor argument at index.
+    pub fn take_fd(&mut self, index : usize) -> Option<OwnedFd> {
+        if let Some(WaylandArg::Fd(_)) = self.args.get(index) {
+            match std::mem::replace(&mut self.args[index], WaylandArg::Int(-1)) {
+                WaylandArg::Fd(fd) => Some(fd),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// Object known by a [WaylandConnection].
+#[derive(Debug)]
+struct WaylandObject {
+    /// Interface of the object
+    interface : &'static WaylandInterface,
+
+    /// Version of the object
+    version : u32,
+
+    /// False once destroyed, until the id is deleted. Events of destroyed objects are decoded then ignored.
+    alive : bool,
+}
+
+/// Transport carrying the messages of a [WaylandConnection].
+#[derive(Debug)]
+enum WaylandTransport {
+    /// Messages encoded by nswindow over the socket
+    Socket(UnixStream),
+
+    /// Messages marshalled and dispatched by libwayland
+    Libwayland(WaylandLibDisplay),
+}
+
+/// Connection to a Wayland compositor. Can also be used as the server side by the mock compositor.
+#[derive(Debug)]
+pub(crate) struct WaylandConnection {
+    /// Transport of messages
+    transport : WaylandTransport,
+
+    /// Connection is the server side, decoding requests instead of events.
+    server : bool,
+
+    /// Objects indexed by id. Server created objects are in `server_objects`.
+    objects : Vec<Option<WaylandObject>>,
+
+    /// Objects created by the other side with ids starting at [WL_SERVER_ID_START].
+    server_objects : Vec<Option<WaylandObject>>,
+
+    /// Ids deleted available for reuse.
+    free_ids : Vec<WaylandId>,
+
+    /// Bytes to send
+    out_bytes : Vec<u8>,
+
+    /// File descriptors to send with the offset in bytes to send of the message carrying them
+    out_fds : VecDeque<(usize, OwnedFd)>,
+
+    /// Bytes received not decoded yet
+    in_bytes : Vec<u8>,
+
+    /// File descriptors received not decoded yet
+    in_fds : VecDeque<OwnedFd>,
+}
+
+impl WaylandConnection {
+
+    /// Connect to the compositor socket given by `$WAYLAND_SOCKET` or `$WAYLAND_DISPLAY`, with libwayland if it can be loaded.
+    /// 
+    /// `$WAYLAND_DISPLAY` is relative to `$XDG_RUNTIME_DIR` unless absolute and is `wayland-0` if not set.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowManagerDisplayUnset`]) if `$XDG_RUNTIME_DIR` is needed but not set, or if `$WAYLAND_DISPLAY`
+    /// isn't set and `wayland-0` can't be connected.
+    /// Returns Err([`WindowError::WindowManagerConnectionFailed`]) if socket cannot be connected.
+    pub fn connect() -> Result<WaylandConnection, WindowError> {
+
+        // Errors are reported by the native connection, which fails the same way.
+        if let Some(display) = WaylandLibDisplay::connect() {
+            return Ok(WaylandConnection::new(WaylandTransport::Libwayland(display), false));
+        }
+
+        // Socket already connected by parent process.
+        if let Ok(fd) = std::env::var("WAYLAND_SOCKET") {
+            return match fd.parse::<RawFd>() {
+                Ok(fd) => {
+                    std::env::remove_var("WAYLAND_SOCKET");
+                    Ok(WaylandConnection::from_stream(unsafe { UnixStream::from_raw_fd(fd) }, false))
+                },
+                Err(_) => Err(WindowError::WindowManagerConnectionFailed(format!("WAYLAND_SOCKET={}", fd))),
+            };
+        }
+
+        match wayland_socket_path() {
+            Some(path) => match UnixStream::connect(&path) {
+                Ok(stream) => Ok(WaylandConnection::from_stream(stream, false)),
+                Err(_) if std::env::var_os("WAYLAND_DISPLAY").is_none() => Err(WindowError::WindowManagerDisplayUnset("WAYLAND_DISPLAY".to_string())),
+                Err(_) => Err(WindowError::WindowManagerConnectionFailed(path.display().to_string())),
+            },
+            None => Err(WindowError::WindowManagerDisplayUnset("XDG_RUNTIME_DIR".to_string())),
+        }
+    }
+
+    /// Create a connection from an already connected stream. `server` is true for the compositor side.
+    pub fn from_stream(stream : UnixStream, server : bool) -> WaylandConnection {
+        WaylandConnection::new(WaylandTransport::Socket(stream), server)
+    }
+
+    /// Create a client connection using libwayland on an already connected stream.
+    /// 
+    /// Returns None if libwayland-client is missing.
+    #[cfg(all(test, any(feature = "raw_window_handle", feature = "vulkan", feature = "opengl")))]
+    pub fn from_libwayland(stream : UnixStream) -> Option<WaylandConnection> {
+        WaylandLibDisplay::connect_to_fd(stream.into()).map(|display| WaylandConnection::new(WaylandTransport::Libwayland(display), false))
+    }
+
+    /// Create a connection using a transport.
+    fn new(transport : WaylandTransport, server : bool) -> WaylandConnection {
+        let mut connection = WaylandConnection { transport, server, objects : Vec::new(), server_objects : Vec::new(), free_ids : Vec::new(), 
+            out_bytes : Vec::new(), out_fds : VecDeque::new(), in_bytes : Vec::new(), in_fds : VecDeque::new() };
+
+        // Id 0 is null and 1 is always wl_display.
+        connection.objects.push(None);
+        connection.insert_object(WL_DISPLAY_ID, &WL_DISPLAY, 1);
+        connection
+    }
+
+    /// Raw file descriptor of the socket, used to poll.
+    pub fn fd(&self) -> RawFd {
+        match &self.transport {
+            WaylandTransport::Socket(stream) => stream.as_raw_fd(),
+            WaylandTransport::Libwayland(display) => display.fd(),
+        }
+    }
+
+    /// libwayland `wl_display` pointer, null if connection doesn't use libwayland.
+    pub fn display_ptr(&self) -> *mut c_void {
+        match &self.transport {
+            WaylandTransport::Socket(_) => std::ptr::null_mut(),
+            WaylandTransport::Libwayland(display) => display.display(),
+        }
+    }
+
+    /// libwayland `wl_proxy` pointer of an object, null if it doesn't exist or connection doesn't use libwayland.
+    pub fn proxy_ptr(&self, id : WaylandId) -> *mut c_void {
+        match &self.transport {
+            WaylandTransport::Socket(_) => std::ptr::null_mut(),
+            WaylandTransport::Libwayland(display) => display.proxy(id),
+        }
+    }
+
+    /// Create a new object and returns its id. Ids of deleted objects are reused.
+    pub fn new_object(&mut self, interface : &'static WaylandInterface, version : u32) -> WaylandId {
+        if let WaylandTransport::Libwayland(display) = &mut self.transport {
+            let id = display.create(interface);
+            if id != 0 {
+                self.insert_object(id, interface, version);
+            }
+            return id;
+        }
+
+        let object = Some(WaylandObject { interface, version, alive : true });
+
+        if self.server {
+            let id = WL_SERVER_ID_START + self.server_objects.len() as WaylandId;
+            self.server_objects.push(object);
+            id
+        } else {
+            match self.free_ids.pop() {
+                Some(id) => {
+                    self.objects[id as usize] = object;
+                    id
+                },
+                None => {
+                    self.objects.push(object);
+                    (self.objects.len() - 1) as WaylandId
+                },
+            }
+        }
+    }
+
+    /// Insert an object created by the other side.
+    pub fn insert_object(&mut self, id : WaylandId, interface : &'static WaylandInterface, version : u32) {
+        let (list, index) = if id >= WL_SERVER_ID_START {
+            (&mut self.server_objects, (id - WL_SERVER_ID_START) as usize)
+        } else {
+            (&mut self.objects, id as usize)
+        };
+
+        if list.len() <= index {
+            list.resize_with(index + 1, || None);
+        }
+        list[index] = Some(WaylandObject { interface, version, alive : true });
+    }
+
+    /// Returns the interface of an object if it exists.
+    pub fn interface(&self, id : WaylandId) -> Option<&'static WaylandInterface> {
+        self.object(id).map(|o| o.interface)
+    }
+
+    /// Returns the version of an object, 0 if it doesn't exist.
+    pub fn version(&self, id : WaylandId) -> u32 {
+        self.object(id).map_or(0, |o| o.version)
+    }
+
+    /// Returns true if object exists and isn't destroyed.
+    pub fn alive(&self, id : WaylandId) -> bool {
+        self.object(id).map_or(false, |o| o.alive)
+    }
+
+    /// Mark an object as destroyed after its destructor request was sent. Id is freed when the server sends `wl_display.delete_id`.
+    /// 
+    /// With libwayland, the proxy is destroyed and the object forgotten right away since libwayland handles `wl_display.delete_id`.
+    pub fn destroyed(&mut self, id : WaylandId) {
+        if let WaylandTransport::Libwayland(display) = &mut self.transport {
+            display.destroy(id);
+            if id > WL_DISPLAY_ID {
+                if let Some(object) = self.objects.get_mut(id as usize) {
+                    *object = None;
+                }
+            }
+            return;
+        }
+
+        if let Some(object) = self.object_mut(id) {
+            object.alive = false;
+        }
+    }
+
+    /// Delete an object and free its id for reuse.
+    pub fn delete(&mut self, id : WaylandId) {
+        if id >= WL_SERVER_ID_START {
+            if let Some(object) = self.server_objects.get_mut((id - WL_SERVER_ID_START) as usize) {
+                *object = None;
+            }
+        } else if id > WL_DISPLAY_ID && (id as usize) < self.objects.len() && self.objects[id as usize].is_some() {
+            self.objects[id as usize] = None;
+            if !self.server {
+                self.free_ids.push(id);
+            }
+        }
+    }
+
+    /// Encode a message in the send buffer. Use [WaylandConnection::flush] to send.
+    /// 
+    /// Messages carry at most [WL_MAX_FDS] file descriptors, which is more than any message of the protocol.
+    pub fn send(&mut self, sender : WaylandId, opcode : u16, args : Vec<WaylandArg>) {
+        if let WaylandTransport::Libwayland(display) = &mut self.transport {
+            display.send(sender, opcode, args);
+            return;
+        }
+
+        let start = self.out_bytes.len();
+
+        // Header is written once size is known.
+        self.out_bytes.extend_from_slice(&[0; WL_HEADER_SIZE]);
+
+        for arg in args {
+            match arg {
+                WaylandArg::Int(v) => self.out_bytes.extend_from_slice(&v.to_ne_bytes()),
+                WaylandArg::Uint(v) | WaylandArg::Object(v) | WaylandArg::NewId(v) => self.out_bytes.extend_from_slice(&v.to_ne_bytes()),
+                WaylandArg::Fixed(v) => self.out_bytes.extend_from_slice(&((v * 256.0) as i32).to_ne_bytes()),
+                WaylandArg::Str(None) => self.out_bytes.extend_from_slice(&0u32.to_ne_bytes()),
+                WaylandArg::Str(Some(v)) => {
+                    // Length include the null terminator.
+                    let mut bytes = v.into_bytes();
+                    bytes.push(0);
+                    self.write_array(&bytes);
+                },
+                WaylandArg::Array(v) => self.write_array(&v),
+                WaylandArg::Fd(fd) => self.out_fds.push_back((start, fd)),
+            }
+        }
+
+        let size = (self.out_bytes.len() - start) as u32;
+        self.out_bytes[start..start + 4].copy_from_slice(&sender.to_ne_bytes());
+        self.out_bytes[start + 4..start + 8].copy_from_slice(&((size << 16) | opcode as u32).to_ne_bytes());
+    }
+
+    /// Write an array with length and padding to 32 bits.
+    fn write_array(&mut self, bytes : &[u8]) {
+        self.out_bytes.extend_from_slice(&(bytes.len() as u32).to_ne_bytes());
+        self.out_bytes.extend_from_slice(bytes);
+        while self.out_bytes.len() % 4 != 0 {
+            self.out_bytes.push(0);
+        }
+    }
+
+    /// Send all encoded messages and file descriptors to the other side.
+    /// 
+    /// File descriptors are received before the bytes of their message, never after. When more than [WL_MAX_FDS] are queued, 
+    /// bytes are sent up to the first message whose file descriptors don't fit, which are sent with the next sendmsg.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowManagerConnectionLost`]) if socket is closed.
+    pub fn flush(&mut self) -> Result<(), WindowError> {
+        let socket = match &mut self.transport {
+            WaylandTransport::Socket(stream) => stream.as_raw_fd(),
+            WaylandTransport::Libwayland(display) => return display.flush(),
+        };
+
+        while !self.out_bytes.is_empty() {
+            let mut count = self.out_fds.len().min(WL_MAX_FDS);
+            let mut end = self.out_bytes.len();
+            if count < self.out_fds.len() {
+                // File descriptors of a message are never split between two sendmsg.
+                end = self.out_fds[count].0;
+                while count > 0 && self.out_fds[count - 1].0 == end {
+                    count -= 1;
+                }
+            }
+            let raw : Vec<RawFd> = self.out_fds.iter().take(count).map(|(_, fd)| fd.as_raw_fd()).collect();
+
+            match send_with_fds(socket, &self.out_bytes[..end], &raw) {
+                Ok(sent) => { 
+                    self.out_bytes.drain(..sent);
+
+                    // File descriptors are duplicated by the kernel and can be closed.
+                    self.out_fds.drain(..count);
+                    for (offset, _) in self.out_fds.iter_mut() {
+                        *offset -= sent;
+                    }
+                },
+                Err(err) if err.kind() == ErrorKind::Interrupted => {},
+                Err(_) => return Err(WindowError::WindowManagerConnectionLost),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read available bytes and file descriptors from socket. If `blocking` is false, returns Ok(false) when nothing is available.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowManagerConnectionLost`]) if socket is closed.
+    pub fn read(&mut self, blocking : bool) -> Result<bool, WindowError> {
+        let socket = match &mut self.transport {
+            WaylandTransport::Socket(stream) => stream.as_raw_fd(),
+            WaylandTransport::Libwayland(display) => return display.read(blocking),
+        };
+
+        let mut buffer = [0u8; WL_MAX_MESSAGE_SIZE];
+        let mut fds : Vec<OwnedFd> = Vec::new();
+
+        match recv_with_fds(socket, &mut buffer, &mut fds, blocking) {
+            Ok(0) => Err(WindowError::WindowManagerConnectionLost),
+            Ok(read) => {
+                self.in_bytes.extend_from_slice(&buffer[..read]);
+                self.in_fds.extend(fds);
+                Ok(true)
+            },
+            Err(err) if err.kind() == ErrorKind::WouldBlock || err.kind() == ErrorKind::Interrupted => Ok(false),
+            Err(_) => Err(WindowError::WindowManagerConnectionLost),
+        }
+    }
+
+    /// Wait until socket is readable or timeout in milliseconds expire. Negative timeout wait forever.
+    /// 
+    /// Returns true if socket is readable.
+    pub fn poll(&self, timeout : i32) -> bool {
+        let socket = match &self.transport {
+            WaylandTransport::Socket(stream) => stream.as_raw_fd(),
+            WaylandTransport::Libwayland(display) => return display.poll(timeout),
+        };
+
+        let mut pfd = libc::pollfd { fd : socket, events : libc::POLLIN, revents : 0 };
+        unsafe { libc::poll(&mut pfd, 1, timeout) > 0 }
+    }
+
+    /// Decode the next complete message received. 
+    /// 
+    /// Returns None if no complete message is available.
+    /// 
+    /// # Errors
+    /// Returns Some(Err([`WindowError::WindowManagerConnectionLost`])) if message is malformed or sent by an unknown object.
+    pub fn next_message(&mut self) -> Option<Result<WaylandMessage, WindowError>> {
+        if let WaylandTransport::Libwayland(display) = &mut self.transport {
+            return display.next_message().map(Ok);
+        }
+
+        if self.in_bytes.len() < WL_HEADER_SIZE {
+            return None;
+        }
+
+        let sender = u32::from_ne_bytes(self.in_bytes[0..4].try_into().unwrap());
+        let word = u32::from_ne_bytes(self.in_bytes[4..8].try_into().unwrap());
+        let size = (word >> 16) as usize;
+        let opcode = (word & 0xffff) as u16;
+
+        if size < WL_HEADER_SIZE {
+            return Some(Err(WindowError::WindowManagerConnectionLost));
+        }
+        if self.in_bytes.len() < size {
+            return None;
+        }
+
+        let body : Vec<u8> = self.in_bytes.drain(..size).skip(WL_HEADER_SIZE).collect();
+
+        let interface = match self.interface(sender) {
+            Some(interface) => interface,
+            None => return Some(Err(WindowError::WindowManagerConnectionLost)),
+        };
+        let messages = if self.server { interface.requests } else { interface.events };
+        let desc = match messages.get(opcode as usize) {
+            Some(desc) => desc,
+            None => return Some(Err(WindowError::WindowManagerConnectionLost)),
+        };
+
+        let version = self.version(sender);
+        match self.decode(version, desc.signature, desc.new_id, &body) {
+            Some(args) => Some(Ok(WaylandMessage { sender, opcode, args })),
+            None => Some(Err(WindowError::WindowManagerConnectionLost)),
+        }
+    }
+
+    /// Decode arguments of a message body from its signature. Objects created by the message are inserted with the `version` of the sender.
+    fn decode(&mut self, version : u32, signature : &str, new_id : Option<&'static WaylandInterface>, body : &[u8]) -> Option<Vec<WaylandArg>> {
+        let mut args : Vec<WaylandArg> = Vec::new();
+        let mut pos : usize = 0;
+
+        let mut word = |pos : &mut usize| -> Option<u32> {
+            let bytes = body.get(*pos..*pos + 4)?;
+            *pos += 4;
+            Some(u32::from_ne_bytes(bytes.try_into().unwrap()))
+        };
+
+        for c in signature.chars() {
+            match c {
+                '?' => continue,
+                'i' => args.push(WaylandArg::Int(word(&mut pos)? as i32)),
+                'u' => args.push(WaylandArg::Uint(word(&mut pos)?)),
+                'f' => args.push(WaylandArg::Fixed(word(&mut pos)? as i32 as f64 / 256.0)),
+                'o' => args.push(WaylandArg::Object(word(&mut pos)?)),
+                'n' => {
+                    let id = word(&mut pos)?;
+
+                    // wl_registry.bind give interface name and version before id.
+                    let interface = match new_id {
+                        Some(interface) => Some((interface, version)),
+                        None => match args.iter().rev().find_map(|a| if let WaylandArg::Str(Some(name)) = a { Some(name) } else { None }) {
+                            Some(name) => interface_by_name(name).map(|i| (i, self.version_hint(&args))),
+                            None => None,
+                        },
+                    };
+
+                    if let Some((interface, version)) = interface {
+                        self.insert_object(id, interface, version);
+                    }
+                    args.push(WaylandArg::NewId(id));
+                },
+                's' | 'a' => {
+                    let len = word(&mut pos)? as usize;
+                    let bytes = body.get(pos..pos + len)?.to_vec();
+                    pos += (len + 3) & !3;
+
+                    if c == 'a' {
+                        args.push(WaylandArg::Array(bytes));
+                    } else if len == 0 {
+                        args.push(WaylandArg::Str(None));
+                    } else {
+                        // Remove null terminator
+                        args.push(WaylandArg::Str(Some(String::from_utf8_lossy(&bytes[..len - 1]).to_string())));
+                    }
+                },
+                'h' => args.push(WaylandArg::Fd(self.in_fds.pop_front()?)),
+                _ => return None,
+            }
+        }
+
+        Some(args)
+    }
+
+    /// Version of an object bound by wl_registry.bind, which is the last uint given or 1.
+    fn version_hint(&self, args : &[WaylandArg]) -> u32 {
+        match args.last() {
+            Some(WaylandArg::Uint(version)) => *version,
+            _ => 1,
+        }
+    }
+
+    /// Find an object from id.
+    fn object(&self, id : WaylandId) -> Option<&WaylandObject> {
+        if id >= WL_SERVER_ID_START {
+            self.server_objects.get((id - WL_SERVER_ID_START) as usize)?.as_ref()
+        } else {
+            self.objects.get(id as usize)?.as_ref()
+        }
+    }
+
+    /// Find a mutable object from id.
+    fn object_mut(&mut self, id : WaylandId) -> Option<&mut WaylandObject> {
+        if id >= WL_SERVER_ID_START {
+            self.server_objects.get_mut((id - WL_SERVER_ID_START) as usize)?.as_mut()
+        } else {
+            self.objects.get_mut(id as usize)?.as_mut()
+        }
+    }
+}
+
+/// Get the compositor socket path from `$WAYLAND_DISPLAY` and `$XDG_RUNTIME_DIR`.
+/// 
+/// Returns None if path is relative and `$XDG_RUNTIME_DIR` isn't set.
+pub(crate) fn wayland_socket_path() -> Option<PathBuf> {
+    let display = std::env::var("WAYLAND_DISPLAY").unwrap_or("wayland-0".to_string());
+    let display = PathBuf::from(display);
+
+    if display.is_absolute() {
+        Some(display)
+    } else {
+        std::env::var("XDG_RUNTIME_DIR").ok().map(|dir| PathBuf::from(dir).join(display))
+    }
+}
+
+/// Send bytes with file descriptors as SCM_RIGHTS ancillary data.
+fn send_with_fds(socket : RawFd, bytes : &[u8], fds : &[RawFd]) -> std::io::Result<usize> {
+    unsafe {
+        let mut iov = libc::iovec { iov_base : bytes.as_ptr() as *mut libc::c_void, iov_len : bytes.len() };
+        let mut cmsg_buffer = vec![0u8; libc::CMSG_SPACE((WL_MAX_FDS * std::mem::size_of::<RawFd>()) as u32) as usize];
+
+        let mut msg : libc::msghdr = std::mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+
+        if !fds.is_empty() {
+            let fds_size = std::mem::size_of_val(fds);
+            msg.msg_control = cmsg_buffer.as_mut_ptr() as *mut libc::c_void;
+            msg.msg_controllen = libc::CMSG_SPACE(fds_size as u32) as _;
+
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN(fds_size as u32) as _;
+            std::ptr::copy_nonoverlapping(fds.as_ptr() as *const u8, libc::CMSG_DATA(cmsg), fds_size);
+        }
+
+        let sent = libc::sendmsg(socket, &msg, libc::MSG_NOSIGNAL);
+        if sent < 0 {
+            Err(Error::last_os_error())
+        } else {
+            Ok(sent as usize)
+        }
+    }
+}
+
+/// Receive bytes with file descriptors from SCM_RIGHTS ancillary data.
+fn recv_with_fds(socket : RawFd, bytes : &mut [u8], fds : &mut Vec<OwnedFd>, blocking : bool) -> std::io::Result<usize> {
+    unsafe {
+        let mut iov = libc::iovec { iov_base : bytes.as_mut_ptr() as *mut libc::c_void, iov_len : bytes.len() };
+        let mut cmsg_buffer = vec![0u8; libc::CMSG_SPACE((WL_MAX_FDS * std::mem::size_of::<RawFd>()) as u32) as usize];
+
+        let mut msg : libc::msghdr = std::mem::zeroed();
+        msg.msg_iov = &mut iov;
+        msg.msg_iovlen = 1;
+        msg.msg_control = cmsg_buffer.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = cmsg_buffer.len() as _;
+
+        let flags = libc::MSG_CMSG_CLOEXEC | if blocking { 0 } else { libc::MSG_DONTWAIT };
+        let read = libc::recvmsg(socket, &mut msg, flags);
+        if read < 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let data = libc::CMSG_DATA(cmsg) as *const RawFd;
+                let count = ((*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize) / std::mem::size_of::<RawFd>();
+                for i in 0..count {
+                    fds.push(OwnedFd::from_raw_fd(std::ptr::read_unaligned(data.add(i))));
+                }
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+
+        Ok(read as usize)
+    }
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Wayland wire protocol tests
+#[cfg(test)]
+mod tests {
+    use std::{fs::File, os::unix::net::UnixStream};
+
+    use super::{WaylandArg, WaylandConnection, WL_MAX_FDS};
+    use super::super::protocol::{WL_CALLBACK, WL_DISPLAY_ERROR, WL_DISPLAY_GET_REGISTRY, WL_DISPLAY_ID, WL_REGISTRY, WL_REGISTRY_BIND, WL_SHM, 
+        WL_SHM_CREATE_POOL, WL_SHM_POOL};
+
+    #[test]
+    fn wayland_wlib_encode_decode() {
+        let (client, server) = UnixStream::pair().unwrap();
+        let mut client = WaylandConnection::from_stream(client, false);
+        let mut server = WaylandConnection::from_stream(server, true);
+
+        let registry = client.new_object(&WL_REGISTRY, 1);
+        client.send(WL_DISPLAY_ID, WL_DISPLAY_GET_REGISTRY, vec![WaylandArg::NewId(registry)]);
+        client.send(registry, WL_REGISTRY_BIND, vec![WaylandArg::Uint(7), WaylandArg::Str(Some("wl_callback".to_string())), 
+            WaylandArg::Uint(1), WaylandArg::NewId(3)]);
+        client.flush().unwrap();
+
+        assert!(server.read(true).unwrap());
+
+        // Registry is created by get_registry
+        let message = server.next_message().unwrap().unwrap();
+        assert_eq!((message.sender, message.opcode, message.uint(0)), (WL_DISPLAY_ID, WL_DISPLAY_GET_REGISTRY, registry));
+        assert!(server.interface(registry) == Some(&WL_REGISTRY));
+
+        // Bound object interface is given by name
+        let message = server.next_message().unwrap().unwrap();
+        assert_eq!((message.sender, message.opcode, message.uint(0), message.string(1), message.uint(2)), (registry, WL_REGISTRY_BIND, 7, "wl_callback", 1));
+        assert!(server.interface(3) == Some(&WL_CALLBACK));
+        assert!(server.next_message().is_none());
+
+        // String padding and null terminator
+        server.send(WL_DISPLAY_ID, WL_DISPLAY_ERROR, vec![WaylandArg::Object(registry), WaylandArg::Uint(2), WaylandArg::Str(Some("abcd".to_string()))]);
+        server.flush().unwrap();
+        assert!(client.read(true).unwrap());
+
+        let message = client.next_message().unwrap().unwrap();
+        assert_eq!((message.uint(0), message.uint(1), message.string(2)), (registry, 2, "abcd"));
+        assert!(client.next_message().is_none());
+    }
+
+    #[test]
+    fn wayland_wlib_fds() {
+        let (client, server) = UnixStream::pair().unwrap();
+        let mut client = WaylandConnection::from_stream(client, false);
+        let mut server = WaylandConnection::from_stream(server, true);
+
+        let shm = client.new_object(&WL_SHM, 1);
+        server.insert_object(shm, &WL_SHM, 1);
+
+        // More file descriptors than one sendmsg carries, each one must come with its own message.
+        let count = WL_MAX_FDS + 2;
+        for _ in 0..count {
+            let pool = client.new_object(&WL_SHM_POOL, 1);
+            client.send(shm, WL_SHM_CREATE_POOL, vec![WaylandArg::NewId(pool), WaylandArg::Fd(File::open("/dev/null").unwrap().into()), 
+                WaylandArg::Int(4096)]);
+        }
+        client.flush().unwrap();
+        assert!(client.out_fds.is_empty());
+
+        let mut received = 0;
+        while received < count {
+            match server.next_message() {
+                Some(message) => {
+                    assert!(matches!(message.unwrap().args[1], WaylandArg::Fd(_)));
+                    received += 1;
+                },
+                None => assert!(server.read(true).unwrap()),
+            }
+        }
+        assert!(server.in_fds.is_empty());
+    }
+
+    #[test]
+    fn wayland_wlib_object_ids() {
+        let (client, _server) = UnixStream::pair().unwrap();
+        let mut client = WaylandConnection::from_stream(client, false);
+
+        // 1 is wl_display
+        let first = client.new_object(&WL_CALLBACK, 1);
+        let second = client.new_object(&WL_CALLBACK, 1);
+        assert_eq!((first, second), (2, 3));
+
+        // Destroyed object stays until deleted
+        client.destroyed(first);
+        assert!(!client.alive(first));
+        assert!(client.interface(first).is_some());
+
+        client.delete(first);
+        assert!(client.interface(first).is_none());
+        assert_eq!(client.new_object(&WL_REGISTRY, 1), first);
+
+        // wl_display can't be deleted
+        client.delete(WL_DISPLAY_ID);
+        assert!(client.alive(WL_DISPLAY_ID));
+    }
+
+    #[test]
+    fn wayland_wlib_connection_lost() {
+        let (client, server) = UnixStream::pair().unwrap();
+        let mut client = WaylandConnection::from_stream(client, false);
+        drop(server);
+
+        assert!(client.read(true).is_err());
+    }
+}
@@ -0,0 +1,510 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+use std::{collections::HashMap, fs::File, os::{fd::OwnedFd, unix::fs::FileExt}};
+
+use crate::{event::KeyCode, keyboard::KeyboardLayout, linux::keysym::{keysym_from_name, keysym_label, keysym_name, keysym_to_char, Keysym}};
+
+/// Real modifier masks as sent by wl_keyboard.modifiers.
+pub(crate) const WL_XKB_MOD_SHIFT : u32 = 0x01;
+pub(crate) const WL_XKB_MOD_LOCK : u32 = 0x02;
+pub(crate) const WL_XKB_MOD_CONTROL : u32 = 0x04;
+pub(crate) const WL_XKB_MOD_1 : u32 = 0x08;
+pub(crate) const WL_XKB_MOD_2 : u32 = 0x10;
+pub(crate) const WL_XKB_MOD_3 : u32 = 0x20;
+pub(crate) const WL_XKB_MOD_4 : u32 = 0x40;
+pub(crate) const WL_XKB_MOD_5 : u32 = 0x80;
+
+/// Token of the xkb keymap text format.
+#[derive(Debug, Clone, PartialEq)]
+enum XkbToken {
+    /// Identifier or number like `Shift`, `Level2` or `38`
+    Ident(String),
+
+    /// Quoted string without quotes
+    Str(String),
+
+    /// Key name without angle brackets like `AD01`
+    Key(String),
+
+    /// Any other character like `{`, `=` or `+`
+    Punct(char),
+}
+
+/// Key type mapping modifiers to a shift level.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct WaylandKeyType {
+    /// Name of the type like `ALPHABETIC`
+    pub name : String,
+
+    /// Modifiers used by the type, others are ignored.
+    pub modifiers : u32,
+
+    /// Level (0 based) of each combination of modifiers. Combinations not mapped are level 0.
+    pub map : Vec<(u32, usize)>,
+}
+
+/// Symbols of a key.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WaylandKey {
+    /// Explicit type name of each group. None if the type is inferred from symbols.
+    pub types : Vec<Option<String>>,
+
+    /// Keysyms of each level of each group.
+    pub symbols : Vec<Vec<Keysym>>,
+
+    /// False if key must not repeat.
+    pub repeat : bool,
+}
+
+/// Keymap received from the compositor in the xkb text format, resolved without libxkbcommon.
+/// 
+/// Only keycodes, types and symbols are used since modifiers state is computed by the compositor.
+/// 
+/// Reference(s)
+/// <https://xkbcommon.org/doc/current/keymap-text-format-v1.html>
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct WaylandKeymap {
+    /// Types declared in xkb_types
+    pub types : Vec<WaylandKeyType>,
+
+    /// Keys by xkb keycode
+    pub keys : HashMap<KeyCode, WaylandKey>,
+
+    /// Name of each group (layout) like `English (US)`
+    pub groups : Vec<String>,
+}
+
+impl WaylandKeymap {
+
+    /// Parse a keymap from its text.
+    /// 
+    /// Returns None if the keymap lacks the keycodes or symbols sections.
+    pub fn parse(text : &str) -> Option<WaylandKeymap> {
+        let tokens = xkb_tokenize(text);
+        let mut keymap = WaylandKeymap::default();
+
+        // Keycodes by key name, aliases included.
+        let mut keycodes : HashMap<String, KeyCode> = HashMap::new();
+        let mut aliases : Vec<(String, String)> = Vec::new();
+        for statement in xkb_split(xkb_section(&tokens, "xkb_keycodes")?, ';') {
+            match statement {
+                [XkbToken::Key(name), XkbToken::Punct('='), XkbToken::Ident(code)] => if let Ok(code) = code.parse::<KeyCode>() {
+                    keycodes.insert(name.clone(), code);
+                },
+                [XkbToken::Ident(alias), XkbToken::Key(name), XkbToken::Punct('='), XkbToken::Key(real)] if alias == "alias" => 
+                    aliases.push((name.clone(), real.clone())),
+                _ => {},
+            }
+        }
+        for (name, real) in aliases {
+            if let Some(code) = keycodes.get(&real).copied() {
+                keycodes.entry(name).or_insert(code);
+            }
+        }
+
+        // Types are optional since unknown types fallback to shift levels.
+        let mut vmods : HashMap<String, u32> = HashMap::new();
+        for statement in xkb_split(xkb_section(&tokens, "xkb_types").unwrap_or(&[]), ';') {
+            match statement {
+                [XkbToken::Ident(keyword), rest @ ..] if keyword == "virtual_modifiers" => xkb_virtual_modifiers(rest, &mut vmods),
+                [XkbToken::Ident(keyword), XkbToken::Str(name), XkbToken::Punct('{'), body @ .., XkbToken::Punct('}')] if keyword == "type" => 
+                    keymap.types.push(xkb_key_type(name, body, &vmods)),
+                _ => {},
+            }
+        }
+
+        for statement in xkb_split(xkb_section(&tokens, "xkb_symbols")?, ';') {
+            match statement {
+                [XkbToken::Ident(keyword), rest @ ..] if keyword == "name" => 
+                    if let (Some(group), Some(XkbToken::Str(name))) = (xkb_group(rest), rest.last()) {
+                        if keymap.groups.len() <= group {
+                            keymap.groups.resize(group + 1, String::new());
+                        }
+                        keymap.groups[group] = name.clone();
+                    },
+                [XkbToken::Ident(keyword), XkbToken::Key(name), XkbToken::Punct('{'), body @ .., XkbToken::Punct('}')] if keyword == "key" => 
+                    if let Some(code) = keycodes.get(name) {
+                        keymap.keys.insert(*code, xkb_key(body));
+                    },
+                _ => {},
+            }
+        }
+
+        Some(keymap)
+    }
+
+    /// Read and parse a keymap from the shared memory file descriptor sent by wl_keyboard.keymap.
+    /// 
+    /// Returns None if file can't be read or keymap can't be parsed.
+    pub fn from_fd(fd : OwnedFd, size : u32) -> Option<WaylandKeymap> {
+        // File is read at offset 0 since it may be shared with other clients.
+        let mut text = vec![0; size as usize];
+        File::from(fd).read_exact_at(&mut text, 0).ok()?;
+        WaylandKeymap::parse(&String::from_utf8_lossy(&text))
+    }
+
+    /// Get the keysym of a key in `group` with the `modifiers` mask active.
+    /// 
+    /// Returns 0 (NoSymbol) if key has no symbol at that level.
+    pub fn keysym(&self, key : KeyCode, group : u32, modifiers : u32) -> Keysym {
+        let key = match self.keys.get(&key) {
+            Some(key) if !key.symbols.is_empty() => key,
+            _ => return 0,
+        };
+
+        // Groups out of range wrap around like xkb does.
+        let group = group as usize % key.symbols.len();
+        let symbols = &key.symbols[group];
+        let name = match key.types.get(group).cloned().flatten() {
+            Some(name) => name,
+            None => xkb_default_type(symbols).to_string(),
+        };
+
+        let level = match self.types.iter().find(|t| t.name == name) {
+            Some(key_type) => {
+                let active = modifiers & key_type.modifiers;
+                key_type.map.iter().find(|(mask, _)| *mask == active).map(|(_, level)| *level).unwrap_or(0)
+            },
+            None if symbols.len() > 1 && modifiers & WL_XKB_MOD_SHIFT != 0 => 1,
+            None => 0,
+        };
+
+        symbols.get(level).copied().unwrap_or(0)
+    }
+
+    /// Returns false if key is declared as not repeating.
+    pub fn repeats(&self, key : KeyCode) -> bool {
+        self.keys.get(&key).map(|k| k.repeat).unwrap_or(true)
+    }
+
+    /// Get the [KeyboardLayout] of a group.
+    pub fn layout(&self, group : u32) -> KeyboardLayout {
+        KeyboardLayout { group, name : self.groups.get(group as usize).cloned().unwrap_or_default() }
+    }
+
+    /// Get the label of key under the layout group given.
+    /// 
+    /// Printable keys return their uppercase character and others their keysym name like `Escape`.
+    pub fn key_label(&self, key : KeyCode, group : u32) -> Option<String> {
+        match self.keysym(key, group, 0) {
+            0 => None,
+            keysym => Some(keysym_label(keysym).unwrap_or_else(|| keysym_name(keysym))),
+        }
+    }
+}
+
+/// Split keymap text into tokens. Comments are skipped.
+fn xkb_tokenize(text : &str) -> Vec<XkbToken> {
+    let mut tokens : Vec<XkbToken> = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() || c == '\0' => {},
+            '/' if chars.peek() == Some(&'/') => for c in chars.by_ref() {
+                if c == '\n' { break; }
+            },
+            '#' => for c in chars.by_ref() {
+                if c == '\n' { break; }
+            },
+            '"' => tokens.push(XkbToken::Str(chars.by_ref().take_while(|c| *c != '"').collect())),
+            '<' => tokens.push(XkbToken::Key(chars.by_ref().take_while(|c| *c != '>').collect())),
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = String::from(c);
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                    ident.push(c);
+                }
+                tokens.push(XkbToken::Ident(ident));
+            },
+            c => tokens.push(XkbToken::Punct(c)),
+        }
+    }
+
+    tokens
+}
+
+/// Get the tokens inside the braces of a section like `xkb_symbols "pc+us" { ... };`.
+fn xkb_section<'a>(tokens : &'a [XkbToken], name : &str) -> Option<&'a [XkbToken]> {
+    let start = tokens.iter().position(|t| matches!(t, XkbToken::Ident(i) if i == name))?;
+    let open = start + tokens[start..].iter().position(|t| *t == XkbToken::Punct('{'))? + 1;
+
+    let mut depth = 1;
+    for (index, token) in tokens.iter().enumerate().skip(open) {
+        match token {
+            XkbToken::Punct('{') => depth += 1,
+            XkbToken::Punct('}') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&tokens[open..index]);
+                }
+            },
+            _ => {},
+        }
+    }
+    None
+}
+
+/// Split tokens on `separator` outside of braces, brackets and parentheses. Empty parts are skipped.
+fn xkb_split(tokens : &[XkbToken], separator : char) -> Vec<&[XkbToken]> {
+    let mut parts : Vec<&[XkbToken]> = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            XkbToken::Punct('{' | '[' | '(') => depth += 1,
+            XkbToken::Punct('}' | ']' | ')') => depth -= 1,
+            XkbToken::Punct(c) if *c == separator && depth == 0 => {
+                if index > start {
+                    parts.push(&tokens[start..index]);
+                }
+                start = index + 1;
+            },
+            _ => {},
+        }
+    }
+    if start < tokens.len() {
+        parts.push(&tokens[start..]);
+    }
+
+    parts
+}
+
+/// Get the group index (0 based) of a `[GroupN]` index at start of tokens.
+fn xkb_group(tokens : &[XkbToken]) -> Option<usize> {
+    match tokens {
+        [XkbToken::Punct('['), XkbToken::Ident(group), XkbToken::Punct(']'), ..] => {
+            let number = group.trim_start_matches(|c : char| c.is_alphabetic()).parse::<usize>().ok()?;
+            number.checked_sub(1)
+        },
+        _ => None,
+    }
+}
+
+/// Get the mask of modifiers like `Shift+LevelThree`. Virtual modifiers are resolved with `vmods`.
+fn xkb_mask(tokens : &[XkbToken], vmods : &HashMap<String, u32>) -> u32 {
+    tokens.iter().fold(0, |mask, token| match token {
+        XkbToken::Ident(name) => mask | match name.as_str() {
+            "Shift" => WL_XKB_MOD_SHIFT,
+            "Lock" => WL_XKB_MOD_LOCK,
+            "Control" => WL_XKB_MOD_CONTROL,
+            "Mod1" => WL_XKB_MOD_1,
+            "Mod2" => WL_XKB_MOD_2,
+            "Mod3" => WL_XKB_MOD_3,
+            "Mod4" => WL_XKB_MOD_4,
+            "Mod5" => WL_XKB_MOD_5,
+            "all" | "All" => 0xff,
+            name => vmods.get(name).copied().unwrap_or(0),
+        },
+        _ => mask,
+    })
+}
+
+/// Declare virtual modifiers like `NumLock,Alt=Mod1`. Undefined values use the usual mapping of the modifier name.
+fn xkb_virtual_modifiers(tokens : &[XkbToken], vmods : &mut HashMap<String, u32>) {
+    for declaration in xkb_split(tokens, ',') {
+        if let [XkbToken::Ident(name), rest @ ..] = declaration {
+            let mask = match rest {
+                [XkbToken::Punct('='), value @ ..] => xkb_mask(value, vmods),
+                _ => match name.as_str() {
+                    "NumLock" => WL_XKB_MOD_2,
+                    "Alt" | "Meta" => WL_XKB_MOD_1,
+                    "Super" | "Hyper" => WL_XKB_MOD_4,
+                    "LevelThree" => WL_XKB_MOD_5,
+                    "LevelFive" => WL_XKB_MOD_3,
+                    _ => 0,
+                },
+            };
+            vmods.insert(name.clone(), mask);
+        }
+    }
+}
+
+/// Parse the body of a `type "NAME" { ... }` statement.
+fn xkb_key_type(name : &str, body : &[XkbToken], vmods : &HashMap<String, u32>) -> WaylandKeyType {
+    let mut key_type = WaylandKeyType { name : name.to_string(), ..Default::default() };
+
+    for statement in xkb_split(body, ';') {
+        match statement {
+            [XkbToken::Ident(keyword), XkbToken::Punct('='), mask @ ..] if keyword == "modifiers" => 
+                key_type.modifiers = xkb_mask(mask, vmods),
+            [XkbToken::Ident(keyword), XkbToken::Punct('['), rest @ ..] if keyword == "map" => {
+                if let Some(close) = rest.iter().position(|t| *t == XkbToken::Punct(']')) {
+                    if let Some(XkbToken::Ident(level)) = rest.last() {
+                        let level = level.trim_start_matches(|c : char| c.is_alphabetic()).parse::<usize>().unwrap_or(1);
+                        key_type.map.push((xkb_mask(&rest[..close], vmods), level.saturating_sub(1)));
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+
+    key_type
+}
+
+/// Parse the body of a `key <NAME> { ... }` statement.
+fn xkb_key(body : &[XkbToken]) -> WaylandKey {
+    let mut key = WaylandKey { types : Vec::new(), symbols : Vec::new(), repeat : true };
+    let mut default_type : Option<String> = None;
+
+    for entry in xkb_split(body, ',') {
+        match entry {
+            // Groups without index follow each other.
+            [XkbToken::Punct('['), ..] => key.symbols.push(xkb_symbols(entry)),
+            [XkbToken::Ident(keyword), rest @ ..] if keyword == "symbols" => {
+                let group = xkb_group(rest).unwrap_or(key.symbols.len());
+                if let Some(list) = rest.iter().position(|t| *t == XkbToken::Punct('=')) {
+                    if key.symbols.len() <= group {
+                        key.symbols.resize(group + 1, Vec::new());
+                    }
+                    key.symbols[group] = xkb_symbols(&rest[list + 1..]);
+                }
+            },
+            [XkbToken::Ident(keyword), rest @ ..] if keyword == "type" => if let Some(XkbToken::Str(name)) = rest.last() {
+                match xkb_group(rest) {
+                    Some(group) => {
+                        if key.types.len() <= group {
+                            key.types.resize(group + 1, None);
+                        }
+                        key.types[group] = Some(name.clone());
+                    },
+                    None => default_type = Some(name.clone()),
+                }
+            },
+            [XkbToken::Ident(keyword), XkbToken::Punct('='), XkbToken::Ident(value)] if keyword == "repeat" => 
+                key.repeat = !matches!(value.as_str(), "No" | "no" | "False" | "false"),
+            _ => {},
+        }
+    }
+
+    // Type without group applies to groups without explicit type.
+    key.types.resize(key.symbols.len(), None);
+    if default_type.is_some() {
+        for key_type in key.types.iter_mut().filter(|t| t.is_none()) {
+            *key_type = default_type.clone();
+        }
+    }
+
+    key
+}
+
+/// Parse a list of keysyms like `[ a, A ]`. Levels with many keysyms like `{ a, b }` keep the first one.
+fn xkb_symbols(tokens : &[XkbToken]) -> Vec<Keysym> {
+    let inner = match tokens {
+        [XkbToken::Punct('['), inner @ .., XkbToken::Punct(']')] => inner,
+        _ => return Vec::new(),
+    };
+
+    xkb_split(inner, ',').iter().map(|level| level.iter().find_map(|t| match t {
+        XkbToken::Ident(name) => Some(keysym_from_name(name).unwrap_or(0)),
+        _ => None,
+    }).unwrap_or(0)).collect()
+}
+
+/// Infer the type of a key without explicit type from its symbols like xkb does.
+fn xkb_default_type(symbols : &[Keysym]) -> &'static str {
+    // Lowercase keysym followed by its uppercase
+    let alphabetic = |lower : usize| match (symbols.get(lower).copied().and_then(keysym_to_char), 
+        symbols.get(lower + 1).copied().and_then(keysym_to_char)) {
+        (Some(l), Some(u)) => l.is_lowercase() && l.to_uppercase().eq(std::iter::once(u)),
+        _ => false,
+    };
+    let keypad = symbols.iter().take(2).any(|s| (0xff80..=0xffbd).contains(s));
+
+    match symbols.len() {
+        0 | 1 => "ONE_LEVEL",
+        2 if keypad => "KEYPAD",
+        2 if alphabetic(0) => "ALPHABETIC",
+        2 => "TWO_LEVEL",
+        _ if alphabetic(0) && alphabetic(2) => "FOUR_LEVEL_ALPHABETIC",
+        _ if alphabetic(0) => "FOUR_LEVEL_SEMIALPHABETIC",
+        _ if keypad => "FOUR_LEVEL_KEYPAD",
+        _ => "FOUR_LEVEL",
+    }
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// Keymap parser tests
+#[cfg(test)]
+mod tests {
+    use super::{WaylandKeymap, WL_XKB_MOD_2, WL_XKB_MOD_5, WL_XKB_MOD_LOCK, WL_XKB_MOD_SHIFT};
+    use super::super::mock::MOCK_KEYMAP;
+
+    #[test]
+    fn wayland_xkb_parse() {
+        let keymap = WaylandKeymap::parse(MOCK_KEYMAP).unwrap();
+
+        assert_eq!(keymap.types.len(), 5);
+        assert_eq!(keymap.groups, vec!["English (US)".to_string(), "French".to_string()]);
+        assert_eq!(keymap.layout(1).name, "French");
+        assert!(keymap.repeats(24));
+        assert!(!keymap.repeats(50));
+
+        assert!(WaylandKeymap::parse("xkb_keymap { xkb_keycodes { <ESC> = 9; }; };").is_none());
+    }
+
+    #[test]
+    fn wayland_xkb_keysym() {
+        let keymap = WaylandKeymap::parse(MOCK_KEYMAP).unwrap();
+
+        // <AD01> is q in group 1 and a in group 2 with inferred ALPHABETIC type.
+        assert_eq!(keymap.keysym(24, 0, 0), 0x0071);
+        assert_eq!(keymap.keysym(24, 0, WL_XKB_MOD_SHIFT), 0x0051);
+        assert_eq!(keymap.keysym(24, 0, WL_XKB_MOD_LOCK), 0x0051);
+        assert_eq!(keymap.keysym(24, 1, 0), 0x0061);
+        assert_eq!(keymap.keysym(24, 2, 0), 0x0071);
+
+        // <AE01> is explicitly TWO_LEVEL so Lock is ignored.
+        assert_eq!(keymap.keysym(10, 0, WL_XKB_MOD_SHIFT), 0x0021);
+        assert_eq!(keymap.keysym(10, 0, WL_XKB_MOD_LOCK), 0x0031);
+
+        // <AE05> reaches level 3 with LevelThree virtual modifier.
+        assert_eq!(keymap.keysym(14, 0, WL_XKB_MOD_5), 0x010020ac);
+
+        // <KP1> uses NumLock.
+        assert_eq!(keymap.keysym(87, 0, 0), 0xff9c);
+        assert_eq!(keymap.keysym(87, 0, WL_XKB_MOD_2), 0xffb1);
+
+        // Alias and unknown keys.
+        assert_eq!(keymap.keysym(66, 0, 0), 0xffe5);
+        assert_eq!(keymap.keysym(200, 0, 0), 0);
+    }
+
+    #[test]
+    fn wayland_xkb_key_label() {
+        let keymap = WaylandKeymap::parse(MOCK_KEYMAP).unwrap();
+
+        assert_eq!(keymap.key_label(24, 0), Some("Q".to_string()));
+        assert_eq!(keymap.key_label(24, 1), Some("A".to_string()));
+        assert_eq!(keymap.key_label(9, 0), Some("Escape".to_string()));
+        assert_eq!(keymap.key_label(65, 0), Some("space".to_string()));
+        assert_eq!(keymap.key_label(200, 0), None);
+    }
+}
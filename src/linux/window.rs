@@ -22,9 +22,9 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use crate::{WindowBuilder, WindowError, WindowFullScreenMode, WindowHandle, WindowPosition, WindowRelativePosition, WindowSize};
+use crate::{WindowError, WindowFullScreenMode, WindowHandle, WindowPosition, WindowRelativePosition, WindowSize};
 
-use super::{wayland::window::WaylandWindow, x11::window::X11Window};
+use super::{headless::window::HeadlessWindow, wayland::window::WaylandWindow, x11::window::X11Window};
 
 
 /// Match abstraction of possible linux Window.
@@ -37,89 +37,284 @@ pub(crate) enum LinuxWindow {
     X11(X11Window),
 
     /// Wayland linux window
-    Wayland(WaylandWindow)
+    Wayland(WaylandWindow),
+
+    /// Headless window in memory
+    Headless(HeadlessWindow)
 
 }
 
 impl LinuxWindow {
 
-    #[inline(always)]
-    pub(crate) fn rebuild(&mut self, builder : &WindowBuilder) -> Result<WindowHandle, WindowError> {
-        todo!()
-    }
-
     /// Get position and size of a [Window](crate::Window) from a [WindowHandle]. 
     /// Usually used to query parent size and position.
     #[inline(always)]
     pub(crate) fn get_window_pos_size(&mut self, handle : WindowHandle) -> (WindowPosition, WindowSize) {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.get_window_pos_size(handle),
+            LinuxWindow::Wayland(lw) => lw.get_window_pos_size(handle),
+            LinuxWindow::Headless(lw) => lw.get_window_pos_size(handle),
+        }
     }
 
     #[inline(always)]
     pub fn set_parent(&mut self, parent : Option<WindowHandle>) -> Result<bool, WindowError> {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.set_parent(parent),
+            LinuxWindow::Wayland(lw) => lw.set_parent(parent),
+            LinuxWindow::Headless(lw) => lw.set_parent(parent),
+        }
+    }
+    
+    #[inline(always)]
+    pub fn native_handle(&self) -> usize {
+        match self {
+            LinuxWindow::X11(lw) => lw.native_handle(),
+            LinuxWindow::Wayland(lw) => lw.native_handle(),
+            LinuxWindow::Headless(lw) => lw.native_handle(),
+        }
     }
     
     #[inline(always)]
     pub fn set_title(&mut self, title : &str) -> Result<bool, WindowError>{
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.set_title(title),
+            LinuxWindow::Wayland(lw) => lw.set_title(title),
+            LinuxWindow::Headless(lw) => lw.set_title(title),
+        }
     }
 
     #[inline(always)]
     pub fn set_size(&mut self, size : WindowSize) -> Result<bool, WindowError> {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.set_size(size),
+            LinuxWindow::Wayland(lw) => lw.set_size(size),
+            LinuxWindow::Headless(lw) => lw.set_size(size),
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_size_min(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        match self {
+            LinuxWindow::X11(lw) => lw.set_size_min(size),
+            LinuxWindow::Wayland(lw) => lw.set_size_min(size),
+            LinuxWindow::Headless(lw) => lw.set_size_min(size),
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_size_max(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        match self {
+            LinuxWindow::X11(lw) => lw.set_size_max(size),
+            LinuxWindow::Wayland(lw) => lw.set_size_max(size),
+            LinuxWindow::Headless(lw) => lw.set_size_max(size),
+        }
     }
 
     #[inline(always)]
     pub fn set_icon(&mut self, icon : Option<&mut dyn std::io::Read>) {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.set_icon(icon),
+            LinuxWindow::Wayland(lw) => lw.set_icon(icon),
+            LinuxWindow::Headless(lw) => lw.set_icon(icon),
+        }
     }
 
     #[inline(always)]
     pub fn set_taskbar(&mut self, show : bool) {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.set_taskbar(show),
+            LinuxWindow::Wayland(lw) => lw.set_taskbar(show),
+            LinuxWindow::Headless(lw) => lw.set_taskbar(show),
+        }
     }
 
     #[inline(always)]
     pub fn restore(&mut self) {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.restore(),
+            LinuxWindow::Wayland(lw) => lw.restore(),
+            LinuxWindow::Headless(lw) => lw.restore(),
+        }
     }
 
     #[inline(always)]
     pub fn show(&mut self) {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.show(),
+            LinuxWindow::Wayland(lw) => lw.show(),
+            LinuxWindow::Headless(lw) => lw.show(),
+        }
     }
 
     #[inline(always)]
     pub fn hide(&mut self) {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.hide(),
+            LinuxWindow::Wayland(lw) => lw.hide(),
+            LinuxWindow::Headless(lw) => lw.hide(),
+        }
     }
 
     #[inline(always)]
     pub fn close(&mut self) {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.close(),
+            LinuxWindow::Wayland(lw) => lw.close(),
+            LinuxWindow::Headless(lw) => lw.close(),
+        }
     }
 
 
     #[inline(always)]
     pub fn set_position(&mut self, position : WindowPosition) {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.set_position(position),
+            LinuxWindow::Wayland(lw) => lw.set_position(position),
+            LinuxWindow::Headless(lw) => lw.set_position(position),
+        }
     }
 
 
     #[inline(always)]
     pub fn set_fullscreen(&mut self, fsmode : WindowFullScreenMode)  {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.set_fullscreen(fsmode),
+            LinuxWindow::Wayland(lw) => lw.set_fullscreen(fsmode),
+            LinuxWindow::Headless(lw) => lw.set_fullscreen(fsmode),
+        }
     }
 
     #[inline(always)]
     pub fn minimize(&mut self)  {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.minimize(),
+            LinuxWindow::Wayland(lw) => lw.minimize(),
+            LinuxWindow::Headless(lw) => lw.minimize(),
+        }
     }
 
     #[inline(always)]
     pub fn maximize(&mut self) {
-        todo!()
+        match self {
+            LinuxWindow::X11(lw) => lw.maximize(),
+            LinuxWindow::Wayland(lw) => lw.maximize(),
+            LinuxWindow::Headless(lw) => lw.maximize(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn grab_input(&mut self) -> Result<bool, WindowError> {
+        match self {
+            LinuxWindow::X11(lw) => lw.grab_input(),
+            LinuxWindow::Wayland(lw) => lw.grab_input(),
+            LinuxWindow::Headless(lw) => lw.grab_input(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn ungrab_input(&mut self) {
+        match self {
+            LinuxWindow::X11(lw) => lw.ungrab_input(),
+            LinuxWindow::Wayland(lw) => lw.ungrab_input(),
+            LinuxWindow::Headless(lw) => lw.ungrab_input(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn set_idle_inhibit(&mut self, inhibit : bool) {
+        match self {
+            LinuxWindow::X11(lw) => lw.set_idle_inhibit(inhibit),
+            LinuxWindow::Wayland(lw) => lw.set_idle_inhibit(inhibit),
+            LinuxWindow::Headless(lw) => lw.set_idle_inhibit(inhibit),
+        }
+    }
+
+    #[inline(always)]
+    pub fn activate(&mut self) {
+        match self {
+            LinuxWindow::X11(lw) => lw.activate(),
+            LinuxWindow::Wayland(lw) => lw.activate(),
+            LinuxWindow::Headless(lw) => lw.activate(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn request_redraw(&mut self) {
+        match self {
+            LinuxWindow::X11(lw) => lw.request_redraw(),
+            LinuxWindow::Wayland(lw) => lw.request_redraw(),
+            LinuxWindow::Headless(lw) => lw.request_redraw(),
+        }
+    }
+
+    #[cfg(feature = "raw_window_handle")]
+    #[inline(always)]
+    pub fn raw_window_handle(&self) -> Result<raw_window_handle::RawWindowHandle, raw_window_handle::HandleError> {
+        match self {
+            LinuxWindow::X11(lw) => lw.raw_window_handle(),
+            LinuxWindow::Wayland(lw) => lw.raw_window_handle(),
+            LinuxWindow::Headless(lw) => lw.raw_window_handle(),
+        }
+    }
+
+    #[cfg(feature = "vulkan")]
+    #[inline(always)]
+    pub unsafe fn create_vulkan_surface(&self, instance : crate::vulkan::VkInstance, 
+        get_instance_proc_addr : crate::vulkan::VkGetInstanceProcAddr) -> Result<crate::vulkan::VkSurfaceKHR, WindowError> {
+        match self {
+            LinuxWindow::X11(lw) => lw.create_vulkan_surface(instance, get_instance_proc_addr),
+            LinuxWindow::Wayland(lw) => lw.create_vulkan_surface(instance, get_instance_proc_addr),
+            LinuxWindow::Headless(lw) => lw.create_vulkan_surface(instance, get_instance_proc_addr),
+        }
+    }
+
+    #[cfg(feature = "cairo")]
+    #[inline(always)]
+    pub fn cairo_surface(&mut self, size : WindowSize) -> Result<crate::cairo::CairoSurface, WindowError> {
+        match self {
+            LinuxWindow::X11(lw) => lw.cairo_surface(size),
+            LinuxWindow::Wayland(lw) => lw.cairo_surface(size),
+            LinuxWindow::Headless(lw) => lw.cairo_surface(size),
+        }
+    }
+
+    #[cfg(feature = "cairo")]
+    #[inline(always)]
+    pub fn cairo_present(&mut self, size : WindowSize, damage : &[(WindowPosition, WindowSize)]) -> Result<(), WindowError> {
+        match self {
+            LinuxWindow::X11(lw) => lw.cairo_present(size, damage),
+            LinuxWindow::Wayland(lw) => lw.cairo_present(size, damage),
+            LinuxWindow::Headless(lw) => lw.cairo_present(size, damage),
+        }
+    }
+
+    #[cfg(feature = "opengl")]
+    #[inline(always)]
+    pub fn create_gl_context(&self, config : &crate::opengl::GLConfig, shared : Option<&crate::opengl::GLContext>) -> Result<crate::opengl::GLContext, WindowError> {
+        match self {
+            LinuxWindow::X11(lw) => lw.create_gl_context(config, shared),
+            LinuxWindow::Wayland(lw) => lw.create_gl_context(config, shared),
+            LinuxWindow::Headless(lw) => lw.create_gl_context(config, shared),
+        }
+    }
+
+    #[inline(always)]
+    pub fn framebuffer(&mut self, size : WindowSize) -> Result<&mut [u32], WindowError> {
+        match self {
+            LinuxWindow::X11(lw) => lw.framebuffer(size),
+            LinuxWindow::Wayland(lw) => lw.framebuffer(size),
+            LinuxWindow::Headless(lw) => lw.framebuffer(size),
+        }
+    }
+
+    #[inline(always)]
+    pub fn present(&mut self, size : WindowSize, damage : &[(WindowPosition, WindowSize)]) -> Result<(), WindowError> {
+        match self {
+            LinuxWindow::X11(lw) => lw.present(size, damage),
+            LinuxWindow::Wayland(lw) => lw.present(size, damage),
+            LinuxWindow::Headless(lw) => lw.present(size, damage),
+        }
     }
 
 
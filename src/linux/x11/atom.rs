@@ -26,6 +26,9 @@ use std::ffi::CString;
 
 use crate::linux::x11::xlib::Atom;
 
+/// Type of properties holding atoms. XA_ATOM of Xatom.h is a cast bindgen doesn't translate.
+pub(crate) const XA_ATOM : Atom = 4;
+
 /// Macro that construct the X11Atoms struct. Since new atoms could be added,
 /// this struct is generated by a macro for convenience.
 macro_rules! x11_atoms_struct {
@@ -62,7 +65,7 @@ macro_rules! x11_atoms_struct {
                     let x11atom = X11Atoms { _NET_WM_STATE_REMOVE : 0, _NET_WM_STATE_ADD : 1, _NET_WM_STATE_TOGGLE : 2,
                     $atom :   super::xlib::XInternAtom(display, CString::new(stringify!($atom)).unwrap().as_ptr(), 1),
                     $($atoms :  super::xlib::XInternAtom(display, CString::new(stringify!($atoms)).unwrap().as_ptr(), 1),)*
-                    xa_atom : 4 };
+                    xa_atom : XA_ATOM };
 
                     // Make sure that all Atoms have value > 0.
                     assert_ne!(x11atom.$atom, 0, "Atom [{}] value must NOT be 0.", stringify!($atom));
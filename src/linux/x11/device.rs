@@ -0,0 +1,182 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::ffi::{c_int, CStr, CString};
+
+use crate::{device::{InputDevice, InputDeviceCapabilities, InputDeviceId, InputDeviceKind, InputDevices}, linux::library::Library};
+
+use super::xlib::{Display, Window, XFree, XGetAtomName, XIAllDevices, XIAnyClassInfo, XIButtonClass, XIButtonClassInfo, XIDependentTouch, XIDeviceInfo, 
+    XIDirectTouch, XIEventMask, XIFloatingSlave, XIKeyClass, XIKeyClassInfo, XIModeAbsolute, XIScrollClass, XISlaveKeyboard, XISlavePointer, 
+    XITouchClass, XITouchClassInfo, XIValuatorClass, XIValuatorClassInfo, XI_HierarchyChangedMask, XQueryExtension, XDefaultRootWindow};
+
+/// Valuator label given by tablets for pen pressure.
+const X11_TABLET_LABEL : &str = "Abs Pressure";
+
+/// XInput2 functions of libXi.
+#[derive(Debug)]
+pub(crate) struct X11XInput2 {
+    /// XInput2 extension major opcode, identifying XInput2 generic events.
+    pub opcode : i32,
+
+    select_events : unsafe extern "C" fn(*mut Display, Window, *mut XIEventMask, c_int) -> c_int,
+    query_device : unsafe extern "C" fn(*mut Display, c_int, *mut c_int) -> *mut XIDeviceInfo,
+    free_device_info : unsafe extern "C" fn(*mut XIDeviceInfo),
+
+    /// Functions are valid while library is open.
+    _library : Library,
+}
+
+impl X11XInput2 {
+    /// Load XInput2 functions from libXi. None if libXi is missing or the server has no XInput2 extension.
+    pub(crate) fn load(display : *mut Display) -> Option<X11XInput2> {
+        let opcode = x11_xinput2_opcode(display)?;
+        let library = Library::open(&[c"libXi.so.6", c"libXi.so"])?;
+        unsafe {
+            Some(X11XInput2 { opcode, select_events : library.symbol(c"XISelectEvents")?, query_device : library.symbol(c"XIQueryDevice")?, 
+                free_device_info : library.symbol(c"XIFreeDeviceInfo")?, _library : library })
+        }
+    }
+
+    /// Select XI_HierarchyChanged events on the root window to be notified of devices hotplug.
+    pub(crate) fn select_hierarchy_events(&self, display : *mut Display) {
+        unsafe {
+            let mut mask : [u8; 4] = (XI_HierarchyChangedMask as u32).to_ne_bytes();
+            let mut evmask = XIEventMask { deviceid: XIAllDevices as i32, mask_len: mask.len() as i32, mask: mask.as_mut_ptr() };
+            (self.select_events)(display, XDefaultRootWindow(display), &mut evmask, 1);
+        }
+    }
+
+    /// Get x11 [InputDevices] from XInput2 device queries.
+    /// 
+    /// Master devices are virtual and are not listed.
+    pub(crate) fn input_devices(&self, display : *mut Display) -> InputDevices {
+        InputDevices::create(self.query_devices(display, XIAllDevices as i32))
+    }
+
+    /// Query x11 input devices with XIQueryDevice. `deviceid` can be XIAllDevices or a device id.
+    pub(crate) fn query_devices(&self, display : *mut Display, deviceid : i32) -> Vec<InputDevice> {
+        let mut list : Vec<InputDevice> = Vec::new();
+
+        unsafe {
+            let mut count : i32 = 0;
+            let infos = (self.query_device)(display, deviceid, &mut count);
+
+            if !infos.is_null() {
+                for info in std::slice::from_raw_parts(infos, count as usize) {
+                    if info.use_ == XISlaveKeyboard as i32 || info.use_ == XISlavePointer as i32 || info.use_ == XIFloatingSlave as i32 {
+                        list.push(x11_device(display, info));
+                    }
+                }
+                (self.free_device_info)(infos);
+            }
+        }
+
+        list
+    }
+}
+
+/// Get the XInput2 extension major opcode used to identify XInput2 generic events.
+/// 
+/// Returns None if XInput2 is not available on the X11 server.
+fn x11_xinput2_opcode(display : *mut Display) -> Option<i32> {
+    unsafe {
+        let name = CString::new("XInputExtension").unwrap();
+        let mut opcode : i32 = 0;
+        let mut event : i32 = 0;
+        let mut error : i32 = 0;
+
+        if XQueryExtension(display, name.as_ptr(), &mut opcode, &mut event, &mut error) != 0 {
+            Some(opcode)
+        } else {
+            None
+        }
+    }
+}
+
+/// Create an [InputDevice] from XIDeviceInfo.
+unsafe fn x11_device(display : *mut Display, info : &XIDeviceInfo) -> InputDevice {
+    let name = CStr::from_ptr(info.name).to_string_lossy().to_string();
+    let mut capabilities = InputDeviceCapabilities::default();
+    let mut touch_mode : Option<i32> = None;
+    let mut pressure = false;
+
+    for class in std::slice::from_raw_parts(info.classes, info.num_classes as usize) {
+        let class : *mut XIAnyClassInfo = *class;
+
+        match (*class).type_ as u32 {
+            XIKeyClass => capabilities.keys = (*(class as *mut XIKeyClassInfo)).num_keycodes as u32,
+            XIButtonClass => capabilities.buttons = (*(class as *mut XIButtonClassInfo)).num_buttons as u32,
+            XIValuatorClass => {
+                let valuator = &*(class as *mut XIValuatorClassInfo);
+                capabilities.axes += 1;
+                capabilities.absolute |= valuator.mode == XIModeAbsolute as i32;
+                pressure |= x11_atom_name(display, valuator.label) == X11_TABLET_LABEL;
+            },
+            XIScrollClass => capabilities.scroll = true,
+            XITouchClass => {
+                let touch = &*(class as *mut XITouchClassInfo);
+                capabilities.touches = touch.num_touches as u32;
+                touch_mode = Some(touch.mode);
+            },
+            _ => {},
+        }
+    }
+
+    let kind = if info.use_ == XISlaveKeyboard as i32 {
+        InputDeviceKind::Keyboard
+    } else {
+        match touch_mode {
+            Some(mode) if mode == XIDirectTouch as i32 => InputDeviceKind::Touchscreen,
+            Some(mode) if mode == XIDependentTouch as i32 => InputDeviceKind::Touchpad,
+            _ => if pressure {
+                InputDeviceKind::Tablet
+            } else if name.to_lowercase().contains("touchpad") {
+                // Touchpad drivers without multitouch still name themselves.
+                InputDeviceKind::Touchpad
+            } else if capabilities.buttons > 0 {
+                InputDeviceKind::Mouse
+            } else {
+                InputDeviceKind::Other
+            },
+        }
+    };
+
+    InputDevice { id: info.deviceid as InputDeviceId, name, kind, capabilities, enabled: info.enabled != 0 }
+}
+
+/// Get the name of an atom. Returns an empty string if atom is None.
+unsafe fn x11_atom_name(display : *mut Display, atom : super::xlib::Atom) -> String {
+    if atom == 0 {
+        return String::new();
+    }
+
+    let name = XGetAtomName(display, atom);
+    if name.is_null() {
+        String::new()
+    } else {
+        let value = CStr::from_ptr(name).to_string_lossy().to_string();
+        XFree(name as *mut std::ffi::c_void);
+        value
+    }
+}
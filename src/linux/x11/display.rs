@@ -125,11 +125,13 @@ fn fetch_display(handle : DisplayHandle, line : &str) -> Display {
     Display { 
         handle,
         identifier, 
+        description: String::new(),
         size, 
         position, 
         resolution, 
         refresh_rate: 0, 
         primary, 
+        scale: 1.0,
         supported: Vec::new() 
     }
 }
@@ -21,3 +21,225 @@ LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
+
+use crate::keyboard::{KeyLocks, KeyModifiers};
+
+use super::xlib::{ControlMask, GenericEvent, KeyPress, KeyRelease, LockMask, Mod1Mask, Mod2Mask, Mod4Mask, ShiftMask, Window, XEvent};
+
+/// Destination of a X11 event fetched by the window manager.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum X11EventRoute {
+    /// Event of the X11 window with this id. Ignored by the window manager if it isn't one of its windows.
+    Window(Window),
+
+    /// KeyPress or KeyRelease of a key grabbed on the root window by a hotkey.
+    Hotkey,
+
+    /// Generic event of the XInput2 extension, like XI_HierarchyChanged.
+    XInput2,
+
+    /// Generic event of the Present extension, like PresentCompleteNotify.
+    Present,
+
+    /// Event of the XKB extension, like XkbStateNotify. XKB events have no window.
+    Xkb,
+
+    /// Event not handled.
+    Ignored,
+}
+
+/// Get the [X11EventRoute] of a event fetched with XNextEvent.
+/// 
+/// Generic events are routed with the opcode of their extension and XKB events with the XKB event base, `xi_opcode`, 
+/// `xkb_event` and `present_opcode` being None if the extension isn't available. Other events of the root window are ignored. Keys grabbed by hotkeys are sent to the root window since they are 
+/// grabbed without owner events.
+pub(crate) fn x11_event_route(event : &XEvent, root : Window, xi_opcode : Option<i32>, xkb_event : Option<i32>, 
+    present_opcode : Option<i32>) -> X11EventRoute {
+    unsafe {
+        match event.type_ as u32 {
+            _ if xkb_event == Some(event.type_) => X11EventRoute::Xkb,
+            GenericEvent if xi_opcode == Some(event.xcookie.extension) => X11EventRoute::XInput2,
+            GenericEvent if present_opcode == Some(event.xcookie.extension) => X11EventRoute::Present,
+            GenericEvent => X11EventRoute::Ignored,
+            KeyPress | KeyRelease if event.xkey.window == root => X11EventRoute::Hotkey,
+            _ if event.xany.window == root => X11EventRoute::Ignored,
+            _ => X11EventRoute::Window(event.xany.window),
+        }
+    }
+}
+
+/// Get [KeyModifiers] from the state mask of a XKeyEvent or XButtonEvent.
+/// 
+/// Alt is mapped on Mod1 and Meta on Mod4, which is the default of most X11 keyboard maps.
+#[inline(always)]
+pub(crate) fn x11_key_modifiers(state : u32) -> KeyModifiers {
+    KeyModifiers { 
+        shift: state & ShiftMask != 0, 
+        ctrl: state & ControlMask != 0, 
+        alt: state & Mod1Mask != 0, 
+        meta: state & Mod4Mask != 0 
+    }
+}
+
+/// Get Caps Lock and Num Lock [KeyLocks] from the state mask of a XKeyEvent. Num Lock is mapped on Mod2. 
+/// Scroll Lock isn't part of the state and is kept from `scroll`.
+#[inline(always)]
+pub(crate) fn x11_key_locks(state : u32, scroll : bool) -> KeyLocks {
+    KeyLocks { caps : state & LockMask != 0, num : state & Mod2Mask != 0, scroll }
+}
+
+/// Get the X11 state mask of [KeyModifiers]. Inverse of [x11_key_modifiers].
+#[inline(always)]
+pub(crate) fn x11_modifiers_mask(modifiers : KeyModifiers) -> u32 {
+    let mut mask = 0;
+    if modifiers.shift { mask |= ShiftMask; }
+    if modifiers.ctrl { mask |= ControlMask; }
+    if modifiers.alt { mask |= Mod1Mask; }
+    if modifiers.meta { mask |= Mod4Mask; }
+    mask
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// X11 event conversion tests
+#[cfg(test)]
+mod tests {
+    use crate::keyboard::{KeyLocks, KeyModifiers};
+
+    use super::{X11EventRoute, super::xlib::{ControlMask, GenericEvent, LockMask, Mod1Mask, Mod2Mask, KeyPress, KeyRelease, MapNotify, Window, XAnyEvent, XEvent, 
+        XGenericEventCookie, XKeyEvent, XI_HierarchyChanged, XkbEvent, XkbStateNotify, XkbStateNotifyEvent, 
+        Expose, PresentCompleteNotify, XExposeEvent}};
+
+    /// Root window id used by routing tests.
+    const ROOT : Window = 1;
+
+    /// XInput2 opcode used by routing tests.
+    const XI_OPCODE : i32 = 131;
+
+    /// XKB event base used by routing tests.
+    const XKB_EVENT : i32 = 85;
+
+    /// Present opcode used by routing tests.
+    const PRESENT_OPCODE : i32 = 148;
+
+    /// XEvent of type KeyPress on `window` with the state mask `state`.
+    fn key_event(window : Window, state : u32) -> XEvent {
+        let mut key : XKeyEvent = unsafe { std::mem::zeroed() };
+        key.type_ = KeyPress as i32;
+        key.window = window;
+        key.root = ROOT;
+        key.keycode = 9;
+        key.state = state;
+        XEvent { xkey : key }
+    }
+
+    #[test]
+    fn x11_modifiers_roundtrip() {
+        let modifiers = KeyModifiers::new(true, false, true, true);
+        let mask = super::x11_modifiers_mask(modifiers);
+
+        assert_eq!(super::x11_key_modifiers(mask), modifiers);
+
+        // Caps Lock and Num Lock are ignored
+        assert_eq!(super::x11_key_modifiers(mask | LockMask | Mod2Mask), modifiers);
+        assert!(super::x11_key_modifiers(LockMask | Mod2Mask).is_empty());
+    }
+
+    #[test]
+    fn x11_key_locks() {
+        assert_eq!(super::x11_key_locks(LockMask | ControlMask, false), KeyLocks { caps : true, num : false, scroll : false });
+        assert_eq!(super::x11_key_locks(Mod2Mask, true), KeyLocks { caps : false, num : true, scroll : true });
+    }
+
+    /// Events of the windows are routed to them with their state, which gives modifiers used by the grab release keys.
+    #[test]
+    fn x11_event_route_window() {
+        let event = key_event(42, ControlMask | Mod1Mask);
+        assert_eq!(super::x11_event_route(&event, ROOT, None, None, None), X11EventRoute::Window(42));
+        assert_eq!(super::x11_key_modifiers(unsafe { event.xkey.state }), KeyModifiers::new(false, true, true, false));
+
+        let mut any : XAnyEvent = unsafe { std::mem::zeroed() };
+        any.type_ = MapNotify as i32;
+        any.window = 7;
+        assert_eq!(super::x11_event_route(&XEvent { xany : any }, ROOT, None, None, None), X11EventRoute::Window(7));
+    }
+
+    /// Keys pressed and released on the root window are hotkeys.
+    #[test]
+    fn x11_event_route_hotkey() {
+        let event = key_event(ROOT, ControlMask);
+        assert_eq!(super::x11_event_route(&event, ROOT, None, None, None), X11EventRoute::Hotkey);
+
+        let mut release = unsafe { event.xkey };
+        release.type_ = KeyRelease as i32;
+        assert_eq!(super::x11_event_route(&XEvent { xkey : release }, ROOT, None, None, None), X11EventRoute::Hotkey);
+    }
+
+    /// Generic events of XInput2, like devices plugged, are routed with its opcode.
+    #[test]
+    fn x11_event_route_xinput2() {
+        let mut cookie : XGenericEventCookie = unsafe { std::mem::zeroed() };
+        cookie.type_ = GenericEvent as i32;
+        cookie.extension = XI_OPCODE;
+        cookie.evtype = XI_HierarchyChanged as i32;
+        let event = XEvent { xcookie : cookie };
+
+        assert_eq!(super::x11_event_route(&event, ROOT, Some(XI_OPCODE), None, None), X11EventRoute::XInput2);
+        assert_eq!(super::x11_event_route(&event, ROOT, Some(XI_OPCODE + 1), None, None), X11EventRoute::Ignored);
+
+        // XInput2 not available
+        assert_eq!(super::x11_event_route(&event, ROOT, None, None, None), X11EventRoute::Ignored);
+    }
+
+    /// Generic events of Present are routed with its opcode, Expose events to their window.
+    #[test]
+    fn x11_event_route_present() {
+        let mut cookie : XGenericEventCookie = unsafe { std::mem::zeroed() };
+        cookie.type_ = GenericEvent as i32;
+        cookie.extension = PRESENT_OPCODE;
+        cookie.evtype = PresentCompleteNotify as i32;
+        let event = XEvent { xcookie : cookie };
+
+        assert_eq!(super::x11_event_route(&event, ROOT, Some(XI_OPCODE), None, Some(PRESENT_OPCODE)), X11EventRoute::Present);
+        assert_eq!(super::x11_event_route(&event, ROOT, Some(XI_OPCODE), None, None), X11EventRoute::Ignored);
+
+        let mut expose : XExposeEvent = unsafe { std::mem::zeroed() };
+        expose.type_ = Expose as i32;
+        expose.window = 42;
+        assert_eq!(super::x11_event_route(&XEvent { xexpose : expose }, ROOT, None, None, Some(PRESENT_OPCODE)), X11EventRoute::Window(42));
+    }
+
+    /// XKB events are routed with the XKB event base, before windows since they have none.
+    #[test]
+    fn x11_event_route_xkb() {
+        let mut state : XkbStateNotifyEvent = unsafe { std::mem::zeroed() };
+        state.type_ = XKB_EVENT;
+        state.xkb_type = XkbStateNotify as i32;
+        state.time = 42;
+        // Union is zeroed first since the core event is larger than XkbStateNotifyEvent.
+        let mut event : XkbEvent = unsafe { std::mem::zeroed() };
+        event.state = state;
+        let event = unsafe { event.core };
+
+        assert_eq!(super::x11_event_route(&event, ROOT, None, Some(XKB_EVENT), None), X11EventRoute::Xkb);
+
+        // XKB not available, time is read as a window.
+        assert_eq!(super::x11_event_route(&event, ROOT, None, None, None), X11EventRoute::Window(42));
+    }
+
+    /// Generic events and events of the root window aren't routed to windows.
+    #[test]
+    fn x11_event_route_ignored() {
+        let mut cookie : XGenericEventCookie = unsafe { std::mem::zeroed() };
+        cookie.type_ = GenericEvent as i32;
+        assert_eq!(super::x11_event_route(&XEvent { xcookie : cookie }, ROOT, None, None, None), X11EventRoute::Ignored);
+
+        let mut any : XAnyEvent = unsafe { std::mem::zeroed() };
+        any.type_ = MapNotify as i32;
+        any.window = ROOT;
+        assert_eq!(super::x11_event_route(&XEvent { xany : any }, ROOT, None, None, None), X11EventRoute::Ignored);
+    }
+
+}
@@ -0,0 +1,269 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Software framebuffer presented with MIT-SHM XShmPutImage, or XPutImage if shared memory isn't available 
+//! like with a remote X server or without libXext, which is loaded at runtime.
+//! 
+//! Reference(s)
+//! <https://www.x.org/releases/current/doc/xextproto/shm.html>
+
+use std::{ffi::{c_char, c_int, c_uint}, rc::Rc, sync::atomic::{AtomicBool, Ordering}};
+
+use crate::{linux::library::Library, WindowPosition, WindowSize};
+
+use super::xlib::{Display, Drawable, Visual, Window, XCreateGC, XCreateImage, XErrorEvent, XFree, XFreeGC, XGetWindowAttributes, XImage, XPutImage, 
+    XSetErrorHandler, XShmSegmentInfo, XSync, XWindowAttributes, XFlush, ZPixmap, GC};
+
+/// Set by [x11_shm_error_handler] when XShmAttach failed.
+static X11_SHM_FAILED : AtomicBool = AtomicBool::new(false);
+
+/// X11 error handler used while attaching shared memory. XShmAttach errors are asynchronous and would terminate the program otherwise.
+unsafe extern "C" fn x11_shm_error_handler(_display : *mut Display, _event : *mut XErrorEvent) -> i32 {
+    X11_SHM_FAILED.store(true, Ordering::SeqCst);
+    0
+}
+
+/// MIT-SHM functions of libXext.
+#[derive(Debug)]
+pub(crate) struct X11Shm {
+    attach : unsafe extern "C" fn(*mut Display, *mut XShmSegmentInfo) -> c_int,
+    detach : unsafe extern "C" fn(*mut Display, *mut XShmSegmentInfo) -> c_int,
+    create_image : unsafe extern "C" fn(*mut Display, *mut Visual, c_uint, c_int, *mut c_char, *mut XShmSegmentInfo, c_uint, c_uint) -> *mut XImage,
+    put_image : unsafe extern "C" fn(*mut Display, Drawable, GC, *mut XImage, c_int, c_int, c_int, c_int, c_uint, c_uint, c_int) -> c_int,
+
+    /// Functions are valid while library is open.
+    _library : Library,
+}
+
+impl X11Shm {
+    /// Load MIT-SHM functions from libXext. None if libXext is missing or the server has no MIT-SHM extension.
+    pub(crate) fn load(display : *mut Display) -> Option<X11Shm> {
+        let library = Library::open(&[c"libXext.so.6", c"libXext.so"])?;
+        unsafe {
+            let query_extension : unsafe extern "C" fn(*mut Display) -> c_int = library.symbol(c"XShmQueryExtension")?;
+            if query_extension(display) == 0 {
+                return None;
+            }
+
+            Some(X11Shm { attach : library.symbol(c"XShmAttach")?, detach : library.symbol(c"XShmDetach")?, 
+                create_image : library.symbol(c"XShmCreateImage")?, put_image : library.symbol(c"XShmPutImage")?, _library : library })
+        }
+    }
+}
+
+/// XImage of a window software framebuffer.
+#[derive(Debug)]
+pub(crate) struct X11Framebuffer {
+    /// X11 server display connection pointer
+    display : *mut Display,
+
+    /// X11 window id
+    window : Window,
+
+    /// Graphics context used to put images, null until first drawn.
+    gc : GC,
+
+    /// XImage of XRGB8888 pixels, null if not allocated.
+    image : *mut XImage,
+
+    /// Shared memory segment of image. Boxed since the image keeps a pointer to it. None if pixels are sent with XPutImage.
+    shm : Option<Box<XShmSegmentInfo>>,
+
+    /// Pixels sent with XPutImage when shared memory isn't available.
+    pixels : Vec<u32>,
+
+    /// Size in pixels of the image
+    size : WindowSize,
+
+    /// MIT-SHM functions, None if shared memory isn't available.
+    mit_shm : Option<Rc<X11Shm>>,
+}
+
+impl PartialEq for X11Framebuffer {
+    fn eq(&self, other: &Self) -> bool {
+        self.window == other.window && self.image == other.image
+    }
+}
+
+impl Drop for X11Framebuffer {
+    fn drop(&mut self) {
+        self.destroy();
+        if !self.gc.is_null() {
+            unsafe { XFreeGC(self.display, self.gc) };
+        }
+    }
+}
+
+impl X11Framebuffer {
+
+    /// Create a framebuffer for window. Image is allocated when first drawn.
+    pub(crate) fn new(display : *mut Display, window : Window, mit_shm : Option<Rc<X11Shm>>) -> X11Framebuffer {
+        X11Framebuffer { display, window, gc : std::ptr::null_mut(), image : std::ptr::null_mut(), shm : None, pixels : Vec::new(), 
+            size : WindowSize::new(0, 0), mit_shm }
+    }
+
+    /// Returns the pixels of the image of `size`, reallocating it if size changed.
+    /// 
+    /// Returns None if the window visual doesn't use 32 bits pixels or the image can't be created.
+    pub(crate) fn pixels(&mut self, size : WindowSize) -> Option<&mut [u32]> {
+        if size != self.size || self.image.is_null() {
+            self.destroy();
+            self.size = size;
+            self.allocate()?;
+        }
+
+        let len = self.size.width as usize * self.size.height as usize;
+        match &self.shm {
+            Some(shm) => Some(unsafe { std::slice::from_raw_parts_mut(shm.shmaddr as *mut u32, len) }),
+            None => Some(&mut self.pixels),
+        }
+    }
+
+    /// Put the damage rectangles of the image of `size` on the window. Rectangles are clipped to size.
+    /// 
+    /// Returns false if the image couldn't be allocated.
+    pub(crate) fn present(&mut self, size : WindowSize, damage : &[(WindowPosition, WindowSize)]) -> bool {
+        if self.pixels(size).is_none() {
+            return false;
+        }
+
+        unsafe {
+            for (position, size) in damage {
+                match (&self.shm, &self.mit_shm) {
+                    (Some(_), Some(mit_shm)) => (mit_shm.put_image)(self.display, self.window, self.gc, self.image, position.x, position.y, 
+                        position.x, position.y, size.width, size.height, 0),
+                    _ => XPutImage(self.display, self.window, self.gc, self.image, position.x, position.y, position.x, position.y, 
+                        size.width, size.height),
+                };
+            }
+
+            // Shared memory must not be drawn until the server read it.
+            if self.shm.is_some() {
+                XSync(self.display, 0);
+            } else {
+                XFlush(self.display);
+            }
+        }
+        true
+    }
+
+    /// Destroy the image and its shared memory. Content of the framebuffer is lost.
+    pub(crate) fn destroy(&mut self) {
+        unsafe {
+            if let (Some(mut shm), Some(mit_shm)) = (self.shm.take(), &self.mit_shm) {
+                (mit_shm.detach)(self.display, shm.as_mut());
+                XSync(self.display, 0);
+                libc::shmdt(shm.shmaddr as *const libc::c_void);
+            }
+            if !self.image.is_null() {
+                // Image data isn't owned by Xlib.
+                (*self.image).data = std::ptr::null_mut();
+                XFree(self.image as *mut libc::c_void);
+                self.image = std::ptr::null_mut();
+            }
+        }
+        self.pixels = Vec::new();
+    }
+
+    /// Create the image with shared memory if supported, otherwise with pixels sent by XPutImage.
+    fn allocate(&mut self) -> Option<()> {
+        if self.size.width == 0 || self.size.height == 0 {
+            return None;
+        }
+
+        unsafe {
+            let mut attributes : XWindowAttributes = std::mem::zeroed();
+            if XGetWindowAttributes(self.display, self.window, &mut attributes) == 0 {
+                return None;
+            }
+            if self.gc.is_null() {
+                self.gc = XCreateGC(self.display, self.window, 0, std::ptr::null_mut());
+            }
+
+            if let Some(mit_shm) = self.mit_shm.clone() {
+                self.allocate_shm(&mit_shm, &attributes);
+            }
+            if self.image.is_null() {
+                self.pixels = vec![0; self.size.width as usize * self.size.height as usize];
+                self.image = XCreateImage(self.display, attributes.visual, attributes.depth as u32, ZPixmap as i32, 0, 
+                    self.pixels.as_mut_ptr() as *mut libc::c_char, self.size.width, self.size.height, 32, self.size.width as i32 * 4);
+            }
+
+            // Pixels are given as u32 only.
+            if self.image.is_null() || (*self.image).bits_per_pixel != 32 {
+                self.destroy();
+                return None;
+            }
+        }
+        Some(())
+    }
+
+    /// Create the image in a shared memory segment attached by the server. Image stays null on failure.
+    unsafe fn allocate_shm(&mut self, mit_shm : &X11Shm, attributes : &XWindowAttributes) {
+        let mut shm : Box<XShmSegmentInfo> = Box::new(std::mem::zeroed());
+        let image = (mit_shm.create_image)(self.display, attributes.visual, attributes.depth as u32, ZPixmap as i32, std::ptr::null_mut(), 
+            shm.as_mut(), self.size.width, self.size.height);
+        if image.is_null() {
+            return;
+        }
+
+        shm.shmid = libc::shmget(libc::IPC_PRIVATE, (*image).bytes_per_line as usize * self.size.height as usize, libc::IPC_CREAT | 0o600);
+        if shm.shmid < 0 {
+            XFree(image as *mut libc::c_void);
+            return;
+        }
+        shm.shmaddr = libc::shmat(shm.shmid, std::ptr::null(), 0) as *mut libc::c_char;
+        if shm.shmaddr as isize == -1 {
+            libc::shmctl(shm.shmid, libc::IPC_RMID, std::ptr::null_mut());
+            XFree(image as *mut libc::c_void);
+            return;
+        }
+        (*image).data = shm.shmaddr;
+
+        X11_SHM_FAILED.store(false, Ordering::SeqCst);
+        let handler = XSetErrorHandler(Some(x11_shm_error_handler));
+        let attached = (mit_shm.attach)(self.display, shm.as_mut()) != 0;
+
+        // Wait for the server to report errors before restoring handler.
+        XSync(self.display, 0);
+        XSetErrorHandler(handler);
+
+        // Segment is removed once detached by both client and server.
+        libc::shmctl(shm.shmid, libc::IPC_RMID, std::ptr::null_mut());
+
+        if attached && !X11_SHM_FAILED.load(Ordering::SeqCst) {
+            self.image = image;
+            self.shm = Some(shm);
+        } else {
+            if attached {
+                // Server may still hold the segment, detach it before the client does.
+                (mit_shm.detach)(self.display, shm.as_mut());
+                XSync(self.display, 0);
+            }
+            libc::shmdt(shm.shmaddr as *const libc::c_void);
+            (*image).data = std::ptr::null_mut();
+            XFree(image as *mut libc::c_void);
+        }
+    }
+}
@@ -0,0 +1,93 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{keyboard::KeyCombination, WindowError};
+
+use super::{event::x11_modifiers_mask, xlib::{BadAccess, Display, GrabModeAsync, LockMask, Mod2Mask, XDefaultRootWindow, XErrorEvent, 
+    XGrabKey, XSetErrorHandler, XSync, XUngrabKey}};
+
+/// Lock modifiers variants grabbed for each hotkey so they fire whatever the Caps Lock (Lock) and Num Lock (Mod2) states.
+const X11_LOCK_VARIANTS : [u32; 4] = [0, LockMask, Mod2Mask, LockMask | Mod2Mask];
+
+/// Set by [x11_grab_error_handler] when XGrabKey failed because combination is already grabbed.
+static X11_GRAB_FAILED : AtomicBool = AtomicBool::new(false);
+
+/// X11 error handler used while grabbing keys. XGrabKey errors are asynchronous and would terminate the program otherwise.
+unsafe extern "C" fn x11_grab_error_handler(_display : *mut Display, event : *mut XErrorEvent) -> i32 {
+    if (*event).error_code as u32 == BadAccess {
+        X11_GRAB_FAILED.store(true, Ordering::SeqCst);
+    }
+    0
+}
+
+/// Grab a hotkey on the root window with XGrabKey for each Caps Lock and Num Lock variant.
+/// 
+/// # Errors
+/// Returns Err([`WindowError::HotkeyUnavailable`]) if another client already grabbed the combination.
+pub(crate) fn x11_grab_hotkey(display : *mut Display, keys : KeyCombination) -> Result<(), WindowError> {
+
+    // X11 keycodes are 8 bits
+    if keys.key > u8::MAX as u32 {
+        return Err(WindowError::HotkeyUnavailable);
+    }
+
+    unsafe {
+        let root = XDefaultRootWindow(display);
+        let mask = x11_modifiers_mask(keys.modifiers);
+
+        X11_GRAB_FAILED.store(false, Ordering::SeqCst);
+        let handler = XSetErrorHandler(Some(x11_grab_error_handler));
+
+        for variant in X11_LOCK_VARIANTS {
+            XGrabKey(display, keys.key as i32, mask | variant, root, 0, GrabModeAsync as i32, GrabModeAsync as i32);
+        }
+
+        // Wait for the server to report errors before restoring handler.
+        XSync(display, 0);
+        XSetErrorHandler(handler);
+
+        if X11_GRAB_FAILED.load(Ordering::SeqCst) {
+            x11_ungrab_hotkey(display, keys);
+            Err(WindowError::HotkeyUnavailable)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Ungrab a hotkey grabbed with [x11_grab_hotkey].
+pub(crate) fn x11_ungrab_hotkey(display : *mut Display, keys : KeyCombination) {
+    unsafe {
+        let root = XDefaultRootWindow(display);
+        let mask = x11_modifiers_mask(keys.modifiers);
+
+        for variant in X11_LOCK_VARIANTS {
+            XUngrabKey(display, keys.key as i32, mask | variant, root);
+        }
+
+        XSync(display, 0);
+    }
+}
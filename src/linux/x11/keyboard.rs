@@ -22,28 +22,76 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use crate::keyboard::WindowKeyboardMode;
+use std::ffi::CString;
+
+use crate::{event::{KeyCode, WindowCoordinate}, keyboard::{KeyLocks, KeyModifiers, WindowKeyboardMode}, WindowSize};
+
+use super::{event::x11_key_modifiers, xlib::{Display, LockMask, Mod2Mask, XInternAtom, XQueryKeymap, XkbGetNamedIndicator, XkbGetState, XkbStateRec, XkbUseCoreKbd}};
+
+/// Name of the Scroll Lock indicator. Scroll Lock isn't a modifier and is only available as an indicator.
+const X11_SCROLL_LOCK_INDICATOR : &str = "Scroll Lock";
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct X11Keyboard {
-    
+    /// X11 server display connection pointer
+    pub(crate) display : *mut Display,
 
 }
 
 impl  X11Keyboard {
 
+    /// Input methods aren't used on X11, keys are sent in every mode.
     #[inline(always)]
-    pub fn set_mode(&mut self, mode : WindowKeyboardMode) {
-        todo!()
-    }
+    pub fn set_mode(&mut self, _mode : WindowKeyboardMode) {}
 
+    /// Input methods aren't used on X11.
     #[inline(always)]
-    pub fn enable_repeat(&mut self) {
-        todo!()
-    }
+    pub fn set_surrounding_text(&mut self, _text : &str, _cursor : usize, _anchor : usize) {}
+
+    /// Input methods aren't used on X11.
+    #[inline(always)]
+    pub fn set_text_cursor(&mut self, _position : WindowCoordinate, _size : WindowSize) {}
+
+    /// Repeats are sent by the server as KeyPress only with detectable auto repeat. The manager drops them when disabled.
+    #[inline(always)]
+    pub fn enable_repeat(&mut self) {}
+
+    /// Repeats are dropped by the manager, the server keeps repeating for other clients.
+    #[inline(always)]
+    pub fn disable_repeat(&mut self) {}
 
+    /// Query keys pressed with XQueryKeymap and modifiers and locks from XKB state.
     #[inline(always)]
-    pub fn disable_repeat(&mut self) {
-        todo!()
+    pub fn query_state(&self) -> (Vec<KeyCode>, KeyModifiers, KeyLocks) {
+        unsafe {
+            // Keymap is a 256 bits vector, one bit per keycode.
+            let mut keymap : [std::os::raw::c_char; 32] = [0; 32];
+            XQueryKeymap(self.display, keymap.as_mut_ptr());
+
+            let mut pressed : Vec<KeyCode> = Vec::new();
+            for keycode in 0..256 {
+                if keymap[keycode / 8] as u8 & (1 << (keycode % 8)) != 0 {
+                    pressed.push(keycode as KeyCode);
+                }
+            }
+
+            let mut state : XkbStateRec = std::mem::zeroed();
+            if XkbGetState(self.display, XkbUseCoreKbd, &mut state) != 0 {
+                return (pressed, KeyModifiers::default(), KeyLocks::default());
+            }
+
+            let scroll_name = CString::new(X11_SCROLL_LOCK_INDICATOR).unwrap();
+            let mut scroll : i32 = 0;
+            XkbGetNamedIndicator(self.display, XInternAtom(self.display, scroll_name.as_ptr(), 1), std::ptr::null_mut(), 
+                &mut scroll, std::ptr::null_mut(), std::ptr::null_mut());
+
+            let locks = KeyLocks { 
+                caps: state.locked_mods as u32 & LockMask != 0, 
+                num: state.locked_mods as u32 & Mod2Mask != 0, 
+                scroll: scroll != 0 
+            };
+
+            (pressed, x11_key_modifiers(state.mods as u32), locks)
+        }
     }
 }
\ No newline at end of file
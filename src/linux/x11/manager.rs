@@ -23,23 +23,66 @@ SOFTWARE.
 */
 
 
-use crate::{display::Displays, event::WindowManagerEvent, WindowBuilder, WindowError, WindowHandle};
+use std::{cell::RefCell, collections::VecDeque, rc::Rc, time::{Duration, Instant}};
 
-use super::{atom::X11Atoms, xlib::Display, xlib::Window, xlib::XEvent, xlib::XOpenDisplay};
+use crate::{device::InputDevices, display::Displays, event::{KeyCode, WindowCoordinate, WindowEvent, WindowFrameTiming, WindowKeyboardEvent, WindowManagerEvent}, 
+    keyboard::{HotkeyId, KeyCombination, KeyModifiers, KeyboardLayout}, linux::{keyboard::LinuxKeyboard, window::LinuxWindow}, window::WindowHandles, 
+    Window, WindowBuilder, WindowError, WindowHandle, WindowPosition, WindowSize};
+
+use super::{atom::{X11Atoms, XA_ATOM}, event::{x11_event_route, x11_key_locks, x11_key_modifiers, X11EventRoute}, hotkey::{x11_grab_hotkey, x11_ungrab_hotkey}, 
+    device::X11XInput2, framebuffer::X11Shm, keyboard::X11Keyboard, 
+    present::{x11_present_instant, X11Present}, screensaver::X11ScreenSaver, window::X11Window,
+    xlib::{Atom, Display, XBlackPixel, XCreateSimpleWindow, XDefaultRootWindow, XDestroyWindow, XEvent, XExposeEvent, XFlush, XFree, XFreeEventData, 
+    XGenericEventCookie, XGetEventData, XGetWindowProperty, XIDeviceDisabled, XIDeviceEnabled, XIHierarchyEvent, XISlaveAdded, XISlaveRemoved, XKeyEvent, 
+    XDefaultScreen, XNextEvent, XOpenDisplay, XPending, XPresentCompleteNotifyEvent, XSelectInput, XSetWMProtocols, XTranslateCoordinates, XkbEvent, 
+    XkbSetDetectableAutoRepeat, XkbStateNotify, ClientMessage, ConfigureNotify, DestroyNotify, Expose, ExposureMask, FocusChangeMask, FocusIn, FocusOut, KeyPress, 
+    KeyPressMask, KeyRelease, KeyReleaseMask, MapNotify, NotifyGrab, NotifyUngrab, PresentCompleteNotify, PropertyChangeMask, PropertyNotify, 
+    StructureNotifyMask, Success, UnmapNotify}, 
+    xkb::{x11_key_label, x11_keyboard_layout, x11_xkb_init}};
 
 pub(crate) struct X11WindowManager {
 
-    /// Used to fetch X11 events
-    event : XEvent,  
+    /// [WindowManagerEvent] generated by X11 events and windows, kept in queue until fetched.
+    queue : VecDeque<WindowManagerEvent>,
 
-    /// [WindowManagerEvent] kept in queue and fetched before X11 events.
-    queue : Vec<WindowManagerEvent>,
+    /// Last [WindowManagerEvent] fetched
+    event : Option<WindowManagerEvent>,
 
     /// X11 server display connection pointer
     x11display : *mut Display,
 
+    /// Root window of the default screen
+    root : super::xlib::Window,
+
     /// List of display
-    displays : Displays, 
+    displays : Rc<Displays>, 
+
+    /// List of input devices
+    devices : InputDevices,
+
+    /// XInput2 used for input devices and hotplug. None if not available.
+    xinput2 : Option<X11XInput2>,
+
+    /// XKB extension event base. None if XKB isn't available.
+    xkb_event : Option<i32>,
+
+    /// Present used to pace redraws. None if not available.
+    present : Option<Rc<X11Present>>,
+
+    /// MIT-SHM used by software framebuffers. None if not available.
+    shm : Option<Rc<X11Shm>>,
+
+    /// MIT-SCREEN-SAVER used to inhibit idle. None if not available.
+    screen_saver : Option<Rc<X11ScreenSaver>>,
+
+    /// Active keyboard layout
+    layout : KeyboardLayout,
+
+    /// System-wide hotkeys registered
+    hotkeys : Vec<(HotkeyId, KeyCombination)>,
+
+    /// Next [HotkeyId] to give
+    hotkey_next : HotkeyId,
 
     /// Atoms for handling x11 window properties
     atoms : X11Atoms,
@@ -47,65 +90,434 @@ pub(crate) struct X11WindowManager {
     /// [Window] collection of this manager.
     windows : Vec<Window>,
 
+    /// Handles given to windows
+    handles : WindowHandles,
+
+    /// X11 window id of each [WindowHandle], shared with windows to set parents.
+    xids : Rc<RefCell<Vec<(WindowHandle, super::xlib::Window)>>>,
+
+    /// Display connection, shared with windows and OpenGL contexts. Declared last so windows, their framebuffers and 
+    /// Cairo surfaces are dropped before the display is closed.
+    connection : Rc<X11Connection>,
+}
+
+/// X11 display connection closed with XCloseDisplay once the manager, its windows and OpenGL contexts are dropped.
+#[derive(Debug)]
+pub(crate) struct X11Connection {
+    /// X11 server display connection pointer
+    display : *mut Display,
 }
 
-impl Drop for X11WindowManager {
+impl Drop for X11Connection {
     fn drop(&mut self) {
         unsafe {
             // Close display connection
-            super::xlib::XCloseDisplay(self.x11display);
+            super::xlib::XCloseDisplay(self.display);
         }
     }
 }
 
+impl X11Connection {
+    /// X11 server display connection pointer, valid while the connection is alive.
+    #[inline(always)]
+    pub(crate) fn display(&self) -> *mut Display {
+        self.display
+    }
+}
+
 impl X11WindowManager {
+    /// Open the display named by `DISPLAY` and query the extensions used.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowManagerDisplayUnset`]) if `DISPLAY` isn't set.
+    /// Returns Err([`WindowError::WindowManagerConnectionFailed`]) if XOpenDisplay failed.
     #[inline(always)]
     pub fn new() -> Result<X11WindowManager, WindowError> {
 
+        let Ok(name) = std::env::var("DISPLAY") else {
+            return Err(WindowError::WindowManagerDisplayUnset("DISPLAY".to_string()));
+        };
+
         unsafe {
-            let event = XEvent { type_: 0 };
             let display = XOpenDisplay(std::ptr::null());
+            if display.is_null() {
+                return Err(WindowError::WindowManagerConnectionFailed(name));
+            }
             let atoms = X11Atoms::new(display);
 
+            // Input devices and hotplug need XInput2
+            let xinput2 = X11XInput2::load(display);
+            let devices = match &xinput2 {
+                Some(xinput2) => {
+                    xinput2.select_hierarchy_events(display);
+                    xinput2.input_devices(display)
+                },
+                None => InputDevices::create(Vec::new()),
+            };
+
+            // Keyboard layout need XKB
+            let xkb_event = x11_xkb_init(display);
+            let layout = match xkb_event {
+                Some(_) => {
+                    // Repeated keys are sent as KeyPress only instead of KeyRelease and KeyPress pairs.
+                    XkbSetDetectableAutoRepeat(display, 1, std::ptr::null_mut());
+                    x11_keyboard_layout(display)
+                },
+                None => KeyboardLayout::default(),
+            };
+
+            // Redraws are paced by the display with Present
+            let present = X11Present::load(display).map(Rc::new);
+
+            // Software framebuffers are shared with the server with MIT-SHM
+            let shm = X11Shm::load(display).map(Rc::new);
+
+            // Idle is inhibited by suspending the screen saver
+            let screen_saver = X11ScreenSaver::load(display).map(Rc::new);
+
+            let connection = Rc::new(X11Connection { display });
+
             match super::display::x11_displays() {
                 Ok(screens) => Ok(X11WindowManager { 
-                    event,
-                    queue : Vec::new(), 
+                    queue : VecDeque::new(), 
+                    event : None,
                     x11display: display, 
-                    displays: screens,
+                    root : XDefaultRootWindow(display),
+                    displays: Rc::new(screens),
+                    devices,
+                    xinput2,
+                    xkb_event,
+                    present,
+                    shm,
+                    screen_saver,
+                    layout,
+                    hotkeys : Vec::new(),
+                    hotkey_next : 1,
                     atoms,
-                    windows : Vec::new()
+                    windows : Vec::new(),
+                    handles : WindowHandles::default(),
+                    xids : Rc::new(RefCell::new(Vec::new())),
+                    connection,
                 }),
+                // Connection is closed when dropped.
                 Err(err) => Err(err),
             }
         }
     }
     
 
+    /// Poll an event. Pending X11 events are fetched when queue is empty.
     #[inline(always)]
-    pub fn event(&self) -> Option<&WindowManagerEvent> {
-        todo!()
+    pub fn event(&mut self) -> Option<&WindowManagerEvent> {
+        if self.queue.is_empty() {
+            self.dispatch(false);
+        }
+
+        self.next_event();
+        self.event.as_ref()
     }
 
+    /// Wait for an event, blocking on XNextEvent. Xlib exits the process with its default IO error handler when 
+    /// connection with the server is lost, so an event always comes.
     #[inline(always)]
-    pub fn event_wait(&self) -> &WindowManagerEvent {
-        todo!()
+    pub fn event_wait(&mut self) -> &WindowManagerEvent {
+        self.dispatch(true);
+
+        self.next_event();
+        self.event.get_or_insert(WindowManagerEvent::new(WindowHandle::NULL, WindowEvent::NoEvent))
     }
 
+    /// Fetch and handle X11 events with XNextEvent, then queue redraws of windows without Present. Blocks until an 
+    /// event is queued if `blocking`, otherwise only events already received are fetched.
+    /// 
+    /// Reference(s)
+    /// <https://tronche.com/gui/x/xlib/event-handling/manipulating-event-queue/XNextEvent.html>
+    fn dispatch(&mut self, blocking : bool) {
+        let mut event = XEvent { type_ : 0 };
 
-    #[inline(always)]
+        loop {
+            // XPending flushes requests before checking.
+            while unsafe { XPending(self.x11display) } > 0 {
+                unsafe { XNextEvent(self.x11display, &mut event) };
+                self.handle(&mut event);
+            }
+
+            // Redraws requested without Present are sent right away, without waiting.
+            self.queue_redraws();
+            if !blocking || !self.queue.is_empty() {
+                return;
+            }
+
+            unsafe { XNextEvent(self.x11display, &mut event) };
+            self.handle(&mut event);
+        }
+    }
+
+    /// Handle a X11 event according to its [X11EventRoute].
+    fn handle(&mut self, event : &mut XEvent) {
+        match x11_event_route(event, self.root, self.xinput2.as_ref().map(|xi| xi.opcode), self.xkb_event, self.present.as_ref().map(|p| p.opcode)) {
+            X11EventRoute::Window(xid) => self.window_event(xid, event),
+            X11EventRoute::Hotkey => { self.hotkey_event(unsafe { &event.xkey }); },
+            X11EventRoute::XInput2 => { self.xi_hierarchy_changed(unsafe { &mut event.xcookie }); },
+            X11EventRoute::Present => { self.present_complete_notify(unsafe { &mut event.xcookie }); },
+            // XKB events are read from the XkbEvent union, which begins with the core event.
+            X11EventRoute::Xkb => { self.xkb_state_notify(&XkbEvent { core : *event }); },
+            X11EventRoute::Ignored => {},
+        }
+    }
+
+    /// Queue the [WindowEvent] of a event of X11 window `xid`. Events of windows not managed are ignored.
+    fn window_event(&mut self, xid : super::xlib::Window, event : &XEvent) {
+        let Some(index) = self.windows.iter().position(|w| matches!(&w.window, LinuxWindow::X11(lw) if lw.window == xid)) else {
+            return;
+        };
+        let handle = self.windows[index].handle;
+
+        let events = unsafe {
+            match event.type_ as u32 {
+                KeyPress | KeyRelease => {
+                    let key = &event.xkey;
+                    let window = &mut self.windows[index];
+
+                    // State is the one before the key, which has the modifiers held with it. Grab release keys are checked with them.
+                    let locks = x11_key_locks(key.state, window.keyboard.locks().scroll);
+                    window.keyboard.set_modifiers(x11_key_modifiers(key.state), locks);
+
+                    if key.type_ == KeyPress as i32 {
+                        // Repeats come as KeyPress only with detectable auto repeat.
+                        if window.keyboard.is_pressed(key.keycode) && !window.keyboard.repeat() {
+                            return;
+                        }
+                        vec![WindowEvent::Keyboard(WindowKeyboardEvent::KeyDown(key.keycode))]
+                    } else {
+                        vec![WindowEvent::Keyboard(WindowKeyboardEvent::KeyUp(key.keycode))]
+                    }
+                },
+                FocusIn | FocusOut => {
+                    // Focus moved to grabs, like a hotkey pressed, stays on the window.
+                    if event.xfocus.mode == NotifyGrab as i32 || event.xfocus.mode == NotifyUngrab as i32 {
+                        return;
+                    }
+                    match event.type_ as u32 {
+                        FocusIn => vec![WindowEvent::Focus],
+                        _ => vec![WindowEvent::Blur],
+                    }
+                },
+                // Window mapped again after being iconified is restored to the states it has.
+                MapNotify => {
+                    if let LinuxWindow::X11(lw) = &mut self.windows[index].window {
+                        lw.set_mapped(true);
+                    }
+                    match self.windows[index].minimized {
+                        true => [vec![WindowEvent::Shown], self.state_events(index, xid, true)].concat(),
+                        false => vec![WindowEvent::Shown],
+                    }
+                },
+                // Window managers unmap windows they iconify, windows hidden are withdrawn by the window.
+                UnmapNotify => match &mut self.windows[index].window {
+                    LinuxWindow::X11(lw) => {
+                        lw.set_mapped(false);
+                        match lw.hidden {
+                            false => vec![WindowEvent::Minimized],
+                            true => vec![WindowEvent::Hidden],
+                        }
+                    },
+                    _ => vec![WindowEvent::Hidden],
+                },
+                ConfigureNotify => {
+                    let configure = &event.xconfigure;
+                    let window = &self.windows[index];
+
+                    // Synthetic events of the window manager are relative to the root window, real ones to the window manager frame.
+                    let position = match configure.send_event {
+                        0 => {
+                            let (mut x, mut y, mut child) = (0, 0, 0);
+                            XTranslateCoordinates(self.x11display, xid, self.root, 0, 0, &mut x, &mut y, &mut child);
+                            WindowPosition::new(x, y)
+                        },
+                        _ => WindowPosition::new(configure.x, configure.y),
+                    };
+                    let size = WindowSize::new(configure.width as u32, configure.height as u32);
+
+                    match (position != window.desktop_position, size != window.size) {
+                        (true, true) => vec![WindowEvent::MovedResized(position, size)],
+                        (true, false) => vec![WindowEvent::Moved(position)],
+                        (false, true) => vec![WindowEvent::Resized(size)],
+                        (false, false) => return,
+                    }
+                },
+                Expose => {
+                    self.expose_event(&event.xexpose);
+                    return;
+                },
+                DestroyNotify => vec![WindowEvent::Closed],
+                ClientMessage if event.xclient.data.l[0] as Atom == self.atoms.WM_DELETE_WINDOW => vec![WindowEvent::CloseButtonPressed],
+                PropertyNotify if event.xproperty.atom == self.atoms._NET_WM_STATE => self.state_events(index, xid, false),
+                _ => return,
+            }
+        };
+
+        self.queue.extend(events.into_iter().map(|event| WindowManagerEvent::new(handle, event)));
+    }
+
+    /// Events bringing the states of the [Window] at `index` to the _NET_WM_STATE of its X11 window `xid`. Window 
+    /// is restored first if a state was removed or if it was `deiconified`.
+    /// 
+    /// Reference(s)
+    /// <https://specifications.freedesktop.org/wm-spec/latest/ar01s05.html#id-1.6.8>
+    fn state_events(&self, index : usize, xid : super::xlib::Window, deiconified : bool) -> Vec<WindowEvent> {
+        let states = self.wm_states(xid);
+        let fullscreen = states.contains(&self.atoms._NET_WM_STATE_FULLSCREEN);
+        let maximized = states.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_VERT) && states.contains(&self.atoms._NET_WM_STATE_MAXIMIZED_HORZ);
+        let window = &self.windows[index];
+
+        let restored = deiconified || (window.fullscreen && !fullscreen) || (window.maximized && !maximized);
+        let mut events = Vec::new();
+        if restored {
+            events.push(WindowEvent::Restored);
+        }
+        if fullscreen && (restored || !window.fullscreen) {
+            events.push(WindowEvent::Fullscreen);
+        }
+        if maximized && (restored || !window.maximized) {
+            events.push(WindowEvent::Maximized);
+        }
+        events
+    }
+
+    /// Atoms of the _NET_WM_STATE property of X11 window `xid`, read with XGetWindowProperty.
+    /// 
+    /// Reference(s)
+    /// <https://tronche.com/gui/x/xlib/window-information/XGetWindowProperty.html>
+    fn wm_states(&self, xid : super::xlib::Window) -> Vec<Atom> {
+        unsafe {
+            let (mut kind, mut format, mut count, mut after) = (0, 0, 0, 0);
+            let mut data : *mut u8 = std::ptr::null_mut();
+
+            if XGetWindowProperty(self.x11display, xid, self.atoms._NET_WM_STATE, 0, 1024, 0, XA_ATOM, &mut kind, &mut format, &mut count, 
+                &mut after, &mut data) != Success as i32 || data.is_null() {
+                return Vec::new();
+            }
+
+            // Format 32 properties are returned as longs.
+            let states = match format {
+                32 => std::slice::from_raw_parts(data as *const Atom, count as usize).to_vec(),
+                _ => Vec::new(),
+            };
+            XFree(data as *mut std::ffi::c_void);
+            states
+        }
+    }
+
+    /// Pop the next event from queue and update the [Window] it refers to.
+    fn next_event(&mut self) {
+        let event = self.queue.pop_front();
+
+        if let Some(event) = &event {
+            if let Some(index) = self.windows.iter().position(|w| w.handle == event.window) {
+                if self.windows[index].handle_event(&event.event) {
+                    self.queue.push_back(WindowManagerEvent::new(event.window, WindowEvent::InputGrabReleased));
+                }
+
+                // Closed windows are removed from collection with their handle.
+                if event.event == WindowEvent::Closed {
+                    self.handles.remove(event.window);
+                    self.xids.borrow_mut().retain(|(handle, _)| *handle != event.window);
+                    self.windows.remove(index);
+                }
+            }
+        }
+
+        self.event = event;
+    }
+
+    /// Create a X11 [Window] from builder properties with XCreateSimpleWindow.
+    /// 
+    /// Initial maximized, fullscreen, minimized and taskbar states are given before the window is mapped.
+    /// 
+    /// Reference(s)
+    /// <https://tronche.com/gui/x/xlib/window/XCreateWindow.html>
     pub(crate) fn build(&mut self, builder : &WindowBuilder) -> Result<WindowHandle, WindowError> {
-        todo!()
+
+        if builder.min_size > builder.max_size {
+            return Err(WindowError::WindowMinSizeBiggerThanMax);
+        }
+        if builder.size > builder.max_size || builder.size < builder.min_size {
+            return Err(WindowError::WindowSizeOOB);
+        }
+
+        #[cfg(not(feature = "single_opt"))]
+        if let Some(parent) = builder.parent {
+            if !self.windows.iter().any(|w| w.handle == parent) {
+                return Err(WindowError::InvalidWindowHandle);
+            }
+        }
+
+        let xid = unsafe {
+            let black = XBlackPixel(self.x11display, XDefaultScreen(self.x11display));
+            let xid = XCreateSimpleWindow(self.x11display, self.root, 0, 0, builder.size.width.max(1), builder.size.height.max(1), 0, black, black);
+            XSelectInput(self.x11display, xid, (ExposureMask | KeyPressMask | KeyReleaseMask | StructureNotifyMask | FocusChangeMask 
+                | PropertyChangeMask) as libc::c_long);
+
+            // Close button sends a WM_DELETE_WINDOW client message instead of killing the connection.
+            let mut protocols = [self.atoms.WM_DELETE_WINDOW];
+            XSetWMProtocols(self.x11display, xid, protocols.as_mut_ptr(), protocols.len() as i32);
+            xid
+        };
+        if let Some(present) = &self.present {
+            present.select(self.x11display, xid);
+        }
+
+        let handle = self.handles.insert();
+        self.xids.borrow_mut().push((handle, xid));
+
+        let lw = X11Window::new(self.connection.clone(), xid, self.xids.clone(), self.displays.clone(), self.present.clone(), self.shm.clone(), 
+            self.screen_saver.clone());
+        let mut window = Window::new(builder, LinuxWindow::X11(lw), self.displays.clone(), handle);
+        window.keyboard.keyboard = Some(LinuxKeyboard::X11(X11Keyboard { display : self.x11display }));
+
+        // Window refused is destroyed before being managed. Its DestroyNotify is ignored.
+        if let Err(err) = window.set_position(builder.position.clone()) {
+            unsafe {
+                XDestroyWindow(self.x11display, xid);
+                XFlush(self.x11display);
+            }
+            self.xids.borrow_mut().retain(|(h, _)| *h != handle);
+            self.handles.remove(handle);
+            return Err(err);
+        }
+
+        window.window.set_title(&window.title)?;
+        window.window.set_size_min(window.min_size)?;
+        window.window.set_size_max(window.max_size)?;
+        #[cfg(not(feature = "single_opt"))]
+        window.window.set_parent(builder.parent)?;
+
+        if let LinuxWindow::X11(lw) = &mut window.window {
+            lw.set_initial_states(builder.maximized, builder.fsmode.clone(), builder.minimized, builder.taskbar);
+        }
+        if builder.visible {
+            window.window.show();
+        }
+
+        self.windows.push(window);
+        Ok(handle)
     }
 
     #[inline(always)]
-    pub fn window(&mut self, window : WindowHandle) -> Result<&Window, WindowError> {
-        todo!()
+    pub fn window(&self, window : WindowHandle) -> Result<&Window, WindowError> {
+        match self.windows.iter().find(|w| w.handle == window) {
+            Some(window) => Ok(window),
+            None => Err(WindowError::InvalidWindowHandle),
+        }
     } 
 
     #[inline(always)]
     pub fn window_mut(&mut self, window : WindowHandle) -> Result<&mut Window, WindowError> {
-        todo!()
+        match self.windows.iter_mut().find(|w| w.handle == window) {
+            Some(window) => Ok(window),
+            None => Err(WindowError::InvalidWindowHandle),
+        }
     }
 
 
@@ -113,4 +525,215 @@ impl X11WindowManager {
     pub fn displays(&self) -> &Displays {
         &self.displays
     }
+
+    /// Xlib display connection and default screen.
+    #[cfg(feature = "raw_window_handle")]
+    #[inline(always)]
+    pub(crate) fn raw_display_handle(&self) -> Result<raw_window_handle::RawDisplayHandle, raw_window_handle::HandleError> {
+        let screen = unsafe { XDefaultScreen(self.x11display) };
+        Ok(raw_window_handle::XlibDisplayHandle::new(std::ptr::NonNull::new(self.x11display as *mut std::ffi::c_void), screen).into())
+    }
+
+    /// Vulkan instance extensions of VK_KHR_xlib_surface.
+    #[cfg(feature = "vulkan")]
+    #[inline(always)]
+    pub(crate) fn vulkan_required_extensions(&self) -> Result<&'static [&'static std::ffi::CStr], WindowError> {
+        Ok(super::vulkan::X11_VULKAN_EXTENSIONS)
+    }
+
+    #[inline(always)]
+    pub fn input_devices(&self) -> &InputDevices {
+        &self.devices
+    }
+
+    #[inline(always)]
+    pub fn keyboard_layout(&self) -> &KeyboardLayout {
+        &self.layout
+    }
+
+    #[inline(always)]
+    pub fn key_label(&self, key : KeyCode) -> Option<String> {
+        match self.xkb_event {
+            Some(_) => x11_key_label(self.x11display, key, self.layout.group),
+            None => None,
+        }
+    }
+
+    pub fn register_hotkey(&mut self, key : KeyCode, modifiers : KeyModifiers) -> Result<HotkeyId, WindowError> {
+        let keys = KeyCombination::new(key, modifiers);
+
+        if self.hotkeys.iter().any(|(_, hk)| *hk == keys) {
+            Err(WindowError::HotkeyUnavailable)
+        } else {
+            match x11_grab_hotkey(self.x11display, keys) {
+                Ok(_) => {
+                    let id = self.hotkey_next;
+                    self.hotkey_next += 1;
+                    self.hotkeys.push((id, keys));
+                    Ok(id)
+                },
+                Err(err) => Err(err),
+            }
+        }
+    }
+
+    pub fn unregister_hotkey(&mut self, hotkey : HotkeyId) -> bool {
+        match self.hotkeys.iter().position(|(id, _)| *id == hotkey) {
+            Some(index) => {
+                let (_, keys) = self.hotkeys.remove(index);
+                x11_ungrab_hotkey(self.x11display, keys);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Queue [WindowEvent::HotkeyPressed] or [WindowEvent::HotkeyReleased] if a KeyPress or KeyRelease event match
+    /// a registered hotkey. Lock modifiers are ignored by [x11_key_modifiers].
+    /// 
+    /// Returns true if the key event was a hotkey.
+    pub(crate) fn hotkey_event(&mut self, event : &XKeyEvent) -> bool {
+        let modifiers = x11_key_modifiers(event.state);
+
+        match self.hotkeys.iter().find(|(_, keys)| keys.matches(event.keycode, modifiers)) {
+            Some((id, _)) => {
+                let event = if event.type_ == KeyPress as i32 {
+                    WindowEvent::HotkeyPressed(*id)
+                } else if event.type_ == KeyRelease as i32 {
+                    WindowEvent::HotkeyReleased(*id)
+                } else {
+                    return false;
+                };
+                self.queue.push_back(WindowManagerEvent::new(WindowHandle::NULL, event));
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Update the keyboard layout from a XkbStateNotify event and queue [WindowEvent::KeyboardLayoutChanged] 
+    /// if the group changed.
+    /// 
+    /// Returns false if the event isn't a XKB event.
+    pub(crate) fn xkb_state_notify(&mut self, event : &XkbEvent) -> bool {
+
+        match self.xkb_event {
+            Some(event_base) if unsafe { event.type_ } == event_base => unsafe {
+                if event.any.xkb_type == XkbStateNotify as i32 && event.state.group as u32 != self.layout.group {
+                    // Fetch name again since groups names can change with group.
+                    self.layout = x11_keyboard_layout(self.x11display);
+                    self.queue.push_back(WindowManagerEvent::new(WindowHandle::NULL, WindowEvent::KeyboardLayoutChanged));
+                }
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Update input devices from a XInput2 XI_HierarchyChanged generic event and queue 
+    /// [WindowEvent::DeviceAdded] and [WindowEvent::DeviceRemoved] events.
+    /// 
+    /// Returns false if the cookie isn't a XInput2 event.
+    pub(crate) fn xi_hierarchy_changed(&mut self, cookie : &mut XGenericEventCookie) -> bool {
+
+        match &self.xinput2 {
+            Some(xinput2) if xinput2.opcode == cookie.extension => unsafe {
+                if XGetEventData(self.x11display, cookie) == 0 {
+                    return false;
+                }
+
+                let event = &*(cookie.data as *mut XIHierarchyEvent);
+                for info in std::slice::from_raw_parts(event.info, event.num_info as usize) {
+                    let flags = info.flags as u32;
+
+                    if flags & XISlaveAdded != 0 {
+                        for device in xinput2.query_devices(self.x11display, info.deviceid) {
+                            let id = device.id;
+                            if self.devices.add(device) {
+                                self.queue.push_back(WindowManagerEvent::new(WindowHandle::NULL, WindowEvent::DeviceAdded(id)));
+                            }
+                        }
+                    } else if flags & XISlaveRemoved != 0 {
+                        if self.devices.remove(info.deviceid as u32) {
+                            self.queue.push_back(WindowManagerEvent::new(WindowHandle::NULL, WindowEvent::DeviceRemoved(info.deviceid as u32)));
+                        }
+                    } else if flags & (XIDeviceEnabled | XIDeviceDisabled) != 0 {
+                        // Enabled state is kept in list only.
+                        for device in xinput2.query_devices(self.x11display, info.deviceid) {
+                            self.devices.add(device);
+                        }
+                    }
+                }
+
+                XFreeEventData(self.x11display, cookie);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Queue [WindowEvent::Exposed] of a Expose event. Exposed [Window] requests a redraw once the event is fetched.
+    pub(crate) fn expose_event(&mut self, event : &XExposeEvent) {
+        if let Some(window) = self.windows.iter().find(|w| matches!(&w.window, LinuxWindow::X11(lw) if lw.window == event.window)) {
+            self.queue.push_back(WindowManagerEvent::new(window.handle, WindowEvent::Exposed(WindowCoordinate { x : event.x, y : event.y }, 
+                WindowSize::new(event.width as u32, event.height as u32))));
+        }
+    }
+
+    /// Queue [WindowEvent::RedrawRequested] of the window of a Present PresentCompleteNotify generic event if it requested a redraw.
+    /// 
+    /// Returns false if the cookie isn't a Present event.
+    pub(crate) fn present_complete_notify(&mut self, cookie : &mut XGenericEventCookie) -> bool {
+
+        match self.present.as_ref().map(|p| p.opcode) {
+            Some(opcode) if opcode == cookie.extension => unsafe {
+                if XGetEventData(self.x11display, cookie) == 0 {
+                    return false;
+                }
+
+                if cookie.evtype == PresentCompleteNotify as i32 {
+                    let event = &*(cookie.data as *mut XPresentCompleteNotifyEvent);
+                    let presented = x11_present_instant(event.ust);
+                    if let Some(index) = self.windows.iter().position(|w| matches!(&w.window, LinuxWindow::X11(lw) if lw.window == event.window)) {
+                        self.queue_redraw(index, presented);
+                    }
+                }
+
+                XFreeEventData(self.x11display, cookie);
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Queue [WindowEvent::RedrawRequested] of windows that requested a redraw without the Present extension. They are drawn right away.
+    pub(crate) fn queue_redraws(&mut self) {
+        for index in 0..self.windows.len() {
+            if matches!(&self.windows[index].window, LinuxWindow::X11(lw) if lw.redraw.present.is_none() && lw.redraw.requested) {
+                self.queue_redraw(index, Instant::now());
+            }
+        }
+    }
+
+    /// Queue [WindowEvent::RedrawRequested] of window at index if it requested a redraw, its last frame presented at `presented`.
+    fn queue_redraw(&mut self, index : usize, presented : Instant) {
+        let interval = self.refresh_interval(&self.windows[index]);
+        let window = &mut self.windows[index];
+
+        if let LinuxWindow::X11(lw) = &mut window.window {
+            if lw.redraw.complete() {
+                self.queue.push_back(WindowManagerEvent::new(window.handle, WindowEvent::RedrawRequested(WindowFrameTiming { 
+                    target : presented + interval, interval })));
+            }
+        }
+    }
+
+    /// Refresh interval of the display [Window] is on, or of the primary display if outside of displays.
+    fn refresh_interval(&self, window : &Window) -> Duration {
+        let position = window.desktop_position;
+        let display = self.displays.list.iter().find(|d| position.x >= d.position.x && position.y >= d.position.y 
+            && ((position.x - d.position.x) as usize) < d.resolution.width && ((position.y - d.position.y) as usize) < d.resolution.height);
+
+        display.or(self.displays.primary()).map_or(Duration::ZERO, |d| d.refresh_interval())
+    }
 }
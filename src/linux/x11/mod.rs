@@ -22,6 +22,7 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
+#[cfg(test)]
 use std::{panic::catch_unwind, thread};
 
 /// XLib bindings
@@ -51,9 +52,36 @@ pub(crate) mod pointer;
 /// X11 Keyboard
 pub(crate) mod keyboard;
 
-/// This function spawn a new thread and try to connect to X11 server to see if available.
+/// X11 Input devices from XInput2
+pub(crate) mod device;
+
+/// X11 Keyboard layout from XKB
+pub(crate) mod xkb;
+
+/// X11 System-wide hotkeys
+pub(crate) mod hotkey;
+
+/// X11 Software framebuffer with MIT-SHM
+pub(crate) mod framebuffer;
+
+/// X11 Frame pacing with Present
+pub(crate) mod present;
+
+/// X11 Idle inhibition with MIT-SCREEN-SAVER
+pub(crate) mod screensaver;
+
+/// X11 Vulkan surfaces
+#[cfg(feature = "vulkan")]
+pub(crate) mod vulkan;
+
+/// X11 OpenGL contexts with GLX and EGL
+#[cfg(feature = "opengl")]
+pub(crate) mod opengl;
+
+/// This function spawn a new thread and try to connect to X11 server to see if available. Used by tests needing a X11 server.
 /// 
 /// Return true if x11 server is available and supported. False otherwise.
+#[cfg(test)]
 pub fn x11_supported() -> bool { 
 
     unsafe {
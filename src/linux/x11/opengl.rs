@@ -0,0 +1,442 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! OpenGL contexts with GLX or EGL, loaded at runtime from libGL and libEGL.
+//! 
+//! Reference(s)
+//! <https://registry.khronos.org/OpenGL/extensions/ARB/GLX_ARB_create_context.txt>
+//! <https://registry.khronos.org/EGL/extensions/KHR/EGL_KHR_platform_x11.txt>
+
+use std::{ffi::{c_char, c_int, c_uint, c_void, CStr}, rc::Rc, sync::atomic::{AtomicBool, Ordering}};
+
+use crate::{linux::{egl::{has_extension, Egl, EglContext, EGL_PLATFORM_X11_KHR}, library::Library}, opengl::{GLApi, GLConfig, GLProfile}, WindowError};
+
+use super::{manager::X11Connection, xlib::{Display, Window, XErrorEvent, XFree, XGetWindowAttributes, XScreenNumberOfScreen, XSetErrorHandler, XSync, 
+    XVisualIDFromVisual, XWindowAttributes}};
+
+type GLXFBConfig = *mut c_void;
+type GLXContext = *mut c_void;
+
+// GLX framebuffer attributes
+const GLX_DOUBLEBUFFER : c_int = 5;
+const GLX_RED_SIZE : c_int = 8;
+const GLX_GREEN_SIZE : c_int = 9;
+const GLX_BLUE_SIZE : c_int = 10;
+const GLX_ALPHA_SIZE : c_int = 11;
+const GLX_DEPTH_SIZE : c_int = 12;
+const GLX_STENCIL_SIZE : c_int = 13;
+const GLX_VISUAL_ID : c_int = 0x800B;
+const GLX_DRAWABLE_TYPE : c_int = 0x8010;
+const GLX_RENDER_TYPE : c_int = 0x8011;
+const GLX_X_RENDERABLE : c_int = 0x8012;
+const GLX_WINDOW_BIT : c_int = 0x1;
+const GLX_RGBA_BIT : c_int = 0x1;
+const GLX_SAMPLE_BUFFERS : c_int = 100000;
+const GLX_SAMPLES : c_int = 100001;
+
+// GLX_ARB_create_context attributes
+const GLX_CONTEXT_MAJOR_VERSION_ARB : c_int = 0x2091;
+const GLX_CONTEXT_MINOR_VERSION_ARB : c_int = 0x2092;
+const GLX_CONTEXT_FLAGS_ARB : c_int = 0x2094;
+const GLX_CONTEXT_PROFILE_MASK_ARB : c_int = 0x9126;
+const GLX_CONTEXT_DEBUG_BIT_ARB : c_int = 0x1;
+const GLX_CONTEXT_CORE_PROFILE_BIT_ARB : c_int = 0x1;
+const GLX_CONTEXT_COMPATIBILITY_PROFILE_BIT_ARB : c_int = 0x2;
+const GLX_CONTEXT_ES2_PROFILE_BIT_EXT : c_int = 0x4;
+
+/// Set by [x11_glx_error_handler] when glXCreateContextAttribsARB failed.
+static X11_GLX_FAILED : AtomicBool = AtomicBool::new(false);
+
+/// X11 error handler used while creating GLX contexts. Unsupported versions are reported with X errors that would terminate the program otherwise.
+unsafe extern "C" fn x11_glx_error_handler(_display : *mut Display, _event : *mut XErrorEvent) -> i32 {
+    X11_GLX_FAILED.store(true, Ordering::SeqCst);
+    0
+}
+
+/// `glXCreateContextAttribsARB`
+type GlxCreateContextAttribs = unsafe extern "C" fn(*mut Display, GLXFBConfig, GLXContext, c_int, *const c_int) -> GLXContext;
+
+/// `glXSwapIntervalEXT`
+type GlxSwapIntervalExt = unsafe extern "C" fn(*mut Display, Window, c_int);
+
+/// `glXSwapIntervalMESA`
+type GlxSwapIntervalMesa = unsafe extern "C" fn(c_uint) -> c_int;
+
+/// GLX functions of libGL.
+#[derive(Debug)]
+struct Glx {
+    choose_fb_config : unsafe extern "C" fn(*mut Display, c_int, *const c_int, *mut c_int) -> *mut GLXFBConfig,
+    get_fb_config_attrib : unsafe extern "C" fn(*mut Display, GLXFBConfig, c_int, *mut c_int) -> c_int,
+    query_extensions_string : unsafe extern "C" fn(*mut Display, c_int) -> *const c_char,
+    make_context_current : unsafe extern "C" fn(*mut Display, Window, Window, GLXContext) -> c_int,
+    get_current_context : unsafe extern "C" fn() -> GLXContext,
+    swap_buffers : unsafe extern "C" fn(*mut Display, Window),
+    destroy_context : unsafe extern "C" fn(*mut Display, GLXContext),
+    get_proc_address : unsafe extern "C" fn(*const c_char) -> *const c_void,
+
+    /// Functions are valid while library is open.
+    _library : Library,
+}
+
+impl Glx {
+    /// Load GLX from libGL. None if libGL or one of its functions is missing.
+    fn load() -> Option<Glx> {
+        let library = Library::open(&[c"libGL.so.1", c"libGL.so"])?;
+        unsafe {
+            Some(Glx {
+                choose_fb_config : library.symbol(c"glXChooseFBConfig")?,
+                get_fb_config_attrib : library.symbol(c"glXGetFBConfigAttrib")?,
+                query_extensions_string : library.symbol(c"glXQueryExtensionsString")?,
+                make_context_current : library.symbol(c"glXMakeContextCurrent")?,
+                get_current_context : library.symbol(c"glXGetCurrentContext")?,
+                swap_buffers : library.symbol(c"glXSwapBuffers")?,
+                destroy_context : library.symbol(c"glXDestroyContext")?,
+                get_proc_address : library.symbol(c"glXGetProcAddressARB")?,
+                _library : library,
+            })
+        }
+    }
+
+    /// Get the extension function `name` as `T`.
+    unsafe fn extension<T : Copy>(&self, name : &CStr) -> Option<T> {
+        let function = (self.get_proc_address)(name.as_ptr());
+        (!function.is_null()).then(|| std::mem::transmute_copy::<*const c_void, T>(&function))
+    }
+}
+
+/// Context of the library used.
+#[derive(Debug)]
+enum X11GLApi {
+    /// GLX context drawing on the window.
+    Glx { glx : Rc<Glx>, context : GLXContext },
+
+    /// EGL context drawing on a window surface.
+    Egl(EglContext),
+}
+
+/// OpenGL context of an X11 window.
+#[derive(Debug)]
+pub(crate) struct X11GLContext {
+    /// X11 server display connection pointer
+    display : *mut Display,
+
+    /// X11 window id drawn on
+    window : Window,
+
+    /// Config with the sizes of the chosen framebuffer
+    config : GLConfig,
+
+    /// GLX or EGL context
+    api : X11GLApi,
+
+    /// Display connection kept open while the context exists, since contexts may outlive their manager. Declared last 
+    /// so the EGL context is dropped before it.
+    _connection : Rc<X11Connection>,
+}
+
+impl Drop for X11GLContext {
+    fn drop(&mut self) {
+        unsafe {
+            match &self.api {
+                X11GLApi::Glx { glx, context } => {
+                    if (glx.get_current_context)() == *context {
+                        (glx.make_context_current)(self.display, 0, 0, std::ptr::null_mut());
+                    }
+                    (glx.destroy_context)(self.display, *context);
+                },
+                // EGL context is destroyed when dropped.
+                X11GLApi::Egl(_) => {},
+            }
+        }
+    }
+}
+
+impl X11GLContext {
+    /// Create a context of `config` drawing on window, sharing objects with `shared` if given.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowOpenGLUnsupported`]) if the library or context creation extension is missing and 
+    /// Err([`WindowError::WindowOpenGLContextFailed`]) if no framebuffer matches config, context creation failed or `shared` uses 
+    /// another library or display.
+    pub(crate) fn new(connection : Rc<X11Connection>, window : Window, config : &GLConfig, shared : Option<&X11GLContext>) -> Result<X11GLContext, WindowError> {
+        let display = connection.display();
+        if let Some(shared) = shared {
+            if shared.display != display || shared.config.api != config.api {
+                return Err(WindowError::WindowOpenGLContextFailed);
+            }
+        }
+
+        let mut attributes : XWindowAttributes = unsafe { std::mem::zeroed() };
+        if unsafe { XGetWindowAttributes(display, window, &mut attributes) } == 0 {
+            return Err(WindowError::WindowOpenGLContextFailed);
+        }
+
+        match config.api {
+            GLApi::Glx => {
+                let glx = match shared.map(|shared| &shared.api) {
+                    Some(X11GLApi::Glx { glx, .. }) => glx.clone(),
+                    _ => Rc::new(Glx::load().ok_or(WindowError::WindowOpenGLUnsupported)?),
+                };
+                unsafe { Self::new_glx(connection, window, &attributes, config, glx, shared) }
+            },
+            GLApi::Egl => {
+                let egl = match shared.map(|shared| &shared.api) {
+                    Some(X11GLApi::Egl(shared)) => shared.egl.clone(),
+                    _ => Rc::new(Egl::load().ok_or(WindowError::WindowOpenGLUnsupported)?),
+                };
+                unsafe { Self::new_egl(connection, window, &attributes, config, egl, shared) }
+            },
+        }
+    }
+
+    /// Create a context with glXCreateContextAttribsARB on the framebuffer config of the window visual.
+    unsafe fn new_glx(connection : Rc<X11Connection>, window : Window, attributes : &XWindowAttributes, config : &GLConfig, 
+        glx : Rc<Glx>, shared : Option<&X11GLContext>) -> Result<X11GLContext, WindowError> {
+        let display = connection.display();
+        let screen = XScreenNumberOfScreen(attributes.screen);
+        let visual = XVisualIDFromVisual(attributes.visual) as c_int;
+
+        let extensions = (glx.query_extensions_string)(display, screen);
+        let create_context = match extensions.is_null() || !has_extension(CStr::from_ptr(extensions), "GLX_ARB_create_context") {
+            true => None,
+            false => glx.extension::<GlxCreateContextAttribs>(c"glXCreateContextAttribsARB"),
+        }.ok_or(WindowError::WindowOpenGLUnsupported)?;
+
+        // Configs are sorted by preference. Window can only be drawn by configs of its visual.
+        let mut count = 0;
+        let configs = (glx.choose_fb_config)(display, screen, glx_fb_config_attributes(config).as_ptr(), &mut count);
+        if configs.is_null() {
+            return Err(WindowError::WindowOpenGLContextFailed);
+        }
+        let fb_config = std::slice::from_raw_parts(configs, count as usize).iter().copied().find(|fb_config| {
+            let mut id = 0;
+            (glx.get_fb_config_attrib)(display, *fb_config, GLX_VISUAL_ID, &mut id);
+            id == visual
+        });
+        XFree(configs as *mut c_void);
+        let fb_config = fb_config.ok_or(WindowError::WindowOpenGLContextFailed)?;
+
+        let share = match shared.map(|shared| &shared.api) {
+            Some(X11GLApi::Glx { context, .. }) => *context,
+            _ => std::ptr::null_mut(),
+        };
+
+        X11_GLX_FAILED.store(false, Ordering::SeqCst);
+        let handler = XSetErrorHandler(Some(x11_glx_error_handler));
+        let context = create_context(display, fb_config, share, 1, glx_context_attributes(config).as_ptr());
+
+        // Wait for the server to report errors before restoring handler.
+        XSync(display, 0);
+        XSetErrorHandler(handler);
+
+        if context.is_null() || X11_GLX_FAILED.load(Ordering::SeqCst) {
+            if !context.is_null() {
+                (glx.destroy_context)(display, context);
+            }
+            return Err(WindowError::WindowOpenGLContextFailed);
+        }
+
+        let attrib = |attribute| {
+            let mut value = 0;
+            (glx.get_fb_config_attrib)(display, fb_config, attribute, &mut value);
+            value as u8
+        };
+        let config = GLConfig { red : attrib(GLX_RED_SIZE), green : attrib(GLX_GREEN_SIZE), blue : attrib(GLX_BLUE_SIZE), 
+            alpha : attrib(GLX_ALPHA_SIZE), depth : attrib(GLX_DEPTH_SIZE), stencil : attrib(GLX_STENCIL_SIZE), 
+            samples : attrib(GLX_SAMPLES), ..*config };
+
+        Ok(X11GLContext { display, window, config, api : X11GLApi::Glx { glx, context }, _connection : connection })
+    }
+
+    /// Create a context and window surface with EGL on the X11 platform, preferring configs of the window visual.
+    unsafe fn new_egl(connection : Rc<X11Connection>, window : Window, attributes : &XWindowAttributes, config : &GLConfig, 
+        egl : Rc<Egl>, shared : Option<&X11GLContext>) -> Result<X11GLContext, WindowError> {
+        let display = connection.display();
+        let visual = XVisualIDFromVisual(attributes.visual) as i32;
+        let egl_display = egl.platform_display(EGL_PLATFORM_X11_KHR, "EGL_EXT_platform_x11", display as *mut c_void);
+
+        let shared = match shared.map(|shared| &shared.api) {
+            Some(X11GLApi::Egl(shared)) => Some(shared),
+            _ => None,
+        };
+        let context = EglContext::new(egl, egl_display, window as usize, Some(visual), config, shared)?;
+
+        Ok(X11GLContext { display, window, config : *context.config(), api : X11GLApi::Egl(context), _connection : connection })
+    }
+
+    /// Make the context current on the calling thread.
+    pub(crate) fn make_current(&self) -> Result<(), WindowError> {
+        let current = unsafe {
+            match &self.api {
+                X11GLApi::Glx { glx, context } => (glx.make_context_current)(self.display, self.window, self.window, *context) != 0,
+                X11GLApi::Egl(context) => context.make_current(),
+            }
+        };
+
+        match current {
+            true => Ok(()),
+            false => Err(WindowError::WindowOpenGLContextFailed),
+        }
+    }
+
+    /// Swap buffers of the window with glXSwapBuffers or eglSwapBuffers.
+    pub(crate) fn swap_buffers(&self) -> Result<(), WindowError> {
+        unsafe {
+            match &self.api {
+                X11GLApi::Glx { glx, .. } => {
+                    (glx.swap_buffers)(self.display, self.window);
+                    Ok(())
+                },
+                X11GLApi::Egl(context) => match context.swap_buffers() {
+                    true => Ok(()),
+                    false => Err(WindowError::WindowOpenGLContextFailed),
+                },
+            }
+        }
+    }
+
+    /// Set swap interval with GLX_EXT_swap_control, GLX_MESA_swap_control or eglSwapInterval. Negative intervals need GLX_EXT_swap_control_tear on GLX.
+    pub(crate) fn set_swap_interval(&self, interval : i32) -> Result<(), WindowError> {
+        let set = unsafe {
+            match &self.api {
+                X11GLApi::Glx { glx, .. } => {
+                    let mut attributes : XWindowAttributes = std::mem::zeroed();
+                    XGetWindowAttributes(self.display, self.window, &mut attributes);
+                    let extensions = (glx.query_extensions_string)(self.display, XScreenNumberOfScreen(attributes.screen));
+                    let extensions = match extensions.is_null() {
+                        true => c"",
+                        false => CStr::from_ptr(extensions),
+                    };
+
+                    if has_extension(extensions, "GLX_EXT_swap_control") && (interval >= 0 || has_extension(extensions, "GLX_EXT_swap_control_tear")) {
+                        match glx.extension::<GlxSwapIntervalExt>(c"glXSwapIntervalEXT") {
+                            Some(swap_interval) => { swap_interval(self.display, self.window, interval); true },
+                            None => false,
+                        }
+                    } else if has_extension(extensions, "GLX_MESA_swap_control") && interval >= 0 {
+                        match glx.extension::<GlxSwapIntervalMesa>(c"glXSwapIntervalMESA") {
+                            Some(swap_interval) => swap_interval(interval as c_uint) == 0,
+                            None => false,
+                        }
+                    } else {
+                        false
+                    }
+                },
+                X11GLApi::Egl(context) => context.set_swap_interval(interval),
+            }
+        };
+
+        match set {
+            true => Ok(()),
+            false => Err(WindowError::WindowOpenGLContextFailed),
+        }
+    }
+
+    /// Get an OpenGL function with glXGetProcAddressARB or eglGetProcAddress.
+    pub(crate) fn get_proc_address(&self, name : &CStr) -> *const c_void {
+        unsafe {
+            match &self.api {
+                X11GLApi::Glx { glx, .. } => (glx.get_proc_address)(name.as_ptr()),
+                X11GLApi::Egl(context) => context.get_proc_address(name),
+            }
+        }
+    }
+
+    /// Config with the sizes of the chosen framebuffer.
+    #[inline(always)]
+    pub(crate) fn config(&self) -> &GLConfig {
+        &self.config
+    }
+}
+
+/// glXChooseFBConfig attributes of a double buffered RGBA window with config sizes.
+fn glx_fb_config_attributes(config : &GLConfig) -> Vec<c_int> {
+    let mut attributes = vec![GLX_X_RENDERABLE, 1, GLX_DRAWABLE_TYPE, GLX_WINDOW_BIT, GLX_RENDER_TYPE, GLX_RGBA_BIT, GLX_DOUBLEBUFFER, 1,
+        GLX_RED_SIZE, config.red as c_int, GLX_GREEN_SIZE, config.green as c_int, GLX_BLUE_SIZE, config.blue as c_int, 
+        GLX_ALPHA_SIZE, config.alpha as c_int, GLX_DEPTH_SIZE, config.depth as c_int, GLX_STENCIL_SIZE, config.stencil as c_int];
+
+    if config.samples > 0 {
+        attributes.extend([GLX_SAMPLE_BUFFERS, 1, GLX_SAMPLES, config.samples as c_int]);
+    }
+    attributes.push(0);
+    attributes
+}
+
+/// glXCreateContextAttribsARB attributes of config version, profile and debug flag.
+fn glx_context_attributes(config : &GLConfig) -> Vec<c_int> {
+    let profile = match config.profile {
+        GLProfile::Core => GLX_CONTEXT_CORE_PROFILE_BIT_ARB,
+        GLProfile::Compatibility => GLX_CONTEXT_COMPATIBILITY_PROFILE_BIT_ARB,
+        GLProfile::Es => GLX_CONTEXT_ES2_PROFILE_BIT_EXT,
+    };
+    let flags = match config.debug {
+        true => GLX_CONTEXT_DEBUG_BIT_ARB,
+        false => 0,
+    };
+
+    vec![GLX_CONTEXT_MAJOR_VERSION_ARB, config.major as c_int, GLX_CONTEXT_MINOR_VERSION_ARB, config.minor as c_int, 
+        GLX_CONTEXT_PROFILE_MASK_ARB, profile, GLX_CONTEXT_FLAGS_ARB, flags, 0]
+}
+
+/*************
+* UNIT TESTS * 
+*************/
+
+/// X11 OpenGL context tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn x11_opengl_extension() {
+        let extensions = c"GLX_ARB_create_context GLX_ARB_create_context_profile GLX_EXT_swap_control";
+
+        assert!(has_extension(extensions, "GLX_ARB_create_context"));
+        assert!(has_extension(extensions, "GLX_EXT_swap_control"));
+
+        // Prefix of another extension only
+        assert!(!has_extension(extensions, "GLX_ARB_create"));
+        assert!(!has_extension(c"", "GLX_EXT_swap_control"));
+    }
+
+    #[test]
+    fn x11_opengl_glx_attributes() {
+        let config = GLConfig::default();
+        assert_eq!(glx_fb_config_attributes(&config), vec![GLX_X_RENDERABLE, 1, GLX_DRAWABLE_TYPE, GLX_WINDOW_BIT, GLX_RENDER_TYPE, GLX_RGBA_BIT, 
+            GLX_DOUBLEBUFFER, 1, GLX_RED_SIZE, 8, GLX_GREEN_SIZE, 8, GLX_BLUE_SIZE, 8, GLX_ALPHA_SIZE, 0, GLX_DEPTH_SIZE, 24, GLX_STENCIL_SIZE, 8, 0]);
+        assert_eq!(glx_context_attributes(&config), vec![GLX_CONTEXT_MAJOR_VERSION_ARB, 3, GLX_CONTEXT_MINOR_VERSION_ARB, 3, 
+            GLX_CONTEXT_PROFILE_MASK_ARB, GLX_CONTEXT_CORE_PROFILE_BIT_ARB, GLX_CONTEXT_FLAGS_ARB, 0, 0]);
+
+        // MSAA and debug
+        let config = GLConfig { samples : 4, debug : true, ..GLConfig::new(4, 6, GLProfile::Compatibility) };
+        assert!(glx_fb_config_attributes(&config).ends_with(&[GLX_SAMPLE_BUFFERS, 1, GLX_SAMPLES, 4, 0]));
+        assert_eq!(glx_context_attributes(&config), vec![GLX_CONTEXT_MAJOR_VERSION_ARB, 4, GLX_CONTEXT_MINOR_VERSION_ARB, 6, 
+            GLX_CONTEXT_PROFILE_MASK_ARB, GLX_CONTEXT_COMPATIBILITY_PROFILE_BIT_ARB, GLX_CONTEXT_FLAGS_ARB, GLX_CONTEXT_DEBUG_BIT_ARB, 0]);
+
+        // OpenGL ES
+        let config = GLConfig::new(3, 2, GLProfile::Es);
+        assert_eq!(glx_context_attributes(&config)[4..6], [GLX_CONTEXT_PROFILE_MASK_ARB, GLX_CONTEXT_ES2_PROFILE_BIT_EXT]);
+    }
+}
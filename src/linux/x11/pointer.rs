@@ -56,6 +56,11 @@ impl X11Pointer {
         todo!()
     }
 
+    #[inline(always)]
+    pub fn locked(&self) -> bool {
+        todo!()
+    }
+
     #[inline(always)]
     pub fn set_cursor(&mut self, cursor : WindowCursor) {
         todo!()
@@ -0,0 +1,116 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! Frame pacing with the Present extension, loaded at runtime from libXpresent. XPresentNotifyMSC asks for a 
+//! PresentCompleteNotify when the next frame of the display (MSC) starts. Redraws are sent right away without it.
+//! 
+//! Reference(s)
+//! <https://gitlab.freedesktop.org/xorg/proto/xorgproto/-/blob/master/presentproto.txt>
+
+use std::{ffi::{c_int, c_uint}, rc::Rc, time::{Duration, Instant}};
+
+use crate::linux::library::Library;
+
+use super::xlib::{Display, Window, XFlush, PresentCompleteNotifyMask, XID};
+
+/// Present functions of libXpresent.
+#[derive(Debug)]
+pub(crate) struct X11Present {
+    /// Present extension opcode, identifying Present generic events.
+    pub opcode : i32,
+
+    notify_msc : unsafe extern "C" fn(*mut Display, Window, u32, u64, u64, u64),
+    select_input : unsafe extern "C" fn(*mut Display, Window, c_uint) -> XID,
+
+    /// Functions are valid while library is open.
+    _library : Library,
+}
+
+impl X11Present {
+    /// Load Present functions from libXpresent. None if libXpresent is missing or the server has no Present extension.
+    pub(crate) fn load(display : *mut Display) -> Option<X11Present> {
+        let library = Library::open(&[c"libXpresent.so.1", c"libXpresent.so"])?;
+        unsafe {
+            let query_extension : unsafe extern "C" fn(*mut Display, *mut c_int, *mut c_int, *mut c_int) -> c_int = 
+                library.symbol(c"XPresentQueryExtension")?;
+            let (mut opcode, mut event, mut error) = (0, 0, 0);
+            if query_extension(display, &mut opcode, &mut event, &mut error) == 0 {
+                return None;
+            }
+
+            Some(X11Present { opcode, notify_msc : library.symbol(c"XPresentNotifyMSC")?, select_input : library.symbol(c"XPresentSelectInput")?, 
+                _library : library })
+        }
+    }
+
+    /// Select PresentCompleteNotify events of window.
+    pub(crate) fn select(&self, display : *mut Display, window : Window) {
+        unsafe {
+            (self.select_input)(display, window, PresentCompleteNotifyMask);
+        }
+    }
+}
+
+/// Redraw state of a X11 window.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct X11Redraw {
+    /// Redraws are paced by PresentCompleteNotify. None if Present isn't available.
+    pub present : Option<Rc<X11Present>>,
+
+    /// Redraw requested and not sent yet.
+    pub requested : bool,
+
+    /// XPresentNotifyMSC sent, waiting for its PresentCompleteNotify.
+    pub pending : bool,
+}
+
+impl X11Redraw {
+    /// Request a redraw, asking for a PresentCompleteNotify of window if none is pending.
+    pub(crate) fn request(&mut self, display : *mut Display, window : Window) {
+        self.requested = true;
+
+        if let Some(present) = &self.present {
+            if !self.pending {
+                self.pending = true;
+                unsafe {
+                    // Completes at the first MSC after the current one.
+                    (present.notify_msc)(display, window, 0, 0, 1, 0);
+                    XFlush(display);
+                }
+            }
+        }
+    }
+
+    /// Take the redraw requested once the frame is complete. Returns true if a redraw was requested.
+    pub(crate) fn complete(&mut self) -> bool {
+        self.pending = false;
+        std::mem::take(&mut self.requested)
+    }
+}
+
+/// Get the [Instant] of a PresentCompleteNotify from its UST, in microseconds of CLOCK_MONOTONIC.
+pub(crate) fn x11_present_instant(ust : u64) -> Instant {
+    let elapsed = super::super::monotonic_time().saturating_sub(Duration::from_micros(ust));
+    super::super::presentation_instant(elapsed)
+}
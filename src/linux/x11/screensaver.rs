@@ -0,0 +1,74 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! Idle inhibition with XScreenSaverSuspend of the MIT-SCREEN-SAVER extension, loaded at runtime from libXss.
+//! 
+//! Suspensions are counted by the server for each client and released when the client disconnects, so windows 
+//! inhibiting idle don't interfere and a crashed application doesn't leave the screen saver disabled. The screen 
+//! saver settings and DPMS timeouts are never changed.
+//! 
+//! Reference(s)
+//! <https://www.x.org/releases/current/doc/man/man3/Xss.3.xhtml>
+
+use std::ffi::c_int;
+
+use crate::linux::library::Library;
+
+use super::xlib::{Display, XFlush};
+
+/// MIT-SCREEN-SAVER functions of libXss.
+#[derive(Debug)]
+pub(crate) struct X11ScreenSaver {
+    suspend : unsafe extern "C" fn(*mut Display, c_int),
+
+    /// Functions are valid while library is open.
+    _library : Library,
+}
+
+impl X11ScreenSaver {
+    /// Load XScreenSaverSuspend from libXss. None if libXss is missing or the server has no MIT-SCREEN-SAVER extension.
+    pub(crate) fn load(display : *mut Display) -> Option<X11ScreenSaver> {
+        let library = Library::open(&[c"libXss.so.1", c"libXss.so"])?;
+        unsafe {
+            let query_extension : unsafe extern "C" fn(*mut Display, *mut c_int, *mut c_int) -> c_int = 
+                library.symbol(c"XScreenSaverQueryExtension")?;
+            let (mut event, mut error) = (0, 0);
+            if query_extension(display, &mut event, &mut error) == 0 {
+                return None;
+            }
+
+            Some(X11ScreenSaver { suspend : library.symbol(c"XScreenSaverSuspend")?, _library : library })
+        }
+    }
+
+    /// Suspend the screen saver and DPMS, or release a suspension. Screen saver resumes once every suspension of the 
+    /// client is released.
+    pub(crate) fn suspend(&self, display : *mut Display, suspend : bool) {
+        unsafe {
+            (self.suspend)(display, suspend as c_int);
+            XFlush(display);
+        }
+    }
+}
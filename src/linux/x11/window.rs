@@ -24,7 +24,555 @@ SOFTWARE.
 
 // For set_icon : http://www.leonerd.org.uk/code/xseticon/
 
-#[derive(Debug, PartialEq)]
+use std::{cell::RefCell, ffi::{CStr, CString}, rc::Rc};
+
+use crate::{display::Displays, WindowError, WindowFullScreenMode, WindowHandle, WindowPosition, WindowSize};
+
+use super::{atom::XA_ATOM, framebuffer::{X11Framebuffer, X11Shm}, manager::X11Connection, present::{X11Present, X11Redraw}, 
+    screensaver::X11ScreenSaver};
+
+use super::xlib::{Atom, ButtonPressMask, ButtonReleaseMask, ClientMessage, CurrentTime, Display, GrabModeAsync, GrabSuccess, IconicState, PMaxSize, PMinSize, PPosition, PointerMotionMask, 
+    PropModeReplace, StateHint, SubstructureNotifyMask, SubstructureRedirectMask, Time, Window, XChangeProperty, XDefaultRootWindow, XDefaultScreen, XDeleteProperty, 
+    XDestroyWindow, XEvent, XFlush, XGetGeometry, XGrabKeyboard, XGrabPointer, XIconifyWindow, XInternAtom, XMapWindow, XMoveWindow, 
+    XResizeWindow, XSendEvent, XSetTransientForHint, XSetWMHints, XSetWMNormalHints, XSizeHints, XStoreName, XTranslateCoordinates, 
+    XUngrabKeyboard, XUngrabPointer, XWMHints, XWithdrawWindow};
+
+#[cfg(feature = "cairo")]
+use super::xlib::{XGetWindowAttributes, XWindowAttributes};
+
+#[derive(Debug)]
 pub struct X11Window {
-    
+    /// X11 server display connection pointer
+    pub(crate) display : *mut Display,
+
+    /// X11 window id
+    pub(crate) window : Window,
+
+    /// Software framebuffer of the window
+    pub(crate) framebuffer : X11Framebuffer,
+
+    /// Redraw paced by the Present extension
+    pub(crate) redraw : X11Redraw,
+
+    /// MIT-SCREEN-SAVER shared with the manager, None if not available.
+    pub(crate) screen_saver : Option<Rc<X11ScreenSaver>>,
+
+    /// Idle inhibition requested
+    pub(crate) idle_inhibit : bool,
+
+    /// Screen saver suspended by the window, while idle is inhibited and the window is mapped.
+    pub(crate) idle_suspended : bool,
+
+    /// Window is mapped, neither hidden nor iconified.
+    pub(crate) mapped : bool,
+
+    /// X11 window id of each [WindowHandle] of the display, shared with the manager.
+    pub(crate) windows : Rc<RefCell<Vec<(WindowHandle, Window)>>>,
+
+    /// List of display, used to place fullscreen windows.
+    pub(crate) displays : Rc<Displays>,
+
+    /// Minimum size given to the window manager with WM_NORMAL_HINTS
+    pub(crate) size_min : WindowSize,
+
+    /// Maximum size given to the window manager with WM_NORMAL_HINTS
+    pub(crate) size_max : WindowSize,
+
+    /// Window withdrawn by hide, restore must not map it.
+    pub(crate) hidden : bool,
+
+    /// Cairo surface drawing on the window
+    #[cfg(feature = "cairo")]
+    pub(crate) cairo : crate::linux::cairo::LinuxCairoSurface,
+
+    /// Display connection kept open while the window exists. Declared last so the framebuffer and Cairo surface are 
+    /// dropped before it.
+    pub(crate) connection : Rc<X11Connection>,
+}
+
+impl PartialEq for X11Window {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.connection, &other.connection) && self.window == other.window
+    }
+}
+
+impl X11Window {
+
+    /// Wrap the X11 window `window` created by the manager. Window starts hidden, its size limits are given once built.
+    pub(crate) fn new(connection : Rc<X11Connection>, window : Window, windows : Rc<RefCell<Vec<(WindowHandle, Window)>>>, displays : Rc<Displays>, 
+        present : Option<Rc<X11Present>>, shm : Option<Rc<X11Shm>>, screen_saver : Option<Rc<X11ScreenSaver>>) -> X11Window {
+        let display = connection.display();
+        X11Window { 
+            display, 
+            window, 
+            framebuffer : X11Framebuffer::new(display, window, shm), 
+            redraw : X11Redraw { present, ..Default::default() }, 
+            screen_saver, 
+            idle_inhibit : false,
+            idle_suspended : false,
+            mapped : false,
+            windows, 
+            displays, 
+            size_min : WindowSize::new(0, 0), 
+            size_max : WindowSize::new(0, 0), 
+            hidden : true,
+            #[cfg(feature = "cairo")] 
+            cairo : crate::linux::cairo::LinuxCairoSurface::new(),
+            connection,
+        }
+    }
+
+    /// X11 window id
+    #[inline(always)]
+    pub fn native_handle(&self) -> usize {
+        self.window as usize
+    }
+
+    /// Get position on the root window and size of a window of this display with XGetGeometry. 
+    /// Closed windows are empty at the origin.
+    /// 
+    /// Reference(s)
+    /// <https://tronche.com/gui/x/xlib/window-information/XGetGeometry.html>
+    pub(crate) fn get_window_pos_size(&self, handle : WindowHandle) -> (WindowPosition, WindowSize) {
+        let Some(window) = self.xid(handle) else {
+            return (WindowPosition::new(0, 0), WindowSize::new(0, 0));
+        };
+
+        unsafe {
+            let (mut root, mut child) = (0, 0);
+            let (mut x, mut y, mut width, mut height, mut border, mut depth) = (0, 0, 0, 0, 0, 0);
+            if XGetGeometry(self.display, window, &mut root, &mut x, &mut y, &mut width, &mut height, &mut border, &mut depth) == 0 {
+                return (WindowPosition::new(0, 0), WindowSize::new(0, 0));
+            }
+
+            // Geometry is relative to the X11 parent, which is the frame of the window manager.
+            XTranslateCoordinates(self.display, window, root, 0, 0, &mut x, &mut y, &mut child);
+            (WindowPosition::new(x, y), WindowSize::new(width, height))
+        }
+    }
+
+    /// Make the window transient for its parent with XSetTransientForHint, letting the window manager keep it above.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::InvalidWindowHandle`]) if the parent isn't a window of this display.
+    /// 
+    /// Reference(s)
+    /// <https://tronche.com/gui/x/xlib/ICC/client-to-window-manager/XSetTransientForHint.html>
+    #[inline(always)]
+    pub fn set_parent(&mut self, parent : Option<WindowHandle>) -> Result<bool, WindowError> {
+        unsafe {
+            match parent {
+                Some(parent) => match self.xid(parent) {
+                    Some(parent) => { XSetTransientForHint(self.display, self.window, parent); },
+                    None => return Err(WindowError::InvalidWindowHandle),
+                },
+                None => { XDeleteProperty(self.display, self.window, XInternAtom(self.display, c"WM_TRANSIENT_FOR".as_ptr(), 0)); },
+            }
+            XFlush(self.display);
+        }
+        Ok(true)
+    }
+
+    /// X11 window id of a [WindowHandle] of this display.
+    fn xid(&self, handle : WindowHandle) -> Option<Window> {
+        self.windows.borrow().iter().find(|(h, _)| *h == handle).map(|(_, window)| *window)
+    }
+
+    /// Set WM_NAME with XStoreName and the UTF-8 _NET_WM_NAME read by EWMH window managers. Nul characters are removed.
+    /// 
+    /// Reference(s)
+    /// <https://specifications.freedesktop.org/wm-spec/latest/ar01s05.html#id-1.6.2>
+    #[inline(always)]
+    pub fn set_title(&mut self, title : &str) -> Result<bool, WindowError> {
+        let title = CString::new(title.replace('\0', "")).unwrap_or_default();
+
+        unsafe {
+            XStoreName(self.display, self.window, title.as_ptr());
+            XChangeProperty(self.display, self.window, XInternAtom(self.display, c"_NET_WM_NAME".as_ptr(), 0), 
+                XInternAtom(self.display, c"UTF8_STRING".as_ptr(), 0), 8, PropModeReplace as i32, title.as_ptr() as *const u8, 
+                title.as_bytes().len() as i32);
+            XFlush(self.display);
+        }
+        Ok(true)
+    }
+
+    /// Resize the window with XResizeWindow. Window managers may adjust the size.
+    #[inline(always)]
+    pub fn set_size(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        unsafe {
+            XResizeWindow(self.display, self.window, size.width.max(1), size.height.max(1));
+            XFlush(self.display);
+        }
+        Ok(true)
+    }
+
+    #[inline(always)]
+    pub fn set_size_min(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        self.size_min = size;
+        self.set_size_hints();
+        Ok(true)
+    }
+
+    #[inline(always)]
+    pub fn set_size_max(&mut self, size : WindowSize) -> Result<bool, WindowError> {
+        self.size_max = size;
+        self.set_size_hints();
+        Ok(true)
+    }
+
+    /// Give the minimum and maximum sizes to the window manager with XSetWMNormalHints. Position set by the program is kept.
+    /// 
+    /// Reference(s)
+    /// <https://tronche.com/gui/x/xlib/ICC/client-to-window-manager/XSetWMNormalHints.html>
+    fn set_size_hints(&self) {
+        unsafe {
+            let mut hints : XSizeHints = std::mem::zeroed();
+            hints.flags = (PPosition | PMinSize | PMaxSize) as libc::c_long;
+            hints.min_width = self.size_min.width.min(i32::MAX as u32) as i32;
+            hints.min_height = self.size_min.height.min(i32::MAX as u32) as i32;
+            hints.max_width = self.size_max.width.min(i32::MAX as u32) as i32;
+            hints.max_height = self.size_max.height.min(i32::MAX as u32) as i32;
+            XSetWMNormalHints(self.display, self.window, &mut hints);
+            XFlush(self.display);
+        }
+    }
+
+    /// Icon formats aren't decoded, the window keeps the icon given by the window manager.
+    #[inline(always)]
+    pub fn set_icon(&mut self, _icon : Option<&mut dyn std::io::Read>) {}
+
+    /// Add or remove _NET_WM_STATE_SKIP_TASKBAR with a EWMH _NET_WM_STATE client message.
+    /// 
+    /// Reference(s)
+    /// <https://specifications.freedesktop.org/wm-spec/latest/ar01s05.html#id-1.6.8>
+    #[inline(always)]
+    pub fn set_taskbar(&mut self, show : bool) {
+        let skip = unsafe { XInternAtom(self.display, c"_NET_WM_STATE_SKIP_TASKBAR".as_ptr(), 0) };
+
+        // Action 0 removes the state, 1 adds it. Source indication 1 is a normal application.
+        self.send_wm_message(c"_NET_WM_STATE", [if show { 0 } else { 1 }, skip as libc::c_long, 0, 1, 0]);
+    }
+
+    /// Move the window with XMoveWindow. Window managers may adjust the position.
+    #[inline(always)]
+    pub fn set_position(&mut self, position : WindowPosition) {
+        unsafe {
+            XMoveWindow(self.display, self.window, position.x, position.y);
+            XFlush(self.display);
+        }
+    }
+
+    /// Remove fullscreen and maximized states. Minimized windows are mapped again, which deiconify them.
+    #[inline(always)]
+    pub fn restore(&mut self) {
+        let (fullscreen, vert, horz) = self.state_atoms();
+
+        // Action 0 removes the states. Source indication 1 is a normal application.
+        self.send_wm_message(c"_NET_WM_STATE", [0, fullscreen as libc::c_long, 0, 1, 0]);
+        self.send_wm_message(c"_NET_WM_STATE", [0, vert as libc::c_long, horz as libc::c_long, 1, 0]);
+
+        if !self.hidden {
+            unsafe {
+                XMapWindow(self.display, self.window);
+                XFlush(self.display);
+            }
+        }
+    }
+
+    /// Map the window with XMapWindow. [WindowEvent::Shown](crate::event::WindowEvent::Shown) is sent with MapNotify.
+    #[inline(always)]
+    pub fn show(&mut self) {
+        self.hidden = false;
+        unsafe {
+            XMapWindow(self.display, self.window);
+            XFlush(self.display);
+        }
+    }
+
+    /// Withdraw the window with XWithdrawWindow, removing it from the window manager too.
+    /// 
+    /// Reference(s)
+    /// <https://tronche.com/gui/x/xlib/ICC/client-to-window-manager/XWithdrawWindow.html>
+    #[inline(always)]
+    pub fn hide(&mut self) {
+        self.hidden = true;
+        unsafe {
+            XWithdrawWindow(self.display, self.window, XDefaultScreen(self.display));
+            XFlush(self.display);
+        }
+    }
+
+    /// Destroy the window with XDestroyWindow. [WindowEvent::Closed](crate::event::WindowEvent::Closed) is sent with DestroyNotify.
+    #[inline(always)]
+    pub fn close(&mut self) {
+        self.set_idle_inhibit(false);
+        #[cfg(feature = "cairo")]
+        self.cairo.destroy();
+        self.framebuffer.destroy();
+
+        unsafe {
+            XDestroyWindow(self.display, self.window);
+            XFlush(self.display);
+        }
+    }
+
+    /// Add _NET_WM_STATE_FULLSCREEN, the window manager covering the monitor the window is on. Window is moved on the 
+    /// display asked first, or covers all monitors with _NET_WM_FULLSCREEN_MONITORS for [WindowFullScreenMode::Desktop].
+    /// 
+    /// Reference(s)
+    /// <https://specifications.freedesktop.org/wm-spec/latest/ar01s06.html#id-1.7.7>
+    #[inline(always)]
+    pub fn set_fullscreen(&mut self, fsmode : WindowFullScreenMode) {
+        self.fullscreen_placement(fsmode);
+
+        let (fullscreen, _, _) = self.state_atoms();
+        self.send_wm_message(c"_NET_WM_STATE", [1, fullscreen as libc::c_long, 0, 1, 0]);
+    }
+
+    /// Place the window for `fsmode` before it is made fullscreen.
+    pub(crate) fn fullscreen_placement(&mut self, fsmode : WindowFullScreenMode) {
+        let display = match fsmode {
+            WindowFullScreenMode::Current => return,
+            WindowFullScreenMode::Primary => self.displays.primary().or_else(|| self.displays.list.first()),
+            WindowFullScreenMode::Display(handle) => self.displays.list.get(handle),
+            WindowFullScreenMode::Desktop => {
+                // Monitors are given as Xinerama indexes, in the order RandR lists them.
+                let list = &self.displays.list;
+                let index = |key : fn(&crate::display::Display) -> i64| (0..list.len()).min_by_key(|i| key(&list[*i])).unwrap_or(0) as libc::c_long;
+                let (top, bottom) = (index(|d| d.position.y as i64), index(|d| -(d.position.y as i64 + d.resolution.height as i64)));
+                let (left, right) = (index(|d| d.position.x as i64), index(|d| -(d.position.x as i64 + d.resolution.width as i64)));
+                self.send_wm_message(c"_NET_WM_FULLSCREEN_MONITORS", [top, bottom, left, right, 1]);
+                return;
+            },
+        };
+
+        if let Some(position) = display.map(|d| WindowPosition::new(d.position.x, d.position.y)) {
+            self.set_position(position);
+        }
+    }
+
+    /// Iconify the window with XIconifyWindow.
+    /// 
+    /// Reference(s)
+    /// <https://tronche.com/gui/x/xlib/ICC/client-to-window-manager/XIconifyWindow.html>
+    #[inline(always)]
+    pub fn minimize(&mut self) {
+        unsafe {
+            XIconifyWindow(self.display, self.window, XDefaultScreen(self.display));
+            XFlush(self.display);
+        }
+    }
+
+    /// Add _NET_WM_STATE_MAXIMIZED_VERT and _NET_WM_STATE_MAXIMIZED_HORZ.
+    #[inline(always)]
+    pub fn maximize(&mut self) {
+        let (_, vert, horz) = self.state_atoms();
+        self.send_wm_message(c"_NET_WM_STATE", [1, vert as libc::c_long, horz as libc::c_long, 1, 0]);
+    }
+
+    /// Give the states of a window not mapped yet. Window managers read _NET_WM_STATE and the WM_HINTS initial state 
+    /// when the window is mapped and ignore client messages before.
+    /// 
+    /// Reference(s)
+    /// <https://specifications.freedesktop.org/wm-spec/latest/ar01s05.html#id-1.6.8>
+    pub(crate) fn set_initial_states(&mut self, maximized : bool, fsmode : Option<WindowFullScreenMode>, minimized : bool, taskbar : bool) {
+        let (fullscreen, vert, horz) = self.state_atoms();
+        let mut states : Vec<Atom> = Vec::new();
+
+        if maximized {
+            states.extend([vert, horz]);
+        }
+        if let Some(fsmode) = fsmode {
+            self.fullscreen_placement(fsmode);
+            states.push(fullscreen);
+        }
+        if !taskbar {
+            states.push(unsafe { XInternAtom(self.display, c"_NET_WM_STATE_SKIP_TASKBAR".as_ptr(), 0) });
+        }
+
+        unsafe {
+            XChangeProperty(self.display, self.window, XInternAtom(self.display, c"_NET_WM_STATE".as_ptr(), 0), XA_ATOM, 32, 
+                PropModeReplace as i32, states.as_ptr() as *const u8, states.len() as i32);
+
+            if minimized {
+                let mut hints : XWMHints = std::mem::zeroed();
+                hints.flags = StateHint as libc::c_long;
+                hints.initial_state = IconicState as i32;
+                XSetWMHints(self.display, self.window, &mut hints);
+            }
+            XFlush(self.display);
+        }
+    }
+
+    /// Atoms of _NET_WM_STATE_FULLSCREEN, _NET_WM_STATE_MAXIMIZED_VERT and _NET_WM_STATE_MAXIMIZED_HORZ.
+    fn state_atoms(&self) -> (Atom, Atom, Atom) {
+        unsafe {
+            (XInternAtom(self.display, c"_NET_WM_STATE_FULLSCREEN".as_ptr(), 0), 
+            XInternAtom(self.display, c"_NET_WM_STATE_MAXIMIZED_VERT".as_ptr(), 0), 
+            XInternAtom(self.display, c"_NET_WM_STATE_MAXIMIZED_HORZ".as_ptr(), 0))
+        }
+    }
+
+    /// Grab keyboard and pointer with XGrabKeyboard and XGrabPointer. Pointer is confined to the window.
+    /// 
+    /// Reference(s)
+    /// <https://tronche.com/gui/x/xlib/input/XGrabKeyboard.html>
+    #[inline(always)]
+    pub fn grab_input(&mut self) -> Result<bool, WindowError> {
+        unsafe {
+            if XGrabKeyboard(self.display, self.window, 1, GrabModeAsync as i32, GrabModeAsync as i32, CurrentTime as Time) != GrabSuccess as i32 {
+                return Err(WindowError::WindowInputGrabFailed);
+            }
+
+            if XGrabPointer(self.display, self.window, 1, ButtonPressMask | ButtonReleaseMask | PointerMotionMask, 
+                GrabModeAsync as i32, GrabModeAsync as i32, self.window, 0, CurrentTime as Time) != GrabSuccess as i32 {
+                // Keyboard grab alone is not wanted.
+                XUngrabKeyboard(self.display, CurrentTime as Time);
+                return Err(WindowError::WindowInputGrabFailed);
+            }
+
+            XFlush(self.display);
+        }
+
+        Ok(true)
+    }
+
+    /// Release keyboard and pointer grabbed.
+    #[inline(always)]
+    pub fn ungrab_input(&mut self) {
+        unsafe {
+            XUngrabPointer(self.display, CurrentTime as Time);
+            XUngrabKeyboard(self.display, CurrentTime as Time);
+            XFlush(self.display);
+        }
+    }
+
+    /// Suspend the screen saver and DPMS with XScreenSaverSuspend while the window is mapped. Ignored without MIT-SCREEN-SAVER.
+    /// 
+    /// Reference(s)
+    /// <https://www.x.org/releases/current/doc/man/man3/Xss.3.xhtml>
+    #[inline(always)]
+    pub fn set_idle_inhibit(&mut self, inhibit : bool) {
+        self.idle_inhibit = inhibit;
+        self.update_idle_suspend();
+    }
+
+    /// Set if the window is mapped, given by MapNotify and UnmapNotify. Idle is only inhibited while mapped.
+    pub(crate) fn set_mapped(&mut self, mapped : bool) {
+        self.mapped = mapped;
+        self.update_idle_suspend();
+    }
+
+    /// Suspend or release the screen saver when idle inhibition or mapping changed.
+    fn update_idle_suspend(&mut self) {
+        let suspend = self.idle_inhibit && self.mapped;
+        if let Some(screen_saver) = &self.screen_saver {
+            if suspend != self.idle_suspended {
+                screen_saver.suspend(self.display, suspend);
+                self.idle_suspended = suspend;
+            }
+        }
+    }
+
+    /// Ask the window manager to activate the window with a EWMH _NET_ACTIVE_WINDOW client message sent to the root window.
+    /// 
+    /// Reference(s)
+    /// <https://specifications.freedesktop.org/wm-spec/latest/ar01s03.html#id-1.4.10>
+    #[inline(always)]
+    pub fn activate(&mut self) {
+        // Source indication 1 is a normal application.
+        self.send_wm_message(c"_NET_ACTIVE_WINDOW", [1, CurrentTime as libc::c_long, 0, 0, 0]);
+    }
+
+    /// Send a EWMH client message about this window to the window manager through the root window.
+    /// 
+    /// Reference(s)
+    /// <https://specifications.freedesktop.org/wm-spec/latest/ar01s03.html>
+    fn send_wm_message(&self, message_type : &CStr, data : [libc::c_long; 5]) {
+        unsafe {
+            let root = XDefaultRootWindow(self.display);
+            let mut event = XEvent { type_ : 0 };
+            event.xclient.type_ = ClientMessage as i32;
+            event.xclient.display = self.display;
+            event.xclient.window = self.window;
+            event.xclient.message_type = XInternAtom(self.display, message_type.as_ptr(), 0);
+            event.xclient.format = 32;
+            event.xclient.data.l = data;
+
+            XSendEvent(self.display, root, 0, (SubstructureRedirectMask | SubstructureNotifyMask) as libc::c_long, &mut event);
+            XFlush(self.display);
+        }
+    }
+
+    /// Redraw is sent with the next PresentCompleteNotify, or right away if the Present extension isn't available.
+    #[inline(always)]
+    pub fn request_redraw(&mut self) {
+        self.redraw.request(self.display, self.window);
+    }
+
+    /// Xlib window id.
+    #[cfg(feature = "raw_window_handle")]
+    #[inline(always)]
+    pub fn raw_window_handle(&self) -> Result<raw_window_handle::RawWindowHandle, raw_window_handle::HandleError> {
+        Ok(raw_window_handle::XlibWindowHandle::new(self.window).into())
+    }
+
+    /// Vulkan surface with VK_KHR_xlib_surface.
+    #[cfg(feature = "vulkan")]
+    #[inline(always)]
+    pub unsafe fn create_vulkan_surface(&self, instance : crate::vulkan::VkInstance, 
+        get_instance_proc_addr : crate::vulkan::VkGetInstanceProcAddr) -> Result<crate::vulkan::VkSurfaceKHR, WindowError> {
+        super::vulkan::x11_vulkan_surface(self.display, self.window, instance, get_instance_proc_addr)
+    }
+
+    /// OpenGL context with GLX or EGL.
+    #[cfg(feature = "opengl")]
+    #[inline(always)]
+    pub fn create_gl_context(&self, config : &crate::opengl::GLConfig, shared : Option<&crate::opengl::GLContext>) -> Result<crate::opengl::GLContext, WindowError> {
+        let shared = match shared.map(|shared| &shared.context) {
+            Some(crate::linux::opengl::LinuxGLContext::X11(shared)) => Some(shared),
+            Some(_) => return Err(WindowError::WindowOpenGLContextFailed),
+            None => None,
+        };
+        let context = super::opengl::X11GLContext::new(self.connection.clone(), self.window, config, shared)?;
+        Ok(crate::opengl::GLContext { context : crate::linux::opengl::LinuxGLContext::X11(context) })
+    }
+
+    /// cairo-xlib surface drawing on the window, of the window visual.
+    #[cfg(feature = "cairo")]
+    #[inline(always)]
+    pub fn cairo_surface(&mut self, size : WindowSize) -> Result<crate::cairo::CairoSurface, WindowError> {
+        let (display, window) = (self.display, self.window);
+        self.cairo.xlib(display as *mut std::ffi::c_void, window, || unsafe {
+            let mut attributes : XWindowAttributes = std::mem::zeroed();
+            (XGetWindowAttributes(display, window, &mut attributes) != 0).then(|| attributes.visual as *mut std::ffi::c_void)
+        }, size)
+    }
+
+    /// Drawing is already on the window, only flushed.
+    #[cfg(feature = "cairo")]
+    #[inline(always)]
+    pub fn cairo_present(&mut self, _size : WindowSize, _damage : &[(WindowPosition, WindowSize)]) -> Result<(), WindowError> {
+        self.cairo.flush();
+        unsafe { XFlush(self.display) };
+        Ok(())
+    }
+
+    /// Pixels of the software framebuffer of `size`, reallocated if size changed.
+    #[inline(always)]
+    pub fn framebuffer(&mut self, size : WindowSize) -> Result<&mut [u32], WindowError> {
+        self.framebuffer.pixels(size).ok_or(WindowError::WindowFramebufferFailed)
+    }
+
+    /// Put damaged rectangles of the software framebuffer on the window with XShmPutImage or XPutImage.
+    /// 
+    /// Reference(s)
+    /// <https://www.x.org/releases/current/doc/xextproto/shm.html>
+    #[inline(always)]
+    pub fn present(&mut self, size : WindowSize, damage : &[(WindowPosition, WindowSize)]) -> Result<(), WindowError> {
+        match self.framebuffer.present(size, damage) {
+            true => Ok(()),
+            false => Err(WindowError::WindowFramebufferFailed),
+        }
+    }
 }
\ No newline at end of file
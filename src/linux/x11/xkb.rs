@@ -0,0 +1,111 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use std::ffi::CStr;
+
+use crate::{event::KeyCode, keyboard::KeyboardLayout, linux::keysym::{keysym_label, Keysym}};
+
+use super::xlib::{Display, XFree, XGetAtomName, XKeysymToString, XkbAllocKeyboard, XkbFreeKeyboard, XkbGetNames, XkbGetState, XkbGroupNamesMask, 
+    XkbGroupStateMask, XkbKeycodeToKeysym, XkbMajorVersion, XkbMinorVersion, XkbQueryExtension, XkbSelectEventDetails, XkbStateNotify, XkbStateRec, XkbUseCoreKbd};
+
+/// Get the XKB extension event base used to identify XKB events and select XkbStateNotify group changes.
+/// 
+/// Returns None if XKB is not available on the X11 server.
+pub(crate) fn x11_xkb_init(display : *mut Display) -> Option<i32> {
+    unsafe {
+        let mut opcode : i32 = 0;
+        let mut event_base : i32 = 0;
+        let mut error : i32 = 0;
+        let mut major : i32 = XkbMajorVersion as i32;
+        let mut minor : i32 = XkbMinorVersion as i32;
+
+        if XkbQueryExtension(display, &mut opcode, &mut event_base, &mut error, &mut major, &mut minor) != 0 {
+            // Only group changes are needed.
+            XkbSelectEventDetails(display, XkbUseCoreKbd, XkbStateNotify, XkbGroupStateMask as u64, XkbGroupStateMask as u64);
+            Some(event_base)
+        } else {
+            None
+        }
+    }
+}
+
+/// Get the active [KeyboardLayout] from XKB state and group names.
+pub(crate) fn x11_keyboard_layout(display : *mut Display) -> KeyboardLayout {
+    unsafe {
+        let mut state : XkbStateRec = std::mem::zeroed();
+        if XkbGetState(display, XkbUseCoreKbd, &mut state) != 0 {
+            return KeyboardLayout::default();
+        }
+
+        let group = state.group as u32;
+        let mut name = String::new();
+
+        let desc = XkbAllocKeyboard();
+        if !desc.is_null() {
+            if XkbGetNames(display, XkbGroupNamesMask, desc) == 0 && !(*desc).names.is_null() {
+                let atom = (*(*desc).names).groups[group as usize];
+                if atom != 0 {
+                    let atom_name = XGetAtomName(display, atom);
+                    if !atom_name.is_null() {
+                        name = CStr::from_ptr(atom_name).to_string_lossy().to_string();
+                        XFree(atom_name as *mut std::ffi::c_void);
+                    }
+                }
+            }
+            XkbFreeKeyboard(desc, 0, 1);
+        }
+
+        KeyboardLayout { group, name }
+    }
+}
+
+/// Get the label of key under the layout group given.
+/// 
+/// Printable keys return their uppercase character and others their keysym name like `Escape`.
+pub(crate) fn x11_key_label(display : *mut Display, key : KeyCode, group : u32) -> Option<String> {
+    
+    // X11 keycodes are 8 bits
+    if key > u8::MAX as KeyCode {
+        return None;
+    }
+
+    unsafe {
+        let keysym = XkbKeycodeToKeysym(display, key as u8, group as i32, 0);
+        if keysym == 0 {
+            return None;
+        }
+
+        match keysym_label(keysym as Keysym) {
+            Some(label) => Some(label),
+            None => {
+                let name = XKeysymToString(keysym);
+                if name.is_null() {
+                    None
+                } else {
+                    Some(CStr::from_ptr(name).to_string_lossy().to_string())
+                }
+            },
+        }
+    }
+}
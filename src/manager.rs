@@ -25,7 +25,7 @@ SOFTWARE.
 
 use nscfg::{match_cfg, target_cfg};
 
-use crate::{display::Displays, WindowError, Window, WindowBuilder, WindowHandle, event::WindowManagerEvent};
+use crate::{device::InputDevices, display::Displays, event::{KeyCode, WindowManagerEvent}, keyboard::{HotkeyId, KeyModifiers, KeyboardLayout}, WindowError, Window, WindowBuilder, WindowHandle};
 
 #[cfg(test)]
 mod tests{
@@ -33,6 +33,86 @@ mod tests{
     include!("tests/manager.rs");
 }
 
+/// Environment variable overriding the [WindowBackend] chosen by [WindowManager::new], like `NSWINDOW_BACKEND=x11`.
+pub const WM_BACKEND_ENV : &str = "NSWINDOW_BACKEND";
+
+/// System window manager backends a [WindowManager] can use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowBackend {
+    /// Wayland compositor
+    Wayland,
+
+    /// X11 server
+    X11,
+
+    /// Windows in memory on synthetic [Displays], without display server. Used to run window logic in tests, 
+    /// like with `NSWINDOW_BACKEND=headless cargo test`. Never chosen by [WindowManager::new] unless named by [WM_BACKEND_ENV].
+    Headless,
+}
+
+impl WindowBackend {
+    /// Backends tried in order by [WindowManager::new].
+    pub(crate) const DEFAULT_ORDER : [WindowBackend; 2] = [WindowBackend::Wayland, WindowBackend::X11];
+
+    /// Returns the backend named `name` like `wayland`, `x11` or `headless`, ignoring case. None if unknown.
+    pub(crate) fn from_name(name : &str) -> Option<WindowBackend> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "wayland" => Some(WindowBackend::Wayland),
+            "x11" => Some(WindowBackend::X11),
+            "headless" => Some(WindowBackend::Headless),
+            _ => None,
+        }
+    }
+}
+
+/// Builder of a [WindowManager] using the [WindowBackend] chosen, created by [WindowManager::with_backend].
+/// 
+/// Backends are tried in the order given until one connects, ignoring [WM_BACKEND_ENV].
+/// 
+/// # Example
+/// ```no_run
+/// use nswindow::{WindowBackend, WindowManager};
+/// 
+/// // X11 is used if available, Wayland otherwise.
+/// let wm = WindowManager::with_backend(WindowBackend::X11).with_backend(WindowBackend::Wayland).build();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowManagerBuilder {
+    /// Backends tried in order
+    backends : Vec<WindowBackend>,
+}
+
+impl WindowManagerBuilder {
+
+    /// Add `backend` to the backends tried, after those already given.
+    pub fn with_backend(&mut self, backend : WindowBackend) -> &mut Self {
+        if !self.backends.contains(&backend) {
+            self.backends.push(backend);
+        }
+        self
+    }
+
+    /// Create a [WindowManager] with the first backend that connects. [WindowBackend::Headless] gets a single 
+    /// 1920x1080 display at 60hz, use [WindowManager::new_headless] to choose its displays.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::NoWindowManager`]) with the reason each backend was rejected, like 
+    /// [`WindowError::WindowManagerDisplayUnset`], [`WindowError::WindowManagerConnectionFailed`] or 
+    /// [`WindowError::WindowManagerMissingProtocol`].
+    pub fn build(&self) -> Result<WindowManager, WindowError> {
+
+        let mut rejected : Vec<(WindowBackend, WindowError)> = Vec::new();
+        for backend in &self.backends {
+            match WindowManager::connect(*backend) {
+                Ok(wm) => return Ok(wm),
+                Err(err) => rejected.push((*backend, err)),
+            }
+        }
+        Err(WindowError::NoWindowManager(rejected))
+
+    }
+}
+
 /// [WindowManager] is used to create and manipulate [Window].
 /// 
 /// It is also used to fetch [WindowManagerEvent], get [Desktop] and [Displays] informations.
@@ -44,27 +124,49 @@ mod tests{
 pub struct WindowManager {
     /// Linux [WindowManager] abstraction for calls.
     #[cfg(target_os = "linux")]
-    wm : crate::linux::manager::LinuxWindowManager,
+    pub(crate) wm : crate::linux::manager::LinuxWindowManager,
 }
 
 impl WindowManager {
 
     /// Create a new instance of window manager.
     /// 
+    /// The backend named by the [WM_BACKEND_ENV] environment variable is used if set. Otherwise each backend 
+    /// is tried in order, Linux trying Wayland first then falling back to X11.
+    /// 
     /// Returns Ok(WindowManager) on success.
     /// 
     /// # Errors
-    /// Returns Err([`WindowError::NoWindowManager`]) if no suitable window manager available on system.
+    /// Returns Err([`WindowError::NoWindowManager`]) with the reason each backend was rejected if no suitable window manager available on system.
+    /// Returns Err([`WindowError::WindowBackendInvalid`]) if [WM_BACKEND_ENV] doesn't name a [WindowBackend].
     pub fn new() -> Result<WindowManager, crate::WindowError> {
 
+        let backends = match std::env::var(WM_BACKEND_ENV) {
+            Ok(name) => match WindowBackend::from_name(&name) {
+                Some(backend) => vec![backend],
+                None => return Err(WindowError::WindowBackendInvalid(name)),
+            },
+            Err(_) => WindowBackend::DEFAULT_ORDER.to_vec(),
+        };
+        WindowManagerBuilder { backends }.build()
+
+    }
+
+    /// Returns a [WindowManagerBuilder] trying `backend` first. Other backends can be added as fallbacks 
+    /// before calling [WindowManagerBuilder::build].
+    pub fn with_backend(backend : WindowBackend) -> WindowManagerBuilder {
+        WindowManagerBuilder { backends : vec![backend] }
+    }
+
+    /// Connect `backend` only.
+    fn connect(backend : WindowBackend) -> Result<WindowManager, crate::WindowError> {
+
         match_cfg! {
-            linux => {  // Linux implementation try to open a Wayland connection first then fallback to X11.
-                match WindowManager::new_wayland() {    // Try wayland first
-                    Ok(wm) => Ok(wm),
-                    Err(_) => match WindowManager::new_x11() {  // Fallback to X11 else
-                        Ok(wm) => Ok(wm),
-                        Err(_) => Err(WindowError::NoWindowManager),
-                    },
+            linux => {
+                match backend {
+                    WindowBackend::Wayland => WindowManager::new_wayland(),
+                    WindowBackend::X11 => WindowManager::new_x11(),
+                    WindowBackend::Headless => Ok(WindowManager::new_headless(super::linux::headless::headless_displays())),
                 }
             },
             _ => {
@@ -74,29 +176,48 @@ impl WindowManager {
 
     }
 
+    /// Returns the [WindowBackend] in use, to log it for example.
+    #[inline(always)]
+    pub fn backend(&self) -> WindowBackend {
+        self.wm.backend()
+    }
+
     target_cfg! {
         linux & !doc => {
             /// Create a new x11 [WindowManager].
+            /// 
+            /// # Errors
+            /// Returns Err([`WindowError::WindowManagerDisplayUnset`]) if `DISPLAY` isn't set.
+            /// Returns Err([`WindowError::WindowManagerConnectionFailed`]) if XOpenDisplay failed.
             pub fn new_x11() -> Result<WindowManager, crate::WindowError> {
-                if super::linux::x11::x11_supported() {
-                    
-                    match super::linux::x11::manager::X11WindowManager::new() {
-                        Ok(wm) => Ok(WindowManager{ wm: super::linux::manager::LinuxWindowManager::X11(wm)}),
-                        Err(err) => Err(err),
-                    }
-                } else {
-                    Err(WindowError::WindowManagerNotSupported)
+                match super::linux::x11::manager::X11WindowManager::new() {
+                    Ok(wm) => Ok(WindowManager{ wm: super::linux::manager::LinuxWindowManager::X11(wm)}),
+                    Err(err) => Err(err),
                 }
             }
 
             /// Create a new wayland [WindowManager].
+            /// 
+            /// # Errors
+            /// Returns Err([`WindowError::WindowManagerDisplayUnset`]) if `WAYLAND_DISPLAY` isn't set and `wayland-0` can't be connected.
+            /// Returns Err([`WindowError::WindowManagerConnectionFailed`]) if the compositor socket can't be connected.
+            /// Returns Err([`WindowError::WindowManagerMissingProtocol`]) if the compositor lacks `wl_compositor` or `xdg_wm_base`.
             pub fn new_wayland() -> Result<WindowManager, crate::WindowError> {
-                if super::linux::wayland::wayland_supported() {
-                    todo!()
-                } else {
-                    Err(WindowError::WindowManagerNotSupported)
+                match super::linux::wayland::manager::WaylandWindowManager::new() {
+                    Ok(wm) => Ok(WindowManager{ wm: super::linux::manager::LinuxWindowManager::Wayland(wm)}),
+                    Err(err) => Err(err),
                 }
             }
+
+            /// Create a headless [WindowManager] showing windows in memory on synthetic `displays`.
+            /// 
+            /// Every [Window] request is accepted and followed by the events a system window manager would send, 
+            /// so that window logic runs without display server. Display handles are set to their index in list and 
+            /// the desktop, if None, is the bounding box of the displays. There is no input device nor keymap and 
+            /// [WindowManager::event_wait] returns [WindowEvent::NoEvent](crate::event::WindowEvent::NoEvent) if no event is queued since none will ever come.
+            pub fn new_headless(displays : Displays) -> WindowManager {
+                WindowManager{ wm: super::linux::manager::LinuxWindowManager::Headless(super::linux::headless::manager::HeadlessWindowManager::new(displays)) }
+            }
         }
     }
 
@@ -116,7 +237,7 @@ impl WindowManager {
         // WindowParentLoop
 
 
-        todo!()
+        self.wm.build(builder)
     }
 
 
@@ -132,6 +253,9 @@ impl WindowManager {
     /// 
     /// This will block code execution until a [WindowManagerEvent] occur.
     /// Mostly used for [Retained Mode](https://en.wikipedia.org/wiki/Retained_mode) GUI application.
+    /// 
+    /// Returns [WindowEvent::NoEvent](crate::event::WindowEvent::NoEvent) with [WindowHandle::NULL] instead of blocking forever 
+    /// if no event will ever come, like when the connection with the system window manager is lost.
     #[inline(always)]
     pub fn event_wait(&mut self) -> &WindowManagerEvent {
         self.wm.event_wait()
@@ -151,12 +275,68 @@ impl WindowManager {
         self.wm.window_mut(window)
     }
 
+    /// Get the Vulkan instance extensions to enable for [Window::create_vulkan_surface()](crate::Window::create_vulkan_surface).
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowVulkanUnsupported`]) if the backend can't create Vulkan surfaces.
+    #[cfg(feature = "vulkan")]
+    #[inline(always)]
+    pub fn vulkan_required_extensions(&self) -> Result<&'static [&'static std::ffi::CStr], WindowError> {
+        self.wm.vulkan_required_extensions()
+    }
+
     /// Get the client screens informations. 
     #[inline(always)]
     pub fn displays(&self) -> &Displays {
         self.wm.displays()
     }
 
+    /// Get the connected input devices like keyboards, mice, touchpads, touchscreens and tablets.
+    /// 
+    /// List is kept up to date when [WindowEvent::DeviceAdded](crate::event::WindowEvent::DeviceAdded) and 
+    /// [WindowEvent::DeviceRemoved](crate::event::WindowEvent::DeviceRemoved) happen.
+    #[inline(always)]
+    pub fn input_devices(&self) -> &InputDevices {
+        self.wm.input_devices()
+    }
+
+    /// Get the active [KeyboardLayout].
+    #[inline(always)]
+    pub fn keyboard_layout(&self) -> &KeyboardLayout {
+        self.wm.keyboard_layout()
+    }
+
+    /// Get the label printed by a physical key under the active [KeyboardLayout].
+    /// 
+    /// Used by rebinding screens to show that the key at the `Q` position shows `A` on a french layout.
+    /// Printable keys are returned uppercase, as on keycaps, and other keys with their name like `Escape`.
+    /// 
+    /// Returns None if key isn't mapped.
+    #[inline(always)]
+    pub fn key_label(&self, key : KeyCode) -> Option<String> {
+        self.wm.key_label(key)
+    }
+
+    /// Register a system-wide hotkey that fires even when none of the [Window] has focus, like push-to-talk shortcuts.
+    /// 
+    /// [WindowEvent::HotkeyPressed](crate::event::WindowEvent::HotkeyPressed) and [WindowEvent::HotkeyReleased](crate::event::WindowEvent::HotkeyReleased) 
+    /// happen with the returned [HotkeyId]. Caps Lock and Num Lock states are ignored.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::HotkeyUnavailable`]) if the combination is already registered by this or another application.
+    #[inline(always)]
+    pub fn register_hotkey(&mut self, key : KeyCode, modifiers : KeyModifiers) -> Result<HotkeyId, WindowError> {
+        self.wm.register_hotkey(key, modifiers)
+    }
+
+    /// Unregister a system-wide hotkey registered with [WindowManager::register_hotkey()].
+    /// 
+    /// Returns true if hotkey was unregistered, false if [HotkeyId] doesn't exist.
+    #[inline(always)]
+    pub fn unregister_hotkey(&mut self, hotkey : HotkeyId) -> bool {
+        self.wm.unregister_hotkey(hotkey)
+    }
+
     
 
 }
\ No newline at end of file
@@ -0,0 +1,186 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! [OpenGL](https://www.opengl.org/) contexts of [Window](crate::Window).
+//! 
+//! OpenGL isn't linked. `libGL.so.1` or `libEGL.so.1` are loaded when the first [GLContext] is created, so any 
+//! driver like Mesa's llvmpipe works.
+//! 
+//! # Example
+//! ```no_run
+//! # use nswindow::{WindowManager, WindowBuilder, opengl::{GLConfig, GLProfile}};
+//! let mut wm = WindowManager::new().unwrap();
+//! let window = WindowBuilder::new().build(&mut wm).unwrap();
+//! 
+//! let config = GLConfig::new(3, 3, GLProfile::Core);
+//! let context = wm.window(window).unwrap().create_gl_context(&config, None).unwrap();
+//! context.make_current().unwrap();
+//! context.set_swap_interval(1).unwrap();
+//! 
+//! // Load functions with context.get_proc_address(), draw, then
+//! context.swap_buffers().unwrap();
+//! ```
+//! 
+//! # Known issue(s)
+//! * `(Linux only)` Wayland has no GLX, contexts are always created with EGL on a wl_egl_window. If libwayland-client 
+//!   or `libwayland-egl.so.1` can't be loaded, Wayland returns [WindowError::WindowOpenGLUnsupported](crate::WindowError::WindowOpenGLUnsupported).
+
+use std::ffi::{c_void, CStr};
+
+use crate::WindowError;
+
+/// Library used to create a [GLContext].
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum GLApi {
+    /// GLX, the OpenGL extension of X11.
+    #[default]
+    Glx,
+
+    /// EGL, the Khronos native platform interface.
+    Egl,
+}
+
+/// OpenGL profile of a [GLContext].
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub enum GLProfile {
+    /// Core profile without deprecated functions.
+    #[default]
+    Core,
+
+    /// Compatibility profile with deprecated functions.
+    Compatibility,
+
+    /// OpenGL ES.
+    Es,
+}
+
+/// Requested properties of a [GLContext] and of its framebuffer.
+/// 
+/// Color, depth and stencil are minimum sizes in bits. `samples` is the number of MSAA samples, 0 to disable multisampling.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct GLConfig {
+    /// Library used to create the context.
+    pub api : GLApi,
+
+    /// Major version of OpenGL.
+    pub major : u8,
+
+    /// Minor version of OpenGL.
+    pub minor : u8,
+
+    /// Profile of the context.
+    pub profile : GLProfile,
+
+    /// Create a debug context.
+    pub debug : bool,
+
+    /// Red bits.
+    pub red : u8,
+
+    /// Green bits.
+    pub green : u8,
+
+    /// Blue bits.
+    pub blue : u8,
+
+    /// Alpha bits.
+    pub alpha : u8,
+
+    /// Depth buffer bits.
+    pub depth : u8,
+
+    /// Stencil buffer bits.
+    pub stencil : u8,
+
+    /// MSAA samples.
+    pub samples : u8,
+}
+
+impl GLConfig {
+    /// Create a [GLConfig] of OpenGL `major`.`minor` with `profile`. Framebuffer is RGB888 with 24 bits depth and 8 bits stencil.
+    pub fn new(major : u8, minor : u8, profile : GLProfile) -> GLConfig {
+        GLConfig { major, minor, profile, ..Default::default() }
+    }
+}
+
+impl Default for GLConfig {
+    /// OpenGL 3.3 core with GLX, RGB888 with 24 bits depth and 8 bits stencil. Window visuals usually have no alpha.
+    fn default() -> Self {
+        Self { api: GLApi::Glx, major: 3, minor: 3, profile: GLProfile::Core, debug: false, 
+            red: 8, green: 8, blue: 8, alpha: 0, depth: 24, stencil: 8, samples: 0 }
+    }
+}
+
+/// OpenGL context created by [Window::create_gl_context()](crate::Window::create_gl_context), drawing on its [Window].
+/// 
+/// Context is destroyed when dropped and must be dropped before its [Window] is closed.
+#[derive(Debug)]
+pub struct GLContext {
+    /// Linux context of the X11 or Wayland window.
+    #[cfg(target_os = "linux")]
+    pub(crate) context : crate::linux::opengl::LinuxGLContext,
+}
+
+impl GLContext {
+    /// Make the context current on this thread, drawing on its [Window].
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowOpenGLContextFailed`]) if the context couldn't be made current.
+    #[inline(always)]
+    pub fn make_current(&self) -> Result<(), WindowError> {
+        self.context.make_current()
+    }
+
+    /// Show the back buffer on the [Window]. Context must be current.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowOpenGLContextFailed`]) if buffers couldn't be swapped.
+    #[inline(always)]
+    pub fn swap_buffers(&self) -> Result<(), WindowError> {
+        self.context.swap_buffers()
+    }
+
+    /// Set the number of display refreshes to wait for in [GLContext::swap_buffers()]. 0 disables vsync, 1 enables it and 
+    /// -1 enables adaptive vsync if supported. Context must be current.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowOpenGLContextFailed`]) if the interval isn't supported.
+    #[inline(always)]
+    pub fn set_swap_interval(&self, interval : i32) -> Result<(), WindowError> {
+        self.context.set_swap_interval(interval)
+    }
+
+    /// Get the address of the OpenGL function `name`, like `c"glClear"`. Null if not found.
+    #[inline(always)]
+    pub fn get_proc_address(&self, name : &CStr) -> *const c_void {
+        self.context.get_proc_address(name)
+    }
+
+    /// Get the [GLConfig] the context was created with. Sizes are the ones of the chosen framebuffer, at least those requested.
+    #[inline(always)]
+    pub fn config(&self) -> &GLConfig {
+        self.context.config()
+    }
+}
@@ -60,7 +60,7 @@ pub struct WindowPointer {
 
     /// Linux [WindowPointer] abstraction for calls. Is set as [Option] since [WindowBuilder] can use it.
     #[cfg(target_os = "linux")]
-    pointer : Option<crate::linux::pointer::LinuxPointer>,
+    pub(crate) pointer : Option<crate::linux::pointer::LinuxPointer>,
 
     /// [PointerMode] used for [EventMouse](super::event::EventMouse) events.
     pub(crate) mode : WindowPointerMode,
@@ -91,6 +91,17 @@ impl WindowPointer {
         }
     }
 
+    /// Copy of the pointer properties without the system pointer. Used to create a [Window](crate::Window) from a [WindowBuilder](crate::WindowBuilder).
+    pub(crate) fn properties(&self) -> WindowPointer {
+        WindowPointer { 
+            pointer : None,
+            mode : self.mode, 
+            visible : self.visible, 
+            confined : self.confined,
+            cursor : self.cursor,
+        }
+    }
+
     /// Returns the pointer [WindowPointerMode].
     pub fn mode(&self) -> WindowPointerMode {
         self.mode
@@ -203,6 +214,23 @@ impl WindowPointer {
 
     }
 
+    /// Returns true while the window manager locks the pointer in place for [WindowPointerMode::Acceleration].
+    /// 
+    /// Lock can be dropped by the window manager, when the window loses focus for example, and is restored once granted again.
+    pub fn locked(&self) -> bool {
+
+        match_cfg! {
+            linux => {
+                match &self.pointer {
+                    Some(lp) => lp.locked(),
+                    None => false,
+                }
+            },
+            _ => false,
+        }
+
+    }
+
 
     /// Returns the current [WindowCursor] glyph showed.
     pub fn cursor(&self) -> WindowCursor {
@@ -0,0 +1,48 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+//! [raw-window-handle](https://crates.io/crates/raw-window-handle) traits, giving the handles of the system to graphics 
+//! libraries like wgpu and ash.
+//! 
+//! # Known issue(s)
+//! * `(Linux only)` Wayland handles are pointers of libwayland objects. If libwayland-client can't be loaded, nswindow 
+//!   speaks the Wayland protocol itself and Wayland handles return [HandleError::NotSupported].
+
+use raw_window_handle::{DisplayHandle, HandleError, HasDisplayHandle, HasWindowHandle, WindowHandle};
+
+use crate::{Window, WindowManager};
+
+impl HasWindowHandle for Window {
+    fn window_handle(&self) -> Result<WindowHandle<'_>, HandleError> {
+        // Handle stays valid while the window is borrowed.
+        self.window.raw_window_handle().map(|raw| unsafe { WindowHandle::borrow_raw(raw) })
+    }
+}
+
+impl HasDisplayHandle for WindowManager {
+    fn display_handle(&self) -> Result<DisplayHandle<'_>, HandleError> {
+        // Connection stays open while the window manager is borrowed.
+        self.wm.raw_display_handle().map(|raw| unsafe { DisplayHandle::borrow_raw(raw) })
+    }
+}
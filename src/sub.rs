@@ -37,6 +37,7 @@ const RES_WPARENT : bool = true;
 const SHOW_WPARENT : bool = false;
 const HIDE_WPARENT : bool = true;
 const CLOSE_WPARENT : bool = true;
+const GRAB_INPUT : bool = false;
 const ANCHOR : Option<WindowPosition> = None;
 
 /// Anchors used to dock to parent [Window].
@@ -106,6 +107,11 @@ pub struct SubWindow {
     /// Overriden to true if any anchor is set.
     pub close_with_parent : bool,
 
+    /// Grab pointer and keyboard while shown, like menus and dropdowns. Clicking outside dismisses and closes the [Window].
+    /// 
+    /// Only effective when created with a parent on Wayland, where such sub windows are popups.
+    pub grab_input : bool,
+
 }
 
 impl SubWindow {
@@ -118,7 +124,8 @@ impl SubWindow {
             restore_with_parent: RES_WPARENT, 
             show_with_parent: SHOW_WPARENT, 
             hide_with_parent: HIDE_WPARENT, 
-            close_with_parent: CLOSE_WPARENT }
+            close_with_parent: CLOSE_WPARENT,
+            grab_input: GRAB_INPUT }
     }
 
     /// Reset [SubWindow] to default parameters.
@@ -129,5 +136,6 @@ impl SubWindow {
         self.show_with_parent = SHOW_WPARENT;
         self.hide_with_parent = HIDE_WPARENT;
         self.close_with_parent = CLOSE_WPARENT;
+        self.grab_input = GRAB_INPUT;
     }
 }
\ No newline at end of file
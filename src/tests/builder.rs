@@ -196,8 +196,7 @@ fn ut_window_builder_modifications() {
 
     nscfg::match_cfg! {
         !single_opt:ft => {    // For parent and sub
-            const PARENTHANDLE : usize = 1;
-            const PARENT : Option<WindowHandle> = Some(&PARENTHANDLE);
+            const PARENT : Option<WindowHandle> = Some(WindowHandle::new(1, 1));
 
             let mut sub = crate::sub::SubWindow::new();
             sub.block_parent_until_closed = true;
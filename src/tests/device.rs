@@ -0,0 +1,84 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use super::{InputDevice, InputDeviceCapabilities, InputDeviceKind, InputDevices};
+
+/// Create an [InputDevice] for tests.
+fn create_device(id : u32, name : &str, kind : InputDeviceKind) -> InputDevice {
+    InputDevice { id, name: name.to_owned(), kind, capabilities: InputDeviceCapabilities::default(), enabled: true }
+}
+
+/// Unit tests [super::InputDevices] add, get and remove.
+///
+/// # Verification(s)
+/// V1 | Add devices and fetch them by id.
+/// V2 | Adding an existing id replace the device.
+/// V3 | Remove devices.
+#[test]
+fn ut_input_devices_update() {
+    let mut devices = InputDevices::create(Vec::new());
+
+    // V1 | Add devices and fetch them by id.
+    assert!(devices.add(create_device(6, "AT Translated Set 2 keyboard", InputDeviceKind::Keyboard)));
+    assert!(devices.add(create_device(11, "SynPS/2 Synaptics TouchPad", InputDeviceKind::Touchpad)));
+    assert!(devices.list.len() == 2);
+    assert!(devices.get(6).unwrap().kind == InputDeviceKind::Keyboard);
+    assert!(devices.get(11).unwrap().kind == InputDeviceKind::Touchpad);
+    assert!(devices.get(12).is_none());
+
+    // V2 | Adding an existing id replace the device.
+    let mut disabled = create_device(11, "SynPS/2 Synaptics TouchPad", InputDeviceKind::Touchpad);
+    disabled.enabled = false;
+    assert!(!devices.add(disabled));
+    assert!(devices.list.len() == 2);
+    assert!(!devices.get(11).unwrap().enabled);
+
+    // V3 | Remove devices.
+    assert!(devices.remove(6));
+    assert!(!devices.remove(6));
+    assert!(devices.get(6).is_none());
+    assert!(devices.list.len() == 1);
+
+}
+
+/// Unit tests [super::InputDevices::of_kind()].
+///
+/// # Verification(s)
+/// V1 | Only devices of kind are returned.
+#[test]
+fn ut_input_devices_of_kind() {
+    let devices = InputDevices::create(vec![
+        create_device(6, "AT Translated Set 2 keyboard", InputDeviceKind::Keyboard),
+        create_device(9, "Logitech USB Receiver", InputDeviceKind::Mouse),
+        create_device(10, "Logitech USB Receiver Keyboard", InputDeviceKind::Keyboard),
+        create_device(14, "Wacom Intuos S Pen stylus", InputDeviceKind::Tablet),
+    ]);
+
+    // V1 | Only devices of kind are returned.
+    assert!(devices.of_kind(InputDeviceKind::Keyboard).count() == 2);
+    assert!(devices.of_kind(InputDeviceKind::Mouse).count() == 1);
+    assert!(devices.of_kind(InputDeviceKind::Tablet).next().unwrap().id == 14);
+    assert!(devices.of_kind(InputDeviceKind::Touchscreen).count() == 0);
+
+}
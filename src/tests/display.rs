@@ -22,7 +22,7 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use super::{Desktop, Display, DisplayDesktopPosition, DisplayResolution, DisplaySizeMM, DisplaySupportedResolution, Displays, DisplayRefreshRate };
+use super::{Desktop, Display, DisplayDesktopPosition, DisplayResolution, DisplaySizeMM, DisplaySupportedResolution, Displays, DisplayRefreshRate, Duration };
 
 fn create_empty_displays() -> Displays {
     let list : Vec<Display> = Vec::new();
@@ -75,12 +75,13 @@ pub(crate) fn create_displays(primary_first : bool) -> Displays {
     capacities.push(create_supported(320,240, vec![6005]));  
     capacities.push(create_supported(360,202, vec![5951,5913]));  
     capacities.push(create_supported(320,180, vec![5984,5932]));  
-    list.push(Display { handle : 0, identifier: "eDP-1".to_owned(), 
+    list.push(Display { handle : 0, identifier: "eDP-1".to_owned(), description: String::new(), 
                         size: DisplaySizeMM { width: 344, height: 194 }, 
                         position: DisplayDesktopPosition { x: 0, y: 1080 }, 
                         resolution: DisplayResolution { width: 1920, height: 1080 }, 
                         refresh_rate: 6003, 
                         primary: primary_first, 
+                        scale: 1.0, 
                         supported: capacities});
 
 
@@ -133,12 +134,13 @@ pub(crate) fn create_displays(primary_first : bool) -> Displays {
     capacities.push(create_supported(320,240, vec![7281,7500,6005]));  
     capacities.push(create_supported(360,202, vec![5951,5913]));  
     capacities.push(create_supported(320,180, vec![5984,5932]));   
-    list.push(Display { handle : 1, identifier: "HDMI-1".to_owned(), 
+    list.push(Display { handle : 1, identifier: "HDMI-1".to_owned(), description: String::new(), 
                         size: DisplaySizeMM { width: 510, height: 290 }, 
                         position: DisplayDesktopPosition { x: 0, y: 0 }, 
                         resolution: DisplayResolution { width: 1920, height: 1080 }, 
                         refresh_rate: 12000, 
                         primary: !primary_first, 
+                        scale: 1.0, 
                         supported: capacities});
 
     Displays::create(list, Some(create_desktop()))
@@ -257,3 +259,22 @@ fn ut_displays() {
 
 }
 
+/// Unit tests Display::refresh_interval()
+///
+/// # Verification(s)
+/// V1 | Refresh interval is the inverse of the refresh rate.
+/// V2 | Unknown refresh rate gives no interval.
+#[test]
+fn ut_display_refresh_interval() {
+    let mut displays = create_displays(true);
+
+    // V1 | Refresh interval is the inverse of the refresh rate.
+    displays.list[0].refresh_rate = 6000;
+    assert_eq!(displays.list[0].refresh_interval(), Duration::from_nanos(16_666_666));
+    displays.list[0].refresh_rate = 14400;
+    assert_eq!(displays.list[0].refresh_interval(), Duration::from_nanos(6_944_444));
+
+    // V2 | Unknown refresh rate gives no interval.
+    displays.list[0].refresh_rate = 0;
+    assert_eq!(displays.list[0].refresh_interval(), Duration::ZERO);
+}
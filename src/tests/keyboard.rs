@@ -22,7 +22,7 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use crate::keyboard::{WindowKeyboard, WindowKeyboardMode, WKB_DEFAULT_MODE, WKB_DEFAULT_REPEAT};
+use crate::keyboard::{KeyCombination, KeyLocks, KeyModifiers, WindowKeyboard, WindowKeyboardMode, WKB_DEFAULT_MODE, WKB_DEFAULT_REPEAT};
     
 /// Unit tests [super::WindowKeyboard] default values.
 ///
@@ -36,6 +36,9 @@ fn ut_window_keyboard_default() {
     assert!(wkb.keyboard == None);
     assert!(wkb.mode == WKB_DEFAULT_MODE);
     assert!(wkb.auto_repeat == WKB_DEFAULT_REPEAT);
+    assert!(wkb.pressed.is_empty());
+    assert!(wkb.modifiers().is_empty());
+    assert!(wkb.locks() == KeyLocks::default());
 
 }
 
@@ -63,4 +66,63 @@ fn ut_window_keyboard_update() {
     wkb.disable_repeat();
     assert!(wkb.auto_repeat == WKB_DEFAULT_REPEAT);
 
-}
\ No newline at end of file
+}
+
+/// Unit tests [super::KeyCombination] matching.
+///
+/// # Verification(s)
+/// V1 | Default [super::KeyModifiers] are empty.
+/// V2 | Combination match only with exact key and modifiers.
+#[test]
+fn ut_key_combination_matches() {
+    
+    // V1 | Default [super::KeyModifiers] are empty.
+    assert!(KeyModifiers::default().is_empty());
+    assert!(!KeyModifiers::new(false, true, false, false).is_empty());
+
+    // V2 | Combination match only with exact key and modifiers.
+    let ctrl_alt = KeyModifiers::new(false, true, true, false);
+    let kc = KeyCombination::new(9, ctrl_alt);
+
+    assert!(kc.matches(9, ctrl_alt));
+    assert!(!kc.matches(10, ctrl_alt));
+    assert!(!kc.matches(9, KeyModifiers::default()));
+    assert!(!kc.matches(9, KeyModifiers::new(true, true, true, false)));
+
+}
+
+/// Unit tests [super::WindowKeyboard] keys state tracking.
+///
+/// # Verification(s)
+/// V1 | Keys pressed and released are tracked.
+/// V2 | Modifiers and locks are kept.
+/// V3 | Clear release keys and modifiers but keep locks.
+#[test]
+fn ut_window_keyboard_state() {
+    let mut wkb = WindowKeyboard::new();
+
+    // V1 | Keys pressed and released are tracked.
+    wkb.key_down(38);
+    wkb.key_down(38);
+    wkb.key_down(50);
+    assert!(wkb.is_pressed(38));
+    assert!(wkb.is_pressed(50));
+    assert!(wkb.pressed.len() == 2);
+    wkb.key_up(38);
+    assert!(!wkb.is_pressed(38));
+    assert!(wkb.is_pressed(50));
+
+    // V2 | Modifiers and locks are kept.
+    let modifiers = KeyModifiers::new(true, false, false, false);
+    let locks = KeyLocks { caps: true, num: true, scroll: false };
+    wkb.set_modifiers(modifiers, locks);
+    assert!(wkb.modifiers() == modifiers);
+    assert!(wkb.locks() == locks);
+
+    // V3 | Clear release keys and modifiers but keep locks.
+    wkb.clear();
+    assert!(!wkb.is_pressed(50));
+    assert!(wkb.modifiers().is_empty());
+    assert!(wkb.locks() == locks);
+
+}
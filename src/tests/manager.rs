@@ -23,15 +23,17 @@ SOFTWARE.
 */
 
 /// WindowManager unit tests
+/// 
+/// Tests use the headless backend so they run without display server.
 
 use std::{i32, u32};
 
-use crate::{ WindowBuilder, WindowError, WindowHandle, WindowManager};
+use crate::{ event::WindowEvent, WindowBuilder, WindowError, WindowHandle, WindowManager};
 
 /// WindowManager::build() unit test
 #[test]
 fn window_manager_ut_build() {
-    match WindowManager::new() {
+    match WindowManager::with_backend(super::WindowBackend::Headless).build() {
         Ok(mut wm) => {
             match WindowBuilder::new().build(&mut wm){
                 Ok(_) => { },
@@ -42,10 +44,37 @@ fn window_manager_ut_build() {
     }
 }
 
+/// WindowBackend::from_name() unit test
+#[test]
+fn window_manager_ut_backend_name() {
+    assert_eq!(super::WindowBackend::from_name("wayland"), Some(super::WindowBackend::Wayland));
+    assert_eq!(super::WindowBackend::from_name(" X11\n"), Some(super::WindowBackend::X11));
+    assert_eq!(super::WindowBackend::from_name("Headless"), Some(super::WindowBackend::Headless));
+    assert_eq!(super::WindowBackend::from_name("win32"), None);
+}
+
+/// WindowManager::with_backend() and WindowManager::backend() unit test
+#[test]
+fn window_manager_ut_backend() {
+    match WindowManager::with_backend(super::WindowBackend::Headless).build() {
+        Ok(wm) => match WindowManager::with_backend(wm.backend()).build() {
+            Ok(other) => assert_eq!(other.backend(), wm.backend()),
+            Err(err) => panic!("{:?}", err),
+        },
+        Err(err) => assert!(false, "{:?}", err),
+    }
+
+    // Backends are tried once, in the order given.
+    let mut builder = WindowManager::with_backend(super::WindowBackend::Headless);
+    builder.with_backend(super::WindowBackend::X11).with_backend(super::WindowBackend::Headless);
+    assert_eq!(&builder, WindowManager::with_backend(super::WindowBackend::Headless).with_backend(super::WindowBackend::X11));
+    assert_eq!(builder.build().map(|wm| wm.backend()).ok(), Some(super::WindowBackend::Headless));
+}
+
 /// WindowManager::event() unit test
 #[test]
 fn window_manager_ut_event() {
-    match WindowManager::new() {
+    match WindowManager::with_backend(super::WindowBackend::Headless).build() {
         Ok(mut wm) => {
             wm.event();
         },
@@ -53,10 +82,27 @@ fn window_manager_ut_event() {
     }
 }
 
+/// WindowManager::event_wait() unit test
+#[test]
+fn window_manager_ut_event_wait() {
+    match WindowManager::with_backend(super::WindowBackend::Headless).build() {
+        Ok(mut wm) => {
+            // Headless events are queued by requests, none will come otherwise.
+            assert_eq!(wm.event_wait().event, WindowEvent::NoEvent);
+
+            match WindowBuilder::new().build(&mut wm){
+                Ok(wh) => assert_eq!(wm.event_wait().window, wh),
+                Err(err) => panic!("{:?}", err),
+            }
+        },
+        Err(err) => assert!(false, "{:?}", err),
+    }
+}
+
 /// WindowManager::window() and WindowManager::window_mut() unit test
 #[test]
 fn window_manager_ut_window() {
-    match WindowManager::new() {
+    match WindowManager::with_backend(super::WindowBackend::Headless).build() {
         Ok(mut wm) => {
             match WindowBuilder::new().build(&mut wm){
                 Ok(wh) => {
@@ -81,7 +127,7 @@ fn window_manager_ut_window() {
 /// WindowManager::displays() unit test
 #[test]
 fn window_manager_ut_displays() {
-    match WindowManager::new() {
+    match WindowManager::with_backend(super::WindowBackend::Headless).build() {
         Ok(wm) => {
             wm.displays();
         },
@@ -92,9 +138,9 @@ fn window_manager_ut_displays() {
 /// WindowManager::build() error unit tests
 #[test]
 fn window_manager_ut_build_error() {
-    match WindowManager::new() {
+    match WindowManager::with_backend(super::WindowBackend::Headless).build() {
         Ok(mut wm) => {
-            let invalid_handle : WindowHandle = &0;
+            let invalid_handle = WindowHandle::NULL;
 
             // WindowRelativePositionOOB
             match WindowBuilder::new().hide()
@@ -126,8 +172,8 @@ fn window_manager_ut_build_error() {
                     match WindowBuilder::new().hide()
                     .parent(Some(invalid_handle))
                     .build(&mut wm) {
-                        Ok(_) => panic!("WindowMinSizeBiggerThanMax expected"),
-                        Err(err) => assert!(err == WindowError::WindowMinSizeBiggerThanMax),
+                        Ok(_) => panic!("InvalidWindowHandle expected"),
+                        Err(err) => assert!(err == WindowError::InvalidWindowHandle),
                         }
                 },
                 _ => {}
@@ -143,12 +189,47 @@ fn window_manager_ut_build_error() {
 /// WindowManager::window() and window_mut() error unit tests
 #[test]
 fn window_manager_ut_window_error() {
-    match WindowManager::new() {
+    match WindowManager::with_backend(super::WindowBackend::Headless).build() {
         Ok(mut wm) => {
-            let invalid_handle : WindowHandle = &0;
+            let invalid_handle = WindowHandle::NULL;
             assert!(wm.window(invalid_handle).expect_err("InvalidWindowHandle expected!") == WindowError::InvalidWindowHandle);
             assert!(wm.window_mut(invalid_handle).expect_err("InvalidWindowHandle expected!") == WindowError::InvalidWindowHandle);
         },
         Err(err) => assert!(false, "{:?}", err),
     }
 }
+
+/// WindowManager::input_devices() unit test
+#[test]
+fn window_manager_ut_input_devices() {
+    match WindowManager::with_backend(super::WindowBackend::Headless).build() {
+        Ok(wm) => {
+            wm.input_devices();
+        },
+        Err(err) => assert!(false, "{:?}", err),
+    }
+}
+
+/// WindowManager::new_headless() unit test
+#[test]
+fn window_manager_ut_headless() {
+    let mut displays = crate::display::tests::create_displays(true);
+    displays.desktop = None;
+    let mut wm = WindowManager::new_headless(displays);
+    assert_eq!(wm.backend(), super::WindowBackend::Headless);
+    assert_eq!(wm.displays().list.len(), 2);
+    assert!(wm.displays().desktop.is_some());
+
+    let wh = WindowBuilder::new().size(crate::WindowSize::new(800, 600)).build(&mut wm).unwrap();
+    wm.window_mut(wh).unwrap().maximize();
+
+    // Events of the window are given in order with its states updated.
+    let mut maximized = false;
+    while let Some(event) = wm.event() {
+        assert_eq!(event.window, wh);
+        maximized |= event.event == crate::event::WindowEvent::Maximized;
+    }
+    assert!(maximized);
+    assert!(wm.window(wh).unwrap().visible());
+    assert!(wm.window_mut(wh).unwrap().maximized());
+}
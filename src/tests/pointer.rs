@@ -49,6 +49,7 @@ fn ut_window_pointer_default() {
     assert!(wp.confined == WP_DEFAULT_CONFINED);
     assert!(wp.cursor == WP_DEFAULT_CURSOR);
     assert!(wp.position().is_none());
+    assert!(!wp.locked());
 
 }
 
@@ -33,6 +33,7 @@ fn assert_sub_default(sub : &super::SubWindow) {
     assert!(sub.show_with_parent == super::SHOW_WPARENT);
     assert!(sub.hide_with_parent == super::HIDE_WPARENT);
     assert!(sub.close_with_parent == super::CLOSE_WPARENT);
+    assert!(sub.grab_input == super::GRAB_INPUT);
 }
 
 /// Assert all subwindow anchors default values
@@ -62,6 +63,7 @@ fn ut_subwindow_reset() {
     sub.show_with_parent = !super::SHOW_WPARENT;
     sub.hide_with_parent = !super::HIDE_WPARENT;
     sub.close_with_parent = !super::CLOSE_WPARENT;
+    sub.grab_input = !super::GRAB_INPUT;
 
     // Reset
     sub.reset();
@@ -26,9 +26,9 @@ SOFTWARE.
 
 
 
-use std::rc::Rc;
+use std::{cell::RefCell, rc::Rc};
 
-use crate::{display::{tests::create_displays, Displays}, Window, WindowError, WindowPosition, WindowRelativePosition, WindowSize};
+use crate::{display::{tests::create_displays, Displays}, event::{WindowCoordinate, WindowEvent}, window::WindowHandles, Window, WindowBuilder, WindowError, WindowHandle, WindowPosition, WindowRelativePosition, WindowSize};
 
 
 fn assert_position(position : &WindowPosition, expected_x : i32, expected_y : i32) {
@@ -426,4 +426,122 @@ fn window_gwdp_limit() {
         Ok(position) => assert!(position.x == 0 && position.y == 0, " Result={:?}", position),
         Err(_) => assert!(false),
     }
-}
\ No newline at end of file
+}
+
+/// Unit tests [super::Window::handle_event] states update.
+///
+/// # Verification(s)
+/// V1 | Window created from builder starts hidden without states.
+/// V2 | Shown, Resized, Moved and ScaleChanged update visibility, size, position and scale.
+/// V3 | Maximized, Fullscreen and Restored update states.
+/// V4 | Minimized, Hidden and Closed update states.
+/// V5 | Exposed requests a redraw.
+#[cfg(target_os = "linux")]
+#[test]
+fn window_ut_handle_event() {
+    use crate::linux::{headless::{window::HeadlessWindow, HeadlessServer}, window::LinuxWindow};
+
+    let mut builder = WindowBuilder::new();
+    builder.title("Events");
+    let lw = LinuxWindow::Headless(HeadlessWindow::new(Rc::new(RefCell::new(HeadlessServer::default())), Rc::new(create_displays(true)), 
+        builder.size, builder.min_size, builder.max_size));
+    let mut window = Window::new(&builder, lw, Rc::new(create_displays(true)), WindowHandle::NULL);
+
+    // V1 | Window created from builder starts hidden without states.
+    assert_eq!(window.title(), "Events");
+    assert!(!window.visible() && !window.minimized() && !window.maximized() && !window.fullscreen());
+
+    // V2 | Shown, Resized, Moved and ScaleChanged update visibility, size, position and scale.
+    assert_eq!(window.scale(), 1.0);
+    window.handle_event(&WindowEvent::Shown);
+    window.handle_event(&WindowEvent::Resized(WindowSize::new(800, 600)));
+    window.handle_event(&WindowEvent::Moved(WindowPosition::new(10, 20)));
+    window.handle_event(&WindowEvent::ScaleChanged(1.5));
+    assert!(window.visible());
+    assert_eq!(window.size(), WindowSize::new(800, 600));
+    assert_position(&window.position(), 10, 20);
+    assert_eq!(window.scale(), 1.5);
+
+    // V3 | Maximized, Fullscreen and Restored update states.
+    window.handle_event(&WindowEvent::Maximized);
+    window.handle_event(&WindowEvent::Fullscreen);
+    assert!(window.maximized() && window.fullscreen());
+    window.handle_event(&WindowEvent::Restored);
+    assert!(!window.maximized() && !window.fullscreen());
+
+    // V4 | Minimized, Hidden and Closed update states.
+    window.handle_event(&WindowEvent::Minimized);
+    assert!(window.minimized());
+    window.handle_event(&WindowEvent::Hidden);
+    assert!(!window.visible());
+    window.handle_event(&WindowEvent::Shown);
+    window.handle_event(&WindowEvent::Closed);
+    assert!(!window.visible());
+
+    // V5 | Exposed requests a redraw.
+    window.handle_event(&WindowEvent::Exposed(WindowCoordinate { x : 0, y : 0 }, WindowSize::new(10, 10)));
+    match &window.window {
+        LinuxWindow::Headless(lw) => assert!(lw.redraw),
+        _ => unreachable!(),
+    }
+}
+
+/// Unit tests [super::Window::framebuffer_clip] damage clipping.
+///
+/// # Verification(s)
+/// V1 | No damage means the whole framebuffer.
+/// V2 | Rectangles inside are kept as is.
+/// V3 | Rectangles crossing borders are clipped.
+/// V4 | Rectangles outside or empty are removed.
+#[test]
+fn window_ut_framebuffer_clip() {
+    let size = WindowSize::new(100, 50);
+
+    // V1 | No damage means the whole framebuffer.
+    assert_eq!(Window::framebuffer_clip(size, &[]), vec![(WindowPosition::new(0, 0), size)]);
+
+    // V2 | Rectangles inside are kept as is.
+    let rect = (WindowPosition::new(10, 5), WindowSize::new(20, 10));
+    assert_eq!(Window::framebuffer_clip(size, &[rect]), vec![rect]);
+
+    // V3 | Rectangles crossing borders are clipped.
+    assert_eq!(Window::framebuffer_clip(size, &[(WindowPosition::new(-10, 40), WindowSize::new(30, 30))]), 
+        vec![(WindowPosition::new(0, 40), WindowSize::new(20, 10))]);
+    assert_eq!(Window::framebuffer_clip(size, &[(WindowPosition::new(90, -5), WindowSize::new(50, 100))]), 
+        vec![(WindowPosition::new(90, 0), WindowSize::new(10, 50))]);
+
+    // V4 | Rectangles outside or empty are removed.
+    assert!(Window::framebuffer_clip(size, &[(WindowPosition::new(100, 0), WindowSize::new(10, 10)), 
+        (WindowPosition::new(-20, 0), WindowSize::new(10, 10)), (WindowPosition::new(5, 5), WindowSize::new(0, 10))]).is_empty());
+}
+
+/// Unit tests [super::WindowHandles] generational handles.
+///
+/// # Verification(s)
+/// V1 | Handles are unique and never null.
+/// V2 | Slot of a removed handle is reused with another generation.
+/// V3 | Stale and null handles don't free the slot again.
+#[test]
+fn window_ut_handles() {
+    let mut handles = WindowHandles::default();
+
+    // V1 | Handles are unique and never null.
+    let first = handles.insert();
+    let second = handles.insert();
+    assert!(first != second && !first.is_null() && !second.is_null());
+    assert!(WindowHandle::NULL.is_null());
+
+    // V2 | Slot of a removed handle is reused with another generation.
+    handles.remove(first);
+    let third = handles.insert();
+    assert!(third != first && third != second);
+
+    // V3 | Stale and null handles don't free the slot again.
+    handles.remove(first);
+    handles.remove(WindowHandle::NULL);
+    let fourth = handles.insert();
+    assert!(fourth != third && fourth != first && fourth != second);
+
+    let set : std::collections::HashSet<WindowHandle> = [first, second, third, fourth].into_iter().collect();
+    assert_eq!(set.len(), 4);
+}
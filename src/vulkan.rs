@@ -0,0 +1,80 @@
+/* 
+Copyright (c) 2024  NickelAnge.Studio 
+Email               mathieu.grenier@nickelange.studio
+Git                 https://github.com/NickelAngeStudio/nswindow
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+
+//! [Vulkan](https://www.vulkan.org/) surfaces of [Window](crate::Window).
+//! 
+//! Vulkan isn't linked. Functions are loaded with the `vkGetInstanceProcAddr` given by the application, so any loader 
+//! like [ash](https://crates.io/crates/ash) or Mesa's lavapipe works.
+//! 
+//! # Example
+//! ```no_run
+//! # use nswindow::{WindowManager, WindowBuilder};
+//! # fn run(instance : nswindow::vulkan::VkInstance, gipa : nswindow::vulkan::VkGetInstanceProcAddr) {
+//! let mut wm = WindowManager::new().unwrap();
+//! 
+//! // Enable these when creating the VkInstance.
+//! let extensions = wm.vulkan_required_extensions().unwrap();
+//! 
+//! let window = WindowBuilder::new().build(&mut wm).unwrap();
+//! let surface = unsafe { wm.window(window).unwrap().create_vulkan_surface(instance, gipa) }.unwrap();
+//! # }
+//! ```
+//! 
+//! # Known issue(s)
+//! * `(Linux only)` VK_KHR_wayland_surface needs libwayland wl_display and wl_surface pointers. If libwayland-client can't 
+//!   be loaded, Wayland returns [WindowError::WindowVulkanUnsupported](crate::WindowError::WindowVulkanUnsupported).
+
+use std::ffi::{c_char, c_void, CStr};
+
+/// Vulkan `VkInstance` dispatchable handle.
+pub type VkInstance = *mut c_void;
+
+/// Vulkan `VkSurfaceKHR` non-dispatchable handle.
+pub type VkSurfaceKHR = u64;
+
+/// Vulkan `VkResult` code.
+pub type VkResult = i32;
+
+/// Vulkan `PFN_vkVoidFunction` returned by [VkGetInstanceProcAddr].
+pub type VkVoidFunction = unsafe extern "system" fn();
+
+/// Vulkan `PFN_vkGetInstanceProcAddr` used to load surface functions.
+pub type VkGetInstanceProcAddr = unsafe extern "system" fn(instance : VkInstance, name : *const c_char) -> Option<VkVoidFunction>;
+
+/// `VK_KHR_surface` instance extension name.
+pub const VK_KHR_SURFACE : &CStr = c"VK_KHR_surface";
+
+/// `VK_KHR_xlib_surface` instance extension name.
+pub const VK_KHR_XLIB_SURFACE : &CStr = c"VK_KHR_xlib_surface";
+
+/// `VK_KHR_wayland_surface` instance extension name.
+pub const VK_KHR_WAYLAND_SURFACE : &CStr = c"VK_KHR_wayland_surface";
+
+/// `VK_SUCCESS` result.
+pub(crate) const VK_SUCCESS : VkResult = 0;
+
+/// `VK_ERROR_EXTENSION_NOT_PRESENT` result, used when a surface function can't be loaded.
+pub(crate) const VK_ERROR_EXTENSION_NOT_PRESENT : VkResult = -7;
+
@@ -35,14 +35,77 @@ use std::rc::Rc;
 
 use nscfg::meta_cfg;
 
-use crate::{ display::{Desktop, DisplayHandle, DisplayResolution, Displays}, frame::WindowFrame, keyboard::WindowKeyboard, pointer::WindowPointer, WindowBuilder, WindowError};
+use crate::{ display::{Desktop, DisplayHandle, DisplayResolution, Displays}, event::{KeyCode, WindowEvent, WindowKeyboardEvent}, frame::WindowFrame, keyboard::{KeyCombination, KeyModifiers, WindowKeyboard}, pointer::WindowPointer, WindowBuilder, WindowError};
 
+/// Default emergency [KeyCombination] releasing an input grab. Ctrl+Alt+Escape.
+pub(crate) const W_DEFAULT_GRAB_RELEASE : Option<KeyCombination> = Some(KeyCombination { key : 9, 
+    modifiers : KeyModifiers { shift : false, ctrl : true, alt : true, meta : false } });
 
 
 /// Window handle used by the [WindowManager](crate::WindowManager).
 /// 
-/// It is the same handle used by the operating system.
-pub type WindowHandle = *const usize;
+/// Handle is opaque and carries a generation : the handle of a closed [Window] stays invalid, returning 
+/// [`WindowError::InvalidWindowHandle`], even when a new [Window] reuses its slot. The handle used by the 
+/// operating system is given by [Window::native_handle()].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WindowHandle {
+    /// Slot of the window in the manager
+    index : u32,
+
+    /// Generation of the slot, 0 for [WindowHandle::NULL].
+    generation : u32,
+}
+
+impl WindowHandle {
+    /// Handle referring to no [Window], given with events not related to a [Window] like 
+    /// [WindowEvent::DeviceAdded](crate::event::WindowEvent::DeviceAdded).
+    pub const NULL : WindowHandle = WindowHandle { index : 0, generation : 0 };
+
+    /// Create a handle of slot `index` at `generation`.
+    pub(crate) const fn new(index : u32, generation : u32) -> WindowHandle {
+        WindowHandle { index, generation }
+    }
+
+    /// Returns true if handle is [WindowHandle::NULL].
+    pub fn is_null(&self) -> bool {
+        self.generation == 0
+    }
+}
+
+/// [WindowHandle] allocator of a window manager. Slot of a removed handle is reused with the next generation.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct WindowHandles {
+    /// Current generation of each slot
+    generations : Vec<u32>,
+
+    /// Slots of removed handles
+    free : Vec<u32>,
+}
+
+impl WindowHandles {
+    /// Allocate a new handle.
+    pub fn insert(&mut self) -> WindowHandle {
+        match self.free.pop() {
+            Some(index) => WindowHandle::new(index, self.generations[index as usize]),
+            None => {
+                self.generations.push(1);
+                WindowHandle::new(self.generations.len() as u32 - 1, 1)
+            },
+        }
+    }
+
+    /// Free the slot of `handle`, invalidating it. Does nothing if handle is stale or null.
+    pub fn remove(&mut self, handle : WindowHandle) {
+        match self.generations.get_mut(handle.index as usize) {
+            Some(generation) if *generation == handle.generation && !handle.is_null() => {
+                // Generation 0 is kept for the null handle.
+                *generation = generation.wrapping_add(1).max(1);
+                self.free.push(handle.index);
+            },
+            _ => {},
+        }
+    }
+}
 
 /// [Window](crate::Window) size as width and height.
 #[derive(Debug, PartialEq, PartialOrd, Copy, Clone)]
@@ -199,14 +262,128 @@ pub struct Window {
 
     /// Show the window in the taskbar.
     pub(crate) taskbar : bool,
+
+    /// Keyboard and pointer are grabbed exclusively by the window.
+    pub(crate) input_grabbed : bool,
+
+    /// Screen blanking and screen saver are inhibited while the window is visible.
+    pub(crate) idle_inhibit : bool,
+
+    /// Emergency [KeyCombination] that release an input grab.
+    pub(crate) grab_release : Option<KeyCombination>,
+
+    /// Scale factor of the window content.
+    pub(crate) scale : f64,
 }
 
 
 impl Window {
 
+    /// Create a [Window] from [WindowBuilder] properties with the system window already created.
+    /// 
+    /// Visibility, minimize, maximize and fullscreen states start cleared and are updated by the system events.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn new(builder : &WindowBuilder, window : crate::linux::window::LinuxWindow, displays : Rc<Displays>, handle : WindowHandle) -> Window {
+        Window { 
+            window, 
+            displays, 
+            handle, 
+            #[cfg(any(doc, not(feature = "single_opt")))]
+            parent : builder.parent, 
+            #[cfg(any(doc, not(feature = "single_opt")))]
+            childs : Vec::new(), 
+            #[cfg(any(doc, not(feature = "single_opt")))]
+            modal : None, 
+            #[cfg(any(doc, not(feature = "single_opt")))]
+            sub : match builder.parent {
+                Some(_) => Some(builder.sub.clone()),
+                None => None,
+            }, 
+            frame : builder.frame.properties(), 
+            keyboard : builder.keyboard.properties(), 
+            pointer : builder.pointer.properties(), 
+            title : builder.title.clone(), 
+            size : builder.size, 
+            min_size : builder.min_size, 
+            max_size : builder.max_size, 
+            desktop_position : WindowPosition { x : 0, y : 0 }, 
+            fullscreen : false, 
+            minimized : false, 
+            maximized : false, 
+            visible : false, 
+            taskbar : builder.taskbar, 
+            input_grabbed : false, 
+            idle_inhibit : false,
+            grab_release : W_DEFAULT_GRAB_RELEASE,
+            scale : 1.0,
+        }
+    }
+
     /// Function invoked by the builder when rebuilding a [Window].
+    /// 
+    /// Builder properties are applied to the existing [Window], which keeps its [WindowHandle]. Frame, keyboard and
+    /// pointer properties are left untouched. Properties are validated before any is applied, so an error leaves the
+    /// [Window] unchanged.
+    /// 
+    /// # Errors
+    /// - Returns Err([`WindowError::WindowMinSizeBiggerThanMax`]) if min size is bigger than max size.
+    /// - Returns Err([`WindowError::WindowSizeOOB`]) if size is not between min and max.
+    /// - Returns errors of [Window::set_parent] and [Window::set_position].
     pub(crate) fn rebuild(&mut self, builder : &WindowBuilder) -> Result<WindowHandle, WindowError> {
-        self.window.rebuild(builder)
+
+        if builder.min_size > builder.max_size {
+            return Err(WindowError::WindowMinSizeBiggerThanMax);
+        }
+        if builder.size > builder.max_size || builder.size < builder.min_size {
+            return Err(WindowError::WindowSizeOOB);
+        }
+
+        // Position is resolved with the new size and parent before anything is applied.
+        #[cfg(not(feature = "single_opt"))]
+        let parent = match builder.parent {
+            Some(parent) if parent == self.handle => return Err(WindowError::WindowParentSelf),
+            parent => parent,
+        };
+        #[cfg(feature = "single_opt")]
+        let parent = None;
+        let position = self.window_desktop_position(builder.size, parent, builder.position.clone(), true)?;
+
+        // Parent is the only property the system may refuse, it is set first so a refusal leaves the window untouched.
+        #[cfg(not(feature = "single_opt"))]
+        self.set_parent(parent)?;
+
+        self.set_title(&builder.title)?;
+        self.min_size = builder.min_size;
+        self.max_size = builder.max_size;
+        self.window.set_size_min(builder.min_size)?;
+        self.window.set_size_max(builder.max_size)?;
+        self.size = builder.size;
+        self.window.set_size(builder.size)?;
+        self.window.set_position(position);
+        self.set_taskbar(builder.taskbar);
+        match &mut builder.icon.as_deref() {
+            Some(icon) => self.set_icon(Some(icon)),
+            None => self.set_icon(None),
+        }
+
+        // States are applied in the same order as build.
+        self.restore();
+        if builder.maximized {
+            self.maximize();
+        }
+        if let Some(fsmode) = &builder.fsmode {
+            self.set_fullscreen(fsmode.clone());
+        }
+        if builder.visible {
+            self.show();
+        } else {
+            self.hide();
+        }
+        if builder.minimized {
+            self.minimize();
+        }
+
+        Ok(self.handle)
     }
 
     /// Returns the [WindowHandle] of the [Window].
@@ -214,6 +391,12 @@ impl Window {
         self.handle
     }
 
+    /// Returns the handle of the [Window] used by the operating system : the window id on X11, 
+    /// the wl_surface object id on Wayland and the window id of the headless backend.
+    pub fn native_handle(&self) -> usize {
+        self.window.native_handle()
+    }
+
     /// Returns immutable array of [Window] childs.
     #[meta_cfg(!single_opt:ft)]
     pub fn childs(&self) -> &Vec<WindowHandle> {
@@ -246,24 +429,21 @@ impl Window {
                         } else  if new_parent == self.handle {
                             Err(WindowError::WindowParentSelf)
                         } else {
-                            self.parent = Some(new_parent);
-                            self.window.set_parent(parent)
+                            self.window.set_parent(parent).map(|changed| { self.parent = parent; changed })
                         }
                     }
                     None => {
                         if new_parent == self.handle {
                             Err(WindowError::WindowParentSelf)
                         } else {
-                            self.parent = Some(new_parent);
-                            self.window.set_parent(parent)
+                            self.window.set_parent(parent).map(|changed| { self.parent = parent; changed })
                         }
                     },
                 }
             },
             None => {
                 if self.parent.is_some() {
-                    self.parent = None;
-                    self.window.set_parent(parent)
+                    self.window.set_parent(parent).map(|changed| { self.parent = parent; changed })
                 } else {
                     Ok(false)
                 }
@@ -287,6 +467,14 @@ impl Window {
         self.size
     }
 
+    /// Get the scale factor of [Window], like 1.5 on a display scaled at 150%. [WindowSize] is multiplied by it
+    /// to get the size in pixels of what is drawn.
+    /// 
+    /// Updated with [WindowEvent::ScaleChanged].
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
     /// Set [WindowSize] of [Window].
     /// 
     /// # Errors
@@ -319,7 +507,7 @@ impl Window {
         } else {
             if self.min_size != size {
                 self.min_size = size;
-                Ok(true)
+                self.window.set_size_min(size)
             } else {
                 Ok(false)
             }
@@ -344,7 +532,7 @@ impl Window {
         } else {
             if self.max_size != size {
                 self.max_size = size;
-                Ok(true)
+                self.window.set_size_max(size)
             } else {
                 Ok(false)
             }
@@ -399,6 +587,7 @@ impl Window {
     /// Hide the [Window] on display.
     pub fn hide(&mut self) {
         if self.visible {
+            self.ungrab_input();
             self.window.hide();
         }
     }
@@ -407,6 +596,7 @@ impl Window {
     /// 
     /// [WindowHandle] will be invalid if invoked again.
     pub fn close(&mut self) {
+        self.ungrab_input();
         self.window.close();
     }
 
@@ -492,6 +682,7 @@ impl Window {
     pub fn minimize(&mut self)  {
         
         if !self.minimized {
+            self.ungrab_input();
             self.window.minimize();
             self.minimized = true;
         }
@@ -513,20 +704,333 @@ impl Window {
 
     }
 
+    /// Returns true if screen blanking is inhibited by the [Window].
+    pub fn idle_inhibit(&self) -> bool {
+        self.idle_inhibit
+    }
+
+    /// Inhibit screen blanking and screen saver while the [Window] is visible, like a video player would. Does nothing if already the same.
+    /// 
+    /// Inhibition is ignored if the system doesn't support it. On X11, the screen saver and DPMS of the server are suspended 
+    /// with MIT-SCREEN-SAVER, desktop screen savers that don't follow it aren't inhibited.
+    pub fn set_idle_inhibit(&mut self, inhibit : bool) {
+        if self.idle_inhibit != inhibit {
+            self.idle_inhibit = inhibit;
+            self.window.set_idle_inhibit(inhibit);
+        }
+    }
+
+    /// Request the focus for the [Window], like when a multi-window application moves the user to another [Window].
+    /// 
+    /// System may refuse to steal focus from other applications and mark the [Window] as urgent instead. 
+    /// [WindowEvent::Focus] is sent when focus is given.
+    pub fn activate(&mut self) {
+        self.window.activate();
+    }
+
+    /// Request a [WindowEvent::RedrawRequested] to draw the next frame in step with the display.
+    /// 
+    /// Requests are paced by the refresh of the display : while a frame is pending, they are merged into one sent once 
+    /// the display is ready for the next frame. A frame must be presented after each [WindowEvent::RedrawRequested] for 
+    /// the system to pace the next one. [WindowEvent::Exposed] requests a redraw as well.
+    pub fn request_redraw(&mut self) {
+        self.window.request_redraw();
+    }
+
+    /// Get the size in pixels of the [Window] software framebuffer, which is [Window::size()] multiplied by [Window::scale()].
+    pub fn framebuffer_size(&self) -> WindowSize {
+        WindowSize::new((self.size.width as f64 * self.scale).round() as u32, (self.size.height as f64 * self.scale).round() as u32)
+    }
+
+    /// Get the software framebuffer of the [Window] to draw pixels in. Pixels are XRGB8888, `0x00RRGGBB` in native endian, 
+    /// row after row of [Window::framebuffer_size()] width.
+    /// 
+    /// Framebuffer is reallocated when its size changes after [WindowEvent::Resized] or [WindowEvent::ScaleChanged]. Content 
+    /// is kept between [Window::present()] otherwise.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowFramebufferFailed`]) if the framebuffer couldn't be allocated.
+    pub fn framebuffer(&mut self) -> Result<&mut [u32], WindowError> {
+        let size = self.framebuffer_size();
+        self.window.framebuffer(size)
+    }
+
+    /// Show the software framebuffer on the [Window]. Only damaged rectangles in pixels are updated, 
+    /// or the whole framebuffer if damage is empty. Rectangles are clipped to [Window::framebuffer_size()].
+    /// 
+    /// Present is ignored until [WindowEvent::Shown].
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowFramebufferFailed`]) if the framebuffer couldn't be allocated or shared with the system.
+    pub fn present(&mut self, damage : &[(WindowPosition, WindowSize)]) -> Result<(), WindowError> {
+        let size = self.framebuffer_size();
+        let damage = Self::framebuffer_clip(size, damage);
+
+        if damage.is_empty() {
+            Ok(())
+        } else {
+            self.window.present(size, &damage)
+        }
+    }
+
+    /// Get the Cairo surface of the [Window] to draw on, of [Window::framebuffer_size()]. 
+    /// 
+    /// Surface follows the size of the [Window] and may change between frames. Get it for each frame, like on 
+    /// [WindowEvent::Exposed], and don't use it after [Window::cairo_present()]. Surface is owned by the [Window] and must not be destroyed.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowCairoUnsupported`]) if the Cairo library is missing or Err([`WindowError::WindowCairoSurfaceFailed`]) 
+    /// if the surface couldn't be created.
+    #[cfg(feature = "cairo")]
+    pub fn cairo_surface(&mut self) -> Result<crate::cairo::CairoSurface, WindowError> {
+        let size = self.framebuffer_size();
+        self.window.cairo_surface(size)
+    }
+
+    /// Show Cairo drawing on the [Window]. Only damaged rectangles in pixels are updated, or the whole surface if damage is empty.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowFramebufferFailed`]) if the surface is drawn in a framebuffer that couldn't be shared with the system.
+    #[cfg(feature = "cairo")]
+    pub fn cairo_present(&mut self, damage : &[(WindowPosition, WindowSize)]) -> Result<(), WindowError> {
+        let size = self.framebuffer_size();
+        let damage = Self::framebuffer_clip(size, damage);
+
+        if damage.is_empty() {
+            Ok(())
+        } else {
+            self.window.cairo_present(size, &damage)
+        }
+    }
+
+    /// Create a Vulkan `VkSurfaceKHR` of the [Window]. Surface functions are loaded with `get_instance_proc_addr`, 
+    /// nswindow doesn't link Vulkan.
+    /// 
+    /// Surface must be destroyed with vkDestroySurfaceKHR before the [Window] is closed.
+    /// 
+    /// # Safety
+    /// `instance` must be a valid VkInstance created with [WindowManager::vulkan_required_extensions()](crate::WindowManager::vulkan_required_extensions) 
+    /// and `get_instance_proc_addr` the function of its loader.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowVulkanUnsupported`]) if the backend can't create Vulkan surfaces or 
+    /// Err([`WindowError::WindowVulkanSurfaceFailed`]) with the `VkResult` if creation failed.
+    #[cfg(feature = "vulkan")]
+    #[inline(always)]
+    pub unsafe fn create_vulkan_surface(&self, instance : crate::vulkan::VkInstance, 
+        get_instance_proc_addr : crate::vulkan::VkGetInstanceProcAddr) -> Result<crate::vulkan::VkSurfaceKHR, WindowError> {
+        self.window.create_vulkan_surface(instance, get_instance_proc_addr)
+    }
+
+    /// Create an OpenGL [GLContext](crate::opengl::GLContext) drawing on the [Window] with the version, profile and framebuffer 
+    /// of `config`. Objects like textures and buffers are shared with the `shared` context of another [Window] if given.
+    /// 
+    /// Context must be dropped before the [Window] is closed.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowOpenGLUnsupported`]) if the backend or the OpenGL library can't create contexts or 
+    /// Err([`WindowError::WindowOpenGLContextFailed`]) if no framebuffer matches `config`, creation failed or `shared` uses another [GLApi](crate::opengl::GLApi).
+    #[cfg(feature = "opengl")]
+    #[inline(always)]
+    pub fn create_gl_context(&self, config : &crate::opengl::GLConfig, shared : Option<&crate::opengl::GLContext>) -> Result<crate::opengl::GLContext, WindowError> {
+        self.window.create_gl_context(config, shared)
+    }
+
+    /// Clip damage rectangles to framebuffer size, removing empty ones. Empty damage is the whole framebuffer.
+    pub(crate) fn framebuffer_clip(size : WindowSize, damage : &[(WindowPosition, WindowSize)]) -> Vec<(WindowPosition, WindowSize)> {
+        if damage.is_empty() {
+            return vec![(WindowPosition::new(0, 0), size)];
+        }
+
+        damage.iter().filter_map(|(position, rect)| {
+            let x1 = (position.x as i64).clamp(0, size.width as i64);
+            let y1 = (position.y as i64).clamp(0, size.height as i64);
+            let x2 = (position.x as i64 + rect.width as i64).clamp(0, size.width as i64);
+            let y2 = (position.y as i64 + rect.height as i64).clamp(0, size.height as i64);
+
+            (x2 > x1 && y2 > y1).then(|| (WindowPosition::new(x1 as i32, y1 as i32), WindowSize::new((x2 - x1) as u32, (y2 - y1) as u32)))
+        }).collect()
+    }
+
+    /// Returns true if keyboard and pointer are grabbed exclusively by the [Window].
+    pub fn input_grabbed(&self) -> bool {
+        self.input_grabbed
+    }
+
+    /// Grab keyboard and pointer exclusively so that system shortcuts and clicks go to the [Window].
+    /// 
+    /// Grab is released automatically when [Window] lose focus, is minimized or closed and when 
+    /// the [Window::grab_release_keys()] combination is pressed.
+    /// 
+    /// Returns Ok(true) if input got grabbed, Ok(false) if already grabbed.
+    /// 
+    /// # Errors
+    /// Returns Err([`WindowError::WindowInputGrabFailed`]) if [Window] is hidden, minimized or if the system refused the grab.
+    pub fn grab_input(&mut self) -> Result<bool, WindowError> {
+        
+        if self.input_grabbed {
+            Ok(false)
+        } else if !self.visible || self.minimized {
+            Err(WindowError::WindowInputGrabFailed)
+        } else {
+            match self.window.grab_input() {
+                Ok(_) => {
+                    self.input_grabbed = true;
+                    Ok(true)
+                },
+                Err(err) => Err(err),
+            }
+        }
+
+    }
+
+    /// Release keyboard and pointer grabbed with [Window::grab_input()]. Does nothing if not grabbed.
+    pub fn ungrab_input(&mut self) {
+
+        if self.input_grabbed {
+            self.input_grabbed = false;
+            self.window.ungrab_input();
+        }
+
+    }
+
+    /// Returns the emergency [KeyCombination] that release an input grab, if any.
+    pub fn grab_release_keys(&self) -> Option<KeyCombination> {
+        self.grab_release
+    }
+
+    /// Set the emergency [KeyCombination] that release an input grab. Ctrl+Alt+Escape by default.
+    /// 
+    /// Set to [None] to disable the emergency release.
+    pub fn set_grab_release_keys(&mut self, keys : Option<KeyCombination>) {
+        self.grab_release = keys;
+    }
+
+    /// Release the input grab if key pressed with modifiers match the emergency [KeyCombination].
+    /// 
+    /// Returns true if the grab was released.
+    pub(crate) fn grab_release_pressed(&mut self, key : KeyCode, modifiers : KeyModifiers) -> bool {
+        
+        match self.grab_release {
+            Some(keys) if self.input_grabbed && keys.matches(key, modifiers) => {
+                self.ungrab_input();
+                true
+            },
+            _ => false,
+        }
+
+    }
+
+    /// Update [Window] states from a [WindowEvent] before it is given to the user.
+    /// 
+    /// Backend must set the keyboard modifiers with [WindowKeyboard::set_modifiers()] before keyboard events.
+    /// 
+    /// Returns true if the input grab was released by the event.
+    pub(crate) fn handle_event(&mut self, event : &WindowEvent) -> bool {
+
+        match event {
+            WindowEvent::Keyboard(WindowKeyboardEvent::KeyDown(key)) => {
+                self.keyboard.key_down(*key);
+                self.grab_release_pressed(*key, self.keyboard.modifiers())
+            },
+            WindowEvent::Keyboard(WindowKeyboardEvent::KeyUp(key)) => {
+                self.keyboard.key_up(*key);
+                false
+            },
+            WindowEvent::Focus => {
+                self.keyboard.resync();
+                false
+            },
+            WindowEvent::Shown => {
+                self.visible = true;
+                false
+            },
+            // Exposed drawing is redrawn with the next frame.
+            WindowEvent::Exposed(_, _) => {
+                self.request_redraw();
+                false
+            },
+            WindowEvent::Moved(position) => {
+                self.desktop_position = *position;
+                false
+            },
+            WindowEvent::Resized(size) => {
+                self.size = *size;
+                false
+            },
+            WindowEvent::ScaleChanged(scale) => {
+                self.scale = *scale;
+                false
+            },
+            WindowEvent::MovedResized(position, size) => {
+                self.desktop_position = *position;
+                self.size = *size;
+                false
+            },
+            WindowEvent::Maximized => {
+                self.maximized = true;
+                self.minimized = false;
+                false
+            },
+            WindowEvent::Fullscreen => {
+                self.fullscreen = true;
+                self.minimized = false;
+                false
+            },
+            WindowEvent::Restored => {
+                self.fullscreen = false;
+                self.minimized = false;
+                self.maximized = false;
+                false
+            },
+            WindowEvent::Blur | WindowEvent::Minimized | WindowEvent::Hidden | WindowEvent::Closed => {
+                match event {
+                    WindowEvent::Minimized => self.minimized = true,
+                    WindowEvent::Hidden | WindowEvent::Closed => self.visible = false,
+                    _ => {},
+                }
+
+                // Keys released elsewhere won't be received.
+                self.keyboard.clear();
+
+                let grabbed = self.input_grabbed;
+                self.ungrab_input();
+                grabbed
+            },
+            _ => false,
+        }
+
+    }
+
     /// Set the window desktop position with or without out of bound checks.
     fn set_window_position(&mut self, position : WindowRelativePosition, check_oob : bool) -> Result<WindowPosition, WindowError> {
 
-        match match &position {
+        #[cfg(not(feature = "single_opt"))]
+        let parent = self.parent;
+        #[cfg(feature = "single_opt")]
+        let parent = None;
+
+        let position = self.window_desktop_position(self.size, parent, position, check_oob)?;
+        self.window.set_position(position);
+        Ok(position)
+
+    }
+
+    /// Get the desktop position a window of `size` with `parent` would have, without moving the [Window].
+    #[cfg_attr(feature = "single_opt", allow(unused_variables))]
+    fn window_desktop_position(&mut self, size : WindowSize, parent : Option<WindowHandle>, position : WindowRelativePosition, check_oob : bool) -> Result<WindowPosition, WindowError> {
+
+        match &position {
             #[cfg (any (doc , not (feature = "single_opt")))]
             WindowRelativePosition::Parent(_) | WindowRelativePosition::ParentCenter => {
-                match self.parent {
-                    Some(parent) => Self::get_window_desktop_position(self.size, self.displays.clone(), 
+                match parent {
+                    Some(parent) => Self::get_window_desktop_position(size, self.displays.clone(), 
                         position, Some(self.window.get_window_pos_size(parent)), check_oob),
                     None => {
                         match &self.displays.desktop {
-                            Some(desktop) => Self::get_window_desktop_position(self.size, self.displays.clone(), 
+                            Some(desktop) => Self::get_window_desktop_position(size, self.displays.clone(), 
                                 position, Some((WindowPosition { x: 0, y: 0 }, WindowSize { width: desktop.current.width as u32, height: desktop.current.height as u32 })), check_oob),
-                            None => Self::get_window_desktop_position(self.size, self.displays.clone(), 
+                            None => Self::get_window_desktop_position(size, self.displays.clone(), 
                                 position, Some((WindowPosition { x: 0, y: 0 }, WindowSize{ width: 0, height: 0 })), check_oob),
                         }
                         
@@ -534,13 +1038,7 @@ impl Window {
                 }
                 
             },
-            _ => Self::get_window_desktop_position(self.size, self.displays.clone(), position, None, check_oob)
-        } {
-            Ok(position) => {
-                self.window.set_position(position);
-                Ok(position)
-            },
-            Err(err) => Err(err),
+            _ => Self::get_window_desktop_position(size, self.displays.clone(), position, None, check_oob)
         }
 
     }
@@ -551,7 +1049,7 @@ impl Window {
     /// 
     /// Returns Err([WindowError::WindowRelativePositionOOB]) if any parts overflow from desktop.
     /// Returns Err([WindowError::DisplayInvalidHandle]) if a display handle doesn't exists.
-    fn get_window_desktop_position(size : WindowSize, displays : Rc<Displays>, position : WindowRelativePosition, pps : Option<(WindowPosition, WindowSize)>, check_oob : bool) -> Result<WindowPosition, WindowError> {
+    pub(crate) fn get_window_desktop_position(size : WindowSize, displays : Rc<Displays>, position : WindowRelativePosition, pps : Option<(WindowPosition, WindowSize)>, check_oob : bool) -> Result<WindowPosition, WindowError> {
         let position : Result<WindowPosition, WindowError> = match position {
             WindowRelativePosition::Desktop(position) => Ok(position),
             WindowRelativePosition::DesktopCenter => {